@@ -0,0 +1,615 @@
+//! Portable data archives and automatic database backups
+//!
+//! An archive is a zip file holding a `manifest.json` and a `data.json` with every plan's
+//! class parameters, events, schedules, favorites, pin and last generation inputs. The
+//! scraped classes cache can optionally be included. Schedules are stored as full class
+//! lists so an archive without the cache is still self-contained. Generation history is
+//! not exported.
+//!
+//! Automatic backups are plain copies of the SQLite file taken with the online backup API,
+//! one per day, kept in a `backups` directory next to the database.
+
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::anyhow;
+use rusqlite::{params, OptionalExtension, Transaction, MAIN_DB};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use zip::{write::FileOptions, ZipArchive, ZipWriter};
+
+use crate::{
+    calendar::normalize_term,
+    database_functions::{ClassRepository, FavoriteRepository, PlanRepository, ScheduleRepository},
+    migrations::{decode_blob, encode_blob},
+    objects::ImportMode,
+    Class, ClassParam, DbPool, Event, FavoriteDetails, PinnedSchedule, ScrapeClassesParameters, TermCalendar,
+};
+
+// === CONFIGURATION CONSTANTS ===
+
+/** Version of the archive layout; bumped whenever `ArchiveData` changes shape */
+const ARCHIVE_FORMAT_VERSION: u32 = 3;
+
+/** Archive entry holding the manifest */
+const MANIFEST_ENTRY: &str = "manifest.json";
+
+/** Archive entry holding the exported data */
+const DATA_ENTRY: &str = "data.json";
+
+/** Largest uncompressed data entry read from an archive, so a crafted archive can't exhaust memory */
+const MAX_DATA_ENTRY_BYTES: u64 = 256 * 1024 * 1024;
+
+/** Directory, next to the database, that holds automatic backups */
+const BACKUP_DIR: &str = "backups";
+
+/** Number of daily backups kept before the oldest are deleted */
+const BACKUP_RETENTION_DAYS: usize = 7;
+
+// === ARCHIVE FORMAT ===
+
+/** Describes an archive so it can be validated before anything is imported */
+#[derive(Serialize, Deserialize)]
+struct ArchiveManifest {
+    format_version: u32,
+    /** Creation time in seconds since the Unix epoch */
+    created_at: i64,
+    includes_cache: bool,
+}
+
+/** Everything owned by one plan */
+#[derive(Serialize, Deserialize)]
+struct ArchivedPlan {
+    name: String,
+    active: bool,
+    class_parameters: Vec<ClassParam>,
+    events: Vec<Event>,
+    schedules: Vec<Vec<Class>>,
+    favorites: Vec<Vec<Class>>,
+    /** Favorite titles, notes, colors and tags keyed by schedule ID; absent before format version 2 */
+    #[serde(default)]
+    favorite_details: HashMap<String, FavoriteDetails>,
+    pinned_schedule: Option<PinnedSchedule>,
+    last_parameters: Option<ScrapeClassesParameters>,
+    /** Term dates and holidays; absent before format version 3 */
+    #[serde(default)]
+    term: Option<TermCalendar>,
+}
+
+/** Contents of the data entry */
+#[derive(Serialize, Deserialize)]
+struct ArchiveData {
+    plans: Vec<ArchivedPlan>,
+    /** Scraped sections; empty unless the cache was exported */
+    #[serde(default)]
+    classes: Vec<Class>,
+}
+
+// === EXPORT ===
+
+/**
+ * Writes all user data to a portable archive
+ *
+ * @param {String} path - Destination file path
+ * @param {bool} include_cache - Whether to include the scraped classes cache
+ * @param {&DbPool} pool - Database connection pool
+ * @returns {Result<(), anyhow::Error>} Success or error
+ * @throws {anyhow::Error} If reading the database or writing the archive fails
+ */
+pub async fn export_archive(path: String, include_cache: bool, pool: &DbPool) -> Result<(), anyhow::Error> {
+    let pool = pool.clone();
+    tokio::task::spawn_blocking(move || -> Result<(), anyhow::Error> {
+        let mut conn = pool.get()?;
+        // Read inside one transaction so the archive is a consistent snapshot
+        let tx = conn.transaction()?;
+        let data = ArchiveData {
+            plans: load_plans(&tx)?,
+            classes: if include_cache { load_cache(&tx)? } else { Vec::new() },
+        };
+        let created_at: i64 = tx.query_row("SELECT CAST(strftime('%s', 'now') AS INTEGER)", [], |row| row.get(0))?;
+        drop(tx);
+
+        let manifest = ArchiveManifest { format_version: ARCHIVE_FORMAT_VERSION, created_at, includes_cache: include_cache };
+        let mut writer = ZipWriter::new(BufWriter::new(File::create(&path)?));
+        writer.start_file(MANIFEST_ENTRY, FileOptions::default())?;
+        writer.write_all(&serde_json::to_vec_pretty(&manifest)?)?;
+        writer.start_file(DATA_ENTRY, FileOptions::default())?;
+        serde_json::to_writer(&mut writer, &data)?;
+        writer.finish()?.flush()?;
+
+        println!("Exported {} plan(s) to {path}.", data.plans.len());
+        Ok(())
+    }).await?
+}
+
+/**
+ * Reads every plan and the data it owns
+ *
+ * @param {&Transaction} tx - Active database transaction
+ * @returns {Result<Vec<ArchivedPlan>, anyhow::Error>} Plans in creation order
+ * @throws {anyhow::Error} If a query fails or stored JSON can't be parsed
+ */
+fn load_plans(tx: &Transaction) -> Result<Vec<ArchivedPlan>, anyhow::Error> {
+    let plans: Vec<(String, String, bool, Option<PinnedSchedule>)> = {
+        let mut stmt = tx.prepare(
+            "SELECT id, name, active, pinned_schedule_id, pinned_table FROM plans ORDER BY created_at, rowid"
+        )?;
+        let plans = stmt.query_map([], |row| {
+            let pinned_id: Option<String> = row.get(3)?;
+            let pinned_table: Option<String> = row.get(4)?;
+            let pinned_schedule = pinned_id.zip(pinned_table).map(|(id, table)| PinnedSchedule { id, table });
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, pinned_schedule))
+        })?.collect::<Result<Vec<_>, _>>()?;
+        plans
+    };
+
+    let mut archived = Vec::with_capacity(plans.len());
+    for (plan_id, name, active, pinned_schedule) in plans {
+        let class_parameters = {
+            let mut stmt = tx.prepare("SELECT data FROM class_parameters WHERE plan_id = ?1 ORDER BY rowid")?;
+            let rows = stmt.query_map(params![plan_id], |row| row.get::<_, String>(0))?;
+            let mut class_parameters = Vec::new();
+            for row in rows {
+                class_parameters.push(serde_json::from_str(&row?)?);
+            }
+            class_parameters
+        };
+        let events = {
+            let mut stmt = tx.prepare(
+                "SELECT id, title, start_time, end_time, day, professor, description FROM events WHERE plan_id = ?1 ORDER BY rowid"
+            )?;
+            let events = stmt.query_map(params![plan_id], |row| {
+                Ok(Event {
+                    id: row.get(0)?,
+                    title: row.get(1)?,
+                    start_time: row.get(2)?,
+                    end_time: row.get(3)?,
+                    day: row.get(4)?,
+                    professor: row.get(5)?,
+                    description: row.get(6)?,
+                })
+            })?.collect::<Result<Vec<_>, _>>()?;
+            events
+        };
+        let last_parameters: Option<String> = tx.query_row(
+            "SELECT parameters FROM last_generation WHERE plan_id = ?1",
+            params![plan_id],
+            |row| row.get(0)
+        ).optional()?;
+
+        archived.push(ArchivedPlan {
+            name,
+            active,
+            class_parameters,
+            events,
+            schedules: ScheduleRepository::load_for_plan(tx, "schedules", &plan_id)?.into_iter().map(|s| s.classes).collect(),
+            favorites: ScheduleRepository::load_for_plan(tx, "favorites", &plan_id)?.into_iter().map(|s| s.classes).collect(),
+            favorite_details: FavoriteRepository::load_details_for_plan(tx, &plan_id)?,
+            pinned_schedule,
+            last_parameters: last_parameters.map(|json| serde_json::from_str(&json)).transpose()?,
+            term: PlanRepository::load_term(tx, &plan_id)?,
+        });
+    }
+    Ok(archived)
+}
+
+/**
+ * Reads the scraped classes cache
+ *
+ * @param {&Transaction} tx - Active database transaction
+ * @returns {Result<Vec<Class>, anyhow::Error>} All cached sections
+ * @throws {anyhow::Error} If the query fails or a row can't be decoded
+ */
+fn load_cache(tx: &Transaction) -> Result<Vec<Class>, anyhow::Error> {
+    let mut stmt = tx.prepare("SELECT data FROM classes ORDER BY rowid")?;
+    let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+    let mut classes = Vec::new();
+    for row in rows {
+        classes.push(decode_blob(&row?)?);
+    }
+    Ok(classes)
+}
+
+// === IMPORT ===
+
+/**
+ * Imports an archive created by `export_archive`
+ *
+ * - Merge: archived plans are added next to the existing ones (renamed if their
+ *   name is taken); the active plan doesn't change and cached sections already
+ *   present are kept
+ * - Replace: all plans and their history are deleted and the archive's plans take
+ *   their place, including which one is active; an archived cache replaces the
+ *   existing cache
+ *
+ * The whole import runs in one transaction, so a failure leaves the database untouched.
+ *
+ * @param {String} path - Archive file path
+ * @param {ImportMode} mode - Merge with or replace the existing data
+ * @param {&DbPool} pool - Database connection pool
+ * @returns {Result<(), anyhow::Error>} Success or error
+ * @throws {anyhow::Error} If the archive is invalid, from a newer release, or the database write fails
+ */
+pub async fn import_archive(path: String, mode: ImportMode, pool: &DbPool) -> Result<(), anyhow::Error> {
+    let pool = pool.clone();
+    tokio::task::spawn_blocking(move || -> Result<(), anyhow::Error> {
+        let (manifest, data) = read_archive(Path::new(&path))?;
+        if data.plans.is_empty() {
+            return Err(anyhow!("Archive contains no plans"));
+        }
+
+        let mut conn = pool.get()?;
+        let tx = conn.transaction()?;
+
+        if mode == ImportMode::Replace {
+            tx.execute_batch(
+                "DELETE FROM events;
+                DELETE FROM class_parameters;
+                DELETE FROM schedules;
+                DELETE FROM favorites;
+                DELETE FROM last_generation;
+                DELETE FROM run_schedules;
+                DELETE FROM generation_runs;
+                DELETE FROM operation_log;
+                DELETE FROM plans;"
+            )?;
+            if manifest.includes_cache {
+                tx.execute("DELETE FROM classes", [])?;
+            }
+        }
+
+        {
+            let mut stmt = tx.prepare("INSERT OR IGNORE INTO classes (id, classname, data) VALUES (?1, ?2, ?3)")?;
+            for class in &data.classes {
+                stmt.execute(params![ClassRepository::section_id(class), format!("{}{}", class.code, class.name), encode_blob(class)?])?;
+            }
+        }
+
+        let has_active = data.plans.iter().any(|plan| plan.active);
+        for (index, plan) in data.plans.iter().enumerate() {
+            // Merged plans never take over; a replacing archive without an active plan activates its first
+            let active = mode == ImportMode::Replace && (plan.active || (!has_active && index == 0));
+            insert_plan(&tx, plan, active)?;
+        }
+
+        ScheduleRepository::remove_orphaned_sections(&tx)?;
+        ClassRepository::rebuild_search_index_in_transaction(&tx)?;
+        tx.commit()?;
+        println!("Imported {} plan(s) from {path}.", data.plans.len());
+        Ok(())
+    }).await?
+}
+
+/**
+ * Reads and validates an archive
+ *
+ * @param {&Path} path - Archive file path
+ * @returns {Result<(ArchiveManifest, ArchiveData), anyhow::Error>} Parsed manifest and data
+ * @throws {anyhow::Error} If the file isn't a Koka archive, its format is newer than supported or its data is too large
+ */
+fn read_archive(path: &Path) -> Result<(ArchiveManifest, ArchiveData), anyhow::Error> {
+    let mut archive = ZipArchive::new(BufReader::new(File::open(path)?))?;
+
+    let manifest: ArchiveManifest = {
+        let entry = archive.by_name(MANIFEST_ENTRY).map_err(|_| anyhow!("Not a Koka archive: missing {MANIFEST_ENTRY}"))?;
+        serde_json::from_reader(entry)?
+    };
+    if manifest.format_version > ARCHIVE_FORMAT_VERSION {
+        return Err(anyhow!(
+            "Archive format version {} is newer than supported version {ARCHIVE_FORMAT_VERSION}",
+            manifest.format_version
+        ));
+    }
+
+    let entry = archive.by_name(DATA_ENTRY).map_err(|_| anyhow!("Not a Koka archive: missing {DATA_ENTRY}"))?;
+    let too_large = || anyhow!("Archive data is too large (at most {MAX_DATA_ENTRY_BYTES} bytes)");
+    if entry.size() > MAX_DATA_ENTRY_BYTES {
+        return Err(too_large());
+    }
+    // The declared size can't be trusted, so the read is capped as well
+    let mut json = String::new();
+    entry.take(MAX_DATA_ENTRY_BYTES + 1).read_to_string(&mut json)?;
+    if json.len() as u64 > MAX_DATA_ENTRY_BYTES {
+        return Err(too_large());
+    }
+    Ok((manifest, serde_json::from_str(&json)?))
+}
+
+/**
+ * Inserts one archived plan under a fresh ID
+ *
+ * @param {&Transaction} tx - Active database transaction
+ * @param {&ArchivedPlan} plan - Plan to insert
+ * @param {bool} active - Whether the plan becomes the active plan
+ * @returns {Result<(), anyhow::Error>} Success or error
+ * @throws {anyhow::Error} If JSON serialization or SQL execution fails
+ */
+fn insert_plan(tx: &Transaction, plan: &ArchivedPlan, active: bool) -> Result<(), anyhow::Error> {
+    let plan_id = Uuid::new_v4().to_string();
+    tx.execute(
+        "INSERT INTO plans (id, name, active, created_at) VALUES (?1, ?2, ?3, CAST(strftime('%s', 'now') AS INTEGER))",
+        params![plan_id, unique_plan_name(tx, &plan.name)?, active]
+    )?;
+
+    for class in &plan.class_parameters {
+        tx.execute(
+            "INSERT OR REPLACE INTO class_parameters (plan_id, id, data) VALUES (?1, ?2, ?3)",
+            params![plan_id, class.id, serde_json::to_string(class)?]
+        )?;
+    }
+    for event in &plan.events {
+        tx.execute(
+            "INSERT OR REPLACE INTO events (plan_id, id, title, start_time, end_time, day, professor, description)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![plan_id, event.id, event.title, event.start_time, event.end_time, event.day, event.professor, event.description]
+        )?;
+    }
+    let constraints = plan.last_parameters.as_ref().map(|parameters| parameters.constraints.clone()).unwrap_or_default();
+    for (table, schedules) in [("schedules", &plan.schedules), ("favorites", &plan.favorites)] {
+        for schedule in schedules {
+            let id = ScheduleRepository::insert_sections_in_transaction(tx, schedule)?;
+            ScheduleRepository::index_schedule_in_transaction(tx, &id, schedule, &constraints)?;
+            tx.execute(&format!("INSERT OR IGNORE INTO {table} (plan_id, id) VALUES (?1, ?2)"), params![plan_id, id])?;
+        }
+    }
+    let term = plan.term.clone().map(normalize_term).transpose()?;
+    PlanRepository::write_term(tx, &plan_id, term.as_ref())?;
+    // Schedule IDs are content hashes, so archived annotations match the re-inserted favorites
+    for (id, details) in &plan.favorite_details {
+        FavoriteRepository::write_details(tx, &plan_id, id, details)?;
+    }
+    if let Some(parameters) = &plan.last_parameters {
+        tx.execute(
+            "INSERT OR REPLACE INTO last_generation (plan_id, parameters) VALUES (?1, ?2)",
+            params![plan_id, serde_json::to_string(parameters)?]
+        )?;
+    }
+    // Schedule IDs are content hashes, so the archived pin still matches after re-insertion
+    if let Some(pin) = plan.pinned_schedule.as_ref().filter(|pin| ["schedules", "favorites"].contains(&pin.table.as_str())) {
+        tx.execute(
+            &format!(
+                "UPDATE plans SET pinned_schedule_id = ?1, pinned_table = ?2
+                 WHERE id = ?3 AND EXISTS(SELECT 1 FROM {} WHERE plan_id = ?3 AND id = ?1)",
+                pin.table
+            ),
+            params![pin.id, pin.table, plan_id]
+        )?;
+    }
+    Ok(())
+}
+
+/**
+ * Picks a plan name that isn't taken, appending " (2)", " (3)", ... if needed
+ *
+ * @param {&Transaction} tx - Active database transaction
+ * @param {&str} name - Preferred name
+ * @returns {Result<String, anyhow::Error>} Unused name
+ * @throws {anyhow::Error} If SQL execution fails
+ */
+fn unique_plan_name(tx: &Transaction, name: &str) -> Result<String, anyhow::Error> {
+    let base = if name.trim().is_empty() { "Imported plan" } else { name.trim() };
+    let mut candidate = base.to_string();
+    let mut suffix = 2;
+    while tx.query_row("SELECT EXISTS(SELECT 1 FROM plans WHERE name = ?1)", params![candidate], |row| row.get::<_, bool>(0))? {
+        candidate = format!("{base} ({suffix})");
+        suffix += 1;
+    }
+    Ok(candidate)
+}
+
+// === AUTOMATIC BACKUPS ===
+
+/**
+ * Takes today's backup of the database if it doesn't exist yet
+ *
+ * This function:
+ * 1. Names the backup after the local date (e.g. backups/programData-2025-01-31.db)
+ * 2. Copies the live database with SQLite's online backup API
+ * 3. Deletes all but the newest BACKUP_RETENTION_DAYS daily backups
+ *
+ * @param {&DbPool} pool - Database connection pool
+ * @returns {Result<Option<PathBuf>, anyhow::Error>} Path of the new backup, or None if today's backup already exists
+ * @throws {anyhow::Error} If the backup directory can't be created or the backup fails
+ */
+pub async fn ensure_daily_backup(pool: &DbPool) -> Result<Option<PathBuf>, anyhow::Error> {
+    let pool = pool.clone();
+    tokio::task::spawn_blocking(move || -> Result<Option<PathBuf>, anyhow::Error> {
+        let conn = pool.get()?;
+        let Some(db_path) = conn.path().filter(|path| !path.is_empty()).map(PathBuf::from) else {
+            return Ok(None); // In-memory databases have nothing to protect
+        };
+        let stem = db_path.file_stem().and_then(|stem| stem.to_str()).unwrap_or("programData").to_string();
+        let backup_dir = db_path.parent().unwrap_or(Path::new(".")).join(BACKUP_DIR);
+        fs::create_dir_all(&backup_dir)?;
+
+        let today: String = conn.query_row("SELECT date('now', 'localtime')", [], |row| row.get(0))?;
+        let backup_path = backup_dir.join(format!("{stem}-{today}.db"));
+        if backup_path.exists() {
+            return Ok(None);
+        }
+
+        conn.backup(MAIN_DB, &backup_path, None)?;
+        println!("Created daily backup {}.", backup_path.display());
+        rotate_backups(&backup_dir, &stem)?;
+        Ok(Some(backup_path))
+    }).await?
+}
+
+/**
+ * Deletes daily backups beyond the retention limit, oldest first
+ * Only files matching the daily naming scheme are considered
+ *
+ * @param {&Path} backup_dir - Backup directory
+ * @param {&str} stem - Database file stem used as the backup prefix
+ * @returns {Result<(), anyhow::Error>} Success or error
+ * @throws {anyhow::Error} If the directory can't be read
+ */
+fn rotate_backups(backup_dir: &Path, stem: &str) -> Result<(), anyhow::Error> {
+    let prefix = format!("{stem}-");
+    let mut backups: Vec<PathBuf> = fs::read_dir(backup_dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_prefix(&prefix)?.strip_suffix(".db"))
+                .is_some_and(|date| date.len() == 10 && date.bytes().all(|b| b.is_ascii_digit() || b == b'-'))
+        })
+        .collect();
+
+    // ISO dates sort chronologically
+    backups.sort();
+    let excess = backups.len().saturating_sub(BACKUP_RETENTION_DAYS);
+    for old_backup in &backups[..excess] {
+        if let Err(e) = fs::remove_file(old_backup) {
+            eprintln!("Warning: Failed to delete old backup {}: {e}", old_backup.display());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        database_functions::{ClassParamRepository, EventRepository, SystemRepository, unit_of_work},
+        objects::NewEvent,
+        test_support::{class, memory_pool, scratch_dir, MON_WED, TUE_THU},
+    };
+
+    /** Names of all plans and whether each is active, in creation order */
+    async fn plan_names(pool: &DbPool) -> Vec<(String, bool)> {
+        PlanRepository::get_all(pool).await.unwrap().into_iter().map(|plan| (plan.name, plan.active)).collect()
+    }
+
+    /**
+     * Fills the active plan with a course, an event, two generated schedules (the second a
+     * favorite with details and pinned) and a term
+     */
+    async fn fill_plan(pool: &DbPool) -> Vec<String> {
+        let first = vec![class("CSC", "116", "001", "Smith,John", MON_WED, 900, 1015)];
+        let second = vec![class("CSC", "116", "002", "Lee,Ann", TUE_THU, 900, 1015)];
+        ClassRepository::save_sections_batch(&[vec![first[0].clone(), second[0].clone()]], pool).await.unwrap();
+        let schedules = vec![first, second];
+        let ids = unit_of_work(pool, move |tx| {
+            ScheduleRepository::save_batch_in_transaction(tx, &schedules, &Default::default())
+        }).await.unwrap();
+
+        ClassParamRepository::import(vec![ClassParam {
+            id: "course-1".to_string(),
+            code: "CSC".to_string(),
+            name: "116".to_string(),
+            section: String::new(),
+            instructor: String::new(),
+        }], pool).await.unwrap();
+        EventRepository::save("events", NewEvent {
+            title: "Work".to_string(),
+            start_time: 1300,
+            end_time: 1500,
+            day: 2,
+            professor: String::new(),
+            description: String::new(),
+        }, pool).await.unwrap();
+        FavoriteRepository::change_status(ids[1].clone(), true, pool).await.unwrap();
+        FavoriteRepository::update_details(ids[1].clone(), FavoriteDetails {
+            title: "Mornings".to_string(),
+            notes: String::new(),
+            color: Some("#3b82f6".to_string()),
+            tags: vec!["safe option".to_string()],
+        }, pool).await.unwrap();
+        SystemRepository::set_display_schedule(Some(PinnedSchedule { id: ids[1].clone(), table: "favorites".to_string() }), pool).await.unwrap();
+        PlanRepository::set_term(Some(TermCalendar {
+            start: "2026-08-17".to_string(),
+            end: "2026-12-04".to_string(),
+            holidays: vec!["2026-09-07".to_string()],
+        }), pool).await.unwrap();
+        ids
+    }
+
+    #[tokio::test]
+    async fn replace_import_restores_an_exported_plan() {
+        let dir = scratch_dir("backup-round-trip");
+        let path = dir.join("export.zip").to_string_lossy().into_owned();
+        let source = memory_pool();
+        let ids = fill_plan(&source).await;
+        export_archive(path.clone(), false, &source).await.unwrap();
+
+        let target = memory_pool();
+        PlanRepository::create("Scratch".to_string(), &target).await.unwrap();
+        import_archive(path, ImportMode::Replace, &target).await.unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(plan_names(&target).await, plan_names(&source).await);
+        let courses = ClassParamRepository::get_all(&target).await.unwrap();
+        assert_eq!(courses.iter().map(|course| course.id.as_str()).collect::<Vec<_>>(), ["course-1"]);
+        let events = EventRepository::load_all("events", &target).await.unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!((events[0].title.as_str(), events[0].start_time, events[0].day), ("Work", 1300, 2));
+
+        let schedules = ScheduleRepository::get_all("schedules", &target).await.unwrap();
+        assert_eq!(schedules.iter().map(|schedule| schedule.id.clone()).collect::<Vec<_>>(), ids);
+        let favorites = ScheduleRepository::get_all("favorites", &target).await.unwrap();
+        assert_eq!(favorites.len(), 1);
+        assert_eq!(favorites[0].id, ids[1]);
+        let details = favorites[0].details.as_ref().expect("favorite details were imported");
+        assert_eq!((details.title.as_str(), details.tags.as_slice()), ("Mornings", ["safe option".to_string()].as_slice()));
+        assert_eq!(details.color.as_deref(), Some("#3b82f6"));
+
+        assert_eq!(
+            SystemRepository::get_display_schedule(&target).await.unwrap(),
+            Some(PinnedSchedule { id: ids[1].clone(), table: "favorites".to_string() })
+        );
+        assert_eq!(PlanRepository::get_term(&target).await.unwrap(), PlanRepository::get_term(&source).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn merged_plans_get_unique_names() {
+        let dir = scratch_dir("backup-merge");
+        let path = dir.join("export.zip").to_string_lossy().into_owned();
+        let pool = memory_pool();
+        let original = plan_names(&pool).await;
+        export_archive(path.clone(), false, &pool).await.unwrap();
+
+        import_archive(path.clone(), ImportMode::Merge, &pool).await.unwrap();
+        import_archive(path, ImportMode::Merge, &pool).await.unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        // Merged plans never become active
+        let name = &original[0].0;
+        assert_eq!(plan_names(&pool).await, [
+            (name.clone(), true),
+            (format!("{name} (2)"), false),
+            (format!("{name} (3)"), false),
+        ]);
+        let conn = pool.get().unwrap();
+        let tx = conn.unchecked_transaction().unwrap();
+        assert_eq!(unique_plan_name(&tx, "  ").unwrap(), "Imported plan");
+    }
+
+    #[test]
+    fn rotation_keeps_only_the_newest_daily_backups() {
+        let dir = scratch_dir("backup-rotation");
+        let days: Vec<String> = (1..=10).map(|day| format!("programData-2026-01-{day:02}.db")).collect();
+        let others = ["programData-latest.db", "programData-2026-01-01.db-journal", "other-2026-01-01.db", "notes.txt"];
+        for name in days.iter().map(String::as_str).chain(others) {
+            File::create(dir.join(name)).unwrap();
+        }
+
+        rotate_backups(&dir, "programData").unwrap();
+
+        let mut remaining: Vec<String> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        remaining.sort();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let mut expected: Vec<String> = days[days.len() - BACKUP_RETENTION_DAYS..].to_vec();
+        expected.extend(others.iter().map(|name| name.to_string()));
+        expected.sort();
+        assert_eq!(remaining, expected);
+    }
+}
@@ -0,0 +1,889 @@
+//! iCalendar (.ics) export of schedules and import of personal commitments
+//!
+//! Each meeting pattern of a section (the weekdays sharing one start and end time) becomes
+//! a weekly recurring VEVENT. Recurrences start on the first matching day of the active
+//! plan's term, end on its last day and skip the term's holidays. Times are written as
+//! floating local times, so calendar apps show classes at the wall-clock time printed in
+//! the course catalog regardless of the device's time zone.
+//!
+//! Importing goes the other way: every weekly recurring VEVENT of another calendar becomes
+//! a row in the `events` table, which blocks those times during schedule generation. The
+//! event ID is derived from the VEVENT's UID, so re-importing an updated calendar replaces
+//! the earlier copies. One-off, all-day, cancelled and already finished events are skipped,
+//! as are events repeating every few weeks rather than every week.
+
+use std::{
+    collections::HashMap,
+    fs,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::anyhow;
+
+use crate::{
+    database_functions::{stable_hash, ClassRepository, EventRepository, PlanRepository, ScheduleRepository},
+    CalendarImport, Class, DbPool, Event, TermCalendar,
+};
+
+// === CONFIGURATION CONSTANTS ===
+
+/** Identifies this application as the producer of exported calendars */
+const PRODUCT_ID: &str = "-//Koka//Schedule Export//EN";
+
+/** Suffix of every exported UID, keeping them unique across producers */
+const UID_DOMAIN: &str = "koka";
+
+/** Maximum length of a content line in octets before it must be folded (RFC 5545 3.1) */
+const MAX_LINE_OCTETS: usize = 75;
+
+/** iCalendar weekday codes for Monday through Sunday; the first five match `TimeBlock::days` */
+const WEEKDAY_CODES: [&str; 7] = ["MO", "TU", "WE", "TH", "FR", "SA", "SU"];
+
+/** Prefix of the IDs of events created by importing a calendar */
+const IMPORTED_ID_PREFIX: &str = "ics-";
+
+/** Minutes in a day */
+const MINUTES_PER_DAY: i32 = 24 * 60;
+
+// === DATES ===
+
+/** Calendar date, stored as days since 1970-01-01 */
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Date(i64);
+
+impl Date {
+    /**
+     * Parses a "YYYY-MM-DD" date
+     *
+     * @param {&str} text - Date to parse
+     * @returns {Result<Date, anyhow::Error>} Parsed date
+     * @throws {anyhow::Error} If the text isn't a valid calendar date
+     */
+    pub(crate) fn parse(text: &str) -> Result<Date, anyhow::Error> {
+        let invalid = || anyhow!("Invalid date '{}'; expected YYYY-MM-DD", text);
+        let mut parts = text.trim().splitn(3, '-');
+        let mut next = || parts.next().and_then(|part| part.parse::<i64>().ok()).ok_or_else(invalid);
+        let (year, month, day) = (next()?, next()?, next()?);
+        Date::from_civil(year, month, day).ok_or_else(invalid)
+    }
+
+    /**
+     * Builds a date from its year, month and day, validating the day of the month
+     *
+     * Uses the days-from-civil algorithm for the proleptic Gregorian calendar.
+     *
+     * @param {i64} year - Year
+     * @param {i64} month - Month, 1-12
+     * @param {i64} day - Day of the month, 1-31
+     * @returns {Option<Date>} Date, or None if it doesn't exist
+     */
+    pub(crate) fn from_civil(year: i64, month: i64, day: i64) -> Option<Date> {
+        let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+        let month_length = match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if leap => 29,
+            2 => 28,
+            _ => return None,
+        };
+        if !(1..=month_length).contains(&day) {
+            return None;
+        }
+
+        let year = if month <= 2 { year - 1 } else { year };
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        Some(Date(era * 146097 + day_of_era - 719468))
+    }
+
+    /**
+     * Splits the date into year, month and day
+     *
+     * @returns {(i64, i64, i64)} Year, month (1-12) and day of the month
+     */
+    fn civil(self) -> (i64, i64, i64) {
+        let days = self.0 + 719468;
+        let era = days.div_euclid(146097);
+        let day_of_era = days - era * 146097;
+        let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+        let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+        (year, month, day)
+    }
+
+    /**
+     * Builds a date from its distance to 1970-01-01
+     *
+     * @param {i64} days - Days since 1970-01-01
+     * @returns {Date} Date
+     */
+    pub(crate) fn from_days_since_epoch(days: i64) -> Date {
+        Date(days)
+    }
+
+    /**
+     * Distance to 1970-01-01
+     *
+     * @returns {i64} Days since 1970-01-01
+     */
+    pub(crate) fn days_since_epoch(self) -> i64 {
+        self.0
+    }
+
+    /**
+     * Day of the week
+     *
+     * @returns {usize} 0 for Monday through 6 for Sunday
+     */
+    pub(crate) fn weekday(self) -> usize {
+        // 1970-01-01 was a Thursday
+        (self.0 + 3).rem_euclid(7) as usize
+    }
+
+    /**
+     * Date a number of days later (or earlier, if negative)
+     *
+     * @param {i64} days - Days to add
+     * @returns {Date} Shifted date
+     */
+    pub(crate) fn add_days(self, days: i64) -> Date {
+        Date(self.0 + days)
+    }
+
+    /**
+     * Formats the date as "YYYY-MM-DD"
+     *
+     * @returns {String} ISO 8601 extended date
+     */
+    pub(crate) fn to_iso(self) -> String {
+        let (year, month, day) = self.civil();
+        format!("{year:04}-{month:02}-{day:02}")
+    }
+
+    /**
+     * Formats the date as "YYYYMMDD", the form used in iCalendar values
+     *
+     * @returns {String} ISO 8601 basic date
+     */
+    fn to_basic(self) -> String {
+        let (year, month, day) = self.civil();
+        format!("{year:04}{month:02}{day:02}")
+    }
+}
+
+/**
+ * Validates a term and brings it into canonical form
+ *
+ * @param {TermCalendar} term - Term as entered
+ * @returns {Result<TermCalendar, anyhow::Error>} Term with zero-padded dates and sorted, deduplicated holidays
+ * @throws {anyhow::Error} If a date is invalid or the term ends before it starts
+ */
+pub(crate) fn normalize_term(term: TermCalendar) -> Result<TermCalendar, anyhow::Error> {
+    let start = Date::parse(&term.start)?;
+    let end = Date::parse(&term.end)?;
+    if end < start {
+        return Err(anyhow!("Term end {} is before its start {}", end.to_iso(), start.to_iso()));
+    }
+    let mut holidays = term.holidays.iter().map(|holiday| Date::parse(holiday)).collect::<Result<Vec<_>, _>>()?;
+    holidays.sort();
+    holidays.dedup();
+    Ok(TermCalendar {
+        start: start.to_iso(),
+        end: end.to_iso(),
+        holidays: holidays.into_iter().map(Date::to_iso).collect(),
+    })
+}
+
+// === EXPORT ===
+
+/**
+ * Writes a stored schedule to an iCalendar file for the active plan's term
+ *
+ * Sections without scheduled meetings (e.g. online sections) are left out.
+ *
+ * @param {String} path - Destination file path (chosen by the user)
+ * @param {String} id - Content-hash ID of the schedule to export
+ * @param {&DbPool} pool - Database connection pool
+ * @returns {Result<usize, anyhow::Error>} Number of recurring events written
+ * @throws {anyhow::Error} If the term isn't set, the schedule doesn't exist, or writing the file fails
+ */
+pub async fn export_schedule_calendar(path: String, id: String, pool: &DbPool) -> Result<usize, anyhow::Error> {
+    let pool = pool.clone();
+    tokio::task::spawn_blocking(move || -> Result<usize, anyhow::Error> {
+        let conn = pool.get()?;
+        let term = PlanRepository::load_active_term(&conn)?
+            .ok_or_else(|| anyhow!("Set the term start and end dates before exporting a calendar"))?;
+        let schedule = ScheduleRepository::load_schedule(&conn, &id)?;
+        if schedule.is_empty() {
+            return Err(anyhow!("Schedule with id '{}' not found", id));
+        }
+
+        let (calendar, event_count) = render_schedule(&id, &schedule, &term)?;
+        fs::write(&path, calendar)?;
+        println!("Exported {event_count} recurring events to {path}");
+        Ok(event_count)
+    }).await?
+}
+
+/**
+ * Renders a schedule as an iCalendar document
+ *
+ * UIDs are derived from the schedule and section IDs, so re-exporting the same schedule
+ * updates the events already imported into a calendar app instead of duplicating them.
+ *
+ * @param {&str} schedule_id - Content-hash ID of the schedule
+ * @param {&[Class]} schedule - Sections in the schedule
+ * @param {&TermCalendar} term - Term bounding the recurrences
+ * @returns {Result<(String, usize), anyhow::Error>} Calendar text and the number of VEVENTs in it
+ * @throws {anyhow::Error} If the term's dates are invalid
+ */
+fn render_schedule(schedule_id: &str, schedule: &[Class], term: &TermCalendar) -> Result<(String, usize), anyhow::Error> {
+    let start = Date::parse(&term.start)?;
+    let end = Date::parse(&term.end)?;
+    let holidays = term.holidays.iter().map(|holiday| Date::parse(holiday)).collect::<Result<Vec<_>, _>>()?;
+    let stamp = utc_timestamp();
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:{PRODUCT_ID}"),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+    ];
+    let mut event_count = 0;
+
+    for class in schedule {
+        let section_id = ClassRepository::section_id(class);
+        for (block_idx, block) in class.classes.iter().enumerate() {
+            for (pattern_idx, ((start_time, end_time), days)) in meeting_patterns(&block.days).into_iter().enumerate() {
+                // First meeting on or after the term start
+                let Some(first) = (0..7).map(|offset| start.add_days(offset))
+                    .find(|date| date.weekday() < 5 && days[date.weekday()]) else { continue };
+                if first > end {
+                    continue;
+                }
+
+                let by_day: Vec<&str> = (0..5).filter(|&day| days[day]).map(|day| WEEKDAY_CODES[day]).collect();
+                let excluded: Vec<String> = holidays.iter()
+                    .filter(|holiday| (first..=end).contains(*holiday) && holiday.weekday() < 5 && days[holiday.weekday()])
+                    .map(|holiday| local_time(*holiday, start_time))
+                    .collect();
+
+                let mut description = String::new();
+                if !block.instructor.trim().is_empty() {
+                    description.push_str(&format!("Instructor: {}\n", block.instructor.trim()));
+                }
+                description.push_str(class.description.trim());
+
+                lines.push("BEGIN:VEVENT".to_string());
+                lines.push(format!("UID:{schedule_id}-{section_id}-{block_idx}-{pattern_idx}@{UID_DOMAIN}"));
+                lines.push(format!("DTSTAMP:{stamp}"));
+                lines.push(format!("DTSTART:{}", local_time(first, start_time)));
+                lines.push(format!("DTEND:{}", local_time(first, end_time)));
+                lines.push(format!("RRULE:FREQ=WEEKLY;BYDAY={};UNTIL={}T235959", by_day.join(","), end.to_basic()));
+                if !excluded.is_empty() {
+                    lines.push(format!("EXDATE:{}", excluded.join(",")));
+                }
+                lines.push(format!("SUMMARY:{}", escape_text(&format!("{} {} ({})", class.code, class.name, block.section.trim()))));
+                if !block.location.trim().is_empty() {
+                    lines.push(format!("LOCATION:{}", escape_text(block.location.trim())));
+                }
+                if !description.trim().is_empty() {
+                    lines.push(format!("DESCRIPTION:{}", escape_text(description.trim())));
+                }
+                lines.push("END:VEVENT".to_string());
+                event_count += 1;
+            }
+        }
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let calendar = lines.iter().map(|line| fold_line(line)).collect::<String>();
+    Ok((calendar, event_count))
+}
+
+/**
+ * Groups the meeting days of a time block by their start and end time
+ *
+ * @param {&[((i32, i32), bool); 5]} days - Meeting times for Monday through Friday
+ * @returns {Vec<((i32, i32), [bool; 5])>} Distinct (start, end) times with the days they apply to, in weekday order
+ */
+pub(crate) fn meeting_patterns(days: &[((i32, i32), bool); 5]) -> Vec<((i32, i32), [bool; 5])> {
+    let mut patterns: Vec<((i32, i32), [bool; 5])> = Vec::new();
+    for (day, &(time, meets)) in days.iter().enumerate() {
+        if !meets || time.0 < 0 || time.1 < time.0 {
+            continue;
+        }
+        match patterns.iter_mut().find(|(pattern_time, _)| *pattern_time == time) {
+            Some((_, pattern_days)) => pattern_days[day] = true,
+            None => {
+                let mut pattern_days = [false; 5];
+                pattern_days[day] = true;
+                patterns.push((time, pattern_days));
+            }
+        }
+    }
+    patterns
+}
+
+/**
+ * Formats a date and HHMM military time as a floating iCalendar DATE-TIME
+ *
+ * @param {Date} date - Calendar date
+ * @param {i32} time - Time as HHMM (e.g. 1345)
+ * @returns {String} Value of the form "YYYYMMDDTHHMMSS"
+ */
+fn local_time(date: Date, time: i32) -> String {
+    format!("{}T{:02}{:02}00", date.to_basic(), time / 100, time % 100)
+}
+
+/**
+ * Current time as a UTC iCalendar DATE-TIME, used for DTSTAMP
+ *
+ * @returns {String} Value of the form "YYYYMMDDTHHMMSSZ"
+ */
+fn utc_timestamp() -> String {
+    let seconds = SystemTime::now().duration_since(UNIX_EPOCH).map(|elapsed| elapsed.as_secs() as i64).unwrap_or(0);
+    let time_of_day = seconds.rem_euclid(86400);
+    format!(
+        "{}T{:02}{:02}{:02}Z",
+        Date(seconds.div_euclid(86400)).to_basic(),
+        time_of_day / 3600,
+        time_of_day % 3600 / 60,
+        time_of_day % 60
+    )
+}
+
+/**
+ * Escapes a TEXT property value (RFC 5545 3.3.11)
+ *
+ * @param {&str} text - Raw text
+ * @returns {String} Text with backslashes, semicolons, commas and newlines escaped
+ */
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/**
+ * Folds a content line to at most 75 octets per physical line and terminates it with CRLF
+ *
+ * Continuation lines start with a single space; multi-byte characters are never split.
+ *
+ * @param {&str} line - Unfolded content line
+ * @returns {String} Folded line including the trailing CRLF
+ */
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + 8);
+    let mut line_octets = 0;
+    for ch in line.chars() {
+        if line_octets + ch.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            line_octets = 1;
+        }
+        folded.push(ch);
+        line_octets += ch.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+// === IMPORT ===
+
+/** Properties of one VEVENT that matter for the import, keyed by upper-case property name */
+#[derive(Default)]
+struct CalendarEntry {
+    properties: HashMap<String, Property>,
+}
+
+/** A content line's value and parameters */
+struct Property {
+    value: String,
+    /** Parameter names are upper-case, values are unquoted */
+    params: HashMap<String, String>,
+}
+
+/**
+ * Local wall-clock start of a VEVENT
+ * Stored as a date and minutes since midnight so UTC times can be shifted across midnight
+ */
+struct LocalDateTime {
+    date: Date,
+    minutes: i32,
+}
+
+/**
+ * Imports the weekly recurring events of an iCalendar file into the active plan's events
+ *
+ * @param {String} path - Source file path (chosen by the user)
+ * @param {i32} utc_offset_minutes - Local offset from UTC, used for times given in UTC (e.g. -240 for EDT)
+ * @param {&DbPool} pool - Database connection pool
+ * @returns {Result<CalendarImport, anyhow::Error>} Counts of imported events and reasons for skipped ones
+ * @throws {anyhow::Error} If the file can't be read, isn't an iCalendar file, or database operation fails
+ */
+pub async fn import_calendar(path: String, utc_offset_minutes: i32, pool: &DbPool) -> Result<CalendarImport, anyhow::Error> {
+    let text = tokio::task::spawn_blocking(move || fs::read_to_string(&path)).await??;
+    let entries = parse_entries(&text)?;
+
+    let today = Date(SystemTime::now().duration_since(UNIX_EPOCH).map(|elapsed| elapsed.as_secs() as i64).unwrap_or(0).div_euclid(86400));
+    let mut events: Vec<Event> = Vec::new();
+    let mut skipped = Vec::new();
+    for entry in &entries {
+        match entry_to_event(entry, utc_offset_minutes, today) {
+            Ok(event) => {
+                // A UID seen twice in one file keeps its last definition
+                events.retain(|existing| existing.id != event.id);
+                events.push(event);
+            }
+            Err(reason) => skipped.push(format!("{}: {reason}", entry.text("SUMMARY").unwrap_or_else(|| "(untitled)".to_string()))),
+        }
+    }
+
+    let (added, updated, unchanged) = EventRepository::import("events", events, pool).await?;
+    println!("Imported calendar: {added} added, {updated} updated, {unchanged} unchanged, {} skipped", skipped.len());
+    Ok(CalendarImport { added, updated, unchanged, skipped })
+}
+
+/**
+ * Splits an iCalendar document into its VEVENTs
+ *
+ * Folded lines are joined first. Properties of components nested inside a VEVENT
+ * (such as VALARM) are ignored.
+ *
+ * @param {&str} text - Document text
+ * @returns {Result<Vec<CalendarEntry>, anyhow::Error>} Events in document order
+ * @throws {anyhow::Error} If the text isn't an iCalendar document
+ */
+fn parse_entries(text: &str) -> Result<Vec<CalendarEntry>, anyhow::Error> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in text.lines() {
+        match raw.strip_prefix([' ', '\t']) {
+            Some(continuation) if !lines.is_empty() => lines.last_mut().unwrap().push_str(continuation),
+            _ => lines.push(raw.to_string()),
+        }
+    }
+    if !lines.iter().any(|line| line.trim().eq_ignore_ascii_case("BEGIN:VCALENDAR")) {
+        return Err(anyhow!("Not an iCalendar file"));
+    }
+
+    let mut entries = Vec::new();
+    let mut components: Vec<String> = Vec::new();
+    let mut current: Option<CalendarEntry> = None;
+    for line in lines.iter().filter(|line| !line.trim().is_empty()) {
+        let Some((name, property)) = parse_content_line(line) else { continue };
+        match name.as_str() {
+            "BEGIN" => {
+                let component = property.value.trim().to_ascii_uppercase();
+                if component == "VEVENT" {
+                    current = Some(CalendarEntry::default());
+                }
+                components.push(component);
+            }
+            "END" => {
+                let ended = components.pop();
+                if ended.as_deref() == Some("VEVENT") {
+                    entries.extend(current.take());
+                }
+            }
+            _ if components.last().map(String::as_str) == Some("VEVENT") => {
+                if let Some(entry) = current.as_mut() {
+                    entry.properties.entry(name).or_insert(property);
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(entries)
+}
+
+/**
+ * Parses a content line of the form NAME;PARAM=VALUE:VALUE
+ *
+ * @param {&str} line - Unfolded content line
+ * @returns {Option<(String, Property)>} Upper-case property name and its value, or None if the line is malformed
+ */
+fn parse_content_line(line: &str) -> Option<(String, Property)> {
+    // The value starts at the first colon outside a quoted parameter value
+    let mut in_quotes = false;
+    let colon = line.char_indices().find(|&(_, ch)| {
+        if ch == '"' {
+            in_quotes = !in_quotes;
+        }
+        ch == ':' && !in_quotes
+    })?.0;
+
+    let mut head = line[..colon].split(';');
+    let name = head.next()?.trim().to_ascii_uppercase();
+    let params = head.filter_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        Some((key.trim().to_ascii_uppercase(), value.trim().trim_matches('"').to_string()))
+    }).collect();
+    Some((name, Property { value: line[colon + 1..].to_string(), params }))
+}
+
+impl CalendarEntry {
+    /**
+     * Raw value of a property
+     *
+     * @param {&str} name - Upper-case property name
+     * @returns {Option<&str>} Value, or None if the property is absent
+     */
+    fn raw(&self, name: &str) -> Option<&str> {
+        self.properties.get(name).map(|property| property.value.trim())
+    }
+
+    /**
+     * Unescaped value of a TEXT property
+     *
+     * @param {&str} name - Upper-case property name
+     * @returns {Option<String>} Text, or None if the property is absent or empty
+     */
+    fn text(&self, name: &str) -> Option<String> {
+        let value = self.raw(name)?;
+        let mut text = String::with_capacity(value.len());
+        let mut chars = value.chars();
+        while let Some(ch) = chars.next() {
+            if ch != '\\' {
+                text.push(ch);
+                continue;
+            }
+            match chars.next() {
+                Some('n' | 'N') => text.push('\n'),
+                Some(escaped) => text.push(escaped),
+                None => {}
+            }
+        }
+        Some(text.trim().to_string()).filter(|text| !text.is_empty())
+    }
+
+    /**
+     * Parses a DATE-TIME property as local wall-clock time
+     *
+     * Times in UTC ("Z" suffix) are shifted by the local offset; times with a TZID or
+     * without a zone are taken as local already.
+     *
+     * @param {&str} name - Upper-case property name ("DTSTART" or "DTEND")
+     * @param {i32} utc_offset_minutes - Local offset from UTC
+     * @returns {Result<Option<LocalDateTime>, String>} Time, None if the property is a date without a time, or the reason it can't be read
+     */
+    fn local_time(&self, name: &str, utc_offset_minutes: i32) -> Result<Option<LocalDateTime>, String> {
+        let property = self.properties.get(name).ok_or_else(|| format!("missing {name}"))?;
+        let value = property.value.trim();
+        if property.params.get("VALUE").is_some_and(|kind| kind.eq_ignore_ascii_case("DATE")) {
+            return Ok(None);
+        }
+        let Some((date, time)) = value.split_once(['T', 't']) else {
+            return Ok(None);
+        };
+        let date = parse_basic_date(date).ok_or_else(|| format!("invalid {name} '{value}'"))?;
+        let (time, utc) = match time.strip_suffix(['Z', 'z']) {
+            Some(time) => (time, true),
+            None => (time, false),
+        };
+        let field = |range: std::ops::Range<usize>| time.get(range).and_then(|digits| digits.parse::<i32>().ok());
+        let (Some(hours), Some(minutes)) = (field(0..2), field(2..4)) else {
+            return Err(format!("invalid {name} '{value}'"));
+        };
+        if hours > 23 || minutes > 59 {
+            return Err(format!("invalid {name} '{value}'"));
+        }
+
+        let mut minutes = hours * 60 + minutes + if utc { utc_offset_minutes } else { 0 };
+        let days = minutes.div_euclid(MINUTES_PER_DAY);
+        minutes = minutes.rem_euclid(MINUTES_PER_DAY);
+        Ok(Some(LocalDateTime { date: date.add_days(days as i64), minutes }))
+    }
+}
+
+/**
+ * Converts a VEVENT into a blocking weekly event
+ *
+ * @param {&CalendarEntry} entry - Parsed VEVENT
+ * @param {i32} utc_offset_minutes - Local offset from UTC, for times given in UTC
+ * @param {Date} today - Current date; rules that ended before it are skipped
+ * @returns {Result<Event, String>} Event with a UID-derived ID, or the reason it was skipped
+ */
+fn entry_to_event(entry: &CalendarEntry, utc_offset_minutes: i32, today: Date) -> Result<Event, String> {
+    let uid = entry.raw("UID").filter(|uid| !uid.is_empty()).ok_or("missing UID")?;
+    if entry.properties.contains_key("RECURRENCE-ID") {
+        return Err("changes a single occurrence of a recurring event".to_string());
+    }
+    if entry.raw("STATUS").is_some_and(|status| status.eq_ignore_ascii_case("CANCELLED")) {
+        return Err("cancelled".to_string());
+    }
+
+    let rule: HashMap<String, String> = entry.raw("RRULE")
+        .map(|rule| rule.split(';')
+            .filter_map(|part| part.split_once('='))
+            .map(|(key, value)| (key.trim().to_ascii_uppercase(), value.trim().to_ascii_uppercase()))
+            .collect())
+        .unwrap_or_default();
+    if rule.get("FREQ").map(String::as_str) != Some("WEEKLY") {
+        return Err("not a weekly recurring event".to_string());
+    }
+    if let Some(interval) = rule.get("INTERVAL").filter(|interval| interval.as_str() != "1") {
+        return Err(format!("repeats every {interval} weeks, but blocked times repeat every week"));
+    }
+    if rule.get("UNTIL").and_then(|until| parse_basic_date(until.get(..8)?)).is_some_and(|until| until < today) {
+        return Err("recurrence already ended".to_string());
+    }
+
+    let start = entry.local_time("DTSTART", utc_offset_minutes)?.ok_or("all-day event")?;
+    let end_minutes = match entry.local_time("DTEND", utc_offset_minutes) {
+        Ok(Some(end)) => i32::try_from(end.date.0 - start.date.0).ok()
+            .and_then(|days| days.checked_mul(MINUTES_PER_DAY))
+            .and_then(|minutes| minutes.checked_add(end.minutes))
+            .ok_or("lasts too long")?,
+        Ok(None) => return Err("all-day event".to_string()),
+        Err(_) => {
+            let duration = entry.raw("DURATION").ok_or("missing DTEND or DURATION")?;
+            parse_duration_minutes(duration)
+                .and_then(|minutes| minutes.checked_add(start.minutes))
+                .ok_or("invalid or too long DURATION")?
+        }
+    };
+    // Events running past midnight block the rest of their first day
+    let end_minutes = end_minutes.min(MINUTES_PER_DAY - 1);
+    if end_minutes <= start.minutes {
+        return Err("ends before it starts".to_string());
+    }
+
+    // BYDAY lists the days in the event's own zone; shifting a UTC start across midnight
+    // moves every occurrence by the same number of days
+    let shift = match entry.local_time("DTSTART", 0) {
+        Ok(Some(original)) => start.date.0 - original.date.0,
+        _ => 0,
+    };
+    let mut weekdays: Vec<usize> = match rule.get("BYDAY") {
+        Some(by_day) => by_day.split(',')
+            .filter_map(|day| {
+                // Weekly rules have no ordinals, but tolerate "1MO"-style values
+                let code = day.trim().trim_start_matches(|ch: char| ch == '+' || ch == '-' || ch.is_ascii_digit());
+                WEEKDAY_CODES.iter().position(|&weekday| weekday == code)
+            })
+            .map(|weekday| (weekday as i64 + shift).rem_euclid(7) as usize)
+            .collect(),
+        None => vec![start.date.weekday()],
+    };
+    weekdays.sort_unstable();
+    weekdays.dedup();
+    if weekdays.is_empty() {
+        return Err("no days of the week".to_string());
+    }
+
+    let mut description = String::new();
+    if let Some(location) = entry.text("LOCATION") {
+        description.push_str(&format!("Location: {location}\n"));
+    }
+    if let Some(notes) = entry.text("DESCRIPTION") {
+        description.push_str(&notes);
+    }
+
+    Ok(Event {
+        id: format!("{IMPORTED_ID_PREFIX}{:016x}", stable_hash(uid.as_bytes())),
+        title: entry.text("SUMMARY").unwrap_or_else(|| "Imported event".to_string()),
+        start_time: start.minutes / 60 * 100 + start.minutes % 60,
+        end_time: end_minutes / 60 * 100 + end_minutes % 60,
+        // The events table's day bitmask has Sunday in bit 0, Monday in bit 1, ...
+        day: weekdays.iter().fold(0, |mask, &weekday| mask | (1 << ((weekday + 1) % 7))),
+        professor: String::new(),
+        description: description.trim().to_string(),
+    })
+}
+
+/**
+ * Parses a "YYYYMMDD" date
+ *
+ * @param {&str} text - Date in ISO 8601 basic format
+ * @returns {Option<Date>} Date, or None if invalid
+ */
+fn parse_basic_date(text: &str) -> Option<Date> {
+    if text.len() != 8 || !text.chars().all(|ch| ch.is_ascii_digit()) {
+        return None;
+    }
+    Date::from_civil(text[..4].parse().ok()?, text[4..6].parse().ok()?, text[6..].parse().ok()?)
+}
+
+/**
+ * Parses an iCalendar DURATION such as "PT1H30M" into minutes
+ *
+ * @param {&str} text - Duration value
+ * @returns {Option<i32>} Length in minutes, or None if invalid, negative or too long to represent
+ */
+fn parse_duration_minutes(text: &str) -> Option<i32> {
+    let body = text.trim().strip_prefix('+').unwrap_or(text.trim()).strip_prefix(['P', 'p'])?;
+    let mut minutes: i32 = 0;
+    let mut number = String::new();
+    for ch in body.chars() {
+        match ch.to_ascii_uppercase() {
+            'T' => {}
+            digit if digit.is_ascii_digit() => number.push(digit),
+            unit => {
+                let value: i32 = number.parse().ok()?;
+                number.clear();
+                let unit_minutes = match unit {
+                    'W' => 7 * MINUTES_PER_DAY,
+                    'D' => MINUTES_PER_DAY,
+                    'H' => 60,
+                    'M' => 1,
+                    'S' => 0,
+                    _ => return None,
+                };
+                minutes = value.checked_mul(unit_minutes).and_then(|value| minutes.checked_add(value))?;
+            }
+        }
+    }
+    number.is_empty().then_some(minutes)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_support::{class, FRIDAY, MON_WED, TUE_THU},
+        TimeBlock,
+    };
+
+    /** Builds a term from ISO dates */
+    fn term(start: &str, end: &str, holidays: &[&str]) -> TermCalendar {
+        TermCalendar { start: start.to_string(), end: end.to_string(), holidays: holidays.iter().map(|day| day.to_string()).collect() }
+    }
+
+    /** Renders a schedule and returns the unfolded content lines of each VEVENT */
+    fn render_events(schedule: &[Class], term: &TermCalendar) -> Vec<Vec<String>> {
+        let (calendar, count) = render_schedule("s1", schedule, term).unwrap();
+        let mut events = Vec::new();
+        for line in calendar.replace("\r\n ", "").split("\r\n") {
+            match line {
+                "BEGIN:VEVENT" => events.push(Vec::new()),
+                "END:VEVENT" => {}
+                _ => if let Some(event) = events.last_mut() { event.push(line.to_string()) },
+            }
+        }
+        assert_eq!(events.len(), count);
+        events
+    }
+
+    /** Value of the first property with the given name in an event */
+    fn property<'a>(event: &'a [String], name: &str) -> Option<&'a str> {
+        event.iter().find_map(|line| line.strip_prefix(name)?.strip_prefix(':'))
+    }
+
+    /** Converts the single VEVENT of a calendar holding the given properties */
+    fn convert(properties: &[&str]) -> Result<Event, String> {
+        let text = format!(
+            "BEGIN:VCALENDAR\nBEGIN:VEVENT\nUID:test\n{}\nEND:VEVENT\nEND:VCALENDAR\n",
+            properties.join("\n")
+        );
+        let entries = parse_entries(&text).unwrap();
+        entry_to_event(&entries[0], 0, Date::parse("2025-01-01").unwrap())
+    }
+
+    #[test]
+    fn weekly_events_become_blocked_times() {
+        let event = convert(&["DTSTART:20250106T093000", "DURATION:PT1H15M", "RRULE:FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,WE"]).unwrap();
+        assert_eq!((event.start_time, event.end_time, event.day), (930, 1045, (1 << 1) | (1 << 3)));
+    }
+
+    #[test]
+    fn oversized_durations_are_rejected() {
+        assert_eq!(parse_duration_minutes("P3000000W"), None);
+        assert_eq!(parse_duration_minutes("PT2147483647M"), Some(i32::MAX));
+        assert_eq!(parse_duration_minutes("PT2147483647M1M"), None);
+
+        for end in ["DURATION:PT2147483647M", "DTEND:99991231T100000"] {
+            let error = convert(&["DTSTART:00010101T093000", end, "RRULE:FREQ=WEEKLY"]).err().unwrap();
+            assert!(error.contains("too long"), "{end}: {error}");
+        }
+    }
+
+    #[test]
+    fn rules_repeating_less_than_weekly_are_rejected() {
+        let error = convert(&["DTSTART:20250106T093000", "DTEND:20250106T100000", "RRULE:FREQ=WEEKLY;INTERVAL=2"]).err().unwrap();
+        assert!(error.contains("every 2 weeks"), "{error}");
+    }
+
+    #[test]
+    fn recurrences_start_on_the_first_meeting_day_of_the_term() {
+        // The term runs Wednesday to Thursday, so the Friday section never meets
+        let schedule = [
+            class("CSC", "116", "001", "", MON_WED, 935, 1025),
+            class("MA", "241", "002", "", TUE_THU, 1040, 1130),
+            class("HI", "233", "003", "", FRIDAY, 1300, 1350),
+        ];
+        let events = render_events(&schedule, &term("2026-08-19", "2026-08-20", &[]));
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(property(&events[0], "DTSTART"), Some("20260819T093500"));
+        assert_eq!(property(&events[0], "DTEND"), Some("20260819T102500"));
+        assert_eq!(property(&events[0], "RRULE"), Some("FREQ=WEEKLY;BYDAY=MO,WE;UNTIL=20260820T235959"));
+        assert_eq!(property(&events[1], "DTSTART"), Some("20260820T104000"));
+    }
+
+    #[test]
+    fn holidays_are_excluded_only_on_meeting_days_within_the_term() {
+        // Mondays and Wednesdays are excluded; the Tuesday, the Saturday and the day before the term are not
+        let holidays = ["2026-08-10", "2026-09-07", "2026-09-08", "2026-09-12", "2026-11-25"];
+        let events = render_events(&[class("CSC", "116", "001", "", MON_WED, 1040, 1130)], &term("2026-08-17", "2026-12-01", &holidays));
+
+        assert_eq!(property(&events[0], "EXDATE"), Some("20260907T104000,20261125T104000"));
+
+        let events = render_events(&[class("MA", "241", "002", "", TUE_THU, 1040, 1130)], &term("2026-08-17", "2026-12-01", &["2026-09-07"]));
+        assert_eq!(property(&events[0], "EXDATE"), None);
+    }
+
+    #[test]
+    fn each_meeting_pattern_gets_its_own_event() {
+        let mut days = [((-1, -1), false); 5];
+        days[0] = ((900, 950), true);
+        days[2] = ((900, 950), true);
+        days[4] = ((1000, 1150), true);
+        let lab = Class {
+            code: "CH".to_string(),
+            name: "101".to_string(),
+            description: String::new(),
+            classes: vec![TimeBlock { section: "201".to_string(), location: "Dabney 220".to_string(), days, instructor: "Smith, J".to_string() }],
+        };
+        let events = render_events(&[lab], &term("2026-08-17", "2026-12-01", &[]));
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(property(&events[0], "UID"), Some("s1-CH101/201-0-0@koka"));
+        assert_eq!(property(&events[0], "RRULE"), Some("FREQ=WEEKLY;BYDAY=MO,WE;UNTIL=20261201T235959"));
+        assert_eq!(property(&events[1], "UID"), Some("s1-CH101/201-0-1@koka"));
+        assert_eq!(property(&events[1], "RRULE"), Some("FREQ=WEEKLY;BYDAY=FR;UNTIL=20261201T235959"));
+        assert_eq!(property(&events[1], "DTSTART"), Some("20260821T100000"));
+        assert_eq!(property(&events[1], "DTEND"), Some("20260821T115000"));
+        assert_eq!(property(&events[1], "DESCRIPTION"), Some("Instructor: Smith\\, J"));
+    }
+
+    #[test]
+    fn folding_never_splits_a_character() {
+        // The two-octet character would straddle the 75-octet limit, so it starts the next line
+        let line = format!("{}é{}", "a".repeat(74), "ü".repeat(60));
+        let folded = fold_line(&line);
+
+        let physical: Vec<&str> = folded.strip_suffix("\r\n").unwrap().split("\r\n").collect();
+        assert_eq!(physical[0], "a".repeat(74));
+        assert!(physical.iter().all(|part| part.len() <= MAX_LINE_OCTETS));
+        assert!(physical[1..].iter().all(|part| part.starts_with(' ')));
+        assert_eq!(folded.replace("\r\n ", "").trim_end(), line);
+        assert_eq!(fold_line("SUMMARY:Short"), "SUMMARY:Short\r\n");
+    }
+
+    #[test]
+    fn text_values_are_escaped() {
+        assert_eq!(escape_text("Lab; bring goggles, gloves"), "Lab\\; bring goggles\\, gloves");
+        assert_eq!(escape_text("C:\\temp"), "C:\\\\temp");
+        assert_eq!(escape_text("two\r\nlines\nthree"), "two\\nlines\\nthree");
+        assert_eq!(escape_text("plain"), "plain");
+    }
+}
@@ -0,0 +1,638 @@
+//! Bulk import of class parameters from a pasted course list or CSV
+//!
+//! Free-form text holds one or more courses per line, separated by commas or semicolons:
+//! "CSC 316 001, MA 305; ST 370 Smith". Each course is a 2-3 letter subject and a 3 digit
+//! number, optionally followed by a section ("001", "001L", "sec 002") and an instructor.
+//! Text after a comma that doesn't start a new course continues the previous one, so
+//! "ST 370 Smith, John" and unquoted CSV rows such as "CSC316,001,Smith,John" both work.
+//!
+//! CSV with a header row is read by column name instead: a course column ("course",
+//! "code", "class") or separate "subject" and "number" columns, plus optional "section"
+//! and "instructor" columns.
+//!
+//! The scraper matches instructors as "Last,First" or as a bare last name. Names written any
+//! other way ("John Smith") are resolved against the cached sections of the course, and
+//! reported if the course isn't cached or the name is ambiguous. A bare last name is
+//! resolved too when the course is cached, and otherwise kept as written.
+
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::Read,
+};
+
+use anyhow::anyhow;
+use uuid::Uuid;
+
+use crate::{
+    database_functions::{ClassParamRepository, ClassRepository},
+    ClassParam, ClassParamImport, DbPool,
+};
+
+// === CONFIGURATION CONSTANTS ===
+
+/** Largest accepted paste in bytes, to keep a stray file drop from flooding the course list */
+const MAX_IMPORT_LENGTH: usize = 100_000;
+
+/** Header names recognized for each CSV column */
+const COURSE_HEADERS: [&str; 4] = ["course", "code", "class", "course code"];
+const SUBJECT_HEADERS: [&str; 2] = ["subject", "department"];
+const NUMBER_HEADERS: [&str; 3] = ["number", "catalog", "catalog number"];
+const SECTION_HEADERS: [&str; 2] = ["section", "sec"];
+const INSTRUCTOR_HEADERS: [&str; 3] = ["instructor", "professor", "teacher"];
+
+/** Words that may introduce a section number */
+const SECTION_PREFIXES: [&str; 4] = ["sec", "sec.", "section", "#"];
+
+// === PARSED TYPES ===
+
+/** Course read from one entry of the input, before instructor resolution */
+struct ParsedClass {
+    /** 1-based input line, for error messages */
+    line: usize,
+    code: String,
+    name: String,
+    section: String,
+    instructor: String,
+}
+
+/** Column positions of a CSV with a header row */
+struct CsvColumns {
+    course: Option<usize>,
+    subject: Option<usize>,
+    number: Option<usize>,
+    section: Option<usize>,
+    instructor: Option<usize>,
+}
+
+// === PUBLIC API ===
+
+/**
+ * Parses a pasted course list or CSV and adds the courses to the active plan
+ *
+ * Courses already in the list (same course, section and instructor) are counted as
+ * unchanged. All additions form a single undoable step.
+ *
+ * @param {String} text - Pasted text or CSV file contents
+ * @param {&DbPool} pool - Database connection pool
+ * @returns {Result<ClassParamImport, anyhow::Error>} Counts and the reasons for every skipped entry
+ * @throws {anyhow::Error} If the text is too long or database operation fails
+ */
+pub async fn import_class_list(text: String, pool: &DbPool) -> Result<ClassParamImport, anyhow::Error> {
+    if text.len() > MAX_IMPORT_LENGTH {
+        return Err(anyhow!("Course list is too long ({} bytes, at most {})", text.len(), MAX_IMPORT_LENGTH));
+    }
+
+    let (parsed, mut skipped) = parse_class_list(&text);
+
+    // Load the cached sections of every course whose instructor needs resolving
+    let mut instructors: HashMap<String, Option<Vec<String>>> = HashMap::new();
+    for class in parsed.iter().filter(|class| needs_resolution(&class.instructor)) {
+        let course = format!("{}{}", class.code, class.name);
+        if instructors.contains_key(&course) {
+            continue;
+        }
+        let sections = ClassRepository::get_by_name(course.clone(), pool).await?;
+        let names = if sections.is_empty() {
+            None
+        } else {
+            let mut names: Vec<String> = sections
+                .iter()
+                .flat_map(|class| class.classes.iter())
+                .map(|block| block.instructor.clone())
+                .filter(|name| !name.is_empty())
+                .collect();
+            names.sort();
+            names.dedup();
+            Some(names)
+        };
+        instructors.insert(course, names);
+    }
+
+    let mut classes = Vec::new();
+    for class in parsed {
+        let instructor = if needs_resolution(&class.instructor) {
+            let cached = instructors.get(&format!("{}{}", class.code, class.name)).and_then(Option::as_ref);
+            match resolve_instructor(&class, cached) {
+                Ok(instructor) => instructor,
+                Err(reason) => {
+                    skipped.push((class.line, format!("{} {}: {}", class.code, class.name, reason)));
+                    continue;
+                }
+            }
+        } else {
+            class.instructor
+        };
+        classes.push(ClassParam {
+            id: Uuid::new_v4().to_string(),
+            code: class.code,
+            name: class.name,
+            section: class.section,
+            instructor,
+        });
+    }
+
+    let (added, unchanged) = ClassParamRepository::import(classes, pool).await?;
+    println!("Imported {added} course(s) from a pasted list ({unchanged} already listed, {} skipped)", skipped.len());
+    skipped.sort_by_key(|(line, _)| *line);
+    let skipped = skipped.into_iter().map(|(line, reason)| format!("Line {line}: {reason}")).collect();
+    Ok(ClassParamImport { added, unchanged, skipped })
+}
+
+/**
+ * Reads a course list or CSV file and adds its courses to the active plan
+ *
+ * @param {String} path - Text or CSV file path (chosen by the user)
+ * @param {&DbPool} pool - Database connection pool
+ * @returns {Result<ClassParamImport, anyhow::Error>} Counts and the reasons for every skipped entry
+ * @throws {anyhow::Error} If the file can't be read, is too long, or database operation fails
+ */
+pub async fn import_class_file(path: String, pool: &DbPool) -> Result<ClassParamImport, anyhow::Error> {
+    let text = tokio::task::spawn_blocking(move || read_import_file(&path)).await??;
+    import_class_list(text, pool).await
+}
+
+/**
+ * Reads an import file, refusing files larger than an accepted paste before reading them
+ *
+ * @param {&str} path - File path
+ * @returns {Result<String, anyhow::Error>} File contents; at most one byte over the limit if the file grew after the size check
+ * @throws {anyhow::Error} If the file can't be read, is too large, or isn't UTF-8
+ */
+fn read_import_file(path: &str) -> Result<String, anyhow::Error> {
+    let file = File::open(path)?;
+    let length = file.metadata()?.len();
+    if length > MAX_IMPORT_LENGTH as u64 {
+        return Err(anyhow!("Course list file is too large ({} bytes, at most {})", length, MAX_IMPORT_LENGTH));
+    }
+    let mut text = String::new();
+    file.take(MAX_IMPORT_LENGTH as u64 + 1).read_to_string(&mut text)?;
+    Ok(text)
+}
+
+// === PARSING ===
+
+/**
+ * Splits input text into courses
+ *
+ * @param {&str} text - Pasted text or CSV file contents
+ * @returns {(Vec<ParsedClass>, Vec<(usize, String)>)} Courses in input order, and line numbers and reasons for entries that couldn't be read
+ */
+fn parse_class_list(text: &str) -> (Vec<ParsedClass>, Vec<(usize, String)>) {
+    let mut lines = text.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()).peekable();
+    let columns = lines.peek().and_then(|(_, line)| csv_columns(&split_fields(line, &[','])));
+    if columns.is_some() {
+        lines.next();
+    }
+
+    let mut classes = Vec::new();
+    let mut skipped = Vec::new();
+    for (index, line) in lines {
+        let line_number = index + 1;
+        match &columns {
+            Some(columns) => match parse_csv_row(line, columns, line_number) {
+                Ok(class) => classes.push(class),
+                Err(reason) => skipped.push((line_number, reason)),
+            },
+            None => {
+                let (parsed, reasons) = parse_free_form_line(line, line_number);
+                classes.extend(parsed);
+                skipped.extend(reasons.into_iter().map(|reason| (line_number, reason)));
+            }
+        }
+    }
+    (classes, skipped)
+}
+
+/**
+ * Reads the header row of a CSV, if the line is one
+ *
+ * @param {&[String]} fields - Fields of the first non-empty line
+ * @returns {Option<CsvColumns>} Column positions, or None if the line isn't a header
+ */
+fn csv_columns(fields: &[String]) -> Option<CsvColumns> {
+    let find = |names: &[&str]| {
+        fields
+            .iter()
+            .position(|field| names.contains(&field.trim().to_ascii_lowercase().replace('_', " ").as_str()))
+    };
+    let columns = CsvColumns {
+        course: find(&COURSE_HEADERS),
+        subject: find(&SUBJECT_HEADERS),
+        number: find(&NUMBER_HEADERS),
+        section: find(&SECTION_HEADERS),
+        instructor: find(&INSTRUCTOR_HEADERS),
+    };
+    let has_course = columns.course.is_some() || (columns.subject.is_some() && columns.number.is_some());
+    has_course.then_some(columns)
+}
+
+/**
+ * Reads one data row of a CSV with a header
+ *
+ * @param {&str} line - CSV row
+ * @param {&CsvColumns} columns - Column positions from the header
+ * @param {usize} line_number - 1-based line number
+ * @returns {Result<ParsedClass, String>} Course, or the reason the row couldn't be read
+ */
+fn parse_csv_row(line: &str, columns: &CsvColumns, line_number: usize) -> Result<ParsedClass, String> {
+    let fields = split_fields(line, &[',']);
+    let field = |column: Option<usize>| column.and_then(|index| fields.get(index)).map(|value| value.trim()).unwrap_or_default();
+
+    let course = match columns.course {
+        Some(_) => field(columns.course).to_string(),
+        None => format!("{}{}", field(columns.subject), field(columns.number)),
+    };
+    let (code, name, rest) = parse_course(&course).ok_or_else(|| format!("'{}' is not a course code", course))?;
+    if !rest.trim().is_empty() {
+        return Err(format!("'{}' is not a course code", course));
+    }
+
+    let section = field(columns.section).to_ascii_uppercase();
+    if !section.is_empty() && !is_section(&section) {
+        return Err(format!("'{}' is not a section number", section));
+    }
+    Ok(ParsedClass { line: line_number, code, name, section, instructor: normalize_instructor(field(columns.instructor)) })
+}
+
+/**
+ * Reads the courses of one free-form line
+ *
+ * A course whose section or instructor can't be read is left out entirely rather than
+ * added with a looser filter than intended.
+ *
+ * @param {&str} line - Line of free-form text
+ * @param {usize} line_number - 1-based line number
+ * @returns {(Vec<ParsedClass>, Vec<String>)} Courses, and reasons for the entries that couldn't be read
+ */
+fn parse_free_form_line(line: &str, line_number: usize) -> (Vec<ParsedClass>, Vec<String>) {
+    let mut classes: Vec<ParsedClass> = Vec::new();
+    let mut reasons = Vec::new();
+    // Whether the course being continued was dropped, so its remaining segments are ignored
+    let mut dropped = false;
+    for segment in split_fields(line, &[',', ';']) {
+        let segment = segment.trim();
+        if segment.is_empty() {
+            continue;
+        }
+
+        if let Some((code, name, rest)) = parse_course(segment) {
+            let mut class = ParsedClass { line: line_number, code, name, section: String::new(), instructor: String::new() };
+            dropped = false;
+            match apply_words(&mut class, rest) {
+                Ok(()) => classes.push(class),
+                Err(reason) => {
+                    reasons.push(format!("{segment}: {reason}"));
+                    dropped = true;
+                }
+            }
+            continue;
+        }
+        if dropped {
+            continue;
+        }
+
+        // Not a course: the segment continues the previous course
+        let Some(class) = classes.last_mut() else {
+            reasons.push(format!("'{}' doesn't start with a course code", segment));
+            continue;
+        };
+        let result = if !class.instructor.is_empty() && !class.instructor.contains(',') {
+            // "Smith, John" was split at its comma
+            class.instructor = normalize_instructor(&format!("{},{}", class.instructor, segment));
+            Ok(())
+        } else if class.instructor.is_empty() {
+            apply_words(class, segment)
+        } else {
+            Err("couldn't interpret it".to_string())
+        };
+        if let Err(reason) = result {
+            let class = classes.pop().unwrap();
+            reasons.push(format!("{} {} ('{}'): {}", class.code, class.name, segment, reason));
+            dropped = true;
+        }
+    }
+    (classes, reasons)
+}
+
+/**
+ * Splits the text following a course code into its section and instructor
+ *
+ * @param {&mut ParsedClass} class - Course to fill in
+ * @param {&str} words - Remaining text of the entry
+ * @returns {Result<(), String>} Success, or the reason the text couldn't be read
+ */
+fn apply_words(class: &mut ParsedClass, words: &str) -> Result<(), String> {
+    let mut instructor_words: Vec<&str> = Vec::new();
+    let mut expects_section = false;
+    for word in words.split_whitespace() {
+        let word = word.trim_matches(|character| character == '-' || character == '(' || character == ')');
+        if word.is_empty() {
+            continue;
+        }
+        if instructor_words.is_empty() && class.section.is_empty() {
+            if SECTION_PREFIXES.contains(&word.to_ascii_lowercase().as_str()) {
+                expects_section = true;
+                continue;
+            }
+            let candidate = word.trim_start_matches('#').to_ascii_uppercase();
+            if is_section(&candidate) {
+                class.section = candidate;
+                expects_section = false;
+                continue;
+            }
+        }
+        if expects_section {
+            return Err(format!("'{}' is not a section number", word));
+        }
+        instructor_words.push(word);
+    }
+    if expects_section {
+        return Err("missing section number".to_string());
+    }
+    if !instructor_words.is_empty() {
+        class.instructor = normalize_instructor(&instructor_words.join(" "));
+    }
+    Ok(())
+}
+
+/**
+ * Reads a course code at the start of the text
+ *
+ * @param {&str} text - Text starting with a course such as "CSC 316", "csc316" or "MA-305"
+ * @returns {Option<(String, String, &str)>} Subject (uppercase), number and the remaining text
+ */
+fn parse_course(text: &str) -> Option<(String, String, &str)> {
+    let text = text.trim_start();
+    let subject_length = text.find(|character: char| !character.is_ascii_alphabetic()).unwrap_or(text.len());
+    if !(2..=3).contains(&subject_length) {
+        return None;
+    }
+    let (subject, rest) = text.split_at(subject_length);
+    let rest = rest.trim_start_matches([' ', '-']);
+    let number = rest.get(..3)?;
+    if !number.chars().all(|character| character.is_ascii_digit()) {
+        return None;
+    }
+    let rest = &rest[3..];
+    if rest.starts_with(|character: char| character.is_ascii_alphanumeric()) {
+        return None;
+    }
+    Some((subject.to_ascii_uppercase(), number.to_string(), rest))
+}
+
+/**
+ * Whether a word is a section number: 3 digits and an optional letter (e.g. "001", "001L")
+ *
+ * @param {&str} word - Uppercase candidate
+ * @returns {bool} True if the word is a section number
+ */
+fn is_section(word: &str) -> bool {
+    let bytes = word.as_bytes();
+    (bytes.len() == 3 || bytes.len() == 4)
+        && bytes[..3].iter().all(u8::is_ascii_digit)
+        && bytes[3..].iter().all(u8::is_ascii_uppercase)
+}
+
+/**
+ * Splits a line at separators outside double quotes, unescaping doubled quotes
+ *
+ * @param {&str} line - Line to split
+ * @param {&[char]} separators - Field separators
+ * @returns {Vec<String>} Fields without their surrounding quotes
+ */
+fn split_fields(line: &str, separators: &[char]) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut characters = line.chars().peekable();
+    while let Some(character) = characters.next() {
+        match character {
+            '"' if quoted && characters.peek() == Some(&'"') => {
+                characters.next();
+                fields.last_mut().unwrap().push('"');
+            }
+            '"' => quoted = !quoted,
+            separator if !quoted && separators.contains(&separator) => fields.push(String::new()),
+            _ => fields.last_mut().unwrap().push(character),
+        }
+    }
+    fields
+}
+
+// === INSTRUCTORS ===
+
+/**
+ * Collapses spacing around the comma of a "Last, First" name, as the course form does
+ *
+ * @param {&str} instructor - Instructor as written
+ * @returns {String} Normalized instructor
+ */
+fn normalize_instructor(instructor: &str) -> String {
+    instructor
+        .split(',')
+        .map(|part| part.split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/**
+ * Whether an instructor has to be looked up because it isn't written as "Last,First"
+ *
+ * @param {&str} instructor - Normalized instructor
+ * @returns {bool} True if the name needs resolving
+ */
+fn needs_resolution(instructor: &str) -> bool {
+    !instructor.is_empty() && !instructor.split_once(',').is_some_and(|(last, first)| !last.is_empty() && !first.is_empty())
+}
+
+/**
+ * Finds the cached "Last,First" instructor a loosely written name refers to
+ *
+ * Every word of the written name must appear in the cached name, so "Smith" and
+ * "John Smith" both find "Smith,John". If the course isn't cached, a bare last name is
+ * kept as written, since the scraper's filter matches it against the last name.
+ *
+ * @param {&ParsedClass} class - Course with the written instructor
+ * @param {Option<&Vec<String>>} cached - Distinct instructors of the course's cached sections, None if it isn't cached
+ * @returns {Result<String, String>} Instructor as the scraper reports it, or the reason it couldn't be resolved
+ */
+fn resolve_instructor(class: &ParsedClass, cached: Option<&Vec<String>>) -> Result<String, String> {
+    let Some(cached) = cached else {
+        let last_name = class.instructor.trim_end_matches(',');
+        if !last_name.is_empty() && !last_name.contains([',', ' ']) {
+            return Ok(last_name.to_string());
+        }
+        return Err(format!(
+            "write the instructor as Last,First or just the last name (the course isn't cached yet to look up '{}')",
+            class.instructor
+        ));
+    };
+    let written: Vec<String> = class
+        .instructor
+        .split([',', ' '])
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    let matches: Vec<&String> = cached
+        .iter()
+        .filter(|name| {
+            let words: Vec<String> = name.split([',', ' ']).map(str::to_lowercase).collect();
+            written.iter().all(|word| words.contains(word))
+        })
+        .collect();
+    match matches.as_slice() {
+        [instructor] => Ok((*instructor).clone()),
+        [] => Err(format!("no cached section is taught by '{}'", class.instructor)),
+        _ => Err(format!(
+            "'{}' could be {}",
+            class.instructor,
+            matches.iter().map(|name| name.as_str()).collect::<Vec<_>>().join(" or ")
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        database_functions::ClassParamRepository,
+        test_support::{class, memory_pool, MON_WED, TUE_THU},
+    };
+
+    /** Course, section and instructor of each parsed class */
+    fn summary(classes: &[ParsedClass]) -> Vec<(String, &str, &str)> {
+        classes
+            .iter()
+            .map(|class| (format!("{} {}", class.code, class.name), class.section.as_str(), class.instructor.as_str()))
+            .collect()
+    }
+
+    fn parse(text: &str) -> Vec<(String, String, String)> {
+        let (classes, skipped) = parse_class_list(text);
+        assert!(skipped.is_empty(), "unexpected skipped entries: {skipped:?}");
+        summary(&classes).into_iter().map(|(course, section, instructor)| (course, section.to_string(), instructor.to_string())).collect()
+    }
+
+    fn entry(course: &str, section: &str, instructor: &str) -> (String, String, String) {
+        (course.to_string(), section.to_string(), instructor.to_string())
+    }
+
+    #[test]
+    fn free_form_lines_hold_several_courses() {
+        assert_eq!(parse("CSC 316 001, MA 305, ST 370 Smith"), [
+            entry("CSC 316", "001", ""),
+            entry("MA 305", "", ""),
+            entry("ST 370", "", "Smith"),
+        ]);
+        assert_eq!(parse("csc316; MA-305 001L\n\nST 370"), [
+            entry("CSC 316", "", ""),
+            entry("MA 305", "001L", ""),
+            entry("ST 370", "", ""),
+        ]);
+    }
+
+    #[test]
+    fn unquoted_csv_rows_continue_the_course() {
+        assert_eq!(parse("CSC316,001,Smith,John"), [entry("CSC 316", "001", "Smith,John")]);
+        assert_eq!(parse("ST 370 Smith, John, MA 305 Lee"), [
+            entry("ST 370", "", "Smith,John"),
+            entry("MA 305", "", "Lee"),
+        ]);
+    }
+
+    #[test]
+    fn section_prefixes_are_recognized() {
+        assert_eq!(parse("CSC 316 sec 002; CSC 316 #003; CSC 316 # 004; CSC 316 Section 001L; CSC 316 (sec. 005)"), [
+            entry("CSC 316", "002", ""),
+            entry("CSC 316", "003", ""),
+            entry("CSC 316", "004", ""),
+            entry("CSC 316", "001L", ""),
+            entry("CSC 316", "005", ""),
+        ]);
+    }
+
+    #[test]
+    fn csv_headers_select_columns() {
+        let text = "Subject,Number,Section,Instructor\nCSC,316,001,\"Smith,  John\"\nma,305,,\nXY,1,,\n";
+        let (classes, skipped) = parse_class_list(text);
+        assert_eq!(summary(&classes), [
+            ("CSC 316".to_string(), "001", "Smith,John"),
+            ("MA 305".to_string(), "", ""),
+        ]);
+        assert_eq!(skipped, [(4, "'XY1' is not a course code".to_string())]);
+
+        let (classes, skipped) = parse_class_list("course_code,sec\nCSC 316,2\nST370,002\n");
+        assert_eq!(summary(&classes), [("ST 370".to_string(), "002", "")]);
+        assert_eq!(skipped, [(2, "'2' is not a section number".to_string())]);
+    }
+
+    #[test]
+    fn unreadable_entries_are_reported_by_line() {
+        let text = "Calculus\nCSC 316 sec\nMA 305 sec X1, 001, ST 370\nST 370 Smith,John, extra, 002\nPY 205";
+        let (classes, skipped) = parse_class_list(text);
+
+        // Segments after a dropped course are ignored until the next course code
+        assert_eq!(summary(&classes), [("ST 370".to_string(), "", ""), ("PY 205".to_string(), "", "")]);
+        assert_eq!(skipped, [
+            (1, "'Calculus' doesn't start with a course code".to_string()),
+            (2, "CSC 316 sec: missing section number".to_string()),
+            (3, "MA 305 sec X1: 'X1' is not a section number".to_string()),
+            (4, "ST 370 ('extra'): couldn't interpret it".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn bare_last_names_resolve_against_the_cache() {
+        let parsed = |instructor: &str| ParsedClass {
+            line: 1,
+            code: "ST".to_string(),
+            name: "370".to_string(),
+            section: String::new(),
+            instructor: instructor.to_string(),
+        };
+
+        // Not cached: a last name is kept for the scraper, anything else can't be checked
+        assert_eq!(resolve_instructor(&parsed("Smith"), None), Ok("Smith".to_string()));
+        assert!(resolve_instructor(&parsed("John Smith"), None).is_err());
+
+        let cached = vec!["Lee,Ann".to_string(), "Smith,Amy".to_string(), "Smith,John".to_string()];
+        assert_eq!(resolve_instructor(&parsed("lee"), Some(&cached)), Ok("Lee,Ann".to_string()));
+        assert_eq!(resolve_instructor(&parsed("John Smith"), Some(&cached)), Ok("Smith,John".to_string()));
+        assert_eq!(
+            resolve_instructor(&parsed("Smith"), Some(&cached)),
+            Err("'Smith' could be Smith,Amy or Smith,John".to_string())
+        );
+        assert_eq!(
+            resolve_instructor(&parsed("Brown"), Some(&cached)),
+            Err("no cached section is taught by 'Brown'".to_string())
+        );
+        assert!(!needs_resolution("Smith,John"));
+        assert!(needs_resolution("Smith") && needs_resolution("Smith,"));
+    }
+
+    #[tokio::test]
+    async fn imports_resolve_instructors_and_report_skipped_lines() {
+        let pool = memory_pool();
+        let sections = vec![
+            class("ST", "370", "001", "Smith,John", MON_WED, 900, 950),
+            class("ST", "370", "002", "Lee,Ann", TUE_THU, 900, 950),
+        ];
+        ClassRepository::save_sections_batch(&[sections], &pool).await.unwrap();
+
+        let result = import_class_list("ST 370 john smith\nST 370 Brown\nMA 305 Jones\nCalculus".to_string(), &pool).await.unwrap();
+
+        assert_eq!((result.added, result.unchanged), (2, 0));
+        assert_eq!(result.skipped, [
+            "Line 2: ST 370: no cached section is taught by 'Brown'",
+            "Line 4: 'Calculus' doesn't start with a course code",
+        ]);
+        let classes = ClassParamRepository::get_all(&pool).await.unwrap();
+        let imported: Vec<(&str, &str)> = classes.iter().map(|class| (class.name.as_str(), class.instructor.as_str())).collect();
+        assert_eq!(imported, [("370", "Smith,John"), ("305", "Jones")]);
+
+        let Err(error) = import_class_list("CSC 316\n".repeat(MAX_IMPORT_LENGTH), &pool).await else {
+            panic!("an oversized list was imported");
+        };
+        assert!(error.to_string().contains("bytes"));
+    }
+}
@@ -4,10 +4,17 @@
 //! the repository pattern. It handles all CRUD operations for events, schedules, class
 //! parameters, and system configuration data. All database operations are wrapped in
 //! async functions using tokio::spawn_blocking for non-blocking database access.
+//! 
+//! Commands that change several tables at once compose the repositories'
+//! `*_in_transaction` helpers inside a single `unit_of_work`, so either every step
+//! is applied or none is.
 
-use crate::{objects::NewEvent, Class, ClassParam, ConnectInfo, DbPool, Event};
+use crate::{calendar::normalize_term, migrations::{decode_blob, encode_blob, run_migrations}, objects::NewEvent, Class, ClassParam, ConnectInfo, CourseSearchResult, DbPool, Event, FavoriteDetails, GenerationRun, PinnedSchedule, Plan, ScheduleQuery, SchedulePage, ScheduleConstraints, ScheduleSort, ScrapeClassesParameters, StoredSchedule, TermCalendar, UndoState};
+use crate::tauri_backend::schedule_constraints::penalty;
 use anyhow::anyhow;
-use rusqlite::{params, OptionalExtension, Transaction};
+use rusqlite::{params, params_from_iter, types::Value, Connection, OptionalExtension, Transaction};
+use serde::{Deserialize, Serialize};
+use std::{collections::{HashMap, HashSet}, time::Duration};
 use uuid::Uuid;
 
 // === CONFIGURATION CONSTANTS ===
@@ -15,12 +22,33 @@ use uuid::Uuid;
 /** Primary key for the system configuration table */
 const DATA_TABLE_ID: i32 = 0;
 
+/** Number of generation runs kept per plan when no limit has been configured */
+const DEFAULT_HISTORY_LIMIT: u32 = 20;
+
+/** Maximum number of course search results returned when no limit is given */
+const DEFAULT_SEARCH_LIMIT: usize = 50;
+
+/** Number of undoable actions kept per plan */
+const UNDO_HISTORY_DEPTH: i64 = 50;
+
+/** Maximum length of a favorite's title, in characters */
+const MAX_FAVORITE_TITLE_LENGTH: usize = 100;
+
+/** Maximum length of a favorite tag, in characters */
+const MAX_FAVORITE_TAG_LENGTH: usize = 40;
+
 /** Batch size for bulk database operations to optimize performance */
 const BATCH_SIZE: usize = 100;
 
 /** Whitelist of valid table names to prevent SQL injection */
 const VALID_TABLES: &[&str] = &["events", "schedules", "favorites"];
 
+/** SQL expression for the ID of the active plan, which owns all plan-scoped rows */
+pub(crate) const ACTIVE_PLAN: &str = "(SELECT id FROM plans WHERE active = 1)";
+
+/** Tables holding schedule IDs that can be pinned */
+const SCHEDULE_TABLES: &[&str] = &["schedules", "favorites"];
+
 // === UTILITY FUNCTIONS ===
 
 /**
@@ -30,7 +58,7 @@ const VALID_TABLES: &[&str] = &["events", "schedules", "favorites"];
  * @returns {Result<(), anyhow::Error>} Success or validation error
  * @throws {anyhow::Error} If table name is not in the whitelist
  */
-fn validate_table_name(table: &str) -> Result<(), anyhow::Error> {
+pub(crate) fn validate_table_name(table: &str) -> Result<(), anyhow::Error> {
     if VALID_TABLES.contains(&table) {
         Ok(())
     } else {
@@ -38,11 +66,77 @@ fn validate_table_name(table: &str) -> Result<(), anyhow::Error> {
     }
 }
 
+/**
+ * Packs weekday flags into a bitmask, Monday in the lowest bit
+ * 
+ * @param {&[bool; 5]} days - Flags for Monday through Friday
+ * @returns {i64} Bitmask with bit i set when days[i] is true
+ */
+fn day_mask(days: &[bool; 5]) -> i64 {
+    days.iter()
+        .enumerate()
+        .filter(|(_, &set)| set)
+        .fold(0, |mask, (day, _)| mask | (1 << day))
+}
+
+/**
+ * Computes a 64-bit FNV-1a hash
+ * Unlike std's DefaultHasher the result is stable across runs and Rust versions,
+ * so it can be persisted and shared
+ * 
+ * @param {&[u8]} bytes - Data to hash
+ * @returns {u64} Hash value
+ */
+pub(crate) fn stable_hash(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+// === UNIT OF WORK ===
+
+/**
+ * Runs several repository operations atomically in one transaction
+ * 
+ * The closure receives the transaction and composes repository `*_in_transaction`
+ * helpers on it. The transaction is committed if the closure succeeds and rolled
+ * back if it returns an error, so multi-step commands never leave partial changes.
+ * 
+ * @param {&DbPool} pool - Database connection pool
+ * @param {F} work - Operations to run within the transaction
+ * @returns {Result<T, anyhow::Error>} Value returned by the closure
+ * @throws {anyhow::Error} If any operation fails or the transaction can't be committed
+ * 
+ * @example
+ * unit_of_work(&pool, move |tx| {
+ *     FavoriteRepository::change_status_in_transaction(tx, &id, false)?;
+ *     ScheduleRepository::delete_in_transaction(tx, &id)
+ * }).await?;
+ */
+pub async fn unit_of_work<T, F>(pool: &DbPool, work: F) -> Result<T, anyhow::Error>
+where
+    T: Send + 'static,
+    F: FnOnce(&Transaction) -> Result<T, anyhow::Error> + Send + 'static,
+{
+    let pool = pool.clone();
+    tokio::task::spawn_blocking(move || -> Result<T, anyhow::Error> {
+        let mut conn = pool.get()?;
+        let tx = conn.transaction()?;
+        let result = work(&tx)?;
+        tx.commit()?;
+        Ok(result)
+    }).await?
+}
+
 // === EVENT REPOSITORY ===
 
 /**
  * Repository for managing user-defined events
- * Handles CRUD operations for calendar events with proper transaction management
+ * Handles CRUD operations for calendar events with proper transaction management;
+ * every change is recorded in the operation log so it can be undone
  */
 pub struct EventRepository;
 
@@ -79,13 +173,15 @@ impl EventRepository {
         };
 
         let event_clone_for_thread = event_to_save.clone();
-        tokio::task::spawn_blocking(move || -> Result<(), anyhow::Error> {
-            let mut conn = pool.get()?;
-            let tx = conn.transaction()?;
-            Self::save_event_in_transaction(&tx, &table, &event_clone_for_thread)?;
-            tx.commit()?;
-            Ok(())
-        }).await??;
+        unit_of_work(&pool, move |tx| {
+            Self::save_event_in_transaction(tx, &table, &event_clone_for_thread)?;
+            UndoRepository::record_in_transaction(
+                tx,
+                format!("Add event \"{}\"", event_clone_for_thread.title),
+                vec![Operation::PutEvent { table: table.clone(), event: event_clone_for_thread.clone() }],
+                vec![Operation::DeleteEvent { table, id: event_clone_for_thread.id.clone() }],
+            )
+        }).await?;
 
         Ok(event_to_save)
     }
@@ -102,18 +198,23 @@ impl EventRepository {
     pub async fn update(table: &str, event: Event, pool: &DbPool) -> Result<(), anyhow::Error> {
         validate_table_name(table)?;
         let table = table.to_string();
-        let pool = pool.clone();
-        tokio::task::spawn_blocking(move || -> Result<(), anyhow::Error> {
-            let mut conn = pool.get()?;
-            let tx = conn.transaction()?;
-            Self::update_event_in_transaction(&tx, &table, &event)?;
-            tx.commit()?;
+        unit_of_work(pool, move |tx| {
+            let previous = Self::load_event_in_transaction(tx, &table, &event.id)?;
+            Self::update_event_in_transaction(tx, &table, &event)?;
+            if let Some(previous) = previous {
+                UndoRepository::record_in_transaction(
+                    tx,
+                    format!("Edit event \"{}\"", event.title),
+                    vec![Operation::PutEvent { table: table.clone(), event }],
+                    vec![Operation::PutEvent { table, event: previous }],
+                )?;
+            }
             Ok(())
-        }).await?
+        }).await
     }
 
     /**
-     * Retrieves all events from the specified table
+     * Retrieves all events of the active plan from the specified table
      * 
      * @param {&str} table - Source table name
      * @param {&DbPool} pool - Database connection pool
@@ -126,7 +227,7 @@ impl EventRepository {
         let pool = pool.clone();
         tokio::task::spawn_blocking(move || -> Result<Vec<Event>, anyhow::Error> {
             let conn = pool.get()?;
-            let statement = format!("SELECT id, title, start_time, end_time, day, professor, description FROM {table}");
+            let statement = format!("SELECT id, title, start_time, end_time, day, professor, description FROM {table} WHERE plan_id = {ACTIVE_PLAN}");
             let mut stmt = conn.prepare(&statement)?;
             let events = stmt.query_map([], |row| {
                 Ok(Event {
@@ -145,7 +246,7 @@ impl EventRepository {
 
     /**
      * Deletes an event by ID from the specified table
-     * Simplified operation - single DELETE doesn't require transaction
+     * The deleted event is kept in the operation log so the deletion can be undone
      * 
      * @param {&str} table - Target table name
      * @param {String} event_id - Unique event identifier
@@ -156,13 +257,103 @@ impl EventRepository {
     pub async fn delete(table: &str, event_id: String, pool: &DbPool) -> Result<(), anyhow::Error> {
         validate_table_name(table)?;
         let table = table.to_string();
-        let pool = pool.clone();
-        tokio::task::spawn_blocking(move || -> Result<(), anyhow::Error> {
-            let conn = pool.get()?;
-            let statement = format!("DELETE FROM {table} WHERE id = ?1");
-            conn.execute(&statement, params![event_id])?;
-            Ok(())
-        }).await?
+        unit_of_work(pool, move |tx| {
+            let Some(previous) = Self::load_event_in_transaction(tx, &table, &event_id)? else {
+                return Ok(());
+            };
+            Self::delete_event_in_transaction(tx, &table, &event_id)?;
+            UndoRepository::record_in_transaction(
+                tx,
+                format!("Delete event \"{}\"", previous.title),
+                vec![Operation::DeleteEvent { table: table.clone(), id: event_id }],
+                vec![Operation::PutEvent { table, event: previous }],
+            )
+        }).await
+    }
+
+    /**
+     * Creates or replaces events imported from another calendar as a single undoable step
+     * 
+     * Imported events carry IDs derived from their source UIDs, so importing the same
+     * event again replaces the earlier copy instead of duplicating it.
+     * 
+     * @param {&str} table - Target table name
+     * @param {Vec<Event>} events - Events to write, with UID-derived IDs
+     * @param {&DbPool} pool - Database connection pool
+     * @returns {Result<(usize, usize, usize), anyhow::Error>} Counts of added, updated and unchanged events
+     * @throws {anyhow::Error} If table validation fails or database operation fails
+     */
+    pub async fn import(table: &str, events: Vec<Event>, pool: &DbPool) -> Result<(usize, usize, usize), anyhow::Error> {
+        validate_table_name(table)?;
+        let table = table.to_string();
+        unit_of_work(pool, move |tx| {
+            let (mut added, mut updated, mut unchanged) = (0, 0, 0);
+            let mut forward = Vec::new();
+            let mut inverse = Vec::new();
+            for event in events {
+                let previous = Self::load_event_in_transaction(tx, &table, &event.id)?;
+                match previous {
+                    Some(previous) if previous == event => {
+                        unchanged += 1;
+                        continue;
+                    }
+                    Some(previous) => {
+                        updated += 1;
+                        inverse.push(Operation::PutEvent { table: table.clone(), event: previous });
+                    }
+                    None => {
+                        added += 1;
+                        inverse.push(Operation::DeleteEvent { table: table.clone(), id: event.id.clone() });
+                    }
+                }
+                Self::save_event_in_transaction(tx, &table, &event)?;
+                forward.push(Operation::PutEvent { table: table.clone(), event });
+            }
+            if !forward.is_empty() {
+                let label = format!("Import {} event{} from calendar", forward.len(), if forward.len() == 1 { "" } else { "s" });
+                UndoRepository::record_in_transaction(tx, label, forward, inverse)?;
+            }
+            Ok((added, updated, unchanged))
+        }).await
+    }
+
+    /**
+     * Internal helper: Loads one event of the active plan within an existing transaction
+     * 
+     * @param {&Transaction} tx - Active database transaction
+     * @param {&str} table - Source table name
+     * @param {&str} id - Unique event identifier
+     * @returns {Result<Option<Event>, anyhow::Error>} The event, or None if it doesn't exist
+     * @throws {anyhow::Error} If SQL execution fails
+     */
+    fn load_event_in_transaction(tx: &Transaction, table: &str, id: &str) -> Result<Option<Event>, anyhow::Error> {
+        let statement = format!("SELECT id, title, start_time, end_time, day, professor, description FROM {table} WHERE plan_id = {ACTIVE_PLAN} AND id = ?1");
+        let event = tx.query_row(&statement, params![id], |row| {
+            Ok(Event {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                start_time: row.get(2)?,
+                end_time: row.get(3)?,
+                day: row.get(4)?,
+                professor: row.get(5)?,
+                description: row.get(6)?,
+            })
+        }).optional()?;
+        Ok(event)
+    }
+
+    /**
+     * Internal helper: Deletes an event of the active plan within an existing transaction
+     * 
+     * @param {&Transaction} tx - Active database transaction
+     * @param {&str} table - Target table name
+     * @param {&str} id - Unique event identifier
+     * @returns {Result<(), anyhow::Error>} Success or error
+     * @throws {anyhow::Error} If SQL execution fails
+     */
+    fn delete_event_in_transaction(tx: &Transaction, table: &str, id: &str) -> Result<(), anyhow::Error> {
+        tx.execute(&format!("DELETE FROM {table} WHERE plan_id = {ACTIVE_PLAN} AND id = ?1"), params![id])?;
+        Ok(())
     }
 
     /**
@@ -177,7 +368,7 @@ impl EventRepository {
      */
     fn save_event_in_transaction(tx: &Transaction, table: &str, event: &Event) -> Result<(), anyhow::Error> {
         let insert_statement = format!(
-            "INSERT OR REPLACE INTO {table} (id, title, start_time, end_time, day, professor, description) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"
+            "INSERT OR REPLACE INTO {table} (plan_id, id, title, start_time, end_time, day, professor, description) VALUES ({ACTIVE_PLAN}, ?1, ?2, ?3, ?4, ?5, ?6, ?7)"
         );
         tx.execute(&insert_statement, params![
             event.id, event.title, event.start_time, event.end_time, 
@@ -198,7 +389,7 @@ impl EventRepository {
      */
     fn update_event_in_transaction(tx: &Transaction, table: &str, event: &Event) -> Result<(), anyhow::Error> {
         let update_statement = format!(
-            "UPDATE {table} SET title = ?2, start_time = ?3, end_time = ?4, day = ?5, professor = ?6, description = ?7 WHERE id = ?1"
+            "UPDATE {table} SET title = ?2, start_time = ?3, end_time = ?4, day = ?5, professor = ?6, description = ?7 WHERE plan_id = {ACTIVE_PLAN} AND id = ?1"
         );
         let rows_affected = tx.execute(&update_statement, params![
             event.id, event.title, event.start_time, event.end_time, 
@@ -229,6 +420,8 @@ impl ClassRepository {
      * - Outer Vec: Different class groups/schedules
      * - Inner Vec: Individual classes within each group
      * - Generates composite IDs from course code, name, and sections
+     * - Refreshes the search index entries of the affected courses
+     * - Refreshes the query metadata of stored schedules whose sections changed
      * 
      * @param {&Vec<Vec<Class>>} classes - Nested array of class groups
      * @param {&DbPool} pool - Database connection pool
@@ -237,30 +430,48 @@ impl ClassRepository {
      */
     pub async fn save_sections_batch(classes: &[Vec<Class>], pool: &DbPool) -> Result<(), anyhow::Error> {
         let classes_clone = classes.to_owned();
-        let pool = pool.clone();
-        tokio::task::spawn_blocking(move || -> Result<(), anyhow::Error> {
-            let mut conn = pool.get()?;
-            let tx = conn.transaction()?;
-            
+        unit_of_work(pool, move |tx| {
+            let mut select = tx.prepare("SELECT data FROM classes WHERE id = ?1")?;
             let mut stmt = tx.prepare("INSERT OR REPLACE INTO classes (id, classname, data) VALUES (?, ?, ?)")?;
+            let mut classnames = Vec::new();
+            let mut changed_sections = Vec::new();
             
             for classes_group in classes_clone.iter() {
                 for class in classes_group {
-                    let json_data = serde_json::to_string(class)?;
-                    // Generate composite ID: course code + name + all sections
-                    let mut id = format!("{}{}", class.code, class.name);
-                    for section in &class.classes {
-                        id = format!("{}/{}", id, section.section);
-                    }
+                    let json_data = encode_blob(class)?;
                     let classname = format!("{}{}", class.code, class.name);
-                    stmt.execute(params![id, classname, json_data])?;
+                    let section_id = Self::section_id(class);
+                    let previous: Option<String> = select.query_row(params![section_id], |row| row.get(0)).optional()?;
+                    stmt.execute(params![section_id, classname, json_data])?;
+                    if previous.is_some_and(|previous| previous != json_data) {
+                        changed_sections.push(section_id);
+                    }
+                    if !classnames.contains(&classname) {
+                        classnames.push(classname);
+                    }
                 }
             }
             
             drop(stmt);
-            tx.commit()?;
-            Ok(())
-        }).await?
+            drop(select);
+            Self::reindex_courses_in_transaction(tx, &classnames)?;
+            ScheduleRepository::reindex_schedules_in_transaction(tx, &changed_sections)
+        }).await
+    }
+
+    /**
+     * Generates the composite cache ID of a section: course code + name + all sections
+     * Rescraping a section yields the same ID, so references to it stay valid
+     * 
+     * @param {&Class} class - Section to identify
+     * @returns {String} Composite ID (e.g., "CSC116/001/601")
+     */
+    pub fn section_id(class: &Class) -> String {
+        let mut id = format!("{}{}", class.code, class.name);
+        for section in &class.classes {
+            id = format!("{}/{}", id, section.section);
+        }
+        id
     }
 
     /**
@@ -278,14 +489,156 @@ impl ClassRepository {
             let mut stmt = conn.prepare("SELECT data FROM classes WHERE classname = ?1")?;
             let classes_iter = stmt.query_map(params![name], |row| {
                 let json_data: String = row.get(0)?;
-                serde_json::from_str(&json_data).map_err(|e| {
-                    rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
+                decode_blob(&json_data).map_err(|e| {
+                    rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, e.into())
                 })
             })?;
             let classes = classes_iter.collect::<Result<Vec<Class>, _>>()?;
             Ok(classes)
         }).await?
     }
+
+    /**
+     * Full-text search over cached courses, best matches first
+     * 
+     * Every word of the query must match one of the indexed fields (code, name,
+     * description, prerequisites or instructor names); words match as prefixes and
+     * are stemmed, so "learn" also finds "learning". Code and name matches rank
+     * highest, followed by instructors, prerequisites and description.
+     * 
+     * @param {String} query - Free-text query (e.g., "machine learning", "Smith")
+     * @param {Option<usize>} limit - Maximum number of results (defaults to DEFAULT_SEARCH_LIMIT)
+     * @param {&DbPool} pool - Database connection pool
+     * @returns {Result<Vec<CourseSearchResult>, anyhow::Error>} Ranked matches, empty for a blank query
+     * @throws {anyhow::Error} If database query fails
+     */
+    pub async fn search(query: String, limit: Option<usize>, pool: &DbPool) -> Result<Vec<CourseSearchResult>, anyhow::Error> {
+        let Some(fts_query) = Self::fts_query(&query) else {
+            return Ok(Vec::new());
+        };
+        let limit = limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+        let pool = pool.clone();
+        tokio::task::spawn_blocking(move || -> Result<Vec<CourseSearchResult>, anyhow::Error> {
+            let conn = pool.get()?;
+            let mut stmt = conn.prepare(
+                "SELECT classname, code, name, description, prerequisites, instructors,
+                        snippet(course_search, -1, '<mark>', '</mark>', '…', 12),
+                        bm25(course_search, 0.0, 10.0, 10.0, 1.0, 2.0, 5.0) AS score
+                 FROM course_search
+                 WHERE course_search MATCH ?1
+                 ORDER BY score
+                 LIMIT ?2"
+            )?;
+            let results = stmt.query_map(params![fts_query, limit as i64], |row| {
+                let instructors: String = row.get(5)?;
+                Ok(CourseSearchResult {
+                    classname: row.get(0)?,
+                    code: row.get(1)?,
+                    name: row.get(2)?,
+                    description: row.get(3)?,
+                    prerequisites: row.get(4)?,
+                    instructors: instructors.lines().map(str::to_string).collect(),
+                    snippet: row.get(6)?,
+                    // bm25 is lower-is-better; expose higher-is-better to callers
+                    score: -row.get::<_, f64>(7)?,
+                })
+            })?.collect::<Result<Vec<_>, _>>()?;
+            Ok(results)
+        }).await?
+    }
+
+    /**
+     * Internal helper: Rebuilds the search index entries of the given courses
+     * Courses no longer in the cache are removed from the index
+     * 
+     * @param {&Transaction} tx - Active database transaction
+     * @param {&[String]} classnames - Courses to reindex (code + name, e.g. "CSC116")
+     * @returns {Result<(), anyhow::Error>} Success or error
+     * @throws {anyhow::Error} If a cached row can't be decoded or SQL execution fails
+     */
+    pub(crate) fn reindex_courses_in_transaction(tx: &Transaction, classnames: &[String]) -> Result<(), anyhow::Error> {
+        let mut select = tx.prepare_cached("SELECT data FROM classes WHERE classname = ?1 ORDER BY rowid")?;
+        let mut delete = tx.prepare_cached("DELETE FROM course_search WHERE classname = ?1")?;
+        let mut insert = tx.prepare_cached(
+            "INSERT INTO course_search (classname, code, name, description, prerequisites, instructors) VALUES (?1, ?2, ?3, ?4, ?5, ?6)"
+        )?;
+
+        for classname in classnames {
+            delete.execute(params![classname])?;
+            let sections = select.query_map(params![classname], |row| row.get::<_, String>(0))?
+                .collect::<Result<Vec<_>, _>>()?
+                .iter()
+                .map(|json| decode_blob::<Class>(json))
+                .collect::<Result<Vec<_>, _>>()?;
+            let Some(first) = sections.first() else {
+                continue;
+            };
+
+            let mut instructors: Vec<&str> = Vec::new();
+            for block in sections.iter().flat_map(|section| &section.classes) {
+                let instructor = block.instructor.trim();
+                if !instructor.is_empty() && !instructors.contains(&instructor) {
+                    instructors.push(instructor);
+                }
+            }
+            let (prerequisites, description) = Self::split_description(&first.description);
+            insert.execute(params![classname, first.code, first.name, description, prerequisites, instructors.join("\n")])?;
+        }
+        Ok(())
+    }
+
+    /**
+     * Internal helper: Rebuilds the whole search index from the classes cache
+     * 
+     * @param {&Transaction} tx - Active database transaction
+     * @returns {Result<(), anyhow::Error>} Success or error
+     * @throws {anyhow::Error} If a cached row can't be decoded or SQL execution fails
+     */
+    pub(crate) fn rebuild_search_index_in_transaction(tx: &Transaction) -> Result<(), anyhow::Error> {
+        tx.execute("DELETE FROM course_search", [])?;
+        let classnames = {
+            let mut stmt = tx.prepare("SELECT DISTINCT classname FROM classes")?;
+            let classnames = stmt.query_map([], |row| row.get::<_, String>(0))?
+                .collect::<Result<Vec<_>, _>>()?;
+            classnames
+        };
+        Self::reindex_courses_in_transaction(tx, &classnames)
+    }
+
+    /**
+     * Internal helper: Splits a scraped description into prerequisites and course description
+     * Scraped descriptions look like "Units: 3 | Prerequisites: ... | Description: ..."
+     * 
+     * @param {&str} description - Scraped description text
+     * @returns {(&str, &str)} Prerequisites and course description (the full text if unstructured)
+     */
+    fn split_description(description: &str) -> (&str, &str) {
+        let prerequisites = description
+            .split_once("Prerequisites: ")
+            .map(|(_, rest)| rest.split(" | Description: ").next().unwrap_or(rest))
+            .unwrap_or("");
+        let text = description
+            .split_once("Description: ")
+            .map(|(_, rest)| rest)
+            .unwrap_or(description);
+        (prerequisites.trim(), text.trim())
+    }
+
+    /**
+     * Internal helper: Turns free text into an FTS5 query
+     * Each word becomes a quoted prefix term, so user input can't inject FTS syntax
+     * 
+     * @param {&str} query - Free-text query
+     * @returns {Option<String>} FTS5 query, or None if the query has no searchable words
+     */
+    fn fts_query(query: &str) -> Option<String> {
+        let terms: Vec<String> = query
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(|word| format!("\"{word}\"*"))
+            .collect();
+        (!terms.is_empty()).then(|| terms.join(" "))
+    }
 }
 
 // === CLASS PARAMETER REPOSITORY ===
@@ -298,7 +651,7 @@ pub struct ClassParamRepository;
 
 impl ClassParamRepository {
     /**
-     * Retrieves all class parameters from the database
+     * Retrieves all class parameters of the active plan
      * 
      * @param {&DbPool} pool - Database connection pool
      * @returns {Result<Vec<ClassParam>, anyhow::Error>} Array of class parameters or error
@@ -308,7 +661,7 @@ impl ClassParamRepository {
         let pool = pool.clone();
         tokio::task::spawn_blocking(move || -> Result<Vec<ClassParam>, anyhow::Error> {
             let conn = pool.get()?;
-            let mut stmt = conn.prepare("SELECT data FROM class_parameters")?;
+            let mut stmt = conn.prepare(&format!("SELECT data FROM class_parameters WHERE plan_id = {ACTIVE_PLAN} ORDER BY rowid"))?;
             let rows = stmt.query_map([], |row| row.get(0))?;
             let mut result = Vec::new();
             for row in rows {
@@ -321,7 +674,7 @@ impl ClassParamRepository {
 
     /**
      * Updates or inserts a class parameter using upsert behavior
-     * Uses transaction for consistency even though it's a single operation
+     * Edited courses keep their position in the list; the change is recorded for undo
      * 
      * @param {ClassParam} class - Class parameter object to save
      * @param {&DbPool} pool - Database connection pool
@@ -329,23 +682,85 @@ impl ClassParamRepository {
      * @throws {anyhow::Error} If JSON serialization fails or database operation fails
      */
     pub async fn update(class: ClassParam, pool: &DbPool) -> Result<(), anyhow::Error> {
-        let pool = pool.clone();
-        tokio::task::spawn_blocking(move || -> Result<(), anyhow::Error> {
-            let mut conn = pool.get()?;
-            let tx = conn.transaction()?;
-            
-            let id = class.id.clone();
-            let json_data = serde_json::to_string(&class)?;
-            tx.execute("INSERT OR REPLACE INTO class_parameters (id, data) VALUES (?, ?)", params![id, json_data])?;
-            
-            tx.commit()?;
-            Ok(())
-        }).await?
+        unit_of_work(pool, move |tx| {
+            let previous = Self::load_in_transaction(tx, &class.id)?;
+            if let Some((previous_class, _)) = &previous {
+                if serde_json::to_string(previous_class)? == serde_json::to_string(&class)? {
+                    return Ok(());
+                }
+            }
+
+            let position = Self::put_in_transaction(tx, &class, None)?;
+            let (label, inverse) = match previous {
+                Some((previous_class, _)) => (
+                    format!("Edit course {} {}", class.code, class.name),
+                    Operation::PutClassParam { class: previous_class, position: Some(position) },
+                ),
+                None => (
+                    format!("Add course {} {}", class.code, class.name),
+                    Operation::DeleteClassParam { id: class.id.clone() },
+                ),
+            };
+            UndoRepository::record_in_transaction(
+                tx,
+                label,
+                vec![Operation::PutClassParam { class, position: Some(position) }],
+                vec![inverse],
+            )
+        }).await
+    }
+
+    /**
+     * Appends imported class parameters as a single undoable step
+     * 
+     * Courses already in the list with the same section and instructor are left alone,
+     * as are repeats within the import itself.
+     * 
+     * @param {Vec<ClassParam>} classes - Class parameters to add, with fresh IDs
+     * @param {&DbPool} pool - Database connection pool
+     * @returns {Result<(usize, usize), anyhow::Error>} Counts of added and unchanged courses
+     * @throws {anyhow::Error} If JSON serialization fails or database operation fails
+     */
+    pub async fn import(classes: Vec<ClassParam>, pool: &DbPool) -> Result<(usize, usize), anyhow::Error> {
+        unit_of_work(pool, move |tx| {
+            let mut listed: Vec<ClassParam> = {
+                let mut stmt = tx.prepare(&format!("SELECT data FROM class_parameters WHERE plan_id = {ACTIVE_PLAN}"))?;
+                let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+                rows.map(|json| Ok(serde_json::from_str(&json?)?)).collect::<Result<_, anyhow::Error>>()?
+            };
+
+            let mut unchanged = 0;
+            let mut forward = Vec::new();
+            let mut inverse = Vec::new();
+            for class in classes {
+                let is_listed = listed.iter().any(|existing| {
+                    existing.code == class.code
+                        && existing.name == class.name
+                        && existing.section == class.section
+                        && existing.instructor.eq_ignore_ascii_case(&class.instructor)
+                });
+                if is_listed {
+                    unchanged += 1;
+                    continue;
+                }
+                let position = Self::put_in_transaction(tx, &class, None)?;
+                inverse.push(Operation::DeleteClassParam { id: class.id.clone() });
+                listed.push(class.clone());
+                forward.push(Operation::PutClassParam { class, position: Some(position) });
+            }
+
+            let added = forward.len();
+            if added > 0 {
+                let label = format!("Import {} course{}", added, if added == 1 { "" } else { "s" });
+                UndoRepository::record_in_transaction(tx, label, forward, inverse)?;
+            }
+            Ok((added, unchanged))
+        }).await
     }
 
     /**
      * Removes a class parameter by ID
-     * Simplified operation - single DELETE doesn't require transaction
+     * The removed course is kept in the operation log so the removal can be undone
      * 
      * @param {String} id - Unique class parameter identifier
      * @param {&DbPool} pool - Database connection pool
@@ -353,12 +768,89 @@ impl ClassParamRepository {
      * @throws {anyhow::Error} If database operation fails
      */
     pub async fn remove(id: String, pool: &DbPool) -> Result<(), anyhow::Error> {
-        let pool = pool.clone();
-        tokio::task::spawn_blocking(move || -> Result<(), anyhow::Error> {
-            let conn = pool.get()?;
-            conn.execute("DELETE FROM class_parameters WHERE id=?1", params![id])?;
-            Ok(())
-        }).await?
+        unit_of_work(pool, move |tx| {
+            let Some((previous, position)) = Self::load_in_transaction(tx, &id)? else {
+                return Ok(());
+            };
+            Self::delete_in_transaction(tx, &id)?;
+            UndoRepository::record_in_transaction(
+                tx,
+                format!("Remove course {} {}", previous.code, previous.name),
+                vec![Operation::DeleteClassParam { id }],
+                vec![Operation::PutClassParam { class: previous, position: Some(position) }],
+            )
+        }).await
+    }
+
+    /**
+     * Internal helper: Loads a class parameter and its list position within an existing transaction
+     * 
+     * @param {&Transaction} tx - Active database transaction
+     * @param {&str} id - Unique class parameter identifier
+     * @returns {Result<Option<(ClassParam, i64)>, anyhow::Error>} Class parameter and rowid, or None if missing
+     * @throws {anyhow::Error} If SQL execution fails or JSON deserialization fails
+     */
+    fn load_in_transaction(tx: &Transaction, id: &str) -> Result<Option<(ClassParam, i64)>, anyhow::Error> {
+        let row: Option<(String, i64)> = tx.query_row(
+            &format!("SELECT data, rowid FROM class_parameters WHERE plan_id = {ACTIVE_PLAN} AND id = ?1"),
+            params![id],
+            |row| Ok((row.get(0)?, row.get(1)?))
+        ).optional()?;
+        match row {
+            Some((json, position)) => Ok(Some((serde_json::from_str(&json)?, position))),
+            None => Ok(None),
+        }
+    }
+
+    /**
+     * Internal helper: Updates a class parameter in place or inserts it within an existing transaction
+     * 
+     * Courses are listed in rowid order, so an existing course is updated without
+     * changing its rowid. A new course is inserted at the requested rowid when it is
+     * free (restoring a removed course to its old position), otherwise at the end.
+     * 
+     * @param {&Transaction} tx - Active database transaction
+     * @param {&ClassParam} class - Class parameter object to save
+     * @param {Option<i64>} position - Preferred rowid for a newly inserted course
+     * @returns {Result<i64, anyhow::Error>} Rowid of the stored course
+     * @throws {anyhow::Error} If JSON serialization fails or SQL execution fails
+     */
+    fn put_in_transaction(tx: &Transaction, class: &ClassParam, position: Option<i64>) -> Result<i64, anyhow::Error> {
+        let json_data = serde_json::to_string(class)?;
+        let updated = tx.execute(
+            &format!("UPDATE class_parameters SET data = ?2 WHERE plan_id = {ACTIVE_PLAN} AND id = ?1"),
+            params![class.id, json_data]
+        )?;
+        if updated == 0 {
+            tx.execute(
+                &format!(
+                    "INSERT INTO class_parameters (rowid, plan_id, id, data) VALUES (
+                        CASE WHEN ?3 IS NULL OR EXISTS(SELECT 1 FROM class_parameters WHERE rowid = ?3) THEN NULL ELSE ?3 END,
+                        {ACTIVE_PLAN}, ?1, ?2
+                    )"
+                ),
+                params![class.id, json_data, position]
+            )?;
+        }
+        let position = tx.query_row(
+            &format!("SELECT rowid FROM class_parameters WHERE plan_id = {ACTIVE_PLAN} AND id = ?1"),
+            params![class.id],
+            |row| row.get(0)
+        )?;
+        Ok(position)
+    }
+
+    /**
+     * Internal helper: Deletes a class parameter of the active plan within an existing transaction
+     * 
+     * @param {&Transaction} tx - Active database transaction
+     * @param {&str} id - Unique class parameter identifier
+     * @returns {Result<(), anyhow::Error>} Success or error
+     * @throws {anyhow::Error} If SQL execution fails
+     */
+    fn delete_in_transaction(tx: &Transaction, id: &str) -> Result<(), anyhow::Error> {
+        tx.execute(&format!("DELETE FROM class_parameters WHERE plan_id = {ACTIVE_PLAN} AND id = ?1"), params![id])?;
+        Ok(())
     }
 }
 
@@ -372,143 +864,1361 @@ pub struct ScheduleRepository;
 
 impl ScheduleRepository {
     /**
-     * Retrieves all schedules from the specified table
+     * Retrieves all schedules of the active plan from the specified table in insertion order
+     * 
+     * Schedules are stored as rows of section references, so each schedule is rebuilt
+     * from the classes cache. Schedules referencing a section missing from the cache are
+     * skipped with a warning rather than failing the whole query.
      * 
      * @param {&str} table - Source table name ("schedules" or "favorites")
      * @param {&DbPool} pool - Database connection pool
-     * @returns {Result<Vec<Vec<Class>>, anyhow::Error>} Array of schedule combinations or error
+     * @returns {Result<Vec<StoredSchedule>, anyhow::Error>} Array of schedules with their IDs or error
      * @throws {anyhow::Error} If table validation fails, database query fails, or JSON deserialization fails
      */
-    pub async fn get_all(table: &str, pool: &DbPool) -> Result<Vec<Vec<Class>>, anyhow::Error> {
+    pub async fn get_all(table: &str, pool: &DbPool) -> Result<Vec<StoredSchedule>, anyhow::Error> {
         validate_table_name(table)?;
         let table = table.to_string();
         let pool = pool.clone();
-        tokio::task::spawn_blocking(move || -> Result<Vec<Vec<Class>>, anyhow::Error> {
+        tokio::task::spawn_blocking(move || -> Result<Vec<StoredSchedule>, anyhow::Error> {
             let conn = pool.get()?;
-            let prepared_statement = format!("SELECT data FROM {table}");
-            let mut stmt = conn.prepare(&prepared_statement)?;
-            let rows = stmt.query_map([], |row| row.get(0))?;
-            let mut result = Vec::new();
-            for row in rows {
-                let data: String = row?;
-                result.push(serde_json::from_str(&data)?);
-            }
-            Ok(result)
+            let plan_id: String = conn.query_row(&format!("SELECT {ACTIVE_PLAN}"), [], |row| row.get(0))?;
+            Self::load_for_plan(&conn, &table, &plan_id)
         }).await?
     }
 
     /**
-     * Saves multiple schedules in batch with atomic transaction
+     * Retrieves one page of the active plan's schedules, filtered and sorted in SQL
      * 
-     * This function performs a complete replacement of the schedules table:
-     * 1. Deletes all existing schedules
-     * 2. Inserts new schedules in batches for performance
-     * 3. Uses transaction to ensure atomicity
+     * Filters and sort keys use the schedule_metadata, schedule_instructors and
+     * schedule_sections indexes, so only the requested page is rebuilt from the
+     * classes cache and sent to the frontend. Schedules referencing a section missing
+     * from the cache are left out of both the page and the total.
      * 
-     * @param {Vec<String>} ids - Array of unique schedule identifiers
-     * @param {&Vec<Vec<Class>>} schedules - Array of schedule combinations
+     * @param {ScheduleQuery} query - Table, page window, sort key and filter
      * @param {&DbPool} pool - Database connection pool
-     * @returns {Result<(), anyhow::Error>} Success or error
-     * @throws {anyhow::Error} If JSON serialization fails or database operation fails
+     * @returns {Result<SchedulePage, anyhow::Error>} Requested page and the total number of matches
+     * @throws {anyhow::Error} If table validation fails, database query fails, or JSON deserialization fails
+     * 
+     * @example
+     * // Second page of schedules without Friday classes, fewest days on campus first
+     * let query = ScheduleQuery {
+     *     table: "schedules".to_string(), offset: 50, limit: 50,
+     *     sort: Some(ScheduleSort::DaysOnCampus), descending: false,
+     *     filter: ScheduleFilter { free_days: [false, false, false, false, true], ..Default::default() },
+     * };
      */
-    pub async fn save_batch(ids: Vec<String>, schedules: &[Vec<Class>], pool: &DbPool) -> Result<(), anyhow::Error> {
-        let schedules_clone = schedules.to_owned();
-        let ids_clone = ids.clone();
+    pub async fn query(query: ScheduleQuery, pool: &DbPool) -> Result<SchedulePage, anyhow::Error> {
+        if !SCHEDULE_TABLES.contains(&query.table.as_str()) {
+            return Err(anyhow!("Invalid schedule table: {}", query.table));
+        }
         let pool = pool.clone();
-        tokio::task::spawn_blocking(move || -> Result<(), anyhow::Error> {
-            let mut conn = pool.get()?;
-            let tx = conn.transaction()?;
-            
-            // Clear existing schedules
-            tx.execute("DELETE FROM schedules", [])?;
-            
-            // Insert new schedules in batches
-            let mut stmt = tx.prepare("INSERT INTO schedules (id, data) VALUES (?1, ?2)")?;
-            for chunk_data in ids_clone.chunks(BATCH_SIZE).zip(schedules_clone.chunks(BATCH_SIZE)) {
-                let (id_chunk, schedule_chunk) = chunk_data;
-                for (id, schedule) in id_chunk.iter().zip(schedule_chunk.iter()) {
-                    let json_data = serde_json::to_string(schedule)?;
-                    stmt.execute(params![id, json_data])?;
-                }
+        tokio::task::spawn_blocking(move || -> Result<SchedulePage, anyhow::Error> {
+            let conn = pool.get()?;
+            let table = &query.table;
+
+            let mut conditions = vec![
+                format!("s.plan_id = {ACTIVE_PLAN}"),
+                "NOT EXISTS(SELECT 1 FROM schedule_sections ss LEFT JOIN classes c ON c.id = ss.class_id
+                            WHERE ss.schedule_id = s.id AND c.id IS NULL)".to_string(),
+            ];
+            let mut values: Vec<Value> = Vec::new();
+            for section in &query.filter.include_sections {
+                values.push(Value::Text(section.clone()));
+                conditions.push(format!(
+                    "EXISTS(SELECT 1 FROM schedule_sections ss WHERE ss.schedule_id = s.id AND ss.class_id = ?{})",
+                    values.len()
+                ));
             }
-            
-            drop(stmt);
-            tx.commit()?;
-            Ok(())
+            for instructor in query.filter.exclude_instructors.iter().filter(|name| !name.trim().is_empty()) {
+                values.push(Value::Text(instructor.trim().to_string()));
+                conditions.push(format!(
+                    "NOT EXISTS(SELECT 1 FROM schedule_instructors si WHERE si.schedule_id = s.id AND si.instructor = ?{})",
+                    values.len()
+                ));
+            }
+            if !query.filter.ids.is_empty() {
+                let placeholders: Vec<String> = query.filter.ids.iter().map(|id| {
+                    values.push(Value::Text(id.clone()));
+                    format!("?{}", values.len())
+                }).collect();
+                conditions.push(format!("s.id IN ({})", placeholders.join(", ")));
+            }
+            let free_mask = day_mask(&query.filter.free_days);
+            if free_mask != 0 {
+                values.push(Value::Integer(free_mask));
+                conditions.push(format!("m.day_mask & ?{} = 0", values.len()));
+            }
+            let from = format!(
+                "FROM {table} s JOIN schedule_metadata m ON m.schedule_id = s.id WHERE {}",
+                conditions.join(" AND ")
+            );
+
+            let total: i64 = conn.query_row(&format!("SELECT COUNT(*) {from}"), params_from_iter(&values), |row| row.get(0))?;
+
+            let direction = if query.descending { "DESC" } else { "ASC" };
+            let order = match query.sort {
+                None => format!("s.rowid {direction}"),
+                // Schedules without timed meetings have no start time and always come last
+                Some(ScheduleSort::StartTime) => format!("m.earliest_start IS NULL, m.earliest_start {direction}, s.rowid"),
+                Some(ScheduleSort::DaysOnCampus) => format!("m.campus_days {direction}, s.rowid"),
+                Some(ScheduleSort::Score) => format!("m.penalty {direction}, s.rowid"),
+            };
+            values.push(Value::Integer(query.limit as i64));
+            values.push(Value::Integer(query.offset as i64));
+            let page_ids: Vec<String> = {
+                let mut stmt = conn.prepare(&format!(
+                    "SELECT s.id {from} ORDER BY {order} LIMIT ?{} OFFSET ?{}",
+                    values.len() - 1,
+                    values.len()
+                ))?;
+                let ids = stmt.query_map(params_from_iter(&values), |row| row.get(0))?
+                    .collect::<Result<Vec<_>, _>>()?;
+                ids
+            };
+
+            let mut details = if table == "favorites" {
+                let plan_id: String = conn.query_row(&format!("SELECT {ACTIVE_PLAN}"), [], |row| row.get(0))?;
+                FavoriteRepository::load_details_for_plan(&conn, &plan_id)?
+            } else {
+                HashMap::new()
+            };
+            let mut schedules = Vec::with_capacity(page_ids.len());
+            for id in page_ids {
+                let classes = Self::load_schedule(&conn, &id)?;
+                let details = details.remove(&id);
+                schedules.push(StoredSchedule { id, classes, details });
+            }
+            Ok(SchedulePage { schedules, total: total as usize })
         }).await?
     }
 
     /**
-     * Deletes a specific schedule by ID
-     * Simplified operation - single DELETE doesn't require transaction
+     * Internal helper: Rebuilds the schedules of one plan from a schedule table
      * 
-     * @param {String} id - Unique schedule identifier
-     * @param {&DbPool} pool - Database connection pool
-     * @returns {Result<(), anyhow::Error>} Success or error
-     * @throws {anyhow::Error} If database operation fails
+     * @param {&Connection} conn - Open database connection
+     * @param {&str} table - Source table name ("schedules" or "favorites"), already validated
+     * @param {&str} plan_id - Plan owning the schedules
+     * @returns {Result<Vec<StoredSchedule>, anyhow::Error>} Array of schedules with their IDs in insertion order
+     * @throws {anyhow::Error} If database query fails or JSON deserialization fails
      */
-    pub async fn delete(id: String, pool: &DbPool) -> Result<(), anyhow::Error> {
-        let pool = pool.clone();
-        tokio::task::spawn_blocking(move || -> Result<(), anyhow::Error> {
-            let conn = pool.get()?;
-            conn.execute("DELETE FROM schedules WHERE id = ?1", params![id])?;
-            Ok(())
-        }).await?
-    }
-}
+    pub(crate) fn load_for_plan(conn: &Connection, table: &str, plan_id: &str) -> Result<Vec<StoredSchedule>, anyhow::Error> {
+        let prepared_statement = format!(
+            "SELECT s.id, c.data FROM {table} s
+             JOIN schedule_sections ss ON ss.schedule_id = s.id
+             LEFT JOIN classes c ON c.id = ss.class_id
+             WHERE s.plan_id = ?1
+             ORDER BY s.rowid, ss.position"
+        );
+        let mut stmt = conn.prepare(&prepared_statement)?;
+        let mut rows = stmt.query(params![plan_id])?;
 
-// === FAVORITES REPOSITORY ===
+        let mut result: Vec<StoredSchedule> = Vec::new();
+        let mut incomplete: Vec<String> = Vec::new();
+        while let Some(row) = rows.next()? {
+            let id: String = row.get(0)?;
+            let data: Option<String> = row.get(1)?;
 
-/**
- * Repository for managing user-favorited schedules
- * Provides toggle functionality for adding/removing favorites
- */
-pub struct FavoriteRepository;
+            if result.last().is_none_or(|schedule| schedule.id != id) {
+                result.push(StoredSchedule { id: id.clone(), classes: Vec::new(), details: None });
+            }
+            match data {
+                Some(json) => result.last_mut().unwrap().classes.push(decode_blob(&json)?),
+                None => incomplete.push(id),
+            }
+        }
+
+        if !incomplete.is_empty() {
+            eprintln!("Warning: Skipping {} schedule(s) in {table} with sections missing from the cache", incomplete.len());
+            result.retain(|schedule| !incomplete.contains(&schedule.id));
+        }
+        if table == "favorites" {
+            let mut details = FavoriteRepository::load_details_for_plan(conn, plan_id)?;
+            for schedule in &mut result {
+                schedule.details = details.remove(&schedule.id);
+            }
+        }
+        Ok(result)
+    }
 
-impl FavoriteRepository {
+    /**
+     * Saves multiple schedules in batch within an existing transaction
+     * 
+     * This function performs a complete replacement of the active plan's schedules:
+     * 1. Deletes the plan's existing schedules
+     * 2. Inserts new schedules in batches for performance (duplicates collapse onto one ID)
+     * 3. Removes section rows no longer referenced by schedules or favorites
+     * 4. Clears the pin if the pinned schedule was not regenerated
+     * 
+     * Replaced schedules stay restorable when their run was recorded in the generation
+     * history, since recorded runs keep their section rows alive.
+     * 
+     * @param {&Transaction} tx - Active database transaction (see `unit_of_work`)
+     * @param {&[Vec<Class>]} schedules - Array of schedule combinations
+     * @param {&ScheduleConstraints} constraints - Constraints the schedules are scored against
+     * @returns {Result<Vec<String>, anyhow::Error>} Content-hash ID of each schedule, in input order
+     * @throws {anyhow::Error} If JSON serialization fails or SQL execution fails
+     */
+    pub(crate) fn save_batch_in_transaction(tx: &Transaction, schedules: &[Vec<Class>], constraints: &ScheduleConstraints) -> Result<Vec<String>, anyhow::Error> {
+        // Clear existing schedules
+        tx.execute(&format!("DELETE FROM schedules WHERE plan_id = {ACTIVE_PLAN}"), [])?;
+        
+        // Insert new schedules in batches
+        let mut ids = Vec::with_capacity(schedules.len());
+        for schedule_chunk in schedules.chunks(BATCH_SIZE) {
+            for schedule in schedule_chunk {
+                ids.push(Self::insert_schedule_in_transaction(tx, "schedules", schedule, constraints)?);
+            }
+        }
+        
+        Self::remove_orphaned_sections(tx)?;
+        SystemRepository::clear_stale_pin_in_transaction(tx)?;
+        Ok(ids)
+    }
+
+    /**
+     * Deletes a specific schedule of the active plan within an existing transaction
+     * Section rows are kept if the schedule is still favorited
+     * 
+     * @param {&Transaction} tx - Active database transaction (see `unit_of_work`)
+     * @param {&str} id - Content-hash schedule identifier
+     * @returns {Result<(), anyhow::Error>} Success or error
+     * @throws {anyhow::Error} If SQL execution fails
+     */
+    pub(crate) fn delete_in_transaction(tx: &Transaction, id: &str) -> Result<(), anyhow::Error> {
+        tx.execute(&format!("DELETE FROM schedules WHERE plan_id = {ACTIVE_PLAN} AND id = ?1"), params![id])?;
+        Self::remove_orphaned_sections(tx)?;
+        SystemRepository::clear_stale_pin_in_transaction(tx)?;
+        Ok(())
+    }
+
+    /**
+     * Computes the stable content-hash ID of a schedule
+     * 
+     * The ID is a 64-bit FNV-1a hash of the sorted section IDs, so the same set of
+     * sections always maps to the same ID regardless of order, run or rescrape.
+     * 
+     * @param {&[Class]} schedule - Schedule to identify
+     * @returns {String} 16-character hexadecimal ID
+     */
+    pub fn schedule_id(schedule: &[Class]) -> String {
+        let mut section_ids: Vec<String> = schedule.iter().map(ClassRepository::section_id).collect();
+        section_ids.sort();
+        format!("{:016x}", stable_hash(section_ids.join("\n").as_bytes()))
+    }
+
+    /**
+     * Internal helper: Stores one schedule in the active plan within an existing transaction
+     * 
+     * @param {&Transaction} tx - Active database transaction
+     * @param {&str} table - Target table name ("schedules" or "favorites")
+     * @param {&[Class]} schedule - Schedule to store
+     * @param {&ScheduleConstraints} constraints - Constraints the schedule is scored against
+     * @returns {Result<String, anyhow::Error>} Content-hash ID of the schedule
+     * @throws {anyhow::Error} If JSON serialization fails or SQL execution fails
+     */
+    pub(crate) fn insert_schedule_in_transaction(tx: &Transaction, table: &str, schedule: &[Class], constraints: &ScheduleConstraints) -> Result<String, anyhow::Error> {
+        let id = Self::insert_sections_in_transaction(tx, schedule)?;
+        Self::index_schedule_in_transaction(tx, &id, schedule, constraints)?;
+        tx.execute(&format!("INSERT OR IGNORE INTO {table} (plan_id, id) VALUES ({ACTIVE_PLAN}, ?1)"), params![id])?;
+        Ok(id)
+    }
+
+    /**
+     * Internal helper: Stores the section references of a schedule within an existing transaction
+     * 
+     * Makes sure every section exists in the classes cache and writes the section
+     * references for the schedule's content hash. Section rows are shared by every
+     * plan and table referencing the same schedule.
+     * 
+     * @param {&Transaction} tx - Active database transaction
+     * @param {&[Class]} schedule - Schedule to store
+     * @returns {Result<String, anyhow::Error>} Content-hash ID of the schedule
+     * @throws {anyhow::Error} If JSON serialization fails or SQL execution fails
+     */
+    pub(crate) fn insert_sections_in_transaction(tx: &Transaction, schedule: &[Class]) -> Result<String, anyhow::Error> {
+        let id = Self::schedule_id(schedule);
+        let mut class_stmt = tx.prepare_cached("INSERT OR IGNORE INTO classes (id, classname, data) VALUES (?1, ?2, ?3)")?;
+        let mut section_stmt = tx.prepare_cached("INSERT OR IGNORE INTO schedule_sections (schedule_id, position, class_id) VALUES (?1, ?2, ?3)")?;
+
+        for (position, class) in schedule.iter().enumerate() {
+            let section_id = ClassRepository::section_id(class);
+            class_stmt.execute(params![section_id, format!("{}{}", class.code, class.name), encode_blob(class)?])?;
+            section_stmt.execute(params![id, position as i64, section_id])?;
+        }
+        Ok(id)
+    }
+
+    /**
+     * Internal helper: Deletes section references of schedules that are neither
+     * generated results, favorites nor recorded runs in any plan
+     * 
+     * @param {&Transaction} tx - Active database transaction
+     * @returns {Result<(), anyhow::Error>} Success or error
+     * @throws {anyhow::Error} If SQL execution fails
+     */
+    pub(crate) fn remove_orphaned_sections(tx: &Transaction) -> Result<(), anyhow::Error> {
+        tx.execute(
+            "DELETE FROM schedule_sections WHERE schedule_id NOT IN (
+                SELECT id FROM schedules UNION SELECT id FROM favorites UNION SELECT schedule_id FROM run_schedules
+            )",
+            []
+        )?;
+        tx.execute("DELETE FROM schedule_metadata WHERE schedule_id NOT IN (SELECT schedule_id FROM schedule_sections)", [])?;
+        tx.execute("DELETE FROM schedule_instructors WHERE schedule_id NOT IN (SELECT schedule_id FROM schedule_sections)", [])?;
+        Ok(())
+    }
+
+    /**
+     * Internal helper: Stores the query metadata of a schedule within an existing transaction
+     * 
+     * Records the earliest start, latest end, weekdays with classes and soft-constraint
+     * penalty, plus every instructor, so schedule queries can filter and sort without
+     * decoding sections. Like section rows, metadata is shared by every plan and table
+     * referencing the schedule, so the penalty reflects the constraints it was last saved
+     * with; existing metadata for the ID is replaced, since cached section times can change.
+     * 
+     * @param {&Transaction} tx - Active database transaction
+     * @param {&str} id - Content-hash schedule identifier
+     * @param {&[Class]} schedule - Schedule to index
+     * @param {&ScheduleConstraints} constraints - Constraints the schedule is scored against
+     * @returns {Result<(), anyhow::Error>} Success or error
+     * @throws {anyhow::Error} If SQL execution fails
+     */
+    pub(crate) fn index_schedule_in_transaction(tx: &Transaction, id: &str, schedule: &[Class], constraints: &ScheduleConstraints) -> Result<(), anyhow::Error> {
+        let mut meeting_days = [false; 5];
+        let mut earliest_start: Option<i32> = None;
+        let mut latest_end: Option<i32> = None;
+        for block in schedule.iter().flat_map(|class| &class.classes) {
+            for (day, &((start, end), meets)) in block.days.iter().enumerate() {
+                if meets && start > 0 && end > 0 {
+                    meeting_days[day] = true;
+                    earliest_start = Some(earliest_start.map_or(start, |earliest| earliest.min(start)));
+                    latest_end = Some(latest_end.map_or(end, |latest| latest.max(end)));
+                }
+            }
+        }
+        let campus_days = meeting_days.iter().filter(|&&meets| meets).count() as i64;
+
+        tx.execute(
+            "INSERT OR REPLACE INTO schedule_metadata (schedule_id, earliest_start, latest_end, campus_days, day_mask, penalty)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![id, earliest_start, latest_end, campus_days, day_mask(&meeting_days), penalty(schedule, constraints)]
+        )?;
+        tx.execute("DELETE FROM schedule_instructors WHERE schedule_id = ?1", params![id])?;
+        let mut instructor_stmt = tx.prepare_cached("INSERT OR IGNORE INTO schedule_instructors (schedule_id, instructor) VALUES (?1, ?2)")?;
+        for block in schedule.iter().flat_map(|class| &class.classes) {
+            let instructor = block.instructor.trim();
+            if !instructor.is_empty() {
+                instructor_stmt.execute(params![id, instructor])?;
+            }
+        }
+        Ok(())
+    }
+
+    /**
+     * Internal helper: Recomputes the query metadata of every schedule using the given sections
+     * 
+     * Metadata is derived from section times and instructors, so it must be refreshed when
+     * a rescrape replaces a cached section. Schedules with other sections missing from the
+     * cache are left without metadata; queries exclude them either way. Penalties are
+     * recomputed against the active plan's last generation constraints.
+     * 
+     * @param {&Transaction} tx - Active database transaction
+     * @param {&[String]} class_ids - Cache IDs of the sections that changed
+     * @returns {Result<(), anyhow::Error>} Success or error
+     * @throws {anyhow::Error} If SQL execution fails or a cached row can't be decoded
+     */
+    pub(crate) fn reindex_schedules_in_transaction(tx: &Transaction, class_ids: &[String]) -> Result<(), anyhow::Error> {
+        let mut schedule_ids: HashSet<String> = HashSet::new();
+        {
+            let mut stmt = tx.prepare_cached("SELECT DISTINCT schedule_id FROM schedule_sections WHERE class_id = ?1")?;
+            for class_id in class_ids {
+                for id in stmt.query_map(params![class_id], |row| row.get::<_, String>(0))? {
+                    schedule_ids.insert(id?);
+                }
+            }
+        }
+
+        let constraints = GenerationRepository::last_constraints_in_transaction(tx)?;
+        let mut incomplete_stmt = tx.prepare_cached(
+            "SELECT EXISTS(SELECT 1 FROM schedule_sections ss LEFT JOIN classes c ON c.id = ss.class_id
+                           WHERE ss.schedule_id = ?1 AND c.id IS NULL)"
+        )?;
+        for id in schedule_ids {
+            let incomplete: bool = incomplete_stmt.query_row(params![id], |row| row.get(0))?;
+            if incomplete {
+                tx.execute("DELETE FROM schedule_metadata WHERE schedule_id = ?1", params![id])?;
+                tx.execute("DELETE FROM schedule_instructors WHERE schedule_id = ?1", params![id])?;
+            } else {
+                let schedule = Self::load_schedule(tx, &id)?;
+                Self::index_schedule_in_transaction(tx, &id, &schedule, &constraints)?;
+            }
+        }
+        Ok(())
+    }
+
+    /**
+     * Internal helper: Recomputes the penalty of every schedule of the active plan
+     * Used when the plan's schedules are replaced without being re-saved, e.g. by restoring a run
+     * 
+     * @param {&Transaction} tx - Active database transaction
+     * @param {&ScheduleConstraints} constraints - Constraints the schedules are scored against
+     * @returns {Result<(), anyhow::Error>} Success or error
+     * @throws {anyhow::Error} If SQL execution fails or a cached row can't be decoded
+     */
+    pub(crate) fn rescore_in_transaction(tx: &Transaction, constraints: &ScheduleConstraints) -> Result<(), anyhow::Error> {
+        let ids: Vec<String> = {
+            let mut stmt = tx.prepare(&format!(
+                "SELECT s.id FROM schedules s JOIN schedule_metadata m ON m.schedule_id = s.id WHERE s.plan_id = {ACTIVE_PLAN}"
+            ))?;
+            let ids = stmt.query_map([], |row| row.get(0))?.collect::<Result<_, _>>()?;
+            ids
+        };
+        let mut update_stmt = tx.prepare_cached("UPDATE schedule_metadata SET penalty = ?2 WHERE schedule_id = ?1")?;
+        for id in ids {
+            let schedule = Self::load_schedule(tx, &id)?;
+            update_stmt.execute(params![id, penalty(&schedule, constraints)])?;
+        }
+        Ok(())
+    }
+
+    /**
+     * Internal helper: Rebuilds one schedule from its section references
+     * 
+     * @param {&Connection} conn - Open database connection or transaction
+     * @param {&str} id - Content-hash schedule identifier
+     * @returns {Result<Vec<Class>, anyhow::Error>} The schedule's sections, empty if the ID is unknown
+     * @throws {anyhow::Error} If a section is missing from the cache, SQL execution fails or a cached row can't be decoded
+     */
+    pub(crate) fn load_schedule(conn: &Connection, id: &str) -> Result<Vec<Class>, anyhow::Error> {
+        let mut stmt = conn.prepare_cached(
+            "SELECT ss.class_id, c.data FROM schedule_sections ss
+             LEFT JOIN classes c ON c.id = ss.class_id
+             WHERE ss.schedule_id = ?1 ORDER BY ss.position"
+        )?;
+        let rows = stmt.query_map(params![id], |row| Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        rows.iter()
+            .map(|(class_id, data)| match data {
+                Some(json) => decode_blob(json),
+                None => Err(anyhow!("Schedule {} references section {} missing from the cache", id, class_id)),
+            })
+            .collect()
+    }
+}
+
+// === FAVORITES REPOSITORY ===
+
+/**
+ * Repository for managing user-favorited schedules
+ * Provides toggle functionality for adding/removing favorites
+ */
+pub struct FavoriteRepository;
+
+impl FavoriteRepository {
     /**
      * Changes the favorite status of a schedule (add or remove)
      * 
      * This function provides toggle behavior:
-     * - If schedule is provided: adds to favorites
-     * - If schedule is None: removes from favorites
-     *   Uses transaction to ensure atomic operation
+     * - If favorited is true: adds the stored schedule with this ID to the active plan's favorites
+     * - If favorited is false: removes it from the active plan's favorites
+     *   Uses transaction to ensure atomic operation; the change is recorded for undo
      * 
-     * @param {String} id - Unique schedule identifier
-     * @param {Option<Vec<Class>>} schedule - Schedule data to add (None to remove)
+     * @param {String} id - Content-hash schedule identifier
+     * @param {bool} favorited - Whether the schedule should be a favorite
      * @param {&DbPool} pool - Database connection pool
      * @returns {Result<(), anyhow::Error>} Success or error
-     * @throws {anyhow::Error} If JSON serialization fails or database operation fails
+     * @throws {anyhow::Error} If the schedule doesn't exist or database operation fails
      */
-    pub async fn change_status(id: String, schedule: Option<Vec<Class>>, pool: &DbPool) -> Result<(), anyhow::Error> {
+    pub async fn change_status(id: String, favorited: bool, pool: &DbPool) -> Result<(), anyhow::Error> {
+        unit_of_work(pool, move |tx| {
+            let is_favorite: bool = tx.query_row(
+                &format!("SELECT EXISTS(SELECT 1 FROM favorites WHERE plan_id = {ACTIVE_PLAN} AND id = ?1)"),
+                params![id],
+                |row| row.get(0)
+            )?;
+            if is_favorite == favorited {
+                return Ok(());
+            }
+
+            // Keep the sections and annotations in the log entry: removing the favorite deletes them
+            let schedule = ScheduleRepository::load_schedule(tx, &id)?;
+            let details = Self::load_details_in_transaction(tx, &id)?;
+            let pin_before = SystemRepository::load_pin_in_transaction(tx)?;
+            Self::change_status_in_transaction(tx, &id, favorited)?;
+            let pin_after = SystemRepository::load_pin_in_transaction(tx)?;
+
+            let (label, mut forward, mut inverse) = if favorited {
+                ("Add schedule to favorites", vec![Operation::AddFavorite { schedule, details }], vec![Operation::RemoveFavorite { id }])
+            } else {
+                ("Remove schedule from favorites", vec![Operation::RemoveFavorite { id }], vec![Operation::AddFavorite { schedule, details }])
+            };
+            if pin_before != pin_after {
+                forward.push(Operation::SetPin { pin: pin_after });
+                inverse.push(Operation::SetPin { pin: pin_before });
+            }
+            UndoRepository::record_in_transaction(tx, label.to_string(), forward, inverse)
+        }).await
+    }
+
+    /**
+     * Sets the title, notes, color and tags of a favorite in the active plan
+     * 
+     * Titles and tags are trimmed, empty tags dropped and duplicate tags (ignoring
+     * case) collapsed. The change is recorded for undo.
+     * 
+     * @param {String} id - Content-hash schedule identifier
+     * @param {FavoriteDetails} details - New annotations, replacing the previous ones
+     * @param {&DbPool} pool - Database connection pool
+     * @returns {Result<FavoriteDetails, anyhow::Error>} Annotations as stored
+     * @throws {anyhow::Error} If the schedule isn't a favorite, validation fails, or database operation fails
+     */
+    pub async fn update_details(id: String, details: FavoriteDetails, pool: &DbPool) -> Result<FavoriteDetails, anyhow::Error> {
+        let details = Self::normalize_details(details)?;
+        unit_of_work(pool, move |tx| {
+            let Some(previous) = Self::load_details_in_transaction(tx, &id)? else {
+                return Err(anyhow!("Schedule with id '{}' is not a favorite", id));
+            };
+            if previous == details {
+                return Ok(details);
+            }
+            Self::set_details_in_transaction(tx, &id, &details)?;
+            let label = if details.title.is_empty() { "Edit favorite".to_string() } else { format!("Edit favorite \"{}\"", details.title) };
+            UndoRepository::record_in_transaction(
+                tx,
+                label,
+                vec![Operation::SetFavoriteDetails { id: id.clone(), details: details.clone() }],
+                vec![Operation::SetFavoriteDetails { id, details: previous }],
+            )?;
+            Ok(details)
+        }).await
+    }
+
+    /**
+     * Lists every tag used on the active plan's favorites, for suggestions
+     * 
+     * @param {&DbPool} pool - Database connection pool
+     * @returns {Result<Vec<String>, anyhow::Error>} Distinct tags in alphabetical order
+     * @throws {anyhow::Error} If database query fails
+     */
+    pub async fn get_tags(pool: &DbPool) -> Result<Vec<String>, anyhow::Error> {
         let pool = pool.clone();
-        tokio::task::spawn_blocking(move || -> Result<(), anyhow::Error> {
-            let mut conn = pool.get()?;
-            let tx = conn.transaction()?;
-            
-            match schedule {
-                Some(schedule_unwrapped) => {
-                    // Add to favorites
-                    let json_data = serde_json::to_string(&schedule_unwrapped)?;
-                    tx.execute("INSERT OR REPLACE INTO favorites (id, data) VALUES (?1, ?2)", params![id, json_data])?;
-                },
-                None => {
-                    // Remove from favorites
-                    tx.execute("DELETE FROM favorites WHERE id = ?1", params![id])?;
-                },
+        tokio::task::spawn_blocking(move || -> Result<Vec<String>, anyhow::Error> {
+            let conn = pool.get()?;
+            let mut stmt = conn.prepare(&format!(
+                "SELECT DISTINCT tag.value FROM favorites, json_each(favorites.tags) AS tag
+                 WHERE favorites.plan_id = {ACTIVE_PLAN}
+                 ORDER BY tag.value COLLATE NOCASE"
+            ))?;
+            let tags = stmt.query_map([], |row| row.get(0))?.collect::<Result<Vec<_>, _>>()?;
+            Ok(tags)
+        }).await?
+    }
+
+    /**
+     * Internal helper: Loads the annotations of every favorite in a plan
+     * 
+     * @param {&Connection} conn - Open database connection or transaction
+     * @param {&str} plan_id - Plan owning the favorites
+     * @returns {Result<HashMap<String, FavoriteDetails>, anyhow::Error>} Annotations keyed by schedule ID
+     * @throws {anyhow::Error} If SQL execution fails or stored tags are malformed
+     */
+    pub(crate) fn load_details_for_plan(conn: &Connection, plan_id: &str) -> Result<HashMap<String, FavoriteDetails>, anyhow::Error> {
+        let mut stmt = conn.prepare("SELECT id, title, notes, color, tags FROM favorites WHERE plan_id = ?1")?;
+        let rows = stmt.query_map(params![plan_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get::<_, String>(4)?))
+        })?;
+        let mut details = HashMap::new();
+        for row in rows {
+            let (id, title, notes, color, tags) = row?;
+            details.insert(id, FavoriteDetails { title, notes, color, tags: serde_json::from_str(&tags)? });
+        }
+        Ok(details)
+    }
+
+    /**
+     * Internal helper: Loads the annotations of one favorite of the active plan
+     * 
+     * @param {&Transaction} tx - Active database transaction
+     * @param {&str} id - Content-hash schedule identifier
+     * @returns {Result<Option<FavoriteDetails>, anyhow::Error>} Annotations, or None if the schedule isn't a favorite
+     * @throws {anyhow::Error} If SQL execution fails or stored tags are malformed
+     */
+    fn load_details_in_transaction(tx: &Transaction, id: &str) -> Result<Option<FavoriteDetails>, anyhow::Error> {
+        let row: Option<(String, String, Option<String>, String)> = tx.query_row(
+            &format!("SELECT title, notes, color, tags FROM favorites WHERE plan_id = {ACTIVE_PLAN} AND id = ?1"),
+            params![id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        ).optional()?;
+        match row {
+            Some((title, notes, color, tags)) => Ok(Some(FavoriteDetails { title, notes, color, tags: serde_json::from_str(&tags)? })),
+            None => Ok(None),
+        }
+    }
+
+    /**
+     * Internal helper: Writes the annotations of a favorite in a plan
+     * 
+     * @param {&Connection} conn - Open database connection or transaction
+     * @param {&str} plan_id - Plan owning the favorite
+     * @param {&str} id - Content-hash schedule identifier
+     * @param {&FavoriteDetails} details - Annotations to store, already normalized
+     * @returns {Result<bool, anyhow::Error>} Whether the favorite exists
+     * @throws {anyhow::Error} If SQL execution fails
+     */
+    pub(crate) fn write_details(conn: &Connection, plan_id: &str, id: &str, details: &FavoriteDetails) -> Result<bool, anyhow::Error> {
+        let updated = conn.execute(
+            "UPDATE favorites SET title = ?3, notes = ?4, color = ?5, tags = ?6 WHERE plan_id = ?1 AND id = ?2",
+            params![plan_id, id, details.title, details.notes, details.color, serde_json::to_string(&details.tags)?]
+        )?;
+        Ok(updated > 0)
+    }
+
+    /**
+     * Internal helper: Writes the annotations of a favorite of the active plan within an existing transaction
+     * 
+     * @param {&Transaction} tx - Active database transaction
+     * @param {&str} id - Content-hash schedule identifier
+     * @param {&FavoriteDetails} details - Annotations to store, already normalized
+     * @returns {Result<(), anyhow::Error>} Success or error
+     * @throws {anyhow::Error} If the schedule isn't a favorite or SQL execution fails
+     */
+    fn set_details_in_transaction(tx: &Transaction, id: &str, details: &FavoriteDetails) -> Result<(), anyhow::Error> {
+        let plan_id: String = tx.query_row(&format!("SELECT {ACTIVE_PLAN}"), [], |row| row.get(0))?;
+        if !Self::write_details(tx, &plan_id, id, details)? {
+            return Err(anyhow!("Schedule with id '{}' is not a favorite", id));
+        }
+        Ok(())
+    }
+
+    /**
+     * Internal helper: Validates and cleans up user-entered annotations
+     * 
+     * @param {FavoriteDetails} details - Annotations as entered
+     * @returns {Result<FavoriteDetails, anyhow::Error>} Trimmed annotations with deduplicated tags and a lowercase color
+     * @throws {anyhow::Error} If the title or a tag is too long or the color isn't a "#rrggbb" hex color
+     */
+    fn normalize_details(details: FavoriteDetails) -> Result<FavoriteDetails, anyhow::Error> {
+        let title = details.title.trim().to_string();
+        if title.chars().count() > MAX_FAVORITE_TITLE_LENGTH {
+            return Err(anyhow!("Title can't be longer than {MAX_FAVORITE_TITLE_LENGTH} characters"));
+        }
+
+        let color = match details.color.as_deref().map(str::trim).filter(|color| !color.is_empty()) {
+            Some(color) => {
+                let valid = color.len() == 7 && color.starts_with('#') && color[1..].chars().all(|c| c.is_ascii_hexdigit());
+                if !valid {
+                    return Err(anyhow!("Invalid color '{}'; expected a hex color like #3b82f6", color));
+                }
+                Some(color.to_ascii_lowercase())
+            }
+            None => None,
+        };
+
+        let mut tags: Vec<String> = Vec::new();
+        for tag in details.tags.iter().map(|tag| tag.trim()).filter(|tag| !tag.is_empty()) {
+            if tag.chars().count() > MAX_FAVORITE_TAG_LENGTH {
+                return Err(anyhow!("Tag '{}' is longer than {MAX_FAVORITE_TAG_LENGTH} characters", tag));
+            }
+            if !tags.iter().any(|existing| existing.to_lowercase() == tag.to_lowercase()) {
+                tags.push(tag.to_string());
+            }
+        }
+
+        Ok(FavoriteDetails { title, notes: details.notes.trim_end().to_string(), color, tags })
+    }
+
+    /**
+     * Internal helper: Adds or removes a favorite within an existing transaction
+     * See `change_status`
+     * 
+     * @param {&Transaction} tx - Active database transaction
+     * @param {&str} id - Content-hash schedule identifier
+     * @param {bool} favorited - Whether the schedule should be a favorite
+     * @returns {Result<(), anyhow::Error>} Success or error
+     * @throws {anyhow::Error} If the schedule doesn't exist or SQL execution fails
+     */
+    pub(crate) fn change_status_in_transaction(tx: &Transaction, id: &str, favorited: bool) -> Result<(), anyhow::Error> {
+        if favorited {
+            // Add to favorites; the schedule's sections are already stored under its ID
+            let known: bool = tx.query_row(
+                "SELECT EXISTS(SELECT 1 FROM schedule_sections WHERE schedule_id = ?1)",
+                params![id],
+                |row| row.get(0)
+            )?;
+            if !known {
+                return Err(anyhow!("Schedule with id '{}' not found", id));
+            }
+            tx.execute(&format!("INSERT OR IGNORE INTO favorites (plan_id, id) VALUES ({ACTIVE_PLAN}, ?1)"), params![id])?;
+        } else {
+            // Remove from favorites
+            tx.execute(&format!("DELETE FROM favorites WHERE plan_id = {ACTIVE_PLAN} AND id = ?1"), params![id])?;
+            ScheduleRepository::remove_orphaned_sections(tx)?;
+            SystemRepository::clear_stale_pin_in_transaction(tx)?;
+        }
+        Ok(())
+    }
+}
+
+// === GENERATION REPOSITORY ===
+
+/**
+ * Repository for generation inputs and history
+ * The last inputs are used to detect which courses changed so generation can reuse
+ * previous results; recorded runs keep earlier results restorable
+ */
+pub struct GenerationRepository;
+
+impl GenerationRepository {
+    /**
+     * Retrieves the parameters of the active plan's last successful generation
+     * 
+     * @param {&DbPool} pool - Database connection pool
+     * @returns {Result<Option<ScrapeClassesParameters>, anyhow::Error>} Last parameters or None if never generated
+     * @throws {anyhow::Error} If database query fails or JSON deserialization fails
+     */
+    pub async fn load_last_parameters(pool: &DbPool) -> Result<Option<ScrapeClassesParameters>, anyhow::Error> {
+        let pool = pool.clone();
+        tokio::task::spawn_blocking(move || -> Result<Option<ScrapeClassesParameters>, anyhow::Error> {
+            let conn = pool.get()?;
+            let data: Option<String> = conn.query_row(
+                &format!("SELECT parameters FROM last_generation WHERE plan_id = {ACTIVE_PLAN}"),
+                [],
+                |row| row.get(0)
+            ).optional()?;
+            match data {
+                Some(json) => Ok(Some(serde_json::from_str(&json)?)),
+                None => Ok(None),
+            }
+        }).await?
+    }
+
+    /**
+     * Checks that the active plan's stored schedules are exactly the results of its last generation
+     * 
+     * Reusing the stored schedules for added or removed courses is only correct if none of
+     * the last generation's results went missing, e.g. because the user deleted some. The
+     * last generation keeps a fingerprint of the schedules it stored, so this works without
+     * generation history.
+     * 
+     * @param {&DbPool} pool - Database connection pool
+     * @returns {Result<bool, anyhow::Error>} True if every result of the last generation is stored and nothing else is
+     * @throws {anyhow::Error} If database query fails
+     */
+    pub async fn stored_schedules_complete(pool: &DbPool) -> Result<bool, anyhow::Error> {
+        let pool = pool.clone();
+        tokio::task::spawn_blocking(move || -> Result<bool, anyhow::Error> {
+            let conn = pool.get()?;
+            let recorded: Option<Option<String>> = conn.query_row(
+                &format!("SELECT results_hash FROM last_generation WHERE plan_id = {ACTIVE_PLAN}"),
+                [],
+                |row| row.get(0)
+            ).optional()?;
+            match recorded.flatten() {
+                Some(recorded) => Ok(recorded == Self::results_hash(&conn)?),
+                None => Ok(false),
+            }
+        }).await?
+    }
+
+    /**
+     * Internal helper: Fingerprints the active plan's stored schedules
+     * Order-independent, since only the set of stored results matters
+     * 
+     * @param {&Connection} conn - Open database connection or transaction
+     * @returns {Result<String, anyhow::Error>} 16-character hexadecimal hash of the sorted schedule IDs
+     * @throws {anyhow::Error} If database query fails
+     */
+    fn results_hash(conn: &Connection) -> Result<String, anyhow::Error> {
+        let mut stmt = conn.prepare(&format!("SELECT id FROM schedules WHERE plan_id = {ACTIVE_PLAN} ORDER BY id"))?;
+        let ids = stmt.query_map([], |row| row.get::<_, String>(0))?.collect::<Result<Vec<_>, _>>()?;
+        Ok(format!("{:016x}", stable_hash(ids.join("\n").as_bytes())))
+    }
+
+    /**
+     * Internal helper: Retrieves the constraints of the active plan's last generation
+     * 
+     * @param {&Connection} conn - Open database connection or transaction
+     * @returns {Result<ScheduleConstraints, anyhow::Error>} Last constraints, or none if never generated
+     * @throws {anyhow::Error} If database query fails or JSON deserialization fails
+     */
+    pub(crate) fn last_constraints_in_transaction(conn: &Connection) -> Result<ScheduleConstraints, anyhow::Error> {
+        let data: Option<String> = conn.query_row(
+            &format!("SELECT parameters FROM last_generation WHERE plan_id = {ACTIVE_PLAN}"),
+            [],
+            |row| row.get(0)
+        ).optional()?;
+        match data {
+            Some(json) => Ok(serde_json::from_str::<ScrapeClassesParameters>(&json)?.constraints),
+            None => Ok(ScheduleConstraints::default()),
+        }
+    }
+
+    /**
+     * Internal helper: Stores the parameters of a successful generation in the active plan
+     * Saved after the generated schedules, together with their fingerprint, so the two always match
+     * 
+     * @param {&Transaction} tx - Active database transaction
+     * @param {&ScrapeClassesParameters} parameters - Parameters used for the generation
+     * @returns {Result<(), anyhow::Error>} Success or error
+     * @throws {anyhow::Error} If JSON serialization fails or SQL execution fails
+     */
+    pub(crate) fn save_last_parameters_in_transaction(tx: &Transaction, parameters: &ScrapeClassesParameters) -> Result<(), anyhow::Error> {
+        Self::save_last_parameters_json_in_transaction(tx, &serde_json::to_string(parameters)?)
+    }
+
+    /**
+     * Internal helper: Stores already serialized generation parameters in the active plan
+     * 
+     * @param {&Transaction} tx - Active database transaction
+     * @param {&str} parameters - JSON of the parameters the stored schedules were generated from
+     * @returns {Result<(), anyhow::Error>} Success or error
+     * @throws {anyhow::Error} If SQL execution fails
+     */
+    fn save_last_parameters_json_in_transaction(tx: &Transaction, parameters: &str) -> Result<(), anyhow::Error> {
+        tx.execute(
+            &format!("INSERT OR REPLACE INTO last_generation (plan_id, parameters, results_hash) VALUES ({ACTIVE_PLAN}, ?1, ?2)"),
+            params![parameters, Self::results_hash(tx)?]
+        )?;
+        Ok(())
+    }
+
+    /**
+     * Internal helper: Records a finished generation run in the active plan's history
+     * Runs in the transaction that saves the run's schedules, so the history never
+     * references schedules that weren't stored
+     * 
+     * This function:
+     * 1. Stores the run's inputs, schedule count and duration
+     * 2. Stores the run's schedule IDs in result order
+     * 3. Prunes runs beyond the configured history limit
+     * 
+     * @param {&Transaction} tx - Active database transaction
+     * @param {&ScrapeClassesParameters} parameters - Inputs the schedules were generated from
+     * @param {&[String]} schedule_ids - Content-hash IDs of the generated schedules, in order
+     * @param {Duration} duration - Time the generation took
+     * @returns {Result<String, anyhow::Error>} ID of the recorded run
+     * @throws {anyhow::Error} If JSON serialization fails or SQL execution fails
+     */
+    pub(crate) fn record_run_in_transaction(tx: &Transaction, parameters: &ScrapeClassesParameters, schedule_ids: &[String], duration: Duration) -> Result<String, anyhow::Error> {
+        let json_data = serde_json::to_string(parameters)?;
+        let run_id = Uuid::new_v4().to_string();
+
+        tx.execute(
+            &format!(
+                "INSERT INTO generation_runs (id, plan_id, created_at, parameters, schedule_count, duration_ms)
+                 VALUES (?1, {ACTIVE_PLAN}, CAST(strftime('%s', 'now') AS INTEGER), ?2, ?3, ?4)"
+            ),
+            params![run_id, json_data, schedule_ids.len() as i64, duration.as_millis() as i64]
+        )?;
+        {
+            let mut stmt = tx.prepare("INSERT INTO run_schedules (run_id, position, schedule_id) VALUES (?1, ?2, ?3)")?;
+            for (position, schedule_id) in schedule_ids.iter().enumerate() {
+                stmt.execute(params![run_id, position as i64, schedule_id])?;
+            }
+        }
+
+        let limit = Self::read_history_limit(tx)?;
+        Self::prune_runs_in_transaction(tx, limit)?;
+        Ok(run_id)
+    }
+
+    /**
+     * Lists the active plan's recorded runs, newest first
+     * 
+     * @param {&DbPool} pool - Database connection pool
+     * @returns {Result<Vec<GenerationRun>, anyhow::Error>} Array of runs or error
+     * @throws {anyhow::Error} If database query fails or JSON deserialization fails
+     */
+    pub async fn get_runs(pool: &DbPool) -> Result<Vec<GenerationRun>, anyhow::Error> {
+        let pool = pool.clone();
+        tokio::task::spawn_blocking(move || -> Result<Vec<GenerationRun>, anyhow::Error> {
+            let conn = pool.get()?;
+            let mut stmt = conn.prepare(&format!(
+                "SELECT id, created_at, parameters, schedule_count, duration_ms FROM generation_runs
+                 WHERE plan_id = {ACTIVE_PLAN} ORDER BY rowid DESC"
+            ))?;
+            let rows = stmt.query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?, row.get::<_, String>(2)?, row.get::<_, i64>(3)?, row.get::<_, i64>(4)?))
+            })?;
+
+            let mut runs = Vec::new();
+            for row in rows {
+                let (id, created_at, parameters, schedule_count, duration_ms) = row?;
+                runs.push(GenerationRun {
+                    id,
+                    created_at,
+                    parameters: serde_json::from_str(&parameters)?,
+                    schedule_count: schedule_count as usize,
+                    duration_ms: duration_ms as u64,
+                });
+            }
+            Ok(runs)
+        }).await?
+    }
+
+    /**
+     * Makes a recorded run's schedules the active plan's current schedules
+     * 
+     * The run's inputs become the last generation inputs, so the next generation
+     * compares against the restored results. The run itself stays in the history.
+     * 
+     * @param {String} id - Run to restore
+     * @param {&DbPool} pool - Database connection pool
+     * @returns {Result<(), anyhow::Error>} Success or error
+     * @throws {anyhow::Error} If the run doesn't belong to the active plan or database operation fails
+     */
+    pub async fn restore_run(id: String, pool: &DbPool) -> Result<(), anyhow::Error> {
+        unit_of_work(pool, move |tx| {
+            let parameters: Option<String> = tx.query_row(
+                &format!("SELECT parameters FROM generation_runs WHERE id = ?1 AND plan_id = {ACTIVE_PLAN}"),
+                params![id],
+                |row| row.get(0)
+            ).optional()?;
+            let Some(parameters) = parameters else {
+                return Err(anyhow!("Generation run with id '{}' not found", id));
             };
-            
-            tx.commit()?;
+
+            tx.execute(&format!("DELETE FROM schedules WHERE plan_id = {ACTIVE_PLAN}"), [])?;
+            tx.execute(
+                &format!(
+                    "INSERT OR IGNORE INTO schedules (plan_id, id)
+                     SELECT {ACTIVE_PLAN}, schedule_id FROM run_schedules WHERE run_id = ?1 ORDER BY position"
+                ),
+                params![id]
+            )?;
+            Self::save_last_parameters_json_in_transaction(tx, &parameters)?;
+
+            let constraints = serde_json::from_str::<ScrapeClassesParameters>(&parameters)?.constraints;
+            ScheduleRepository::rescore_in_transaction(tx, &constraints)?;
+            ScheduleRepository::remove_orphaned_sections(tx)?;
+            SystemRepository::clear_stale_pin_in_transaction(tx)?;
+            Ok(())
+        }).await
+    }
+
+    /**
+     * Deletes a recorded run from the active plan's history
+     * The current schedules are not affected
+     * 
+     * @param {String} id - Run to delete
+     * @param {&DbPool} pool - Database connection pool
+     * @returns {Result<(), anyhow::Error>} Success or error
+     * @throws {anyhow::Error} If database operation fails
+     */
+    pub async fn delete_run(id: String, pool: &DbPool) -> Result<(), anyhow::Error> {
+        unit_of_work(pool, move |tx| {
+            let deleted = tx.execute(
+                &format!("DELETE FROM generation_runs WHERE id = ?1 AND plan_id = {ACTIVE_PLAN}"),
+                params![id]
+            )?;
+            if deleted > 0 {
+                tx.execute("DELETE FROM run_schedules WHERE run_id = ?1", params![id])?;
+                ScheduleRepository::remove_orphaned_sections(tx)?;
+            }
             Ok(())
+        }).await
+    }
+
+    /**
+     * Retrieves how many runs are kept per plan
+     * 
+     * The limit is an application-wide setting stored in `data`, not a plan setting:
+     * every plan keeps that many of its own newest runs, and switching plans doesn't
+     * change it.
+     * 
+     * @param {&DbPool} pool - Database connection pool
+     * @returns {Result<u32, anyhow::Error>} Retention limit or error
+     * @throws {anyhow::Error} If database query fails
+     */
+    pub async fn get_history_limit(pool: &DbPool) -> Result<u32, anyhow::Error> {
+        let pool = pool.clone();
+        tokio::task::spawn_blocking(move || -> Result<u32, anyhow::Error> {
+            let conn = pool.get()?;
+            Self::read_history_limit(&conn)
         }).await?
     }
+
+    /**
+     * Sets how many runs are kept per plan and prunes older runs immediately
+     * A limit of 0 disables history. The setting is global, so every plan is pruned to it.
+     * 
+     * @param {u32} limit - Number of most recent runs to keep per plan
+     * @param {&DbPool} pool - Database connection pool
+     * @returns {Result<(), anyhow::Error>} Success or error
+     * @throws {anyhow::Error} If database operation fails
+     */
+    pub async fn set_history_limit(limit: u32, pool: &DbPool) -> Result<(), anyhow::Error> {
+        unit_of_work(pool, move |tx| {
+            tx.execute("UPDATE data SET history_limit = ?1 WHERE id = ?2", params![limit, DATA_TABLE_ID])?;
+            Self::prune_runs_in_transaction(tx, limit)
+        }).await
+    }
+
+    /**
+     * Internal helper: Reads the retention limit
+     * 
+     * @param {&Connection} conn - Open database connection or transaction
+     * @returns {Result<u32, anyhow::Error>} Configured limit, or the default if unset
+     * @throws {anyhow::Error} If SQL execution fails
+     */
+    fn read_history_limit(conn: &Connection) -> Result<u32, anyhow::Error> {
+        let limit: Option<u32> = conn.query_row(
+            "SELECT history_limit FROM data WHERE id = ?1",
+            params![DATA_TABLE_ID],
+            |row| row.get(0)
+        ).optional()?;
+        Ok(limit.unwrap_or(DEFAULT_HISTORY_LIMIT))
+    }
+
+    /**
+     * Internal helper: Deletes all but the newest runs of every plan
+     * 
+     * @param {&Transaction} tx - Active database transaction
+     * @param {u32} limit - Number of runs to keep per plan
+     * @returns {Result<(), anyhow::Error>} Success or error
+     * @throws {anyhow::Error} If SQL execution fails
+     */
+    fn prune_runs_in_transaction(tx: &Transaction, limit: u32) -> Result<(), anyhow::Error> {
+        let pruned = tx.execute(
+            "DELETE FROM generation_runs WHERE id IN (
+                SELECT id FROM (
+                    SELECT id, ROW_NUMBER() OVER (PARTITION BY plan_id ORDER BY rowid DESC) AS rank
+                    FROM generation_runs
+                ) WHERE rank > ?1
+            )",
+            params![limit]
+        )?;
+        if pruned > 0 {
+            tx.execute("DELETE FROM run_schedules WHERE run_id NOT IN (SELECT id FROM generation_runs)", [])?;
+            ScheduleRepository::remove_orphaned_sections(tx)?;
+        }
+        Ok(())
+    }
+}
+
+// === PLAN REPOSITORY ===
+
+/**
+ * Repository for managing named plans
+ * A plan owns its class parameters, events, schedules, favorites, last generation
+ * inputs and pin; exactly one plan is active at a time
+ */
+pub struct PlanRepository;
+
+/** Plan-owned tables whose rows are keyed by (plan_id, id) */
+const PLAN_TABLES: &[&str] = &["events", "class_parameters", "schedules", "favorites"];
+
+impl PlanRepository {
+    /**
+     * Lists all plans in creation order
+     * 
+     * @param {&DbPool} pool - Database connection pool
+     * @returns {Result<Vec<Plan>, anyhow::Error>} Array of plans or error
+     * @throws {anyhow::Error} If database query fails
+     */
+    pub async fn get_all(pool: &DbPool) -> Result<Vec<Plan>, anyhow::Error> {
+        let pool = pool.clone();
+        tokio::task::spawn_blocking(move || -> Result<Vec<Plan>, anyhow::Error> {
+            let conn = pool.get()?;
+            let mut stmt = conn.prepare("SELECT id, name, active, created_at FROM plans ORDER BY created_at, rowid")?;
+            let plans = stmt.query_map([], |row| {
+                Ok(Plan {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    active: row.get(2)?,
+                    created_at: row.get(3)?,
+                })
+            })?.collect::<Result<Vec<_>, _>>()?;
+            Ok(plans)
+        }).await?
+    }
+
+    /**
+     * Creates a new, empty plan
+     * The active plan is left unchanged
+     * 
+     * @param {String} name - Display name, must be unique
+     * @param {&DbPool} pool - Database connection pool
+     * @returns {Result<Plan, anyhow::Error>} Created plan or error
+     * @throws {anyhow::Error} If the name is empty or taken, or database operation fails
+     */
+    pub async fn create(name: String, pool: &DbPool) -> Result<Plan, anyhow::Error> {
+        unit_of_work(pool, move |tx| Self::insert_plan_in_transaction(tx, &name)).await
+    }
+
+    /**
+     * Creates a copy of a plan under a new name
+     * 
+     * Copies the class parameters, events, schedules, favorites, last generation inputs,
+     * pin and term dates. Schedule section rows are shared, so no schedule data is duplicated.
+     * Generation history stays with the original plan.
+     * 
+     * @param {String} id - Plan to copy
+     * @param {String} name - Display name of the copy, must be unique
+     * @param {&DbPool} pool - Database connection pool
+     * @returns {Result<Plan, anyhow::Error>} Created plan or error
+     * @throws {anyhow::Error} If the source plan doesn't exist, the name is taken, or database operation fails
+     */
+    pub async fn duplicate(id: String, name: String, pool: &DbPool) -> Result<Plan, anyhow::Error> {
+        unit_of_work(pool, move |tx| {
+            Self::ensure_exists_in_transaction(tx, &id)?;
+            let plan = Self::insert_plan_in_transaction(tx, &name)?;
+
+            for table in PLAN_TABLES {
+                let columns = Self::copied_columns(tx, table)?;
+                tx.execute(
+                    &format!("INSERT INTO {table} (plan_id, {columns}) SELECT ?1, {columns} FROM {table} WHERE plan_id = ?2 ORDER BY rowid"),
+                    params![plan.id, id]
+                )?;
+            }
+            tx.execute(
+                "INSERT INTO last_generation (plan_id, parameters, results_hash) SELECT ?1, parameters, results_hash FROM last_generation WHERE plan_id = ?2",
+                params![plan.id, id]
+            )?;
+            tx.execute(
+                "UPDATE plans SET (pinned_schedule_id, pinned_table, term_start, term_end, holidays) =
+                    (SELECT pinned_schedule_id, pinned_table, term_start, term_end, holidays FROM plans WHERE id = ?2)
+                 WHERE id = ?1",
+                params![plan.id, id]
+            )?;
+            Ok(plan)
+        }).await
+    }
+
+    /**
+     * Renames a plan
+     * 
+     * @param {String} id - Plan to rename
+     * @param {String} name - New display name, must be unique
+     * @param {&DbPool} pool - Database connection pool
+     * @returns {Result<(), anyhow::Error>} Success or error
+     * @throws {anyhow::Error} If the plan doesn't exist, the name is empty or taken, or database operation fails
+     */
+    pub async fn rename(id: String, name: String, pool: &DbPool) -> Result<(), anyhow::Error> {
+        unit_of_work(pool, move |tx| {
+            Self::ensure_exists_in_transaction(tx, &id)?;
+            let name = Self::validate_name_in_transaction(tx, &name, Some(&id))?;
+            tx.execute("UPDATE plans SET name = ?1 WHERE id = ?2", params![name, id])?;
+            Ok(())
+        }).await
+    }
+
+    /**
+     * Makes a plan the active one
+     * All plan-scoped repositories read and write the active plan
+     * 
+     * @param {String} id - Plan to activate
+     * @param {&DbPool} pool - Database connection pool
+     * @returns {Result<(), anyhow::Error>} Success or error
+     * @throws {anyhow::Error} If the plan doesn't exist or database operation fails
+     */
+    pub async fn switch(id: String, pool: &DbPool) -> Result<(), anyhow::Error> {
+        unit_of_work(pool, move |tx| {
+            Self::ensure_exists_in_transaction(tx, &id)?;
+            tx.execute("UPDATE plans SET active = (id = ?1)", params![id])?;
+            Ok(())
+        }).await
+    }
+
+    /**
+     * Deletes a plan and everything it owns
+     * The active plan and the only remaining plan can't be deleted; switch to another plan first
+     * 
+     * @param {String} id - Plan to delete
+     * @param {&DbPool} pool - Database connection pool
+     * @returns {Result<(), anyhow::Error>} Success or error
+     * @throws {anyhow::Error} If the plan doesn't exist, is the last or active plan, or database operation fails
+     */
+    pub async fn delete(id: String, pool: &DbPool) -> Result<(), anyhow::Error> {
+        unit_of_work(pool, move |tx| {
+            Self::ensure_exists_in_transaction(tx, &id)?;
+            let count: i64 = tx.query_row("SELECT COUNT(*) FROM plans", [], |row| row.get(0))?;
+            if count <= 1 {
+                return Err(anyhow!("Cannot delete the only plan"));
+            }
+            let active: bool = tx.query_row("SELECT active FROM plans WHERE id = ?1", params![id], |row| row.get(0))?;
+            if active {
+                return Err(anyhow!("Cannot delete the active plan; switch to another plan first"));
+            }
+
+            for table in PLAN_TABLES.iter().chain(&["last_generation"]) {
+                tx.execute(&format!("DELETE FROM {table} WHERE plan_id = ?1"), params![id])?;
+            }
+            tx.execute("DELETE FROM generation_runs WHERE plan_id = ?1", params![id])?;
+            tx.execute("DELETE FROM run_schedules WHERE run_id NOT IN (SELECT id FROM generation_runs)", [])?;
+            tx.execute("DELETE FROM operation_log WHERE plan_id = ?1", params![id])?;
+            tx.execute("DELETE FROM plans WHERE id = ?1", params![id])?;
+            ScheduleRepository::remove_orphaned_sections(tx)
+        }).await
+    }
+
+    /**
+     * Retrieves the term dates of the active plan
+     * 
+     * @param {&DbPool} pool - Database connection pool
+     * @returns {Result<Option<TermCalendar>, anyhow::Error>} Term, or None if it hasn't been set
+     * @throws {anyhow::Error} If database query fails
+     */
+    pub async fn get_term(pool: &DbPool) -> Result<Option<TermCalendar>, anyhow::Error> {
+        let pool = pool.clone();
+        tokio::task::spawn_blocking(move || -> Result<Option<TermCalendar>, anyhow::Error> {
+            let conn = pool.get()?;
+            Self::load_active_term(&conn)
+        }).await?
+    }
+
+    /**
+     * Sets or clears the term dates of the active plan
+     * 
+     * @param {Option<TermCalendar>} term - New term, or None to clear it
+     * @param {&DbPool} pool - Database connection pool
+     * @returns {Result<Option<TermCalendar>, anyhow::Error>} Term as stored, with sorted and deduplicated holidays
+     * @throws {anyhow::Error} If a date is invalid, the term ends before it starts, or database operation fails
+     */
+    pub async fn set_term(term: Option<TermCalendar>, pool: &DbPool) -> Result<Option<TermCalendar>, anyhow::Error> {
+        let term = term.map(normalize_term).transpose()?;
+        let pool = pool.clone();
+        tokio::task::spawn_blocking(move || -> Result<Option<TermCalendar>, anyhow::Error> {
+            let conn = pool.get()?;
+            let plan_id: String = conn.query_row(&format!("SELECT {ACTIVE_PLAN}"), [], |row| row.get(0))?;
+            Self::write_term(&conn, &plan_id, term.as_ref())?;
+            Ok(term)
+        }).await?
+    }
+
+    /**
+     * Internal helper: Reads the term dates of a plan
+     * 
+     * @param {&Connection} conn - Open database connection or transaction
+     * @param {&str} plan_id - Plan to read
+     * @returns {Result<Option<TermCalendar>, anyhow::Error>} Term, or None if it hasn't been set
+     * @throws {anyhow::Error} If SQL execution fails or stored holidays are malformed
+     */
+    pub(crate) fn load_term(conn: &Connection, plan_id: &str) -> Result<Option<TermCalendar>, anyhow::Error> {
+        let row: Option<(Option<String>, Option<String>, String)> = conn.query_row(
+            "SELECT term_start, term_end, holidays FROM plans WHERE id = ?1",
+            params![plan_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        ).optional()?;
+        match row {
+            Some((Some(start), Some(end), holidays)) => Ok(Some(TermCalendar { start, end, holidays: serde_json::from_str(&holidays)? })),
+            _ => Ok(None),
+        }
+    }
+
+    /**
+     * Internal helper: Reads the term dates of the active plan
+     * 
+     * @param {&Connection} conn - Open database connection or transaction
+     * @returns {Result<Option<TermCalendar>, anyhow::Error>} Term, or None if it hasn't been set
+     * @throws {anyhow::Error} If SQL execution fails or stored holidays are malformed
+     */
+    pub(crate) fn load_active_term(conn: &Connection) -> Result<Option<TermCalendar>, anyhow::Error> {
+        let plan_id: String = conn.query_row(&format!("SELECT {ACTIVE_PLAN}"), [], |row| row.get(0))?;
+        Self::load_term(conn, &plan_id)
+    }
+
+    /**
+     * Internal helper: Writes the term dates of a plan
+     * 
+     * @param {&Connection} conn - Open database connection or transaction
+     * @param {&str} plan_id - Plan to update
+     * @param {Option<&TermCalendar>} term - Normalized term, or None to clear it
+     * @returns {Result<(), anyhow::Error>} Success or error
+     * @throws {anyhow::Error} If SQL execution fails
+     */
+    pub(crate) fn write_term(conn: &Connection, plan_id: &str, term: Option<&TermCalendar>) -> Result<(), anyhow::Error> {
+        let holidays = serde_json::to_string(&term.map(|term| term.holidays.clone()).unwrap_or_default())?;
+        conn.execute(
+            "UPDATE plans SET term_start = ?2, term_end = ?3, holidays = ?4 WHERE id = ?1",
+            params![plan_id, term.map(|term| &term.start), term.map(|term| &term.end), holidays]
+        )?;
+        Ok(())
+    }
+
+    /**
+     * Internal helper: Inserts a new inactive plan within an existing transaction
+     * 
+     * @param {&Transaction} tx - Active database transaction
+     * @param {&str} name - Display name, must be unique
+     * @returns {Result<Plan, anyhow::Error>} Created plan
+     * @throws {anyhow::Error} If the name is empty or taken, or SQL execution fails
+     */
+    fn insert_plan_in_transaction(tx: &Transaction, name: &str) -> Result<Plan, anyhow::Error> {
+        let name = Self::validate_name_in_transaction(tx, name, None)?;
+        let id = Uuid::new_v4().to_string();
+        let created_at: i64 = tx.query_row(
+            "INSERT INTO plans (id, name, active, created_at) VALUES (?1, ?2, 0, CAST(strftime('%s', 'now') AS INTEGER))
+             RETURNING created_at",
+            params![id, name],
+            |row| row.get(0)
+        )?;
+        Ok(Plan { id, name, active: false, created_at })
+    }
+
+    /**
+     * Internal helper: Trims a plan name and checks that it is non-empty and unused
+     * 
+     * @param {&Transaction} tx - Active database transaction
+     * @param {&str} name - Requested display name
+     * @param {Option<&str>} except_id - Plan allowed to already hold the name (when renaming)
+     * @returns {Result<String, anyhow::Error>} Trimmed name
+     * @throws {anyhow::Error} If the name is empty or used by another plan
+     */
+    fn validate_name_in_transaction(tx: &Transaction, name: &str, except_id: Option<&str>) -> Result<String, anyhow::Error> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(anyhow!("Plan name cannot be empty"));
+        }
+        let taken: bool = tx.query_row(
+            "SELECT EXISTS(SELECT 1 FROM plans WHERE name = ?1 AND id IS NOT ?2)",
+            params![name, except_id],
+            |row| row.get(0)
+        )?;
+        if taken {
+            return Err(anyhow!("A plan named '{}' already exists", name));
+        }
+        Ok(name.to_string())
+    }
+
+    /**
+     * Internal helper: Fails if no plan has the given ID
+     * 
+     * @param {&Transaction} tx - Active database transaction
+     * @param {&str} id - Plan ID to check
+     * @returns {Result<(), anyhow::Error>} Success or error
+     * @throws {anyhow::Error} If the plan doesn't exist or SQL execution fails
+     */
+    fn ensure_exists_in_transaction(tx: &Transaction, id: &str) -> Result<(), anyhow::Error> {
+        let exists: bool = tx.query_row("SELECT EXISTS(SELECT 1 FROM plans WHERE id = ?1)", params![id], |row| row.get(0))?;
+        if !exists {
+            return Err(anyhow!("Plan with id '{}' not found", id));
+        }
+        Ok(())
+    }
+
+    /**
+     * Internal helper: Lists the columns of a plan-owned table other than plan_id
+     * 
+     * @param {&Transaction} tx - Active database transaction
+     * @param {&str} table - Plan-owned table name
+     * @returns {Result<String, anyhow::Error>} Comma-separated column list
+     * @throws {anyhow::Error} If SQL execution fails
+     */
+    fn copied_columns(tx: &Transaction, table: &str) -> Result<String, anyhow::Error> {
+        let mut stmt = tx.prepare(&format!("SELECT name FROM pragma_table_info('{table}') WHERE name != 'plan_id' ORDER BY cid"))?;
+        let columns = stmt.query_map([], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(columns.join(", "))
+    }
 }
 
 // === SYSTEM REPOSITORY ===
 
 /**
  * Repository for managing system configuration and application state
- * Handles Chrome version tracking and selected schedule persistence
+ * Handles Chrome version tracking and the active plan's pinned schedule
  */
 pub struct SystemRepository;
 
@@ -532,104 +2242,360 @@ impl SystemRepository {
     }
 
     /**
-     * Retrieves the currently selected/pinned schedule index
+     * Retrieves the active plan's pinned schedule
+     * 
+     * A pin whose schedule no longer exists in its table is cleared and reported
+     * in the log, so callers never receive a dangling reference.
+     * 
+     * @param {&DbPool} pool - Database connection pool
+     * @returns {Result<Option<PinnedSchedule>, anyhow::Error>} Pinned schedule or None if nothing is pinned
+     * @throws {anyhow::Error} If database query fails
+     */
+    pub async fn get_display_schedule(pool: &DbPool) -> Result<Option<PinnedSchedule>, anyhow::Error> {
+        unit_of_work(pool, |tx| {
+            match Self::clear_stale_pin_in_transaction(tx)? {
+                Some(stale) => {
+                    println!("Pinned schedule {} no longer exists in {}; clearing pin.", stale.id, stale.table);
+                    Ok(None)
+                }
+                None => Self::load_pin_in_transaction(tx),
+            }
+        }).await
+    }
+
+    /**
+     * Pins a schedule of the active plan by its content-hash ID, or clears the pin
+     * The change is recorded for undo
+     * 
+     * @param {Option<PinnedSchedule>} pin - Schedule ID and table to pin, or None to unpin
+     * @param {&DbPool} pool - Database connection pool
+     * @returns {Result<(), anyhow::Error>} Success or error
+     * @throws {anyhow::Error} If the table is invalid, the schedule doesn't exist, or database operation fails
+     */
+    pub async fn set_display_schedule(pin: Option<PinnedSchedule>, pool: &DbPool) -> Result<(), anyhow::Error> {
+        unit_of_work(pool, move |tx| {
+            let previous = Self::load_pin_in_transaction(tx)?;
+            Self::set_pin_in_transaction(tx, pin.as_ref())?;
+            if previous == pin {
+                return Ok(());
+            }
+            let label = if pin.is_some() { "Pin schedule" } else { "Unpin schedule" };
+            UndoRepository::record_in_transaction(
+                tx,
+                label.to_string(),
+                vec![Operation::SetPin { pin }],
+                vec![Operation::SetPin { pin: previous }],
+            )
+        }).await
+    }
+
+    /**
+     * Internal helper: Reads the pin within an existing transaction
+     * 
+     * @param {&Transaction} tx - Active database transaction
+     * @returns {Result<Option<PinnedSchedule>, anyhow::Error>} Stored pin, if any
+     * @throws {anyhow::Error} If SQL execution fails
+     */
+    fn load_pin_in_transaction(tx: &Transaction) -> Result<Option<PinnedSchedule>, anyhow::Error> {
+        let pin: Option<(Option<String>, Option<String>)> = tx.query_row(
+            "SELECT pinned_schedule_id, pinned_table FROM plans WHERE active = 1",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?))
+        ).optional()?;
+        Ok(match pin {
+            Some((Some(id), Some(table))) => Some(PinnedSchedule { id, table }),
+            _ => None,
+        })
+    }
+
+    /**
+     * Internal helper: Sets or clears the pin within an existing transaction
+     * Validates that the pinned schedule exists in the given table
+     * 
+     * @param {&Transaction} tx - Active database transaction
+     * @param {Option<&PinnedSchedule>} pin - Schedule to pin, or None to unpin
+     * @returns {Result<(), anyhow::Error>} Success or error
+     * @throws {anyhow::Error} If the table is invalid, the schedule doesn't exist, or SQL execution fails
+     */
+    pub(crate) fn set_pin_in_transaction(tx: &Transaction, pin: Option<&PinnedSchedule>) -> Result<(), anyhow::Error> {
+        if let Some(pin) = pin {
+            if !SCHEDULE_TABLES.contains(&pin.table.as_str()) {
+                return Err(anyhow!("Invalid schedule table: {}", pin.table));
+            }
+            let exists: bool = tx.query_row(
+                &format!("SELECT EXISTS(SELECT 1 FROM {} WHERE plan_id = {ACTIVE_PLAN} AND id = ?1)", pin.table),
+                params![pin.id],
+                |row| row.get(0)
+            )?;
+            if !exists {
+                return Err(anyhow!("Schedule with id '{}' not found in {}", pin.id, pin.table));
+            }
+        }
+        tx.execute(
+            "UPDATE plans SET pinned_schedule_id = ?1, pinned_table = ?2 WHERE active = 1",
+            params![pin.map(|p| &p.id), pin.map(|p| &p.table)]
+        )?;
+        Ok(())
+    }
+
+    /**
+     * Internal helper: Clears the pin if its schedule was removed from its table
+     * Call after any operation that deletes schedules or favorites
+     * 
+     * @param {&Transaction} tx - Active database transaction
+     * @returns {Result<Option<PinnedSchedule>, anyhow::Error>} The cleared pin, or None if the pin was still valid
+     * @throws {anyhow::Error} If SQL execution fails
+     */
+    pub(crate) fn clear_stale_pin_in_transaction(tx: &Transaction) -> Result<Option<PinnedSchedule>, anyhow::Error> {
+        let Some(pin) = Self::load_pin_in_transaction(tx)? else {
+            return Ok(None);
+        };
+        let exists: bool = SCHEDULE_TABLES.contains(&pin.table.as_str()) && tx.query_row(
+            &format!("SELECT EXISTS(SELECT 1 FROM {} WHERE plan_id = {ACTIVE_PLAN} AND id = ?1)", pin.table),
+            params![pin.id],
+            |row| row.get(0)
+        )?;
+        if exists {
+            return Ok(None);
+        }
+        Self::set_pin_in_transaction(tx, None)?;
+        Ok(Some(pin))
+    }
+}
+
+// === UNDO REPOSITORY ===
+
+/**
+ * Reversible change stored in the operation log
+ * Applied through the same helpers the repositories use, without being recorded again
+ */
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind")]
+enum Operation {
+    /** Inserts or replaces an event */
+    PutEvent { table: String, event: Event },
+    DeleteEvent { table: String, id: String },
+    /** Inserts or updates a course, preferring the given list position for inserts */
+    PutClassParam { class: ClassParam, position: Option<i64> },
+    DeleteClassParam { id: String },
+    /** Favorites a schedule, restoring its sections and annotations if they were removed */
+    AddFavorite {
+        schedule: Vec<Class>,
+        #[serde(default)]
+        details: Option<FavoriteDetails>,
+    },
+    RemoveFavorite { id: String },
+    SetFavoriteDetails { id: String, details: FavoriteDetails },
+    SetPin { pin: Option<PinnedSchedule> },
+}
+
+/**
+ * Repository for the per-plan operation log behind undo and redo
+ * 
+ * Every recorded user action stores the operations it applied and the operations
+ * that revert it. Undo applies the newest action's inverse and moves it to the redo
+ * stack; recording a new action clears the redo stack. Only the newest
+ * UNDO_HISTORY_DEPTH actions of each plan are kept.
+ */
+pub struct UndoRepository;
+
+impl UndoRepository {
+    /**
+     * Retrieves what undo and redo would do next in the active plan
      * 
      * @param {&DbPool} pool - Database connection pool
-     * @returns {Result<Option<i16>, anyhow::Error>} Schedule index or None if no schedule selected
+     * @returns {Result<UndoState, anyhow::Error>} Labels of the next undo and redo steps
      * @throws {anyhow::Error} If database query fails
      */
-    pub async fn get_display_schedule(pool: &DbPool) -> Result<Option<i16>, anyhow::Error> {
+    pub async fn get_state(pool: &DbPool) -> Result<UndoState, anyhow::Error> {
         let pool = pool.clone();
-        tokio::task::spawn_blocking(move || -> Result<Option<i16>, anyhow::Error> {
+        tokio::task::spawn_blocking(move || -> Result<UndoState, anyhow::Error> {
             let conn = pool.get()?;
-            let id: Option<i16> = conn.query_row(
-                "SELECT schedule FROM data WHERE id = ?1", 
-                params![DATA_TABLE_ID], 
-                |row| row.get(0)
-            ).optional()?.flatten();
-            Ok(id)
+            Self::state(&conn)
         }).await?
     }
 
     /**
-     * Sets the currently selected/pinned schedule index
-     * Simplified operation - single UPDATE doesn't require transaction
+     * Reverts the active plan's most recent action
      * 
-     * @param {Option<i16>} id - Schedule index to pin, or None to unpin
      * @param {&DbPool} pool - Database connection pool
-     * @returns {Result<(), anyhow::Error>} Success or error
-     * @throws {anyhow::Error} If database operation fails
+     * @returns {Result<UndoState, anyhow::Error>} Labels of the next undo and redo steps
+     * @throws {anyhow::Error} If there is nothing to undo or the action can no longer be reverted
      */
-    pub async fn set_display_schedule(id: Option<i16>, pool: &DbPool) -> Result<(), anyhow::Error> {
+    pub async fn undo(pool: &DbPool) -> Result<UndoState, anyhow::Error> {
         let pool = pool.clone();
-        tokio::task::spawn_blocking(move || -> Result<(), anyhow::Error> {
-            let conn = pool.get()?;
-            conn.execute("UPDATE data SET schedule = ?1 WHERE id = ?2", params![id, DATA_TABLE_ID])?;
-            Ok(())
+        tokio::task::spawn_blocking(move || -> Result<UndoState, anyhow::Error> {
+            let mut conn = pool.get()?;
+            Self::step(&mut conn, true)
+        }).await?
+    }
+
+    /**
+     * Reapplies the active plan's most recently undone action
+     * 
+     * @param {&DbPool} pool - Database connection pool
+     * @returns {Result<UndoState, anyhow::Error>} Labels of the next undo and redo steps
+     * @throws {anyhow::Error} If there is nothing to redo or the action can no longer be applied
+     */
+    pub async fn redo(pool: &DbPool) -> Result<UndoState, anyhow::Error> {
+        let pool = pool.clone();
+        tokio::task::spawn_blocking(move || -> Result<UndoState, anyhow::Error> {
+            let mut conn = pool.get()?;
+            Self::step(&mut conn, false)
         }).await?
     }
+
+    /**
+     * Internal helper: Records an action of the active plan within an existing transaction
+     * Clears the redo stack and trims the log to UNDO_HISTORY_DEPTH entries
+     * 
+     * @param {&Transaction} tx - Active database transaction
+     * @param {String} label - Human readable description (e.g., "Delete event \"Lunch\"")
+     * @param {Vec<Operation>} forward - Operations the action applied, in order
+     * @param {Vec<Operation>} inverse - Operations that revert the action, in order
+     * @returns {Result<(), anyhow::Error>} Success or error
+     * @throws {anyhow::Error} If JSON serialization fails or SQL execution fails
+     */
+    fn record_in_transaction(tx: &Transaction, label: String, forward: Vec<Operation>, inverse: Vec<Operation>) -> Result<(), anyhow::Error> {
+        tx.execute(&format!("DELETE FROM operation_log WHERE plan_id = {ACTIVE_PLAN} AND undone = 1"), [])?;
+        tx.execute(
+            &format!(
+                "INSERT INTO operation_log (plan_id, created_at, label, forward, inverse)
+                 VALUES ({ACTIVE_PLAN}, CAST(strftime('%s', 'now') AS INTEGER), ?1, ?2, ?3)"
+            ),
+            params![label, serde_json::to_string(&forward)?, serde_json::to_string(&inverse)?]
+        )?;
+        tx.execute(
+            &format!(
+                "DELETE FROM operation_log WHERE plan_id = {ACTIVE_PLAN} AND seq NOT IN (
+                    SELECT seq FROM operation_log WHERE plan_id = {ACTIVE_PLAN} ORDER BY seq DESC LIMIT ?1
+                )"
+            ),
+            params![UNDO_HISTORY_DEPTH]
+        )?;
+        Ok(())
+    }
+
+    /**
+     * Internal helper: Performs one undo or redo step
+     * 
+     * An entry that can no longer be applied (e.g. the pinned schedule was regenerated
+     * away) is removed from the log after rolling back, so it doesn't block older entries.
+     * 
+     * @param {&mut Connection} conn - Open database connection
+     * @param {bool} undo - True to undo the newest action, false to redo the oldest undone one
+     * @returns {Result<UndoState, anyhow::Error>} Labels of the next undo and redo steps
+     * @throws {anyhow::Error} If there is no entry to apply or applying it fails
+     */
+    fn step(conn: &mut Connection, undo: bool) -> Result<UndoState, anyhow::Error> {
+        let (column, order, verb) = if undo { ("inverse", "DESC", "undo") } else { ("forward", "ASC", "redo") };
+        let tx = conn.transaction()?;
+        let entry: Option<(i64, String, String)> = tx.query_row(
+            &format!(
+                "SELECT seq, label, {column} FROM operation_log
+                 WHERE plan_id = {ACTIVE_PLAN} AND undone = ?1 ORDER BY seq {order} LIMIT 1"
+            ),
+            params![!undo],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        ).optional()?;
+        let Some((seq, label, operations)) = entry else {
+            return Err(anyhow!("Nothing to {verb}"));
+        };
+
+        let applied = serde_json::from_str::<Vec<Operation>>(&operations)
+            .map_err(anyhow::Error::from)
+            .and_then(|operations| Self::apply_in_transaction(&tx, &operations));
+        match applied {
+            Ok(()) => {
+                tx.execute("UPDATE operation_log SET undone = ?1 WHERE seq = ?2", params![undo, seq])?;
+                let state = Self::state(&tx)?;
+                tx.commit()?;
+                Ok(state)
+            }
+            Err(e) => {
+                drop(tx);
+                conn.execute("DELETE FROM operation_log WHERE seq = ?1", params![seq])?;
+                Err(anyhow!("Cannot {verb} \"{label}\" ({e}); it was removed from the history"))
+            }
+        }
+    }
+
+    /**
+     * Internal helper: Applies logged operations in order within an existing transaction
+     * 
+     * @param {&Transaction} tx - Active database transaction
+     * @param {&[Operation]} operations - Operations to apply
+     * @returns {Result<(), anyhow::Error>} Success or error
+     * @throws {anyhow::Error} If an operation's target no longer exists or SQL execution fails
+     */
+    fn apply_in_transaction(tx: &Transaction, operations: &[Operation]) -> Result<(), anyhow::Error> {
+        for operation in operations {
+            match operation {
+                Operation::PutEvent { table, event } => {
+                    validate_table_name(table)?;
+                    EventRepository::save_event_in_transaction(tx, table, event)?;
+                }
+                Operation::DeleteEvent { table, id } => {
+                    validate_table_name(table)?;
+                    EventRepository::delete_event_in_transaction(tx, table, id)?;
+                }
+                Operation::PutClassParam { class, position } => {
+                    ClassParamRepository::put_in_transaction(tx, class, *position)?;
+                }
+                Operation::DeleteClassParam { id } => ClassParamRepository::delete_in_transaction(tx, id)?,
+                Operation::AddFavorite { schedule, details } => {
+                    let id = ScheduleRepository::insert_schedule_in_transaction(tx, "favorites", schedule, &GenerationRepository::last_constraints_in_transaction(tx)?)?;
+                    if let Some(details) = details {
+                        FavoriteRepository::set_details_in_transaction(tx, &id, details)?;
+                    }
+                }
+                Operation::RemoveFavorite { id } => FavoriteRepository::change_status_in_transaction(tx, id, false)?,
+                Operation::SetFavoriteDetails { id, details } => FavoriteRepository::set_details_in_transaction(tx, id, details)?,
+                Operation::SetPin { pin } => SystemRepository::set_pin_in_transaction(tx, pin.as_ref())?,
+            }
+        }
+        Ok(())
+    }
+
+    /**
+     * Internal helper: Reads the labels of the next undo and redo steps
+     * 
+     * @param {&Connection} conn - Open database connection or transaction
+     * @returns {Result<UndoState, anyhow::Error>} Labels of the next undo and redo steps
+     * @throws {anyhow::Error} If SQL execution fails
+     */
+    fn state(conn: &Connection) -> Result<UndoState, anyhow::Error> {
+        let next_label = |undone: bool, order: &str| -> Result<Option<String>, anyhow::Error> {
+            Ok(conn.query_row(
+                &format!(
+                    "SELECT label FROM operation_log
+                     WHERE plan_id = {ACTIVE_PLAN} AND undone = ?1 ORDER BY seq {order} LIMIT 1"
+                ),
+                params![undone],
+                |row| row.get(0)
+            ).optional()?)
+        };
+        Ok(UndoState {
+            undo: next_label(false, "DESC")?,
+            redo: next_label(true, "ASC")?,
+        })
+    }
 }
 
 // === DATABASE INITIALIZATION ===
 
 /**
- * Initializes the database schema with all required tables and indexes
+ * Initializes the database schema by applying all pending migrations
  * 
- * Creates the following tables:
- * - data: System configuration (Chrome version, OS, selected schedule)
- * - events: User-defined calendar events
- * - favorites: User-favorited schedules
- * - schedules: Generated schedule combinations
- * - class_parameters: User-defined course parameters for generation
- * - classes: Scraped course data from university systems
- * 
- * Also creates performance indexes on frequently queried columns.
+ * The schema itself (tables and indexes) is defined by the ordered migrations in
+ * the migrations module; see `run_migrations` for versioning and backup behavior.
  * 
  * @param {&DbPool} pool - Database connection pool
  * @returns {Result<(), anyhow::Error>} Success or error
- * @throws {anyhow::Error} If database schema creation fails
+ * @throws {anyhow::Error} If database schema creation or migration fails
  */
 pub fn initialize_database(pool: &DbPool) -> Result<(), anyhow::Error> {
-    let conn = pool.get()?;
-    conn.execute_batch(
-        "BEGIN;
-        CREATE TABLE IF NOT EXISTS data (
-            id SMALLINT PRIMARY KEY, 
-            version TEXT, 
-            os TEXT, 
-            schedule SMALLINT
-        );
-        CREATE TABLE IF NOT EXISTS events (
-            id TEXT PRIMARY KEY, 
-            title TEXT NOT NULL, 
-            start_time INTEGER NOT NULL,
-            end_time INTEGER NOT NULL, 
-            day INTEGER NOT NULL, 
-            professor TEXT, 
-            description TEXT
-        );
-        CREATE TABLE IF NOT EXISTS favorites (
-            id TEXT PRIMARY KEY, 
-            data TEXT NOT NULL
-        );
-        CREATE TABLE IF NOT EXISTS schedules (
-            id TEXT PRIMARY KEY, 
-            data TEXT NOT NULL
-        );
-        CREATE TABLE IF NOT EXISTS class_parameters (
-            id TEXT PRIMARY KEY, 
-            data TEXT NOT NULL
-        );
-        CREATE TABLE IF NOT EXISTS classes (
-            id TEXT PRIMARY KEY, 
-            classname TEXT NOT NULL, 
-            data TEXT NOT NULL
-        );
-        CREATE INDEX IF NOT EXISTS idx_classes_classname ON classes(classname);
-        CREATE INDEX IF NOT EXISTS idx_events_day ON events(day);
-        COMMIT;"
-    )?;
-    Ok(())
+    run_migrations(pool)
 }
 
 // === CONNECTION INFO MANAGEMENT ===
@@ -658,6 +2624,7 @@ pub fn load_connect_info(pool: &DbPool, os: String) -> Result<ConnectInfo, anyho
         |row| Ok(ConnectInfo {
             version: row.get(0)?,
             os: row.get(1)?,
+            ..Default::default()
         })
     ).optional()?;
 
@@ -669,10 +2636,539 @@ pub fn load_connect_info(pool: &DbPool, os: String) -> Result<ConnectInfo, anyho
         None => {
             println!("No connect info in DB. Inserting initial data for os: {os}");
             conn.execute(
-                "INSERT INTO data (id, os, version, schedule) VALUES (?1, ?2, '', NULL)", 
+                "INSERT INTO data (id, os, version) VALUES (?1, ?2, '')", 
                 params![DATA_TABLE_ID, &os]
             )?;
-            Ok(ConnectInfo { os, version: String::new() })
+            Ok(ConnectInfo { os, version: String::new(), ..Default::default() })
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_support::{class, memory_pool, FRIDAY, MON_WED, TUE_THU},
+        Constraint, ScheduleFilter,
+    };
+
+    /**
+     * Caches three schedules and stores them as generated, returning their IDs in order:
+     * A meets Mon/Wed from 9:35, B Mon-Thu from 8:30 and C Mon/Wed/Fri from 10:40
+     */
+    async fn store_schedules(pool: &DbPool) -> Vec<String> {
+        store_scored_schedules(pool, ScheduleConstraints::default()).await
+    }
+
+    /** Like `store_schedules`, scoring the schedules against the given constraints */
+    async fn store_scored_schedules(pool: &DbPool, constraints: ScheduleConstraints) -> Vec<String> {
+        let shared = class("MA", "241", "002", "Jones,Amy", MON_WED, 1040, 1130);
+        let schedules = vec![
+            vec![class("CSC", "116", "001", "Smith,John", MON_WED, 935, 1025), shared.clone()],
+            vec![class("CSC", "116", "002", "Brown,Lee", TUE_THU, 830, 920), shared.clone()],
+            vec![class("CSC", "116", "003", "Smith,John", FRIDAY, 1300, 1350), shared.clone()],
+        ];
+        let sections: Vec<Class> = schedules.iter().map(|schedule| schedule[0].clone()).chain([shared]).collect();
+        ClassRepository::save_sections_batch(&[sections], pool).await.unwrap();
+        unit_of_work(pool, move |tx| ScheduleRepository::save_batch_in_transaction(tx, &schedules, &constraints)).await.unwrap()
+    }
+
+    /** Generation inputs without courses, told apart by the open-sections checkbox */
+    fn parameters(open_only: bool) -> ScrapeClassesParameters {
+        ScrapeClassesParameters {
+            params_checkbox: [open_only, false, false],
+            classes: Vec::new(),
+            events: Vec::new(),
+            selection_mode: Default::default(),
+            constraints: Default::default(),
+        }
+    }
+
+    /** Records a generation run of the given schedules and returns its ID */
+    async fn record_run(pool: &DbPool, parameters: ScrapeClassesParameters, schedule_ids: &[&String]) -> String {
+        let schedule_ids: Vec<String> = schedule_ids.iter().map(|id| id.to_string()).collect();
+        unit_of_work(pool, move |tx| GenerationRepository::record_run_in_transaction(tx, &parameters, &schedule_ids, Duration::ZERO))
+            .await
+            .unwrap()
+    }
+
+    /** Keeps only the given schedule among the generated results, as a later generation would */
+    async fn keep_only(pool: &DbPool, id: &str) {
+        let id = id.to_string();
+        unit_of_work(pool, move |tx| {
+            tx.execute("DELETE FROM schedules WHERE id != ?1", params![id])?;
+            Ok(())
+        }).await.unwrap();
+    }
+
+    /** Runs a query over the generated schedules and returns the page's IDs and the total */
+    async fn query(pool: &DbPool, sort: Option<ScheduleSort>, descending: bool, filter: ScheduleFilter) -> (Vec<String>, usize) {
+        let query = ScheduleQuery { table: "schedules".to_string(), offset: 0, limit: 50, sort, descending, filter };
+        let page = ScheduleRepository::query(query, pool).await.unwrap();
+        (page.schedules.into_iter().map(|schedule| schedule.id).collect(), page.total)
+    }
+
+    #[tokio::test]
+    async fn failed_unit_of_work_rolls_back_every_write() {
+        let pool = memory_pool();
+        let schedule = vec![class("CSC", "116", "001", "Smith,John", MON_WED, 935, 1025)];
+
+        let result = unit_of_work(&pool, move |tx| {
+            ScheduleRepository::save_batch_in_transaction(tx, &[schedule], &ScheduleConstraints::default())?;
+            PlanRepository::insert_plan_in_transaction(tx, "Spring")?;
+            tx.execute("INSERT INTO data (id, os, version, history_limit) VALUES (?1, 'linux', '', 3)", params![DATA_TABLE_ID])?;
+            Err::<(), _>(anyhow!("Interrupted"))
+        }).await;
+        assert_eq!(result.unwrap_err().to_string(), "Interrupted");
+
+        let conn = pool.get().unwrap();
+        for table in ["classes", "schedules", "schedule_sections", "schedule_metadata", "schedule_instructors", "data"] {
+            let count: i64 = conn.query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| row.get(0)).unwrap();
+            assert_eq!(count, 0, "{table} kept a row");
+        }
+        let plans: i64 = conn.query_row("SELECT COUNT(*) FROM plans", [], |row| row.get(0)).unwrap();
+        assert_eq!(plans, 1);
+    }
+
+    #[tokio::test]
+    async fn stored_schedules_are_verified_without_history() {
+        let pool = memory_pool();
+        unit_of_work(&pool, |tx| {
+            tx.execute("INSERT INTO data (id, os, version, history_limit) VALUES (?1, 'linux', '', 0)", params![DATA_TABLE_ID])?;
+            Ok(())
+        }).await.unwrap();
+        let ids = store_schedules(&pool).await;
+        assert!(!GenerationRepository::stored_schedules_complete(&pool).await.unwrap());
+
+        let parameters = parameters(false);
+        unit_of_work(&pool, move |tx| {
+            GenerationRepository::save_last_parameters_in_transaction(tx, &parameters)?;
+            GenerationRepository::record_run_in_transaction(tx, &parameters, &ids, Duration::ZERO)?;
+            Ok(())
+        }).await.unwrap();
+        // History is disabled, so no run backs the check
+        assert!(GenerationRepository::get_runs(&pool).await.unwrap().is_empty());
+        assert!(GenerationRepository::stored_schedules_complete(&pool).await.unwrap());
+
+        let deleted = ScheduleRepository::get_all("schedules", &pool).await.unwrap()[1].id.clone();
+        unit_of_work(&pool, move |tx| ScheduleRepository::delete_in_transaction(tx, &deleted)).await.unwrap();
+        assert!(!GenerationRepository::stored_schedules_complete(&pool).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn duplicate_copies_every_plan_owned_table() {
+        let pool = memory_pool();
+        let ids = store_schedules(&pool).await;
+        let event = NewEvent { title: "Work".to_string(), start_time: 1700, end_time: 2000, day: 1 << 2, professor: String::new(), description: String::new() };
+        EventRepository::save("events", event, &pool).await.unwrap();
+        let course = ClassParam { id: "c1".to_string(), code: "CSC".to_string(), name: "116".to_string(), section: String::new(), instructor: String::new() };
+        ClassParamRepository::update(course, &pool).await.unwrap();
+        FavoriteRepository::change_status(ids[0].clone(), true, &pool).await.unwrap();
+        FavoriteRepository::update_details(ids[0].clone(), FavoriteDetails { title: "Mornings".to_string(), ..Default::default() }, &pool).await.unwrap();
+        SystemRepository::set_display_schedule(Some(PinnedSchedule { id: ids[1].clone(), table: "schedules".to_string() }), &pool).await.unwrap();
+        let term = TermCalendar { start: "2026-08-17".to_string(), end: "2026-12-01".to_string(), holidays: vec!["2026-09-07".to_string()] };
+        PlanRepository::set_term(Some(term), &pool).await.unwrap();
+        let parameters = parameters(true);
+        unit_of_work(&pool, move |tx| GenerationRepository::save_last_parameters_in_transaction(tx, &parameters)).await.unwrap();
+
+        let original = PlanRepository::get_all(&pool).await.unwrap().remove(0);
+        let copy = PlanRepository::duplicate(original.id.clone(), "Copy".to_string(), &pool).await.unwrap();
+        assert!(!copy.active);
+
+        let mut conn = pool.get().unwrap();
+        let tx = conn.transaction().unwrap();
+        let copied_tables: Vec<&str> = PLAN_TABLES.iter().copied().chain(["last_generation"]).collect();
+        for table in &copied_tables {
+            let columns = PlanRepository::copied_columns(&tx, table).unwrap();
+            let rows = |plan_id: &str| -> Vec<String> {
+                tx.prepare(&format!("SELECT json_array({columns}) FROM {table} WHERE plan_id = ?1 ORDER BY rowid")).unwrap()
+                    .query_map(params![plan_id], |row| row.get(0)).unwrap()
+                    .collect::<Result<_, _>>().unwrap()
+            };
+            assert!(!rows(&original.id).is_empty(), "{table} has nothing to copy");
+            assert_eq!(rows(&copy.id), rows(&original.id), "{table} differs");
+        }
+        let plan_columns = |plan_id: &str| -> String {
+            tx.query_row(
+                "SELECT json_array(pinned_schedule_id, pinned_table, term_start, term_end, holidays) FROM plans WHERE id = ?1",
+                params![plan_id],
+                |row| row.get(0)
+            ).unwrap()
+        };
+        assert_eq!(plan_columns(&copy.id), plan_columns(&original.id));
+
+        // Any other table keyed by plan is deliberately left with the original
+        let mut plan_owned: Vec<String> = tx.prepare(
+            "SELECT m.name FROM sqlite_master m, pragma_table_info(m.name) p WHERE m.type = 'table' AND p.name = 'plan_id' ORDER BY m.name"
+        ).unwrap().query_map([], |row| row.get(0)).unwrap().collect::<Result<_, _>>().unwrap();
+        plan_owned.retain(|table| !copied_tables.contains(&table.as_str()));
+        assert_eq!(plan_owned, ["generation_runs", "operation_log"]);
+    }
+
+    #[tokio::test]
+    async fn delete_refuses_the_active_and_the_last_plan() {
+        let pool = memory_pool();
+        let only = PlanRepository::get_all(&pool).await.unwrap().remove(0);
+        let error = PlanRepository::delete(only.id.clone(), &pool).await.unwrap_err();
+        assert_eq!(error.to_string(), "Cannot delete the only plan");
+
+        let other = PlanRepository::create("Spring".to_string(), &pool).await.unwrap();
+        let error = PlanRepository::delete(only.id.clone(), &pool).await.unwrap_err();
+        assert!(error.to_string().starts_with("Cannot delete the active plan"));
+
+        PlanRepository::switch(other.id.clone(), &pool).await.unwrap();
+        PlanRepository::delete(only.id.clone(), &pool).await.unwrap();
+        let remaining = PlanRepository::get_all(&pool).await.unwrap();
+        assert_eq!(remaining.iter().map(|plan| plan.id.as_str()).collect::<Vec<_>>(), [other.id.as_str()]);
+    }
+
+    #[tokio::test]
+    async fn restore_run_brings_back_its_schedules_and_inputs() {
+        let pool = memory_pool();
+        let ids = store_schedules(&pool).await;
+        let run_id = record_run(&pool, parameters(true), &[&ids[1], &ids[0]]).await;
+
+        // A later generation leaves only C, pinned, with different inputs
+        keep_only(&pool, &ids[2]).await;
+        unit_of_work(&pool, |tx| GenerationRepository::save_last_parameters_in_transaction(tx, &parameters(false))).await.unwrap();
+        SystemRepository::set_display_schedule(Some(PinnedSchedule { id: ids[2].clone(), table: "schedules".to_string() }), &pool).await.unwrap();
+
+        GenerationRepository::restore_run(run_id, &pool).await.unwrap();
+
+        let restored: Vec<String> = ScheduleRepository::get_all("schedules", &pool).await.unwrap().into_iter().map(|schedule| schedule.id).collect();
+        assert_eq!(restored, [ids[1].clone(), ids[0].clone()]);
+        let last_parameters = GenerationRepository::load_last_parameters(&pool).await.unwrap().unwrap();
+        assert_eq!(last_parameters.params_checkbox, [true, false, false]);
+        assert!(GenerationRepository::stored_schedules_complete(&pool).await.unwrap());
+        // The pinned schedule is no longer among the results, and the run stays in the history
+        assert!(SystemRepository::get_display_schedule(&pool).await.unwrap().is_none());
+        assert_eq!(GenerationRepository::get_runs(&pool).await.unwrap().len(), 1);
+
+        let error = GenerationRepository::restore_run("missing".to_string(), &pool).await.unwrap_err();
+        assert_eq!(error.to_string(), "Generation run with id 'missing' not found");
+    }
+
+    #[tokio::test]
+    async fn delete_run_drops_only_sections_nothing_else_references() {
+        let pool = memory_pool();
+        let ids = store_schedules(&pool).await;
+        let first = record_run(&pool, parameters(false), &[&ids[0], &ids[1]]).await;
+        let second = record_run(&pool, parameters(true), &[&ids[1]]).await;
+        keep_only(&pool, &ids[2]).await;
+
+        GenerationRepository::delete_run(first, &pool).await.unwrap();
+
+        let runs = GenerationRepository::get_runs(&pool).await.unwrap();
+        assert_eq!(runs.iter().map(|run| &run.id).collect::<Vec<_>>(), [&second]);
+        let current = ScheduleRepository::get_all("schedules", &pool).await.unwrap();
+        assert_eq!(current.iter().map(|schedule| &schedule.id).collect::<Vec<_>>(), [&ids[2]]);
+
+        // A was only kept alive by the deleted run; B by the remaining one and C by the results
+        let referenced: Vec<String> = pool.get().unwrap().prepare("SELECT DISTINCT schedule_id FROM schedule_sections").unwrap()
+            .query_map([], |row| row.get(0)).unwrap()
+            .collect::<Result<_, _>>().unwrap();
+        assert!(!referenced.contains(&ids[0]));
+        assert!(referenced.contains(&ids[1]) && referenced.contains(&ids[2]));
+
+        // Deleting an unknown run changes nothing
+        GenerationRepository::delete_run("missing".to_string(), &pool).await.unwrap();
+        assert_eq!(GenerationRepository::get_runs(&pool).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn query_sorts_schedules() {
+        let pool = memory_pool();
+        let ids = store_schedules(&pool).await;
+        let ordered = |order: [usize; 3]| (order.map(|index| ids[index].clone()).to_vec(), 3);
+
+        assert_eq!(query(&pool, None, false, ScheduleFilter::default()).await, ordered([0, 1, 2]));
+        assert_eq!(query(&pool, None, true, ScheduleFilter::default()).await, ordered([2, 1, 0]));
+        assert_eq!(query(&pool, Some(ScheduleSort::StartTime), false, ScheduleFilter::default()).await, ordered([1, 0, 2]));
+        assert_eq!(query(&pool, Some(ScheduleSort::StartTime), true, ScheduleFilter::default()).await, ordered([2, 0, 1]));
+        assert_eq!(query(&pool, Some(ScheduleSort::DaysOnCampus), false, ScheduleFilter::default()).await, ordered([0, 2, 1]));
+
+        let second_page = ScheduleQuery {
+            table: "schedules".to_string(), offset: 1, limit: 1,
+            sort: Some(ScheduleSort::StartTime), descending: false, filter: ScheduleFilter::default(),
+        };
+        let page = ScheduleRepository::query(second_page, &pool).await.unwrap();
+        assert_eq!(page.schedules.iter().map(|schedule| &schedule.id).collect::<Vec<_>>(), [&ids[0]]);
+        assert_eq!(page.total, 3);
+        assert_eq!(page.schedules[0].classes.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn query_sorts_schedules_by_score() {
+        let pool = memory_pool();
+        // A has 100 minutes of class on Monday and C meets on Friday; B violates neither
+        let constraints = ScheduleConstraints {
+            max_daily_minutes: Some(Constraint { value: 60, hard: false }),
+            free_days: Some(Constraint { value: FRIDAY, hard: false }),
+            ..Default::default()
+        };
+        let ids = store_scored_schedules(&pool, constraints).await;
+        let ordered = |order: [usize; 3]| (order.map(|index| ids[index].clone()).to_vec(), 3);
+
+        assert_eq!(query(&pool, Some(ScheduleSort::Score), false, ScheduleFilter::default()).await, ordered([1, 0, 2]));
+        assert_eq!(query(&pool, Some(ScheduleSort::Score), true, ScheduleFilter::default()).await, ordered([0, 2, 1]));
+    }
+
+    #[tokio::test]
+    async fn query_filters_schedules() {
+        let pool = memory_pool();
+        let ids = store_schedules(&pool).await;
+
+        let including = ScheduleFilter { include_sections: vec!["CSC116/001".to_string(), "MA241/002".to_string()], ..Default::default() };
+        assert_eq!(query(&pool, None, false, including).await, (vec![ids[0].clone()], 1));
+
+        let excluding = ScheduleFilter { exclude_instructors: vec!["smith,john".to_string(), " ".to_string()], ..Default::default() };
+        assert_eq!(query(&pool, None, false, excluding).await, (vec![ids[1].clone()], 1));
+
+        // Names match whole, so part of a name excludes nothing
+        let partial = ScheduleFilter { exclude_instructors: vec!["Lee".to_string(), "Smith".to_string()], ..Default::default() };
+        assert_eq!(query(&pool, None, false, partial).await.1, 3);
+
+        let free_friday = ScheduleFilter { free_days: FRIDAY, ..Default::default() };
+        assert_eq!(query(&pool, None, false, free_friday).await, (vec![ids[0].clone(), ids[1].clone()], 2));
+
+        let by_id = ScheduleFilter { ids: vec![ids[2].clone(), ids[0].clone(), "0000000000000000".to_string()], ..Default::default() };
+        assert_eq!(query(&pool, None, false, by_id).await, (vec![ids[0].clone(), ids[2].clone()], 2));
+
+        let nothing = ScheduleFilter { include_sections: vec!["CSC116/001".to_string()], free_days: MON_WED, ..Default::default() };
+        assert_eq!(query(&pool, None, false, nothing).await, (Vec::new(), 0));
+    }
+
+    #[tokio::test]
+    async fn rescraped_sections_refresh_query_metadata() {
+        let pool = memory_pool();
+        let ids = store_schedules(&pool).await;
+
+        // Section 003 moves from Friday afternoon to Tuesday morning and changes instructor
+        let moved = class("CSC", "116", "003", "Green,Ann", [false, true, false, false, false], 800, 850);
+        ClassRepository::save_sections_batch(&[vec![moved]], &pool).await.unwrap();
+
+        let free_friday = ScheduleFilter { free_days: FRIDAY, ..Default::default() };
+        assert_eq!(query(&pool, None, false, free_friday).await.1, 3);
+        let excluding = ScheduleFilter { exclude_instructors: vec!["Smith,John".to_string()], ..Default::default() };
+        assert_eq!(query(&pool, None, false, excluding).await, (vec![ids[1].clone(), ids[2].clone()], 2));
+        assert_eq!(query(&pool, Some(ScheduleSort::StartTime), false, ScheduleFilter::default()).await.0[0], ids[2]);
+    }
+
+    #[tokio::test]
+    async fn query_skips_schedules_with_uncached_sections() {
+        let pool = memory_pool();
+        let ids = store_schedules(&pool).await;
+        pool.get().unwrap().execute("DELETE FROM classes WHERE id = 'CSC116/002'", []).unwrap();
+
+        assert_eq!(query(&pool, None, false, ScheduleFilter::default()).await, (vec![ids[0].clone(), ids[2].clone()], 2));
+    }
+
+    /** Pins the given generated schedule */
+    async fn pin(pool: &DbPool, id: &str) {
+        let pin = PinnedSchedule { id: id.to_string(), table: "schedules".to_string() };
+        SystemRepository::set_display_schedule(Some(pin), pool).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn pin_follows_its_schedule_rather_than_its_position() {
+        let pool = memory_pool();
+        let ids = store_schedules(&pool).await;
+        pin(&pool, &ids[1]).await;
+
+        // B moves from the second to the first position, as after a regeneration
+        keep_only(&pool, &ids[1]).await;
+        let pinned = SystemRepository::get_display_schedule(&pool).await.unwrap().unwrap();
+        assert_eq!(pinned.id, ids[1]);
+        assert_eq!(pinned.table, "schedules");
+
+        let missing = PinnedSchedule { id: ids[0].clone(), table: "schedules".to_string() };
+        assert!(SystemRepository::set_display_schedule(Some(missing), &pool).await.is_err());
+        let invalid = PinnedSchedule { id: ids[1].clone(), table: "events".to_string() };
+        assert!(SystemRepository::set_display_schedule(Some(invalid), &pool).await.is_err());
+        assert_eq!(SystemRepository::get_display_schedule(&pool).await.unwrap().unwrap().id, ids[1]);
+    }
+
+    #[tokio::test]
+    async fn stale_pins_are_cleared_and_reported() {
+        let pool = memory_pool();
+        let ids = store_schedules(&pool).await;
+
+        let cleared = unit_of_work(&pool, SystemRepository::clear_stale_pin_in_transaction).await.unwrap();
+        assert_eq!(cleared, None);
+
+        pin(&pool, &ids[0]).await;
+        let cleared = unit_of_work(&pool, SystemRepository::clear_stale_pin_in_transaction).await.unwrap();
+        assert_eq!(cleared, None);
+        assert!(SystemRepository::get_display_schedule(&pool).await.unwrap().is_some());
+
+        keep_only(&pool, &ids[1]).await;
+        let cleared = unit_of_work(&pool, SystemRepository::clear_stale_pin_in_transaction).await.unwrap();
+        assert_eq!(cleared, Some(PinnedSchedule { id: ids[0].clone(), table: "schedules".to_string() }));
+        let stored: Option<String> = pool.get().unwrap()
+            .query_row("SELECT pinned_schedule_id FROM plans WHERE active = 1", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(stored, None);
+
+        // Reading a pin whose schedule was removed clears it too
+        pin(&pool, &ids[1]).await;
+        keep_only(&pool, &ids[0]).await;
+        assert_eq!(SystemRepository::get_display_schedule(&pool).await.unwrap(), None);
+    }
+
+    /** Current undo and redo labels */
+    async fn undo_labels(pool: &DbPool) -> (Option<String>, Option<String>) {
+        let state = UndoRepository::get_state(pool).await.unwrap();
+        (state.undo, state.redo)
+    }
+
+    #[tokio::test]
+    async fn undo_and_redo_revert_event_and_course_changes() {
+        let pool = memory_pool();
+        assert_eq!(undo_labels(&pool).await, (None, None));
+        assert!(UndoRepository::undo(&pool).await.is_err());
+
+        let lunch = NewEvent { title: "Lunch".to_string(), start_time: 1200, end_time: 1300, day: 2, professor: String::new(), description: String::new() };
+        let event = EventRepository::save("events", lunch, &pool).await.unwrap();
+        EventRepository::delete("events", event.id.clone(), &pool).await.unwrap();
+        let course = ClassParam { id: "c1".to_string(), code: "CSC".to_string(), name: "116".to_string(), section: String::new(), instructor: String::new() };
+        ClassParamRepository::update(course, &pool).await.unwrap();
+        ClassParamRepository::remove("c1".to_string(), &pool).await.unwrap();
+        assert_eq!(undo_labels(&pool).await, (Some("Remove course CSC 116".to_string()), None));
+
+        UndoRepository::undo(&pool).await.unwrap();
+        assert_eq!(ClassParamRepository::get_all(&pool).await.unwrap().len(), 1);
+        UndoRepository::undo(&pool).await.unwrap();
+        assert!(ClassParamRepository::get_all(&pool).await.unwrap().is_empty());
+        let state = UndoRepository::undo(&pool).await.unwrap();
+        assert_eq!(state.undo, Some("Add event \"Lunch\"".to_string()));
+        let events = EventRepository::load_all("events", &pool).await.unwrap();
+        assert_eq!(events.iter().map(|event| event.id.as_str()).collect::<Vec<_>>(), [event.id.as_str()]);
+
+        // Redo reapplies the oldest undone action first
+        let state = UndoRepository::redo(&pool).await.unwrap();
+        assert!(EventRepository::load_all("events", &pool).await.unwrap().is_empty());
+        assert_eq!(state.redo, Some("Add course CSC 116".to_string()));
+
+        // A new action discards what could still be redone
+        let dinner = NewEvent { title: "Dinner".to_string(), start_time: 1800, end_time: 1900, day: 2, professor: String::new(), description: String::new() };
+        EventRepository::save("events", dinner, &pool).await.unwrap();
+        assert_eq!(undo_labels(&pool).await, (Some("Add event \"Dinner\"".to_string()), None));
+        assert!(UndoRepository::redo(&pool).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn entries_that_can_no_longer_be_applied_are_dropped() {
+        let pool = memory_pool();
+        let ids = store_schedules(&pool).await;
+        pin(&pool, &ids[0]).await;
+        pin(&pool, &ids[1]).await;
+
+        // Undoing the second pin would restore the first, whose schedule is gone
+        keep_only(&pool, &ids[1]).await;
+        let Err(error) = UndoRepository::undo(&pool).await else {
+            panic!("undo should fail when its schedule is gone");
+        };
+        assert!(error.to_string().contains("removed from the history"), "{error}");
+        assert_eq!(SystemRepository::get_display_schedule(&pool).await.unwrap().unwrap().id, ids[1]);
+        assert_eq!(undo_labels(&pool).await, (Some("Pin schedule".to_string()), None));
+
+        // The older entry is still usable
+        UndoRepository::undo(&pool).await.unwrap();
+        assert_eq!(SystemRepository::get_display_schedule(&pool).await.unwrap(), None);
+        assert_eq!(undo_labels(&pool).await, (None, Some("Pin schedule".to_string())));
+    }
+
+    #[tokio::test]
+    async fn undo_history_is_bounded() {
+        let pool = memory_pool();
+        for index in 0..UNDO_HISTORY_DEPTH + 5 {
+            let course = ClassParam { id: format!("c{index}"), code: "CSC".to_string(), name: index.to_string(), section: String::new(), instructor: String::new() };
+            ClassParamRepository::update(course, &pool).await.unwrap();
+        }
+        let logged: i64 = pool.get().unwrap().query_row("SELECT COUNT(*) FROM operation_log", [], |row| row.get(0)).unwrap();
+        assert_eq!(logged, UNDO_HISTORY_DEPTH);
+
+        for _ in 0..UNDO_HISTORY_DEPTH {
+            UndoRepository::undo(&pool).await.unwrap();
+        }
+        assert!(UndoRepository::undo(&pool).await.is_err());
+        assert_eq!(ClassParamRepository::get_all(&pool).await.unwrap().len(), 5);
+    }
+
+    /** Annotations with the given title, color and tags */
+    fn details(title: &str, color: Option<&str>, tags: &[&str]) -> FavoriteDetails {
+        FavoriteDetails {
+            title: title.to_string(),
+            notes: String::new(),
+            color: color.map(str::to_string),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+        }
+    }
+
+    #[tokio::test]
+    async fn favorite_details_are_cleaned_up_and_validated() {
+        let pool = memory_pool();
+        let ids = store_schedules(&pool).await;
+        FavoriteRepository::change_status(ids[0].clone(), true, &pool).await.unwrap();
+
+        let entered = FavoriteDetails {
+            notes: "Backup plan\n\n".to_string(),
+            ..details("  Mornings off ", Some(" #3B82F6 "), &["safe option", " ", "Safe Option", "if I get off waitlist "])
+        };
+        let stored = FavoriteRepository::update_details(ids[0].clone(), entered, &pool).await.unwrap();
+        assert_eq!(stored, FavoriteDetails {
+            notes: "Backup plan".to_string(),
+            ..details("Mornings off", Some("#3b82f6"), &["safe option", "if I get off waitlist"])
+        });
+
+        let favorites = ScheduleRepository::get_all("favorites", &pool).await.unwrap();
+        assert_eq!(favorites[0].details.as_ref(), Some(&stored));
+
+        for invalid in [
+            details("", Some("blue"), &[]),
+            details("", Some("#3b82f"), &[]),
+            details(&"x".repeat(MAX_FAVORITE_TITLE_LENGTH + 1), None, &[]),
+            details("", None, &[&"x".repeat(MAX_FAVORITE_TAG_LENGTH + 1)]),
+        ] {
+            assert!(FavoriteRepository::update_details(ids[0].clone(), invalid, &pool).await.is_err());
+        }
+        assert!(FavoriteRepository::update_details(ids[1].clone(), details("B", None, &[]), &pool).await.is_err());
+        assert_eq!(ScheduleRepository::get_all("favorites", &pool).await.unwrap()[0].details.as_ref(), Some(&stored));
+    }
+
+    #[tokio::test]
+    async fn favorite_tags_are_listed_once_in_alphabetical_order() {
+        let pool = memory_pool();
+        let ids = store_schedules(&pool).await;
+        for (id, tags) in ids.iter().zip([&["waitlist", "Safe option"][..], &["safe option", "mornings"], &[]]) {
+            FavoriteRepository::change_status(id.clone(), true, &pool).await.unwrap();
+            FavoriteRepository::update_details(id.clone(), details("", None, tags), &pool).await.unwrap();
+        }
+
+        assert_eq!(FavoriteRepository::get_tags(&pool).await.unwrap(), ["mornings", "safe option", "Safe option", "waitlist"]);
+    }
+
+    #[tokio::test]
+    async fn undoing_a_removal_restores_the_favorite_with_its_details() {
+        let pool = memory_pool();
+        let ids = store_schedules(&pool).await;
+        FavoriteRepository::change_status(ids[2].clone(), true, &pool).await.unwrap();
+        let stored = FavoriteRepository::update_details(ids[2].clone(), details("Fridays", Some("#16a34a"), &["safe option"]), &pool)
+            .await
+            .unwrap();
+
+        FavoriteRepository::change_status(ids[2].clone(), false, &pool).await.unwrap();
+        assert!(ScheduleRepository::get_all("favorites", &pool).await.unwrap().is_empty());
+
+        UndoRepository::undo(&pool).await.unwrap();
+        let favorites = ScheduleRepository::get_all("favorites", &pool).await.unwrap();
+        assert_eq!(favorites.len(), 1);
+        assert_eq!(favorites[0].id, ids[2]);
+        assert_eq!(favorites[0].details.as_ref(), Some(&stored));
+
+        // Undoing the edit brings back the empty annotations
+        UndoRepository::undo(&pool).await.unwrap();
+        let favorites = ScheduleRepository::get_all("favorites", &pool).await.unwrap();
+        assert_eq!(favorites[0].details, Some(FavoriteDetails::default()));
+    }
+}
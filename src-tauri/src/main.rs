@@ -11,21 +11,40 @@
 // === MODULE IMPORTS ===
 mod tauri_backend;
 mod database_functions;
+mod migrations;
+mod backup;
+mod calendar;
+mod share_code;
+mod schedule_image;
+mod schedule_export;
+mod class_import;
+mod storage;
 mod services;
 mod objects;
+#[cfg(test)]
+mod test_support;
 
 use database_functions::*;
 use tauri::{Manager, Window};
-use tauri_backend::{scrape_classes::{setup_scrape}, event_processor::{EventProcessor, ProcessedEventsResult}};
+use tauri_backend::{scrape_classes::{alternatives_search, scrape_sections, setup_scrape}, class_combinations::{find_section_alternatives, group_by_footprint}, event_processor::{EventProcessor, ProcessedEventsResult, ViewWindow}};
 use services::*;
+use backup::{ensure_daily_backup, export_archive, import_archive};
+use calendar::{export_schedule_calendar, import_calendar};
+use share_code::{decode_schedule_code, encode_schedule_code};
+use schedule_image::{export_schedule_image, render_stored_schedule};
+use schedule_export::export_schedule_table;
+use class_import::{import_class_file, import_class_list};
+use storage::{migrate_legacy_storage, resolve_storage_paths, save_data_dir_override};
 use objects::*;
 
-use std::{env};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use anyhow::{Result};
 
+/** How often the daily backup task checks whether today's backup exists */
+const BACKUP_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
 // === APPLICATION LIFECYCLE COMMANDS ===
 
 /**
@@ -36,6 +55,7 @@ use anyhow::{Result};
  * 2. Sets up Chrome/ChromeDriver resources
  * 3. Initializes database connections and version tracking
  * 4. Updates shared application state
+ * 5. Starts the daily database backup task
  * 
  * Uses compare_exchange to prevent multiple concurrent startup attempts.
  * 
@@ -51,6 +71,17 @@ async fn startup_app(state: tauri::State<'_, AppState>) -> Result<(), String> {
         return Ok(());
     }
     println!("Starting up the application backend...");
+
+    // Take a backup once per day for as long as the app is running
+    let backup_pool = state.db_pool.clone();
+    tokio::spawn(async move {
+        loop {
+            if let Err(e) = ensure_daily_backup(&backup_pool).await {
+                eprintln!("Warning: Daily database backup failed: {e}");
+            }
+            tokio::time::sleep(BACKUP_CHECK_INTERVAL).await;
+        }
+    });
     
     // Delegate to services module for Chrome/ChromeDriver setup
     setup_program(&state.db_pool, state.connect_info.clone()).await
@@ -90,6 +121,34 @@ fn show_splashscreen(window: Window) {
     }
 }
 
+/**
+ * Gets the directories and files Koka stores its data in
+ * 
+ * @param {tauri::State<AppState>} state - Application state holding the resolved paths
+ * @returns {StoragePaths} Paths resolved at startup
+ */
+#[tauri::command]
+fn get_storage_paths(state: tauri::State<'_, AppState>) -> StoragePaths {
+    state.storage.clone()
+}
+
+/**
+ * Sets or clears the directory used for the database and Chrome resources
+ * 
+ * Takes effect on the next launch. The KOKA_DATA_DIR environment variable still
+ * takes precedence over this setting.
+ * 
+ * @param {Option<String>} path - Absolute directory path, or None to use the platform defaults
+ * @param {tauri::State<AppState>} state - Application state holding the settings file location
+ * @returns {Result<(), String>} Success or error message
+ * @throws {String} If the path isn't absolute or the setting can't be saved
+ */
+#[tauri::command]
+fn set_storage_override(path: Option<String>, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    save_data_dir_override(&state.storage.settings_file, path.map(std::path::PathBuf::from))
+        .map_err(|e| format!("Failed to save storage location: {e}"))
+}
+
 // === SCHEDULE GENERATION AND MANAGEMENT COMMANDS ===
 
 /**
@@ -107,11 +166,11 @@ fn show_splashscreen(window: Window) {
  * @param {Vec<bool>} parameters.params_checkbox - Generation constraints (open sections, waitlist OK, etc.)
  * @param {Vec<Event>} parameters.events - User-defined events to avoid conflicts
  * @param {tauri::State<AppState>} state - Application state for database and Chrome access
- * @returns {Result<Vec<Vec<Class>>, String>} Generated schedules or error message
+ * @returns {Result<Vec<StoredSchedule>, String>} Generated schedules with their content-hash IDs or error message
  * @throws {String} If web scraping fails, no valid schedules found, or database errors occur
  */
 #[tauri::command]
-async fn generate_schedules(parameters: ScrapeClassesParameters, state: tauri::State<'_, AppState>) -> Result<Vec<Vec<Class>>, String> {
+async fn generate_schedules(parameters: ScrapeClassesParameters, state: tauri::State<'_, AppState>) -> Result<Vec<StoredSchedule>, String> {
     setup_scrape(parameters, state).await
         .map_err(|err| err.to_string())
 }
@@ -119,12 +178,12 @@ async fn generate_schedules(parameters: ScrapeClassesParameters, state: tauri::S
 /**
  * Deletes a schedule from both regular schedules and favorites
  * 
- * This command handles the complete schedule deletion process:
+ * This command handles the complete schedule deletion process atomically:
  * 1. Removes from favorites table if currently favorited
  * 2. Removes from main schedules table
- * 3. Ensures referential integrity across tables
+ * 3. Ensures referential integrity across tables; a failure leaves both tables unchanged
  * 
- * @param {String} id - Unique schedule identifier (stringified schedule data)
+ * @param {String} id - Content-hash schedule identifier
  * @param {bool} is_favorited - Whether the schedule is currently in favorites
  * @param {tauri::State<AppState>} state - Application state for database access
  * @returns {Result<(), String>} Success or error message
@@ -132,15 +191,15 @@ async fn generate_schedules(parameters: ScrapeClassesParameters, state: tauri::S
  */
 #[tauri::command]
 async fn delete_schedule(id: String, is_favorited: bool, state: tauri::State<'_, AppState>) -> Result<(), String> {
-    // Remove from favorites first if needed
-    if is_favorited {
-        FavoriteRepository::change_status(id.clone(), None, &state.db_pool).await
-            .map_err(|e| format!("Failed to remove from favorites: {e}"))?;
-    }
-    
-    // Remove from main schedules table
-    ScheduleRepository::delete(id, &state.db_pool).await
-        .map_err(|e| format!("Failed to delete from schedules: {e}"))
+    unit_of_work(&state.db_pool, move |tx| {
+        // Remove from favorites first if needed
+        if is_favorited {
+            FavoriteRepository::change_status_in_transaction(tx, &id, false)?;
+        }
+        // Remove from main schedules table
+        ScheduleRepository::delete_in_transaction(tx, &id)
+    }).await
+        .map_err(|e| format!("Failed to delete schedule: {e}"))
 }
 
 /**
@@ -148,61 +207,225 @@ async fn delete_schedule(id: String, is_favorited: bool, state: tauri::State<'_,
  * 
  * This command manages the favorites system:
  * 1. If currently favorited: removes from favorites table
- * 2. If not favorited: adds to favorites table with full schedule data
- * 3. Maintains schedule data integrity for favorites restoration
+ * 2. If not favorited: adds the stored schedule's ID to favorites table
+ * 3. Section data stays shared with the schedules table, so favorites survive rescrapes
  * 
- * @param {String} id - Unique schedule identifier
+ * @param {String} id - Content-hash schedule identifier
  * @param {bool} is_favorited - Current favorite status (true = remove, false = add)
- * @param {Vec<Class>} schedule - Complete schedule data for favorites storage
  * @param {tauri::State<AppState>} state - Application state for database access
  * @returns {Result<(), String>} Success or error message
- * @throws {String} If database operation fails
+ * @throws {String} If the schedule doesn't exist or database operation fails
  */
 #[tauri::command]
-async fn change_favorite_schedule(id: String, is_favorited: bool, schedule: Vec<Class>, state: tauri::State<'_, AppState>) -> Result<(), String> {
-    // Determine operation: None = remove from favorites, Some(schedule) = add to favorites
-    let schedule_option = if is_favorited { None } else { Some(schedule) };
-    FavoriteRepository::change_status(id, schedule_option, &state.db_pool).await
+async fn change_favorite_schedule(id: String, is_favorited: bool, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    FavoriteRepository::change_status(id, !is_favorited, &state.db_pool).await
         .map_err(|e| format!("Failed to change favorite status: {e}"))
 }
 
+/**
+ * Sets the title, notes, color and tags of a favorite schedule
+ * 
+ * @param {String} id - Content-hash schedule identifier
+ * @param {FavoriteDetails} details - New annotations, replacing the previous ones
+ * @param {tauri::State<AppState>} state - Application state for database access
+ * @returns {Result<FavoriteDetails, String>} Annotations as stored, trimmed and deduplicated
+ * @throws {String} If the schedule isn't a favorite, validation fails, or database operation fails
+ */
+#[tauri::command]
+async fn update_favorite_details(id: String, details: FavoriteDetails, state: tauri::State<'_, AppState>) -> Result<FavoriteDetails, String> {
+    FavoriteRepository::update_details(id, details, &state.db_pool).await
+        .map_err(|e| format!("Failed to update favorite details: {e}"))
+}
+
+/**
+ * Lists the tags used on the active plan's favorites
+ * 
+ * @param {tauri::State<AppState>} state - Application state for database access
+ * @returns {Result<Vec<String>, String>} Distinct tags in alphabetical order
+ * @throws {String} If database query fails
+ */
+#[tauri::command]
+async fn get_favorite_tags(state: tauri::State<'_, AppState>) -> Result<Vec<String>, String> {
+    FavoriteRepository::get_tags(&state.db_pool).await
+        .map_err(|e| format!("Failed to get favorite tags: {e}"))
+}
+
 /**
  * Retrieves all schedules from a specified table
  * 
  * @param {String} table - Table name ("schedules" or "favorites")
  * @param {tauri::State<AppState>} state - Application state for database access
- * @returns {Result<Vec<Vec<Class>>, String>} Array of schedules or error message
+ * @returns {Result<Vec<StoredSchedule>, String>} Array of schedules with their IDs or error message
  * @throws {String} If database query fails or table doesn't exist
  */
 #[tauri::command]
-async fn get_schedules(table: String, state: tauri::State<'_, AppState>) -> Result<Vec<Vec<Class>>, String> {
+async fn get_schedules(table: String, state: tauri::State<'_, AppState>) -> Result<Vec<StoredSchedule>, String> {
     ScheduleRepository::get_all(&table, &state.db_pool).await
         .map_err(|e| format!("Failed to get schedules: {e}"))
 }
 
 /**
- * Gets the currently selected/pinned schedule index
+ * Retrieves one page of schedules, filtered and sorted in the backend
  * 
+ * Unlike get_schedules, only the requested page is deserialized and sent, so
+ * large result sets can be browsed without loading them all at once.
+ * 
+ * @param {ScheduleQuery} query - Table, offset/limit, sort key and filter
  * @param {tauri::State<AppState>} state - Application state for database access
- * @returns {Result<Option<i16>, String>} Schedule index or None if no schedule selected
+ * @returns {Result<SchedulePage, String>} Page of schedules with the total match count or error message
+ * @throws {String} If database query fails or table doesn't exist
+ */
+#[tauri::command]
+async fn query_schedules(query: ScheduleQuery, state: tauri::State<'_, AppState>) -> Result<SchedulePage, String> {
+    ScheduleRepository::query(query, &state.db_pool).await
+        .map_err(|e| format!("Failed to query schedules: {e}"))
+}
+
+/**
+ * Retrieves schedules from a table grouped by weekly time footprint
+ * 
+ * Schedules that differ only by interchangeable sections (same meeting times, different
+ * instructor or room) are collapsed into one representative with the other sections
+ * listed as alternatives, so the result list only contains genuinely different weeks.
+ * 
+ * @param {String} table - Table name ("schedules" or "favorites")
+ * @param {tauri::State<AppState>} state - Application state for database access
+ * @returns {Result<Vec<ScheduleGroup>, String>} Grouped schedules or error message
+ * @throws {String} If database query fails or table doesn't exist
+ */
+#[tauri::command]
+async fn get_schedule_groups(table: String, state: tauri::State<'_, AppState>) -> Result<Vec<ScheduleGroup>, String> {
+    let schedules = ScheduleRepository::get_all(&table, &state.db_pool).await
+        .map_err(|e| format!("Failed to get schedules: {e}"))?;
+    Ok(group_by_footprint(schedules.into_iter().map(|schedule| schedule.classes).collect()))
+}
+
+/**
+ * Lists other sections of one course that fit into an existing schedule
+ * 
+ * This command supports registration when a chosen section fills up:
+ * 1. Loads every cached section of the selected course; seat status isn't cached, so
+ *    with "open sections only" set the course is searched again with the same filters
+ *    generation uses
+ * 2. Keeps sections compatible with the rest of the schedule and the blocking events
+ * 3. Ranks them by the soft daily-structure constraints
+ * 
+ * @param {SectionAlternativesParameters} parameters - Schedule, course position, blocking events, search filters and constraints
+ * @param {tauri::State<AppState>} state - Application state for database access and scraping
+ * @returns {Result<Vec<SectionAlternative>, String>} Fitting sections ranked best first or error message
+ * @throws {String} If the course index is invalid, the cache query fails or the search fails
+ */
+#[tauri::command]
+async fn find_alternative_sections(parameters: SectionAlternativesParameters, state: tauri::State<'_, AppState>) -> Result<Vec<SectionAlternative>, String> {
+    let course = parameters.schedule.get(parameters.course_index)
+        .ok_or_else(|| format!("Schedule has no course at index {}", parameters.course_index))?;
+    let candidates = if let Some(search) = alternatives_search(&parameters) {
+        scrape_sections(&search, &state.connect_info, &state.db_pool).await
+            .map_err(|e| format!("Failed to search open sections: {e}"))?
+            .into_iter()
+            .flatten()
+            .collect()
+    } else {
+        ClassRepository::get_by_name(format!("{}{}", course.code, course.name), &state.db_pool).await
+            .map_err(|e| format!("Failed to load cached sections: {e}"))?
+    };
+    Ok(find_section_alternatives(&parameters.schedule, parameters.course_index, candidates, &parameters.events, &parameters.constraints))
+}
+
+/**
+ * Gets the currently pinned schedule
+ * 
+ * @param {tauri::State<AppState>} state - Application state for database access
+ * @returns {Result<Option<PinnedSchedule>, String>} Pinned schedule ID and table, or None if no schedule is pinned
  * @throws {String} If database query fails
  */
 #[tauri::command]
-async fn get_display_schedule(state: tauri::State<'_, AppState>) -> Result<Option<i16>, String> {
+async fn get_display_schedule(state: tauri::State<'_, AppState>) -> Result<Option<PinnedSchedule>, String> {
     SystemRepository::get_display_schedule(&state.db_pool).await.map_err(|e| e.to_string())
 }
 
 /**
- * Sets the currently selected/pinned schedule
+ * Pins a schedule so it is shown by default, or clears the pin
+ * 
+ * @param {Option<PinnedSchedule>} pin - Schedule ID and table to pin, or None to unpin
+ * @param {tauri::State<AppState>} state - Application state for database access
+ * @returns {Result<(), String>} Success or error message
+ * @throws {String} If the schedule doesn't exist or database update fails
+ */
+#[tauri::command]
+async fn set_display_schedule(pin: Option<PinnedSchedule>, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    SystemRepository::set_display_schedule(pin, &state.db_pool).await.map_err(|e| e.to_string())
+}
+
+/**
+ * Lists the active plan's recorded generation runs, newest first
+ * 
+ * @param {tauri::State<AppState>} state - Application state for database access
+ * @returns {Result<Vec<GenerationRun>, String>} Runs with their inputs, counts and durations or error message
+ * @throws {String} If database query fails
+ */
+#[tauri::command]
+async fn get_generation_runs(state: tauri::State<'_, AppState>) -> Result<Vec<GenerationRun>, String> {
+    GenerationRepository::get_runs(&state.db_pool).await
+        .map_err(|e| format!("Failed to get generation history: {e}"))
+}
+
+/**
+ * Replaces the current schedules with those of a recorded run
+ * 
+ * @param {String} id - Run to restore
+ * @param {tauri::State<AppState>} state - Application state for database access
+ * @returns {Result<Vec<StoredSchedule>, String>} Restored schedules with their IDs or error message
+ * @throws {String} If the run doesn't exist or database operation fails
+ */
+#[tauri::command]
+async fn restore_generation_run(id: String, state: tauri::State<'_, AppState>) -> Result<Vec<StoredSchedule>, String> {
+    GenerationRepository::restore_run(id, &state.db_pool).await
+        .map_err(|e| format!("Failed to restore generation run: {e}"))?;
+    ScheduleRepository::get_all("schedules", &state.db_pool).await
+        .map_err(|e| format!("Failed to load restored schedules: {e}"))
+}
+
+/**
+ * Deletes a recorded run from the generation history
  * 
- * @param {Option<i16>} id - Schedule index to pin, or None to unpin
+ * @param {String} id - Run to delete
+ * @param {tauri::State<AppState>} state - Application state for database access
+ * @returns {Result<(), String>} Success or error message
+ * @throws {String} If database deletion fails
+ */
+#[tauri::command]
+async fn delete_generation_run(id: String, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    GenerationRepository::delete_run(id, &state.db_pool).await
+        .map_err(|e| format!("Failed to delete generation run: {e}"))
+}
+
+/**
+ * Gets how many generation runs are kept per plan
+ * The limit is one application-wide setting that applies to every plan
+ * 
+ * @param {tauri::State<AppState>} state - Application state for database access
+ * @returns {Result<u32, String>} Retention limit or error message
+ * @throws {String} If database query fails
+ */
+#[tauri::command]
+async fn get_history_limit(state: tauri::State<'_, AppState>) -> Result<u32, String> {
+    GenerationRepository::get_history_limit(&state.db_pool).await
+        .map_err(|e| format!("Failed to get history limit: {e}"))
+}
+
+/**
+ * Sets how many generation runs are kept per plan, pruning older runs of every plan
+ * 
+ * @param {u32} limit - Number of most recent runs to keep (0 disables history)
  * @param {tauri::State<AppState>} state - Application state for database access
  * @returns {Result<(), String>} Success or error message
  * @throws {String} If database update fails
  */
 #[tauri::command]
-async fn set_display_schedule(id: Option<i16>, state: tauri::State<'_, AppState>) -> Result<(), String> {
-    SystemRepository::set_display_schedule(id, &state.db_pool).await.map_err(|e| e.to_string())
+async fn set_history_limit(limit: u32, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    GenerationRepository::set_history_limit(limit, &state.db_pool).await
+        .map_err(|e| format!("Failed to set history limit: {e}"))
 }
 
 // === EVENT MANAGEMENT COMMANDS ===
@@ -239,20 +462,30 @@ async fn create_event(event_data: NewEvent, state: tauri::State<'_, AppState>) -
  * 1. Loads raw events from database
  * 2. Processes events into calendar-friendly format
  * 3. Organizes events by day and time category (timed vs no-time)
- * 4. Calculates positioning data for calendar rendering
+ * 4. Calculates positioning data within the visible hours, clamping and flagging
+ *    events that extend past them
+ * 
+ * Meetings of a previewed schedule are processed alongside, so the fitted window
+ * covers them too and the calendar grid needs no positioning of its own.
  * 
+ * @param {Option<ViewWindow>} view_window - Hours to show; fitted around the events when omitted
+ * @param {Option<Vec<Event>>} preview_events - Meetings of the schedule being previewed, flagged `isPreview` in the result
  * @param {tauri::State<AppState>} state - Application state for database access
- * @returns {Result<ProcessedEventsResult, String>} Processed events organized for calendar or error
- * @throws {String} If database query fails or event processing fails
+ * @returns {Result<ProcessedEventsResult, String>} Processed events and the effective window, or error
+ * @throws {String} If the window is invalid, database query fails or event processing fails
  */
 #[tauri::command]
-async fn get_events(state: tauri::State<'_, AppState>) -> Result<ProcessedEventsResult, String> {
+async fn get_events(view_window: Option<ViewWindow>, preview_events: Option<Vec<Event>>, state: tauri::State<'_, AppState>) -> Result<ProcessedEventsResult, String> {
+    if let Some(window) = &view_window {
+        window.validate().map_err(|e| format!("Invalid view window: {e}"))?;
+    }
+
     // Load raw events from database
     let raw_events = EventRepository::load_all("events", &state.db_pool).await
         .map_err(|e| format!("Failed to load events: {e}"))?;
     
     // Process events for calendar display (organize by day, calculate positions, etc.)
-    let processed_events = EventProcessor::process_events(raw_events);
+    let processed_events = EventProcessor::process_events(raw_events, preview_events.unwrap_or_default(), view_window);
     Ok(processed_events)
 }
 
@@ -330,6 +563,358 @@ async fn remove_class(id: String, state: tauri::State<'_, AppState>) -> Result<(
         .map_err(|e| format!("Failed to remove class: {e}"))
 }
 
+/**
+ * Adds the courses of a pasted list or CSV to the class parameters
+ * 
+ * Accepts free-form text ("CSC 316 001, MA 305, ST 370 Smith") or CSV with a header row.
+ * The additions form a single undoable step.
+ * 
+ * @param {String} text - Pasted text or CSV file contents
+ * @param {tauri::State<AppState>} state - Application state for database access
+ * @returns {Result<ClassParamImport, String>} Counts and the reasons for every skipped entry
+ * @throws {String} If the text is too long or database operation fails
+ */
+#[tauri::command]
+async fn import_classes(text: String, state: tauri::State<'_, AppState>) -> Result<ClassParamImport, String> {
+    import_class_list(text, &state.db_pool).await
+        .map_err(|e| format!("Failed to import courses: {e}"))
+}
+
+/**
+ * Adds the courses of a text or CSV file to the class parameters
+ * 
+ * @param {String} path - File path (chosen by the user)
+ * @param {tauri::State<AppState>} state - Application state for database access
+ * @returns {Result<ClassParamImport, String>} Counts and the reasons for every skipped entry
+ * @throws {String} If the file can't be read or database operation fails
+ */
+#[tauri::command]
+async fn import_classes_file(path: String, state: tauri::State<'_, AppState>) -> Result<ClassParamImport, String> {
+    import_class_file(path, &state.db_pool).await
+        .map_err(|e| format!("Failed to import courses: {e}"))
+}
+
+/**
+ * Searches the cached course catalog by code, name, description, prerequisites or instructor
+ * 
+ * @param {String} query - Free-text query; every word must match, as a prefix
+ * @param {Option<usize>} limit - Maximum number of results (defaults to 50)
+ * @param {tauri::State<AppState>} state - Application state for database access
+ * @returns {Result<Vec<CourseSearchResult>, String>} Matching courses ranked best first or error message
+ * @throws {String} If database query fails
+ */
+#[tauri::command]
+async fn search_courses(query: String, limit: Option<usize>, state: tauri::State<'_, AppState>) -> Result<Vec<CourseSearchResult>, String> {
+    ClassRepository::search(query, limit, &state.db_pool).await
+        .map_err(|e| format!("Failed to search courses: {e}"))
+}
+
+// === UNDO AND REDO COMMANDS ===
+
+/**
+ * Gets what undo and redo would do next
+ * 
+ * Event, course, favorite and pin changes of the active plan are undoable.
+ * 
+ * @param {tauri::State<AppState>} state - Application state for database access
+ * @returns {Result<UndoState, String>} Labels of the next undo and redo steps or error message
+ * @throws {String} If database query fails
+ */
+#[tauri::command]
+async fn get_undo_state(state: tauri::State<'_, AppState>) -> Result<UndoState, String> {
+    UndoRepository::get_state(&state.db_pool).await
+        .map_err(|e| format!("Failed to get undo state: {e}"))
+}
+
+/**
+ * Reverts the most recent event, course, favorite or pin change
+ * 
+ * @param {tauri::State<AppState>} state - Application state for database access
+ * @returns {Result<UndoState, String>} Labels of the next undo and redo steps or error message
+ * @throws {String} If there is nothing to undo or the change can no longer be reverted
+ */
+#[tauri::command]
+async fn undo(state: tauri::State<'_, AppState>) -> Result<UndoState, String> {
+    UndoRepository::undo(&state.db_pool).await
+        .map_err(|e| format!("Failed to undo: {e}"))
+}
+
+/**
+ * Reapplies the most recently undone change
+ * 
+ * @param {tauri::State<AppState>} state - Application state for database access
+ * @returns {Result<UndoState, String>} Labels of the next undo and redo steps or error message
+ * @throws {String} If there is nothing to redo or the change can no longer be applied
+ */
+#[tauri::command]
+async fn redo(state: tauri::State<'_, AppState>) -> Result<UndoState, String> {
+    UndoRepository::redo(&state.db_pool).await
+        .map_err(|e| format!("Failed to redo: {e}"))
+}
+
+// === PLAN MANAGEMENT COMMANDS ===
+
+/**
+ * Lists all named plans
+ * 
+ * Each plan owns its class parameters, events, schedules, favorites and pinned
+ * schedule. All other commands operate on the active plan.
+ * 
+ * @param {tauri::State<AppState>} state - Application state for database access
+ * @returns {Result<Vec<Plan>, String>} Array of plans in creation order or error message
+ * @throws {String} If database query fails
+ */
+#[tauri::command]
+async fn get_plans(state: tauri::State<'_, AppState>) -> Result<Vec<Plan>, String> {
+    PlanRepository::get_all(&state.db_pool).await
+        .map_err(|e| format!("Failed to get plans: {e}"))
+}
+
+/**
+ * Creates a new, empty plan without switching to it
+ * 
+ * @param {String} name - Unique plan name
+ * @param {tauri::State<AppState>} state - Application state for database access
+ * @returns {Result<Plan, String>} Created plan or error message
+ * @throws {String} If the name is empty or taken, or database insertion fails
+ */
+#[tauri::command]
+async fn create_plan(name: String, state: tauri::State<'_, AppState>) -> Result<Plan, String> {
+    PlanRepository::create(name, &state.db_pool).await
+        .map_err(|e| format!("Failed to create plan: {e}"))
+}
+
+/**
+ * Copies a plan, including its schedules, favorites and pin, under a new name
+ * 
+ * @param {String} id - Plan to copy
+ * @param {String} name - Unique name for the copy
+ * @param {tauri::State<AppState>} state - Application state for database access
+ * @returns {Result<Plan, String>} Created plan or error message
+ * @throws {String} If the plan doesn't exist, the name is taken, or database operation fails
+ */
+#[tauri::command]
+async fn duplicate_plan(id: String, name: String, state: tauri::State<'_, AppState>) -> Result<Plan, String> {
+    PlanRepository::duplicate(id, name, &state.db_pool).await
+        .map_err(|e| format!("Failed to duplicate plan: {e}"))
+}
+
+/**
+ * Renames a plan
+ * 
+ * @param {String} id - Plan to rename
+ * @param {String} name - New unique name
+ * @param {tauri::State<AppState>} state - Application state for database access
+ * @returns {Result<(), String>} Success or error message
+ * @throws {String} If the plan doesn't exist, the name is taken, or database update fails
+ */
+#[tauri::command]
+async fn rename_plan(id: String, name: String, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    PlanRepository::rename(id, name, &state.db_pool).await
+        .map_err(|e| format!("Failed to rename plan: {e}"))
+}
+
+/**
+ * Makes a plan the active one
+ * 
+ * @param {String} id - Plan to activate
+ * @param {tauri::State<AppState>} state - Application state for database access
+ * @returns {Result<(), String>} Success or error message
+ * @throws {String} If the plan doesn't exist or database update fails
+ */
+#[tauri::command]
+async fn switch_plan(id: String, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    PlanRepository::switch(id, &state.db_pool).await
+        .map_err(|e| format!("Failed to switch plan: {e}"))
+}
+
+/**
+ * Deletes an inactive plan and everything it owns
+ * 
+ * @param {String} id - Plan to delete
+ * @param {tauri::State<AppState>} state - Application state for database access
+ * @returns {Result<(), String>} Success or error message
+ * @throws {String} If the plan doesn't exist, is active, or database deletion fails
+ */
+#[tauri::command]
+async fn delete_plan(id: String, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    PlanRepository::delete(id, &state.db_pool).await
+        .map_err(|e| format!("Failed to delete plan: {e}"))
+}
+
+// === DATA EXPORT AND IMPORT COMMANDS ===
+
+/**
+ * Exports all plans and their data to a portable archive
+ * 
+ * @param {String} path - Destination file path (chosen by the user)
+ * @param {bool} include_cache - Whether to include the scraped classes cache
+ * @param {tauri::State<AppState>} state - Application state for database access
+ * @returns {Result<(), String>} Success or error message
+ * @throws {String} If reading the database or writing the file fails
+ */
+#[tauri::command]
+async fn export_data(path: String, include_cache: bool, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    export_archive(path, include_cache, &state.db_pool).await
+        .map_err(|e| format!("Failed to export data: {e}"))
+}
+
+/**
+ * Imports an archive created by export_data
+ * 
+ * @param {String} path - Archive file path (chosen by the user)
+ * @param {ImportMode} mode - "Merge" to add the archived plans, "Replace" to replace all existing plans
+ * @param {tauri::State<AppState>} state - Application state for database access
+ * @returns {Result<(), String>} Success or error message
+ * @throws {String} If the archive is invalid or the import fails (nothing is changed in that case)
+ */
+#[tauri::command]
+async fn import_data(path: String, mode: ImportMode, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    import_archive(path, mode, &state.db_pool).await
+        .map_err(|e| format!("Failed to import data: {e}"))
+}
+
+// === CALENDAR COMMANDS ===
+
+/**
+ * Retrieves the term dates and holidays of the active plan
+ * 
+ * @param {tauri::State<AppState>} state - Application state for database access
+ * @returns {Result<Option<TermCalendar>, String>} Term, or null if it hasn't been set
+ * @throws {String} If database query fails
+ */
+#[tauri::command]
+async fn get_term_calendar(state: tauri::State<'_, AppState>) -> Result<Option<TermCalendar>, String> {
+    PlanRepository::get_term(&state.db_pool).await
+        .map_err(|e| format!("Failed to get term dates: {e}"))
+}
+
+/**
+ * Sets or clears the term dates and holidays of the active plan
+ * 
+ * @param {Option<TermCalendar>} term - Term with "YYYY-MM-DD" dates, or null to clear it
+ * @param {tauri::State<AppState>} state - Application state for database access
+ * @returns {Result<Option<TermCalendar>, String>} Term as stored
+ * @throws {String} If a date is invalid or database operation fails
+ */
+#[tauri::command]
+async fn set_term_calendar(term: Option<TermCalendar>, state: tauri::State<'_, AppState>) -> Result<Option<TermCalendar>, String> {
+    PlanRepository::set_term(term, &state.db_pool).await
+        .map_err(|e| format!("Failed to set term dates: {e}"))
+}
+
+/**
+ * Exports a schedule as an iCalendar file with weekly recurring class meetings
+ * 
+ * @param {String} path - Destination file path (chosen by the user)
+ * @param {String} id - Content-hash schedule identifier
+ * @param {tauri::State<AppState>} state - Application state for database access
+ * @returns {Result<usize, String>} Number of recurring events written
+ * @throws {String} If the term isn't set, the schedule doesn't exist, or writing the file fails
+ */
+#[tauri::command]
+async fn export_calendar(path: String, id: String, state: tauri::State<'_, AppState>) -> Result<usize, String> {
+    export_schedule_calendar(path, id, &state.db_pool).await
+        .map_err(|e| format!("Failed to export calendar: {e}"))
+}
+
+/**
+ * Imports the weekly recurring events of an iCalendar file as blocking events
+ * 
+ * Re-importing a calendar updates the events imported from it before (matched by UID)
+ * instead of duplicating them. The import is a single undoable step.
+ * 
+ * @param {String} path - iCalendar file path (chosen by the user)
+ * @param {i32} utc_offset_minutes - Local offset from UTC, used for times given in UTC
+ * @param {tauri::State<AppState>} state - Application state for database access
+ * @returns {Result<CalendarImport, String>} Counts of imported events and reasons for skipped ones
+ * @throws {String} If the file can't be read or parsed, or database operation fails
+ */
+#[tauri::command]
+async fn import_calendar_events(path: String, utc_offset_minutes: i32, state: tauri::State<'_, AppState>) -> Result<CalendarImport, String> {
+    import_calendar(path, utc_offset_minutes, &state.db_pool).await
+        .map_err(|e| format!("Failed to import calendar: {e}"))
+}
+
+// === SHARE CODE COMMANDS ===
+
+/**
+ * Creates a short, copy-pasteable code for a schedule
+ * 
+ * @param {String} id - Content-hash schedule identifier
+ * @param {tauri::State<AppState>} state - Application state for database access
+ * @returns {Result<String, String>} Share code holding the term and the course/section list
+ * @throws {String} If the schedule doesn't exist or database query fails
+ */
+#[tauri::command]
+async fn get_schedule_code(id: String, state: tauri::State<'_, AppState>) -> Result<String, String> {
+    encode_schedule_code(id, &state.db_pool).await
+        .map_err(|e| format!("Failed to create schedule code: {e}"))
+}
+
+/**
+ * Decodes a schedule code into full class data
+ * Courses missing from the classes cache are scraped first
+ * 
+ * @param {String} code - Share code
+ * @param {tauri::State<AppState>} state - Application state with database and Chrome connection info
+ * @returns {Result<SharedSchedule, String>} Sender's term start and the schedule's classes
+ * @throws {String} If the code is malformed, a section no longer exists, or scraping fails
+ */
+#[tauri::command]
+async fn open_schedule_code(code: String, state: tauri::State<'_, AppState>) -> Result<SharedSchedule, String> {
+    decode_schedule_code(&code, &state.connect_info, &state.db_pool).await
+        .map_err(|e| format!("Failed to open schedule code: {e}"))
+}
+
+// === SCHEDULE EXPORT COMMANDS ===
+
+/**
+ * Exports a schedule as a weekly timetable image
+ * 
+ * @param {String} path - Destination file path ending in .svg or .png (chosen by the user)
+ * @param {String} id - Content-hash schedule identifier
+ * @param {bool} include_events - Whether to draw the user's events as well
+ * @param {tauri::State<AppState>} state - Application state for database access
+ * @returns {Result<(), String>} Success or error message
+ * @throws {String} If the format is unsupported, the schedule doesn't exist, or writing the file fails
+ */
+#[tauri::command]
+async fn export_schedule_picture(path: String, id: String, include_events: bool, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    export_schedule_image(path, id, include_events, &state.db_pool).await
+        .map_err(|e| format!("Failed to export schedule image: {e}"))
+}
+
+/**
+ * Renders a schedule as an SVG timetable for thumbnails and printing
+ * 
+ * @param {String} id - Content-hash schedule identifier
+ * @param {bool} include_events - Whether to draw the user's events as well
+ * @param {tauri::State<AppState>} state - Application state for database access
+ * @returns {Result<String, String>} SVG document
+ * @throws {String} If the schedule doesn't exist or database query fails
+ */
+#[tauri::command]
+async fn get_schedule_svg(id: String, include_events: bool, state: tauri::State<'_, AppState>) -> Result<String, String> {
+    render_stored_schedule(id, include_events, &state.db_pool).await
+        .map_err(|e| format!("Failed to render schedule: {e}"))
+}
+
+/**
+ * Exports the active plan's schedules or favorites as CSV or JSON for spreadsheet comparison
+ * 
+ * @param {String} path - Destination file path ending in .csv or .json (chosen by the user)
+ * @param {String} table - Source table ("schedules" or "favorites")
+ * @param {tauri::State<AppState>} state - Application state for database access
+ * @returns {Result<usize, String>} Number of schedules written
+ * @throws {String} If the format or table is unsupported, or reading or writing fails
+ */
+#[tauri::command]
+async fn export_schedule_table_file(path: String, table: String, state: tauri::State<'_, AppState>) -> Result<usize, String> {
+    export_schedule_table(path, table, &state.db_pool).await
+        .map_err(|e| format!("Failed to export schedules: {e}"))
+}
+
 // === MAIN APPLICATION ENTRY POINT ===
 
 /**
@@ -356,21 +941,30 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Enable detailed error backtraces for debugging
     std::env::set_var("RUST_BACKTRACE", "1");
 
-    // Initialize SQLite database connection pool
-    let manager = r2d2_sqlite::SqliteConnectionManager::file("programData.db");
-    let pool = r2d2::Pool::new(manager)?;
-    
-    // Create database schema if it doesn't exist
-    initialize_database(&pool)?;
-
     // Build and configure Tauri application
     tauri::Builder::default()
         .setup(move |app| {
+            // Resolve the app-data/app-cache locations and adopt data from older releases
+            let storage = resolve_storage_paths(&app.path_resolver())?;
+            migrate_legacy_storage(&storage)?;
+            println!("Using database at {}", storage.database.display());
+
+            // Initialize SQLite database connection pool
+            let manager = r2d2_sqlite::SqliteConnectionManager::file(&storage.database);
+            let pool = r2d2::Pool::new(manager)?;
+
+            // Create or upgrade the database schema
+            initialize_database(&pool)?;
+
             // Initialize shared application state
             app.manage(AppState {
                 db_pool: pool,
-                connect_info: Arc::new(Mutex::new(ConnectInfo::default())),
+                connect_info: Arc::new(Mutex::new(ConnectInfo {
+                    resources_dir: storage.resources.clone(),
+                    ..Default::default()
+                })),
                 startup_complete: AtomicBool::new(false),
+                storage,
             });
             
             // Display application banner
@@ -387,17 +981,36 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         // Register all Tauri command handlers for frontend communication
         .invoke_handler(tauri::generate_handler![
             // Application lifecycle commands
-            startup_app, close_splashscreen, show_splashscreen,
+            startup_app, close_splashscreen, show_splashscreen, get_storage_paths, set_storage_override,
             
             // Schedule generation and management commands
-            generate_schedules, delete_schedule, change_favorite_schedule, 
-            get_schedules, get_display_schedule, set_display_schedule,
+            generate_schedules, delete_schedule, change_favorite_schedule, update_favorite_details, get_favorite_tags, 
+            get_schedules, query_schedules, get_schedule_groups, find_alternative_sections, get_display_schedule, set_display_schedule,
+            get_generation_runs, restore_generation_run, delete_generation_run, get_history_limit, set_history_limit,
             
             // Event management commands (REFACTORED: Event handlers are now cleaner)
             create_event, get_events, delete_event, update_event,
             
             // Class parameter management commands
-            get_classes, update_class, remove_class,
+            get_classes, update_class, remove_class, import_classes, import_classes_file, search_courses,
+
+            // Undo and redo commands
+            get_undo_state, undo, redo,
+
+            // Plan management commands
+            get_plans, create_plan, duplicate_plan, rename_plan, switch_plan, delete_plan,
+
+            // Data export and import commands
+            export_data, import_data,
+
+            // Calendar commands
+            get_term_calendar, set_term_calendar, export_calendar, import_calendar_events,
+
+            // Share code commands
+            get_schedule_code, open_schedule_code,
+
+            // Schedule export commands
+            export_schedule_picture, get_schedule_svg, export_schedule_table_file,
         ])
         .run(tauri::generate_context!())?;
     Ok(())
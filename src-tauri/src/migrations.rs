@@ -0,0 +1,1074 @@
+//! Versioned schema migrations and JSON blob envelopes for programData.db
+//!
+//! The schema version is tracked in SQLite's `user_version` pragma. Each migration runs
+//! in its own transaction together with the version bump, so a failed migration leaves
+//! the database at the previous version. Before upgrading an existing database a copy is
+//! taken with SQLite's online backup API. JSON-serialized `Class` data stored in the
+//! `classes` table is wrapped in a versioned envelope so rows written by older releases
+//! can be upgraded when they are read. Envelopes carry the reserved `$koka` key, so a
+//! payload that happens to have `version` and `data` fields is never mistaken for one.
+
+use anyhow::anyhow;
+use rusqlite::{params, Connection, OptionalExtension, Transaction, MAIN_DB};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::{database_functions::stable_hash, DbPool};
+
+// === MIGRATION DEFINITIONS ===
+
+/**
+ * A single schema migration
+ * Migrations are applied in ascending version order and never edited once released
+ */
+struct Migration {
+    /** Schema version the database is at after this migration */
+    version: i32,
+    /** Short human readable summary for logs */
+    description: &'static str,
+    /** Migration body, executed inside a transaction */
+    apply: fn(&Transaction) -> Result<(), anyhow::Error>,
+}
+
+/** All migrations in the order they must be applied */
+const MIGRATIONS: &[Migration] = &[
+    Migration { version: 1, description: "Baseline schema", apply: create_baseline_schema },
+    Migration { version: 2, description: "Wrap class JSON blobs in versioned envelopes", apply: wrap_class_blobs },
+    Migration { version: 3, description: "Store schedules as section references with content-hash IDs", apply: normalize_schedule_storage },
+    Migration { version: 4, description: "Pin the display schedule by ID instead of list index", apply: pin_schedule_by_id },
+    Migration { version: 5, description: "Named plans owning course lists, events, schedules and pins", apply: introduce_plans },
+    Migration { version: 6, description: "Generation history with a retention limit", apply: add_generation_history },
+    Migration { version: 7, description: "Full-text search index over cached courses", apply: create_course_search_index },
+    Migration { version: 8, description: "Operation log for undo and redo", apply: create_operation_log },
+    Migration { version: 9, description: "Indexed schedule metadata for filtered and sorted queries", apply: add_schedule_metadata },
+    Migration { version: 10, description: "Titles, notes, colors and tags on favorites", apply: add_favorite_details },
+    Migration { version: 11, description: "Term dates and holidays on plans", apply: add_plan_terms },
+    Migration { version: 12, description: "Soft-constraint penalty in schedule metadata", apply: add_schedule_penalty },
+    Migration { version: 13, description: "Fingerprint of the last generation's stored results", apply: add_generation_results_hash },
+    Migration { version: 14, description: "Tag class blob envelopes with a reserved key", apply: tag_blob_envelopes },
+];
+
+/** Current version of the envelope around JSON-serialized Class data */
+pub const CLASS_BLOB_VERSION: u64 = 1;
+
+/** Reserved key marking a JSON object as an envelope; its value is the envelope layout */
+const ENVELOPE_TAG: &str = "$koka";
+
+// === MIGRATION RUNNER ===
+
+/**
+ * Brings the database schema up to the latest version
+ *
+ * This function:
+ * 1. Reads the current schema version from `user_version`
+ * 2. Refuses to touch databases created by a newer release
+ * 3. Backs up existing databases before the first pending migration
+ * 4. Applies each pending migration and its version bump in one transaction
+ *
+ * Migration 3 collapses duplicate legacy schedules, which shifts the rows that the
+ * positional display schedule resolved by migration 4 refers to. The pinned legacy row
+ * is therefore identified in migration 3's transaction, before the collapse, and pinned
+ * in migration 4's transaction.
+ *
+ * @param {&DbPool} pool - Database connection pool
+ * @returns {Result<(), anyhow::Error>} Success or error
+ * @throws {anyhow::Error} If the backup or any migration fails
+ */
+pub fn run_migrations(pool: &DbPool) -> Result<(), anyhow::Error> {
+    let mut conn = pool.get()?;
+    let current_version: i32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    let latest_version = MIGRATIONS.last().map_or(0, |migration| migration.version);
+
+    if current_version > latest_version {
+        return Err(anyhow!(
+            "Database schema version {current_version} is newer than supported version {latest_version}"
+        ));
+    }
+    if current_version == latest_version {
+        return Ok(());
+    }
+
+    if has_user_tables(&conn)? {
+        backup_before_migration(&conn, current_version)?;
+    }
+
+    let mut legacy_pin = None;
+    for migration in MIGRATIONS.iter().filter(|migration| migration.version > current_version) {
+        println!("Applying database migration {}: {}", migration.version, migration.description);
+        let tx = conn.transaction()?;
+        if migration.version == 3 {
+            legacy_pin = resolve_legacy_pin(&tx)?;
+        }
+        (migration.apply)(&tx)?;
+        if let (4, Some(id)) = (migration.version, &legacy_pin) {
+            tx.execute("UPDATE data SET pinned_schedule_id = ?1, pinned_table = 'schedules'", params![id])?;
+        }
+        tx.pragma_update(None, "user_version", migration.version)?;
+        tx.commit()?;
+    }
+
+    println!("Database schema is at version {latest_version}.");
+    Ok(())
+}
+
+/**
+ * Checks whether the database already contains tables (i.e. is not a fresh file)
+ *
+ * @param {&Connection} conn - Open database connection
+ * @returns {Result<bool, anyhow::Error>} True if any non-internal table exists
+ * @throws {anyhow::Error} If the schema query fails
+ */
+fn has_user_tables(conn: &Connection) -> Result<bool, anyhow::Error> {
+    let count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'",
+        [],
+        |row| row.get(0)
+    )?;
+    Ok(count > 0)
+}
+
+/**
+ * Copies the database next to itself before it is migrated
+ * The copy is named after the schema version it contains (e.g. programData.db.v1.bak)
+ *
+ * @param {&Connection} conn - Open database connection
+ * @param {i32} version - Schema version of the database being backed up
+ * @returns {Result<(), anyhow::Error>} Success or error
+ * @throws {anyhow::Error} If the online backup fails
+ */
+fn backup_before_migration(conn: &Connection, version: i32) -> Result<(), anyhow::Error> {
+    let Some(db_path) = conn.path().filter(|path| !path.is_empty()) else {
+        return Ok(()); // In-memory databases have nothing to protect
+    };
+    let backup_path = format!("{db_path}.v{version}.bak");
+    println!("Backing up database to {backup_path} before migrating.");
+    conn.backup(MAIN_DB, &backup_path, None)?;
+    Ok(())
+}
+
+/**
+ * Identifies the legacy display schedule by the content-hash ID migration 3 gives it
+ *
+ * Must run before migration 3, while `data.schedule` still indexes the uncollapsed
+ * legacy schedules table. The ID is derived as migration 3 derives it.
+ *
+ * @param {&Transaction} tx - Transaction of migration 3
+ * @returns {Result<Option<String>, anyhow::Error>} ID of the pinned schedule, or None if no valid index was stored
+ * @throws {anyhow::Error} If the query fails or the pinned legacy row can't be decoded
+ */
+fn resolve_legacy_pin(tx: &Transaction) -> Result<Option<String>, anyhow::Error> {
+    let index: Option<i64> = tx.query_row("SELECT schedule FROM data LIMIT 1", [], |row| row.get(0))
+        .optional()?
+        .flatten();
+    let Some(index) = index.filter(|index| *index >= 0) else {
+        return Ok(None);
+    };
+    let blob: Option<String> = tx.query_row(
+        "SELECT data FROM schedules ORDER BY rowid LIMIT 1 OFFSET ?1",
+        params![index],
+        |row| row.get(0)
+    ).optional()?;
+    let Some(blob) = blob else {
+        return Ok(None);
+    };
+
+    let schedule: Vec<Value> = decode_blob(&blob)?;
+    let mut section_ids = schedule.iter()
+        .map(|class| legacy_section_reference(class).map(|(section_id, _)| section_id))
+        .collect::<Result<Vec<_>, _>>()?;
+    section_ids.sort();
+    Ok(Some(format!("{:016x}", stable_hash(section_ids.join("\n").as_bytes()))))
+}
+
+// === MIGRATIONS ===
+
+/**
+ * Migration 1: creates the original schema
+ * Uses IF NOT EXISTS so databases created before versioning are adopted unchanged
+ *
+ * @param {&Transaction} tx - Active migration transaction
+ * @returns {Result<(), anyhow::Error>} Success or error
+ * @throws {anyhow::Error} If schema creation fails
+ */
+fn create_baseline_schema(tx: &Transaction) -> Result<(), anyhow::Error> {
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS data (
+            id SMALLINT PRIMARY KEY,
+            version TEXT,
+            os TEXT,
+            schedule SMALLINT
+        );
+        CREATE TABLE IF NOT EXISTS events (
+            id TEXT PRIMARY KEY,
+            title TEXT NOT NULL,
+            start_time INTEGER NOT NULL,
+            end_time INTEGER NOT NULL,
+            day INTEGER NOT NULL,
+            professor TEXT,
+            description TEXT
+        );
+        CREATE TABLE IF NOT EXISTS favorites (
+            id TEXT PRIMARY KEY,
+            data TEXT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS schedules (
+            id TEXT PRIMARY KEY,
+            data TEXT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS class_parameters (
+            id TEXT PRIMARY KEY,
+            data TEXT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS classes (
+            id TEXT PRIMARY KEY,
+            classname TEXT NOT NULL,
+            data TEXT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS last_generation (
+            id SMALLINT PRIMARY KEY,
+            parameters TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_classes_classname ON classes(classname);
+        CREATE INDEX IF NOT EXISTS idx_events_day ON events(day);"
+    )?;
+    Ok(())
+}
+
+/**
+ * Migration 2: wraps legacy (unversioned) Class JSON in the versioned envelope
+ *
+ * @param {&Transaction} tx - Active migration transaction
+ * @returns {Result<(), anyhow::Error>} Success or error
+ * @throws {anyhow::Error} If a row cannot be read, parsed or rewritten
+ */
+fn wrap_class_blobs(tx: &Transaction) -> Result<(), anyhow::Error> {
+    for table in ["classes", "schedules", "favorites"] {
+        let rows: Vec<(String, String)> = {
+            let mut stmt = tx.prepare(&format!("SELECT id, data FROM {table}"))?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<Result<Vec<_>, _>>()?;
+            rows
+        };
+
+        let mut update = tx.prepare(&format!("UPDATE {table} SET data = ?1 WHERE id = ?2"))?;
+        for (id, data) in rows {
+            let value: Value = serde_json::from_str(&data)?;
+            if untagged_envelope_parts(&value).is_none() {
+                update.execute(params![encode_blob(&upgrade_blob(0, value)?)?, id])?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/**
+ * Migration 3: replaces schedule JSON blobs with rows of section references
+ * 
+ * Each legacy schedule is decoded, its sections are added to the classes cache if
+ * missing and it is re-inserted under its content-hash ID. Legacy duplicates collapse
+ * into one row. Section and schedule IDs are derived here as they were defined by
+ * this schema version, so later changes to the repositories don't alter the migration.
+ *
+ * @param {&Transaction} tx - Active migration transaction
+ * @returns {Result<(), anyhow::Error>} Success or error
+ * @throws {anyhow::Error} If a legacy row cannot be decoded or the new rows can't be written
+ */
+fn normalize_schedule_storage(tx: &Transaction) -> Result<(), anyhow::Error> {
+    tx.execute_batch(
+        "ALTER TABLE schedules RENAME TO schedules_legacy;
+        ALTER TABLE favorites RENAME TO favorites_legacy;
+        CREATE TABLE schedules (
+            id TEXT PRIMARY KEY
+        );
+        CREATE TABLE favorites (
+            id TEXT PRIMARY KEY
+        );
+        CREATE TABLE schedule_sections (
+            schedule_id TEXT NOT NULL,
+            position INTEGER NOT NULL,
+            class_id TEXT NOT NULL,
+            PRIMARY KEY (schedule_id, position)
+        );
+        CREATE INDEX idx_schedule_sections_class ON schedule_sections(class_id);"
+    )?;
+
+    let mut class_stmt = tx.prepare("INSERT OR IGNORE INTO classes (id, classname, data) VALUES (?1, ?2, ?3)")?;
+    let mut section_stmt = tx.prepare("INSERT OR IGNORE INTO schedule_sections (schedule_id, position, class_id) VALUES (?1, ?2, ?3)")?;
+    for (legacy_table, table) in [("schedules_legacy", "schedules"), ("favorites_legacy", "favorites")] {
+        let blobs: Vec<String> = {
+            let mut stmt = tx.prepare(&format!("SELECT data FROM {legacy_table} ORDER BY rowid"))?;
+            let blobs = stmt.query_map([], |row| row.get(0))?
+                .collect::<Result<Vec<_>, _>>()?;
+            blobs
+        };
+        let mut schedule_stmt = tx.prepare(&format!("INSERT OR IGNORE INTO {table} (id) VALUES (?1)"))?;
+        for blob in blobs {
+            let schedule: Vec<Value> = decode_blob(&blob)?;
+            let references = schedule.iter().map(legacy_section_reference).collect::<Result<Vec<_>, _>>()?;
+
+            // Schedule ID: FNV-1a of the sorted section IDs joined by newlines
+            let mut section_ids: Vec<&str> = references.iter().map(|(section_id, _)| section_id.as_str()).collect();
+            section_ids.sort();
+            let id = format!("{:016x}", stable_hash(section_ids.join("\n").as_bytes()));
+
+            for (position, (class, (section_id, classname))) in schedule.iter().zip(&references).enumerate() {
+                class_stmt.execute(params![section_id, classname, encode_blob(class)?])?;
+                section_stmt.execute(params![id, position as i64, section_id])?;
+            }
+            schedule_stmt.execute(params![id])?;
+        }
+    }
+    drop((class_stmt, section_stmt));
+
+    tx.execute_batch(
+        "DROP TABLE schedules_legacy;
+        DROP TABLE favorites_legacy;"
+    )?;
+    Ok(())
+}
+
+/**
+ * Derives the cache ID and course name of a legacy schedule's class, as defined by migration 3
+ * The ID is code + name followed by "/section" for every section (e.g. "CSC116/001/601")
+ *
+ * @param {&Value} class - Class JSON from a legacy schedule blob
+ * @returns {Result<(String, String), anyhow::Error>} Section ID and course name (code + name)
+ * @throws {anyhow::Error} If the class is missing its code, name or section numbers
+ */
+fn legacy_section_reference(class: &Value) -> Result<(String, String), anyhow::Error> {
+    let field = |value: &Value, key: &str| -> Result<String, anyhow::Error> {
+        value.get(key)
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .ok_or_else(|| anyhow!("Legacy schedule class is missing '{key}'"))
+    };
+    let classname = format!("{}{}", field(class, "code")?, field(class, "name")?);
+    let mut section_id = classname.clone();
+    for block in class.get("classes").and_then(Value::as_array).into_iter().flatten() {
+        section_id = format!("{}/{}", section_id, field(block, "section")?);
+    }
+    Ok((section_id, classname))
+}
+
+/**
+ * Migration 4: replaces the positional display schedule with an ID-based pin
+ * 
+ * The old index referred to the row order of the schedules table, so it is resolved
+ * against that order once and then dropped.
+ *
+ * @param {&Transaction} tx - Active migration transaction
+ * @returns {Result<(), anyhow::Error>} Success or error
+ * @throws {anyhow::Error} If the schema change fails
+ */
+fn pin_schedule_by_id(tx: &Transaction) -> Result<(), anyhow::Error> {
+    let index: Option<i64> = tx.query_row("SELECT schedule FROM data LIMIT 1", [], |row| row.get(0))
+        .optional()?
+        .flatten();
+    let pinned_id: Option<String> = match index {
+        Some(index) if index >= 0 => tx.query_row(
+            "SELECT id FROM schedules ORDER BY rowid LIMIT 1 OFFSET ?1",
+            params![index],
+            |row| row.get(0)
+        ).optional()?,
+        _ => None,
+    };
+
+    tx.execute_batch(
+        "ALTER TABLE data ADD COLUMN pinned_schedule_id TEXT;
+        ALTER TABLE data ADD COLUMN pinned_table TEXT;
+        ALTER TABLE data DROP COLUMN schedule;"
+    )?;
+    if let Some(id) = pinned_id {
+        tx.execute(
+            "UPDATE data SET pinned_schedule_id = ?1, pinned_table = 'schedules'",
+            params![id]
+        )?;
+    }
+    Ok(())
+}
+
+/**
+ * Migration 5: introduces named plans
+ * 
+ * Creates a "Default" plan that adopts the existing course list, events, schedules,
+ * favorites, last generation inputs and pin. Plan-owned tables are rebuilt with a
+ * plan_id column as part of their primary key so a plan can be duplicated without
+ * renaming anything.
+ *
+ * @param {&Transaction} tx - Active migration transaction
+ * @returns {Result<(), anyhow::Error>} Success or error
+ * @throws {anyhow::Error} If the schema change or data copy fails
+ */
+fn introduce_plans(tx: &Transaction) -> Result<(), anyhow::Error> {
+    tx.execute_batch(
+        "CREATE TABLE plans (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL UNIQUE,
+            active INTEGER NOT NULL DEFAULT 0,
+            created_at INTEGER NOT NULL,
+            pinned_schedule_id TEXT,
+            pinned_table TEXT
+        );"
+    )?;
+    tx.execute(
+        "INSERT INTO plans (id, name, active, created_at, pinned_schedule_id, pinned_table)
+         VALUES (?1, 'Default', 1, CAST(strftime('%s', 'now') AS INTEGER),
+                 (SELECT pinned_schedule_id FROM data LIMIT 1), (SELECT pinned_table FROM data LIMIT 1))",
+        params![Uuid::new_v4().to_string()]
+    )?;
+
+    tx.execute_batch(
+        "ALTER TABLE events RENAME TO events_legacy;
+        ALTER TABLE class_parameters RENAME TO class_parameters_legacy;
+        ALTER TABLE schedules RENAME TO schedules_legacy;
+        ALTER TABLE favorites RENAME TO favorites_legacy;
+        ALTER TABLE last_generation RENAME TO last_generation_legacy;
+
+        CREATE TABLE events (
+            plan_id TEXT NOT NULL,
+            id TEXT NOT NULL,
+            title TEXT NOT NULL,
+            start_time INTEGER NOT NULL,
+            end_time INTEGER NOT NULL,
+            day INTEGER NOT NULL,
+            professor TEXT,
+            description TEXT,
+            PRIMARY KEY (plan_id, id)
+        );
+        CREATE TABLE class_parameters (
+            plan_id TEXT NOT NULL,
+            id TEXT NOT NULL,
+            data TEXT NOT NULL,
+            PRIMARY KEY (plan_id, id)
+        );
+        CREATE TABLE schedules (
+            plan_id TEXT NOT NULL,
+            id TEXT NOT NULL,
+            PRIMARY KEY (plan_id, id)
+        );
+        CREATE TABLE favorites (
+            plan_id TEXT NOT NULL,
+            id TEXT NOT NULL,
+            PRIMARY KEY (plan_id, id)
+        );
+        CREATE TABLE last_generation (
+            plan_id TEXT PRIMARY KEY,
+            parameters TEXT NOT NULL
+        );
+
+        INSERT INTO events (plan_id, id, title, start_time, end_time, day, professor, description)
+            SELECT (SELECT id FROM plans), id, title, start_time, end_time, day, professor, description
+            FROM events_legacy ORDER BY rowid;
+        INSERT INTO class_parameters (plan_id, id, data)
+            SELECT (SELECT id FROM plans), id, data FROM class_parameters_legacy ORDER BY rowid;
+        INSERT INTO schedules (plan_id, id)
+            SELECT (SELECT id FROM plans), id FROM schedules_legacy ORDER BY rowid;
+        INSERT INTO favorites (plan_id, id)
+            SELECT (SELECT id FROM plans), id FROM favorites_legacy ORDER BY rowid;
+        INSERT INTO last_generation (plan_id, parameters)
+            SELECT (SELECT id FROM plans), parameters FROM last_generation_legacy LIMIT 1;
+
+        DROP TABLE events_legacy;
+        DROP TABLE class_parameters_legacy;
+        DROP TABLE schedules_legacy;
+        DROP TABLE favorites_legacy;
+        DROP TABLE last_generation_legacy;
+        CREATE INDEX idx_events_day ON events(plan_id, day);
+
+        ALTER TABLE data DROP COLUMN pinned_schedule_id;
+        ALTER TABLE data DROP COLUMN pinned_table;"
+    )?;
+    Ok(())
+}
+
+/**
+ * Migration 6: records generation runs so results survive later generations
+ * 
+ * The schedules currently stored for each plan become that plan's first recorded run,
+ * using the last generation inputs when they are known.
+ *
+ * @param {&Transaction} tx - Active migration transaction
+ * @returns {Result<(), anyhow::Error>} Success or error
+ * @throws {anyhow::Error} If the schema change or data copy fails
+ */
+fn add_generation_history(tx: &Transaction) -> Result<(), anyhow::Error> {
+    tx.execute_batch(
+        "CREATE TABLE generation_runs (
+            id TEXT PRIMARY KEY,
+            plan_id TEXT NOT NULL,
+            created_at INTEGER NOT NULL,
+            parameters TEXT NOT NULL,
+            schedule_count INTEGER NOT NULL,
+            duration_ms INTEGER NOT NULL
+        );
+        CREATE TABLE run_schedules (
+            run_id TEXT NOT NULL,
+            position INTEGER NOT NULL,
+            schedule_id TEXT NOT NULL,
+            PRIMARY KEY (run_id, position)
+        );
+        CREATE INDEX idx_generation_runs_plan ON generation_runs(plan_id);
+        CREATE INDEX idx_run_schedules_schedule ON run_schedules(schedule_id);
+        ALTER TABLE data ADD COLUMN history_limit INTEGER NOT NULL DEFAULT 20;"
+    )?;
+
+    let plans: Vec<(String, String)> = {
+        let mut stmt = tx.prepare(
+            "SELECT p.id, g.parameters FROM plans p
+             JOIN last_generation g ON g.plan_id = p.id
+             WHERE EXISTS(SELECT 1 FROM schedules s WHERE s.plan_id = p.id)"
+        )?;
+        let plans = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        plans
+    };
+    for (plan_id, parameters) in plans {
+        let run_id = Uuid::new_v4().to_string();
+        tx.execute(
+            "INSERT INTO run_schedules (run_id, position, schedule_id)
+             SELECT ?1, ROW_NUMBER() OVER (ORDER BY rowid) - 1, id FROM schedules WHERE plan_id = ?2",
+            params![run_id, plan_id]
+        )?;
+        tx.execute(
+            "INSERT INTO generation_runs (id, plan_id, created_at, parameters, schedule_count, duration_ms)
+             VALUES (?1, ?2, CAST(strftime('%s', 'now') AS INTEGER), ?3,
+                     (SELECT COUNT(*) FROM run_schedules WHERE run_id = ?1), 0)",
+            params![run_id, plan_id, parameters]
+        )?;
+    }
+    Ok(())
+}
+
+/**
+ * Migration 7: adds the FTS5 course search index and fills it from the classes cache
+ *
+ * Each course gets one row: code, name and description of its first cached section,
+ * prerequisites split out of the scraped "... | Prerequisites: ... | Description: ..."
+ * text, and the distinct instructors of all its sections, one per line.
+ *
+ * @param {&Transaction} tx - Active migration transaction
+ * @returns {Result<(), anyhow::Error>} Success or error
+ * @throws {anyhow::Error} If the table can't be created or a cached row can't be decoded
+ */
+fn create_course_search_index(tx: &Transaction) -> Result<(), anyhow::Error> {
+    tx.execute_batch(
+        "CREATE VIRTUAL TABLE course_search USING fts5(
+            classname UNINDEXED,
+            code,
+            name,
+            description,
+            prerequisites,
+            instructors,
+            tokenize = 'porter unicode61 remove_diacritics 2',
+            prefix = '2 3'
+        );"
+    )?;
+
+    let mut courses: Vec<(String, Vec<Value>)> = Vec::new();
+    {
+        let mut stmt = tx.prepare("SELECT classname, data FROM classes ORDER BY rowid")?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let classname: String = row.get(0)?;
+            let section: Value = decode_blob(&row.get::<_, String>(1)?)?;
+            match courses.iter_mut().find(|(name, _)| *name == classname) {
+                Some((_, sections)) => sections.push(section),
+                None => courses.push((classname, vec![section])),
+            }
+        }
+    }
+
+    let text = |value: &Value, key: &str| value.get(key).and_then(Value::as_str).unwrap_or("").to_string();
+    let mut insert = tx.prepare(
+        "INSERT INTO course_search (classname, code, name, description, prerequisites, instructors) VALUES (?1, ?2, ?3, ?4, ?5, ?6)"
+    )?;
+    for (classname, sections) in &courses {
+        let mut instructors: Vec<String> = Vec::new();
+        for block in sections.iter().filter_map(|section| section.get("classes")?.as_array()).flatten() {
+            let instructor = text(block, "instructor").trim().to_string();
+            if !instructor.is_empty() && !instructors.contains(&instructor) {
+                instructors.push(instructor);
+            }
+        }
+        let first = &sections[0];
+        let full_description = text(first, "description");
+        let prerequisites = full_description
+            .split_once("Prerequisites: ")
+            .map(|(_, rest)| rest.split(" | Description: ").next().unwrap_or(rest))
+            .unwrap_or("");
+        let description = full_description
+            .split_once("Description: ")
+            .map(|(_, rest)| rest)
+            .unwrap_or(&full_description);
+        insert.execute(params![
+            classname,
+            text(first, "code"),
+            text(first, "name"),
+            description.trim(),
+            prerequisites.trim(),
+            instructors.join("\n")
+        ])?;
+    }
+    Ok(())
+}
+
+/**
+ * Migration 8: adds the per-plan operation log backing undo and redo
+ *
+ * Each row stores the operations a user action applied and the operations that
+ * revert it, both as JSON; `undone` marks entries currently on the redo stack.
+ *
+ * @param {&Transaction} tx - Active migration transaction
+ * @returns {Result<(), anyhow::Error>} Success or error
+ * @throws {anyhow::Error} If the schema change fails
+ */
+fn create_operation_log(tx: &Transaction) -> Result<(), anyhow::Error> {
+    tx.execute_batch(
+        "CREATE TABLE operation_log (
+            seq INTEGER PRIMARY KEY AUTOINCREMENT,
+            plan_id TEXT NOT NULL,
+            created_at INTEGER NOT NULL,
+            label TEXT NOT NULL,
+            forward TEXT NOT NULL,
+            inverse TEXT NOT NULL,
+            undone INTEGER NOT NULL DEFAULT 0
+        );
+        CREATE INDEX idx_operation_log_plan ON operation_log(plan_id, undone, seq);"
+    )?;
+    Ok(())
+}
+
+/**
+ * Migration 9: adds per-schedule metadata used to filter and sort schedule queries
+ *
+ * Metadata is keyed by content-hash ID like the section references, and is filled
+ * for every schedule already stored. The backfill reads the cached sections with
+ * SQLite's JSON functions, using the class blob layout of this schema version
+ * ({"version": 1, "data": {"classes": [{"days": [[[start, end], meets], ...], "instructor": ...}]}}).
+ *
+ * @param {&Transaction} tx - Active migration transaction
+ * @returns {Result<(), anyhow::Error>} Success or error
+ * @throws {anyhow::Error} If the schema change fails or a cached row can't be decoded
+ */
+fn add_schedule_metadata(tx: &Transaction) -> Result<(), anyhow::Error> {
+    tx.execute_batch(
+        "CREATE TABLE schedule_metadata (
+            schedule_id TEXT PRIMARY KEY,
+            earliest_start INTEGER,
+            latest_end INTEGER,
+            campus_days INTEGER NOT NULL,
+            day_mask INTEGER NOT NULL
+        );
+        CREATE TABLE schedule_instructors (
+            schedule_id TEXT NOT NULL,
+            instructor TEXT NOT NULL COLLATE NOCASE,
+            PRIMARY KEY (schedule_id, instructor)
+        );
+        CREATE INDEX idx_schedule_metadata_start ON schedule_metadata(earliest_start);
+        CREATE INDEX idx_schedule_metadata_days ON schedule_metadata(campus_days);"
+    )?;
+
+    tx.execute_batch(
+        "INSERT INTO schedule_metadata (schedule_id, earliest_start, latest_end, campus_days, day_mask)
+            SELECT ids.schedule_id, MIN(m.start_time), MAX(m.end_time),
+                   COUNT(DISTINCT m.day), COALESCE(SUM(DISTINCT 1 << m.day), 0)
+            FROM (SELECT DISTINCT schedule_id FROM schedule_sections) ids
+            LEFT JOIN (
+                SELECT ss.schedule_id, CAST(d.key AS INTEGER) AS day,
+                       json_extract(d.value, '$[0][0]') AS start_time,
+                       json_extract(d.value, '$[0][1]') AS end_time
+                FROM schedule_sections ss
+                JOIN classes c ON c.id = ss.class_id,
+                     json_each(c.data, '$.data.classes') b,
+                     json_each(b.value, '$.days') d
+                WHERE json_extract(d.value, '$[1]')
+                  AND json_extract(d.value, '$[0][0]') > 0
+                  AND json_extract(d.value, '$[0][1]') > 0
+            ) m ON m.schedule_id = ids.schedule_id
+            GROUP BY ids.schedule_id;
+        INSERT OR IGNORE INTO schedule_instructors (schedule_id, instructor)
+            SELECT ss.schedule_id, trim(json_extract(b.value, '$.instructor'))
+            FROM schedule_sections ss
+            JOIN classes c ON c.id = ss.class_id,
+                 json_each(c.data, '$.data.classes') b
+            WHERE trim(json_extract(b.value, '$.instructor')) <> '';"
+    )?;
+    Ok(())
+}
+
+/**
+ * Migration 10: adds user annotations to favorites
+ *
+ * Tags are stored as a JSON array so plan duplication, which copies favorites
+ * column by column, carries them along.
+ *
+ * @param {&Transaction} tx - Active migration transaction
+ * @returns {Result<(), anyhow::Error>} Success or error
+ * @throws {anyhow::Error} If the schema change fails
+ */
+fn add_favorite_details(tx: &Transaction) -> Result<(), anyhow::Error> {
+    tx.execute_batch(
+        "ALTER TABLE favorites ADD COLUMN title TEXT NOT NULL DEFAULT '';
+        ALTER TABLE favorites ADD COLUMN notes TEXT NOT NULL DEFAULT '';
+        ALTER TABLE favorites ADD COLUMN color TEXT;
+        ALTER TABLE favorites ADD COLUMN tags TEXT NOT NULL DEFAULT '[]';"
+    )?;
+    Ok(())
+}
+
+/**
+ * Migration 11: adds the term a plan covers, used to bound calendar exports
+ *
+ * Dates are stored as "YYYY-MM-DD" text and holidays as a JSON array of such dates.
+ *
+ * @param {&Transaction} tx - Active migration transaction
+ * @returns {Result<(), anyhow::Error>} Success or error
+ * @throws {anyhow::Error} If the schema change fails
+ */
+fn add_plan_terms(tx: &Transaction) -> Result<(), anyhow::Error> {
+    tx.execute_batch(
+        "ALTER TABLE plans ADD COLUMN term_start TEXT;
+        ALTER TABLE plans ADD COLUMN term_end TEXT;
+        ALTER TABLE plans ADD COLUMN holidays TEXT NOT NULL DEFAULT '[]';"
+    )?;
+    Ok(())
+}
+
+/**
+ * Migration 12: adds each schedule's soft-constraint penalty, for sorting by score
+ *
+ * Existing rows start at 0, which is also the penalty of every schedule generated
+ * without constraints; they are scored against the plan's constraints when next saved.
+ *
+ * @param {&Transaction} tx - Active migration transaction
+ * @returns {Result<(), anyhow::Error>} Success or error
+ * @throws {anyhow::Error} If the schema change fails
+ */
+fn add_schedule_penalty(tx: &Transaction) -> Result<(), anyhow::Error> {
+    tx.execute_batch(
+        "ALTER TABLE schedule_metadata ADD COLUMN penalty INTEGER NOT NULL DEFAULT 0;
+        CREATE INDEX idx_schedule_metadata_penalty ON schedule_metadata(penalty);"
+    )?;
+    Ok(())
+}
+
+/**
+ * Migration 13: adds a fingerprint of the schedules each plan's last generation stored
+ *
+ * Existing rows get NULL, which never matches the stored schedules, so the first
+ * generation after upgrading runs in full and records the fingerprint.
+ *
+ * @param {&Transaction} tx - Active migration transaction
+ * @returns {Result<(), anyhow::Error>} Success or error
+ * @throws {anyhow::Error} If the schema change fails
+ */
+fn add_generation_results_hash(tx: &Transaction) -> Result<(), anyhow::Error> {
+    tx.execute_batch("ALTER TABLE last_generation ADD COLUMN results_hash TEXT;")?;
+    Ok(())
+}
+
+/**
+ * Migration 14: adds the reserved tag to class blob envelopes written without it
+ *
+ * Envelopes written before this version are plain {"version": N, "data": ...} objects,
+ * which can't be told apart from a payload with those two fields. Rows already carrying
+ * the tag (written by migrations after the tag was introduced) are left alone.
+ *
+ * @param {&Transaction} tx - Active migration transaction
+ * @returns {Result<(), anyhow::Error>} Success or error
+ * @throws {anyhow::Error} If a row cannot be read, parsed or rewritten
+ */
+fn tag_blob_envelopes(tx: &Transaction) -> Result<(), anyhow::Error> {
+    let rows: Vec<(String, String)> = {
+        let mut stmt = tx.prepare("SELECT id, data FROM classes")?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        rows
+    };
+
+    let mut update = tx.prepare("UPDATE classes SET data = ?1 WHERE id = ?2")?;
+    for (id, data) in rows {
+        if envelope_parts(&serde_json::from_str(&data)?).is_none() {
+            let payload: Value = decode_blob(&data)?;
+            update.execute(params![encode_blob(&payload)?, id])?;
+        }
+    }
+    Ok(())
+}
+
+// === VERSIONED JSON ENVELOPES ===
+
+/**
+ * Serializes Class data wrapped in the current envelope version
+ *
+ * @param {&T} value - Value to serialize (a Class or a schedule of Classes)
+ * @returns {Result<String, anyhow::Error>} JSON of the form {"$koka": 1, "version": N, "data": ...}
+ * @throws {anyhow::Error} If JSON serialization fails
+ */
+pub fn encode_blob<T: Serialize>(value: &T) -> Result<String, anyhow::Error> {
+    Ok(serde_json::to_string(&json!({ ENVELOPE_TAG: 1, "version": CLASS_BLOB_VERSION, "data": value }))?)
+}
+
+/**
+ * Deserializes Class data written by any supported release
+ * 
+ * Rows are matched on the envelope tag first. Untagged envelopes only exist in databases
+ * below schema version 14, where the migrations in between still read them; legacy rows
+ * without any envelope are treated as version 0. Both are upgraded first.
+ *
+ * @param {&str} json - Stored JSON text
+ * @returns {Result<T, anyhow::Error>} Deserialized value
+ * @throws {anyhow::Error} If the JSON is malformed or from a newer, unsupported version
+ */
+pub fn decode_blob<T: DeserializeOwned>(json: &str) -> Result<T, anyhow::Error> {
+    let value: Value = serde_json::from_str(json)?;
+    let data = match envelope_parts(&value).or_else(|| untagged_envelope_parts(&value)) {
+        Some((version, data)) => upgrade_blob(version, data.clone())?,
+        None => upgrade_blob(0, value)?,
+    };
+    Ok(serde_json::from_value(data)?)
+}
+
+/**
+ * Splits a tagged envelope into its version and payload
+ *
+ * @param {&Value} value - Parsed JSON value
+ * @returns {Option<(u64, &Value)>} Version and payload, or None if the value isn't a tagged envelope
+ */
+fn envelope_parts(value: &Value) -> Option<(u64, &Value)> {
+    let object = value.as_object()?;
+    if object.get(ENVELOPE_TAG)?.as_u64()? != 1 {
+        return None;
+    }
+    Some((object.get("version")?.as_u64()?, object.get("data")?))
+}
+
+/**
+ * Splits an envelope in the untagged layout written before schema version 14
+ *
+ * @param {&Value} value - Parsed JSON value
+ * @returns {Option<(u64, &Value)>} Version and payload, or None if the value isn't exactly {"version", "data"}
+ */
+fn untagged_envelope_parts(value: &Value) -> Option<(u64, &Value)> {
+    let object = value.as_object()?;
+    if object.len() != 2 {
+        return None;
+    }
+    Some((object.get("version")?.as_u64()?, object.get("data")?))
+}
+
+/**
+ * Upgrades an envelope's payload step by step to CLASS_BLOB_VERSION
+ * Add a match arm here whenever the Class model changes shape
+ *
+ * @param {u64} version - Envelope version the payload was written at
+ * @param {Value} data - Payload at that version
+ * @returns {Result<Value, anyhow::Error>} Payload at the current version
+ * @throws {anyhow::Error} If the payload is from a newer release
+ */
+fn upgrade_blob(mut version: u64, mut data: Value) -> Result<Value, anyhow::Error> {
+    if version > CLASS_BLOB_VERSION {
+        return Err(anyhow!("Stored data version {version} is newer than supported version {CLASS_BLOB_VERSION}"));
+    }
+
+    while version < CLASS_BLOB_VERSION {
+        data = match version {
+            // Version 1 only introduced the envelope; the payload shape is unchanged
+            0 => data,
+            _ => return Err(anyhow!("No upgrade path from data version {version}")),
+        };
+        version += 1;
+    }
+
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        database_functions::ScheduleRepository,
+        test_support::{class, empty_pool, MON_WED},
+        Class,
+    };
+
+    /** Applies the migrations up to and including `version`, as an older release would have */
+    fn migrate_to(pool: &DbPool, version: i32) {
+        let mut conn = pool.get().unwrap();
+        for migration in MIGRATIONS.iter().filter(|migration| migration.version <= version) {
+            let tx = conn.transaction().unwrap();
+            (migration.apply)(&tx).unwrap();
+            tx.pragma_update(None, "user_version", migration.version).unwrap();
+            tx.commit().unwrap();
+        }
+    }
+
+    #[test]
+    fn schedule_metadata_is_backfilled_from_cached_sections() {
+        let pool = empty_pool();
+        migrate_to(&pool, 8);
+        {
+            let conn = pool.get().unwrap();
+            let plan_id: String = conn.query_row("SELECT id FROM plans WHERE active = 1", [], |row| row.get(0)).unwrap();
+            for (position, class) in [class("CSC", "116", "001", " Smith, J ", MON_WED, 935, 1025), class("MA", "241", "002", "", MON_WED, 1040, 1130)].iter().enumerate() {
+                let section_id = format!("{}{}/{}", class.code, class.name, class.classes[0].section);
+                conn.execute(
+                    "INSERT INTO classes (id, classname, data) VALUES (?1, ?2, ?3)",
+                    params![section_id, format!("{}{}", class.code, class.name), encode_blob(class).unwrap()]
+                ).unwrap();
+                conn.execute(
+                    "INSERT INTO schedule_sections (schedule_id, position, class_id) VALUES ('s1', ?1, ?2)",
+                    params![position as i64, section_id]
+                ).unwrap();
+            }
+            // A schedule whose only section has no set time still gets a row
+            let online = class("HI", "233", "601", "", MON_WED, -1, -1);
+            conn.execute(
+                "INSERT INTO classes (id, classname, data) VALUES ('HI233/601', 'HI233', ?1)",
+                params![encode_blob(&online).unwrap()]
+            ).unwrap();
+            conn.execute("INSERT INTO schedule_sections (schedule_id, position, class_id) VALUES ('s2', 0, 'HI233/601')", []).unwrap();
+            conn.execute("INSERT INTO schedules (plan_id, id) VALUES (?1, 's1'), (?1, 's2')", params![plan_id]).unwrap();
+        }
+        run_migrations(&pool).unwrap();
+
+        let conn = pool.get().unwrap();
+        let metadata = |id: &str| -> (Option<i32>, Option<i32>, i64, i64) {
+            conn.query_row(
+                "SELECT earliest_start, latest_end, campus_days, day_mask FROM schedule_metadata WHERE schedule_id = ?1",
+                params![id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+            ).unwrap()
+        };
+        // Both sections meet Monday and Wednesday
+        assert_eq!(metadata("s1"), (Some(935), Some(1130), 2, 0b101));
+        assert_eq!(metadata("s2"), (None, None, 0, 0));
+
+        let instructors: Vec<String> = conn.prepare("SELECT instructor FROM schedule_instructors ORDER BY schedule_id").unwrap()
+            .query_map([], |row| row.get(0)).unwrap()
+            .collect::<Result<_, _>>().unwrap();
+        assert_eq!(instructors, vec!["Smith, J".to_string()]);
+    }
+
+    #[test]
+    fn course_search_index_is_filled_from_cached_sections() {
+        let pool = empty_pool();
+        migrate_to(&pool, 6);
+        {
+            let conn = pool.get().unwrap();
+            let mut first = class("CSC", "316", "001", "Smith, J", MON_WED, 935, 1025);
+            first.description = "Units: 3 | Prerequisites: CSC 216 | Description: Data structures and algorithms".to_string();
+            let second = class("CSC", "316", "002", "Lee, K", MON_WED, 1040, 1130);
+            for (id, section) in [("CSC316/001", &first), ("CSC316/002", &second)] {
+                conn.execute(
+                    "INSERT INTO classes (id, classname, data) VALUES (?1, 'CSC316', ?2)",
+                    params![id, encode_blob(section).unwrap()]
+                ).unwrap();
+            }
+        }
+        run_migrations(&pool).unwrap();
+
+        let conn = pool.get().unwrap();
+        let row: (String, String, String, String) = conn.query_row(
+            "SELECT code, description, prerequisites, instructors FROM course_search WHERE course_search MATCH 'algorithm'",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        ).unwrap();
+        assert_eq!(row, (
+            "CSC".to_string(),
+            "Data structures and algorithms".to_string(),
+            "CSC 216".to_string(),
+            "Smith, J\nLee, K".to_string()
+        ));
+    }
+
+    #[test]
+    fn payloads_shaped_like_envelopes_round_trip() {
+        let payload = json!({ "version": 7, "data": "not an envelope" });
+        assert_eq!(decode_blob::<Value>(&encode_blob(&payload).unwrap()).unwrap(), payload);
+
+        // Rows written before the tag existed still decode
+        let section = class("CSC", "116", "001", "Smith, J", MON_WED, 935, 1025);
+        let untagged = json!({ "version": 1, "data": section }).to_string();
+        let legacy = serde_json::to_string(&section).unwrap();
+        for json in [untagged, legacy] {
+            assert_eq!(decode_blob::<Class>(&json).unwrap().classes[0].section, "001");
+        }
+    }
+
+    #[test]
+    fn untagged_envelopes_are_tagged_by_migration_14() {
+        let pool = empty_pool();
+        migrate_to(&pool, 13);
+        let section = class("CSC", "116", "001", "Smith, J", MON_WED, 935, 1025);
+        pool.get().unwrap().execute(
+            "INSERT INTO classes (id, classname, data) VALUES ('CSC116/001', 'CSC116', ?1)",
+            params![json!({ "version": 1, "data": section }).to_string()]
+        ).unwrap();
+        run_migrations(&pool).unwrap();
+
+        let data: String = pool.get().unwrap().query_row("SELECT data FROM classes", [], |row| row.get(0)).unwrap();
+        let value: Value = serde_json::from_str(&data).unwrap();
+        assert_eq!(envelope_parts(&value).map(|(version, _)| version), Some(CLASS_BLOB_VERSION));
+        assert_eq!(decode_blob::<Class>(&data).unwrap().classes[0].instructor, "Smith, J");
+    }
+
+    #[test]
+    fn legacy_pin_is_resolved_before_duplicates_collapse() {
+        let pool = empty_pool();
+        migrate_to(&pool, 2);
+
+        let first = class("CSC", "116", "001", "Smith, J", MON_WED, 935, 1025);
+        let second = class("MA", "241", "002", "Lee, K", MON_WED, 1040, 1130);
+        let pinned = class("HI", "233", "601", "", MON_WED, 1200, 1250);
+        {
+            let conn = pool.get().unwrap();
+            // The duplicate at index 1 disappears, so index 2 no longer exists afterwards
+            for schedule in [vec![first.clone(), second.clone()], vec![second.clone(), first.clone()], vec![pinned.clone()]] {
+                conn.execute(
+                    "INSERT INTO schedules (id, data) VALUES (?1, ?2)",
+                    params![Uuid::new_v4().to_string(), encode_blob(&schedule).unwrap()]
+                ).unwrap();
+            }
+            conn.execute("INSERT INTO data (id, version, os, schedule) VALUES (0, '', 'linux', 2)", []).unwrap();
+        }
+        run_migrations(&pool).unwrap();
+
+        let pinned_id: Option<String> = pool.get().unwrap()
+            .query_row("SELECT pinned_schedule_id FROM plans WHERE active = 1", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(pinned_id, Some(ScheduleRepository::schedule_id(&[pinned])));
+    }
+
+    #[test]
+    fn legacy_schedules_become_content_hash_section_references() {
+        let pool = empty_pool();
+        migrate_to(&pool, 2);
+
+        let first = class("CSC", "116", "001", "Smith, J", MON_WED, 935, 1025);
+        let second = class("MA", "241", "002", "Lee, K", MON_WED, 1040, 1130);
+        {
+            let conn = pool.get().unwrap();
+            // The same schedule stored twice in different orders collapses into one row
+            for schedule in [vec![first.clone(), second.clone()], vec![second.clone(), first.clone()]] {
+                conn.execute(
+                    "INSERT INTO schedules (id, data) VALUES (?1, ?2)",
+                    params![Uuid::new_v4().to_string(), encode_blob(&schedule).unwrap()]
+                ).unwrap();
+            }
+        }
+        run_migrations(&pool).unwrap();
+
+        let conn = pool.get().unwrap();
+        let plan_id: String = conn.query_row("SELECT id FROM plans WHERE active = 1", [], |row| row.get(0)).unwrap();
+        let schedules = ScheduleRepository::load_for_plan(&conn, "schedules", &plan_id).unwrap();
+        assert_eq!(schedules.len(), 1);
+        assert_eq!(schedules[0].id, ScheduleRepository::schedule_id(&[first, second]));
+        assert_eq!(schedules[0].classes.len(), 2);
+        assert_eq!(schedules[0].classes[0].code, "CSC");
+
+        let cached: i64 = conn.query_row("SELECT COUNT(*) FROM classes WHERE id IN ('CSC116/001', 'MA241/002')", [], |row| row.get(0)).unwrap();
+        assert_eq!(cached, 2);
+    }
+}
@@ -1,6 +1,7 @@
 use serde::{Serialize, Deserialize};
 use std::{fmt};
 use std::sync::atomic::{AtomicBool};
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
@@ -10,10 +11,23 @@ pub struct AppState {
     pub db_pool: DbPool,
     pub connect_info: Arc<Mutex<ConnectInfo>>,
     pub startup_complete: AtomicBool,
+    pub storage: StoragePaths,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
-pub enum EventType { Events, Schedules }
+/**
+ * On-disk locations resolved at startup
+ */
+#[derive(Debug, Clone, Serialize)]
+pub struct StoragePaths {
+    /** Directory holding the database and its backups */
+    pub data_dir: PathBuf,
+    /** Directory holding downloaded Chrome resources */
+    pub cache_dir: PathBuf,
+    pub database: PathBuf,
+    pub resources: PathBuf,
+    /** File storing the storage directory override */
+    pub settings_file: PathBuf,
+}
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Class { 
@@ -23,6 +37,25 @@ pub struct Class {
     pub classes: Vec<TimeBlock>, 
 }
 
+/**
+ * Cached course matching a full-text search
+ */
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CourseSearchResult {
+    /** Course identifier used by the classes cache (code + name, e.g. "CSC116") */
+    pub classname: String,
+    pub code: String,
+    pub name: String,
+    pub description: String,
+    pub prerequisites: String,
+    /** Distinct instructors across all cached sections */
+    pub instructors: Vec<String>,
+    /** Excerpt of the best-matching field with matches wrapped in <mark> tags */
+    pub snippet: String,
+    /** Relevance, higher is better */
+    pub score: f64,
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct TimeBlock { 
     pub section: String, 
@@ -31,11 +64,259 @@ pub struct TimeBlock {
     pub instructor: String, 
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
+pub struct StoredSchedule {
+    pub id: String,
+    pub classes: Vec<Class>,
+    /** User annotations; only present for favorites */
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<FavoriteDetails>,
+}
+
+/**
+ * User annotations on a favorite schedule
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct FavoriteDetails {
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub notes: String,
+    /** Hex color such as "#3b82f6", or None for the default color */
+    #[serde(default)]
+    pub color: Option<String>,
+    /** Free-form labels such as "safe option", in the order they were added */
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/**
+ * Sort order of schedule queries
+ * Queries without a sort keep the stored order: the generator's ranking for results,
+ * the order they were added for favorites
+ */
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum ScheduleSort {
+    /** Earliest class start of the week */
+    StartTime,
+    /** Number of weekdays with classes */
+    DaysOnCampus,
+    /** Soft-constraint penalty; lowest first is best first */
+    Score,
+}
+
+/**
+ * Conditions a schedule must meet to be returned by a schedule query
+ * All conditions combine with AND; empty lists don't filter
+ */
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct ScheduleFilter {
+    /** Section IDs (e.g., "CSC116/001") the schedule must all contain */
+    #[serde(default)]
+    pub include_sections: Vec<String>,
+    /** Instructor names as scraped ("Last,First"), matched whole and case-insensitively, none of the schedule's sections may have */
+    #[serde(default)]
+    pub exclude_instructors: Vec<String>,
+    /** Weekdays (Mon-Fri) the schedule must leave free */
+    #[serde(default)]
+    pub free_days: [bool; 5],
+    /** Content-hash IDs the schedule must be one of, e.g. to look up the pinned schedule */
+    #[serde(default)]
+    pub ids: Vec<String>,
+}
+
+/** One page of a filtered, sorted schedule query */
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ScheduleQuery {
+    /** Table name ("schedules" or "favorites") */
+    pub table: String,
+    #[serde(default)]
+    pub offset: usize,
+    pub limit: usize,
+    #[serde(default)]
+    pub sort: Option<ScheduleSort>,
+    #[serde(default)]
+    pub descending: bool,
+    #[serde(default)]
+    pub filter: ScheduleFilter,
+}
+
+/** Result of a schedule query */
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SchedulePage {
+    pub schedules: Vec<StoredSchedule>,
+    /** Number of schedules matching the filter across all pages */
+    pub total: usize,
+}
+
+/**
+ * Reference to the schedule shown by default on the scheduler page
+ * Identified by content-hash ID so it survives regeneration and reordering
+ */
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PinnedSchedule {
+    /** Content-hash ID of the pinned schedule */
+    pub id: String,
+    /** Table the schedule lives in ("schedules" or "favorites") */
+    pub table: String,
+}
+
+/**
+ * Named workspace owning its own course list, events, schedules, favorites and pin
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Plan {
+    pub id: String,
+    pub name: String,
+    /** Whether this is the plan all other commands currently operate on */
+    pub active: bool,
+    /** Creation time in seconds since the Unix epoch */
+    pub created_at: i64,
+}
+
+/**
+ * Schedule decoded from a share code
+ */
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SharedSchedule {
+    /** First day of the sender's term ("YYYY-MM-DD"), if they had set one */
+    pub term_start: Option<String>,
+    pub classes: Vec<Class>,
+}
+
+/**
+ * Outcome of importing an iCalendar file into the events table
+ */
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CalendarImport {
+    /** Events created by this import */
+    pub added: usize,
+    /** Previously imported events (same UID) that were changed */
+    pub updated: usize,
+    /** Previously imported events that were already up to date */
+    pub unchanged: usize,
+    /** Reasons for every calendar entry that wasn't imported, e.g. "Dentist: not a weekly recurring event" */
+    pub skipped: Vec<String>,
+}
+
+/**
+ * Outcome of importing a pasted course list into the class parameters
+ */
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ClassParamImport {
+    /** Courses added to the list */
+    pub added: usize,
+    /** Courses that were already in the list with the same section and instructor */
+    pub unchanged: usize,
+    /** Reasons for every entry that wasn't imported, e.g. "Line 3: 'Calculus' doesn't start with a course code" */
+    pub skipped: Vec<String>,
+}
+
+/**
+ * Dates of the term a plan covers
+ * Bounds the weekly recurrences of exported calendars; dates are "YYYY-MM-DD"
+ */
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TermCalendar {
+    /** First day of classes */
+    pub start: String,
+    /** Last day of classes, inclusive */
+    pub end: String,
+    /** Days without classes, in ascending order */
+    #[serde(default)]
+    pub holidays: Vec<String>,
+}
+
+/**
+ * Recorded schedule generation run
+ * Its schedules are retained until the run is deleted or pruned by the history limit
+ */
+#[derive(Serialize, Deserialize, Clone)]
+pub struct GenerationRun {
+    pub id: String,
+    /** Time the run finished, in seconds since the Unix epoch */
+    pub created_at: i64,
+    /** Inputs the run was generated from */
+    pub parameters: ScrapeClassesParameters,
+    pub schedule_count: usize,
+    pub duration_ms: u64,
+}
+
+/**
+ * Actions the next undo and redo would revert or reapply
+ */
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct UndoState {
+    /** Label of the action undone next, or None if there is nothing to undo */
+    pub undo: Option<String>,
+    /** Label of the action redone next, or None if there is nothing to redo */
+    pub redo: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ScheduleGroup {
+    /** Content-hash ID of the representative schedule */
+    pub id: String,
+    pub schedule: Vec<Class>,
+    pub alternatives: Vec<Vec<Class>>,
+    pub count: usize,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+pub enum SelectionMode { #[default] Sequential, Diverse }
+
+/** How an imported archive is combined with the existing data */
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum ImportMode { Merge, Replace }
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Constraint<T> { 
+    pub value: T, 
+    pub hard: bool, 
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub struct FreeBlock { 
+    pub minutes: i32, 
+    pub days: [bool; 5], 
+    pub window: (i32, i32), 
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct ScheduleConstraints { 
+    pub max_daily_minutes: Option<Constraint<i32>>, 
+    pub max_consecutive_minutes: Option<Constraint<i32>>, 
+    pub free_block: Option<Constraint<FreeBlock>>, 
+    pub free_days: Option<Constraint<[bool; 5]>>, 
+}
+
+#[derive(Serialize, Deserialize, Clone)]
 pub struct ScrapeClassesParameters { 
     pub params_checkbox: [bool; 3], 
     pub classes: Vec<ClassParam>, 
     pub events: Vec<EventParam>, 
+    #[serde(default)]
+    pub selection_mode: SelectionMode,
+    #[serde(default)]
+    pub constraints: ScheduleConstraints,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SectionAlternativesParameters { 
+    pub schedule: Vec<Class>, 
+    pub course_index: usize, 
+    pub events: Vec<EventParam>, 
+    /** Search filters as in generation; open sections only makes a fresh search for current seats */
+    #[serde(default)]
+    pub params_checkbox: [bool; 3], 
+    #[serde(default)]
+    pub constraints: ScheduleConstraints, 
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SectionAlternative { 
+    pub class: Class, 
+    pub penalty: u32, 
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -79,9 +360,15 @@ impl fmt::Display for Class {
 }
 
 #[derive(Debug, Serialize, Deserialize, Default, Clone)]
-pub struct ConnectInfo { pub os: String, pub version: String, }
+pub struct ConnectInfo { 
+    pub os: String, 
+    pub version: String, 
+    /** Directory Chrome and ChromeDriver are installed in; not persisted */
+    #[serde(skip)]
+    pub resources_dir: PathBuf, 
+}
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
 pub struct Event {
     pub id: String,
     pub title: String,
@@ -0,0 +1,453 @@
+//! CSV and JSON export of the active plan's `schedules` or `favorites` table
+//!
+//! Rows are written while the sections are read from the database, so exporting
+//! thousands of generated schedules never holds more than one section in memory.
+//! The format is chosen by the file extension.
+//!
+//! CSV has a header row and one row per meeting of each section of each schedule.
+//! A meeting is a set of weekdays sharing one start and end time; sections without set
+//! times get a single row with empty days and times:
+//!
+//! ```text
+//! schedule_id,schedule_number,course,section,instructor,days,start_time,end_time,location
+//! 5f0c...,1,CSC 116,001,"Smith, J",Mon Wed Fri,09:35,10:25,EB2 1231
+//! ```
+//!
+//! JSON is a single object (format version 1):
+//!
+//! ```text
+//! {
+//!   "format": "koka-schedules",
+//!   "version": 1,
+//!   "table": "favorites",
+//!   "schedules": [
+//!     {
+//!       "id": "5f0c...",                  // content-hash schedule ID
+//!       "number": 1,                     // position in the table, starting at 1
+//!       "details": { ... },              // favorites only: title, notes, color, tags
+//!       "sections": [
+//!         {
+//!           "code": "CSC", "name": "116", "section": "001",
+//!           "instructor": "Smith, J", "location": "EB2 1231",
+//!           "meetings": [ { "days": ["Mon", "Wed", "Fri"], "start": "09:35", "end": "10:25" } ]
+//!         }
+//!       ]
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Times are 24-hour "HH:MM" local times; `meetings` is empty for sections without set times.
+//!
+//! Schedules referencing a section missing from the classes cache are left out with a
+//! warning, like everywhere else schedules are read, rather than exported incomplete.
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use anyhow::anyhow;
+use rusqlite::params;
+use serde::Serialize;
+
+use crate::{
+    calendar::meeting_patterns,
+    database_functions::{validate_table_name, FavoriteRepository, ACTIVE_PLAN},
+    migrations::decode_blob,
+    Class, DbPool, FavoriteDetails,
+};
+
+// === CONFIGURATION CONSTANTS ===
+
+/** Identifies JSON exports produced by this module */
+const JSON_FORMAT_NAME: &str = "koka-schedules";
+
+/** Version of the JSON layout documented above */
+const JSON_FORMAT_VERSION: u32 = 1;
+
+/** CSV header, matching the fields written by `write_csv_rows` */
+const CSV_HEADER: &str = "schedule_id,schedule_number,course,section,instructor,days,start_time,end_time,location";
+
+/** Weekday names for Monday through Friday, matching `TimeBlock::days` */
+const DAY_NAMES: [&str; 5] = ["Mon", "Tue", "Wed", "Thu", "Fri"];
+
+// === EXPORT TYPES ===
+
+/** Output format, chosen by the file extension */
+#[derive(Clone, Copy, PartialEq)]
+enum ExportFormat {
+    Csv,
+    Json,
+}
+
+/** Section as written to JSON exports */
+#[derive(Serialize)]
+struct ExportedSection<'a> {
+    code: &'a str,
+    name: &'a str,
+    section: &'a str,
+    instructor: &'a str,
+    location: &'a str,
+    meetings: Vec<ExportedMeeting>,
+}
+
+/** Meeting pattern as written to JSON exports */
+#[derive(Serialize)]
+struct ExportedMeeting {
+    days: Vec<&'static str>,
+    start: String,
+    end: String,
+}
+
+// === PUBLIC API ===
+
+/**
+ * Exports the active plan's schedules or favorites to a CSV or JSON file
+ *
+ * @param {String} path - Destination file path ending in .csv or .json (chosen by the user)
+ * @param {String} table - Source table name ("schedules" or "favorites")
+ * @param {&DbPool} pool - Database connection pool
+ * @returns {Result<usize, anyhow::Error>} Number of schedules written; incomplete ones are skipped
+ * @throws {anyhow::Error} If the extension or table is unsupported, or reading or writing fails
+ */
+pub async fn export_schedule_table(path: String, table: String, pool: &DbPool) -> Result<usize, anyhow::Error> {
+    validate_table_name(&table)?;
+    if table != "schedules" && table != "favorites" {
+        return Err(anyhow!("Only schedules and favorites can be exported, not '{}'", table));
+    }
+    let extension = Path::new(&path)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+    let format = match extension.as_str() {
+        "csv" => ExportFormat::Csv,
+        "json" => ExportFormat::Json,
+        _ => return Err(anyhow!("Unsupported export format '{}'; use .csv or .json", extension)),
+    };
+
+    let pool = pool.clone();
+    tokio::task::spawn_blocking(move || -> Result<usize, anyhow::Error> {
+        let conn = pool.get()?;
+        let plan_id: String = conn.query_row(&format!("SELECT {ACTIVE_PLAN}"), [], |row| row.get(0))?;
+        let mut details = if table == "favorites" {
+            FavoriteRepository::load_details_for_plan(&conn, &plan_id)?
+        } else {
+            Default::default()
+        };
+
+        let mut out = BufWriter::new(File::create(&path)?);
+        match format {
+            ExportFormat::Csv => writeln!(out, "{CSV_HEADER}")?,
+            ExportFormat::Json => write!(
+                out,
+                "{{\"format\":\"{JSON_FORMAT_NAME}\",\"version\":{JSON_FORMAT_VERSION},\"table\":{},\"schedules\":[",
+                serde_json::to_string(&table)?
+            )?,
+        }
+
+        // Each row carries its schedule's count of uncached sections, so an incomplete
+        // schedule is known before any of it is written
+        let mut stmt = conn.prepare(&format!(
+            "SELECT s.id, c.data, COUNT(*) FILTER (WHERE c.id IS NULL) OVER (PARTITION BY s.id) FROM {table} s
+             JOIN schedule_sections ss ON ss.schedule_id = s.id
+             LEFT JOIN classes c ON c.id = ss.class_id
+             WHERE s.plan_id = ?1
+             ORDER BY s.rowid, ss.position"
+        ))?;
+        let mut rows = stmt.query(params![plan_id])?;
+
+        let mut current_id: Option<String> = None;
+        let mut skipped_ids: Vec<String> = Vec::new();
+        let mut schedule_count = 0;
+        let mut section_count = 0;
+        while let Some(row) = rows.next()? {
+            let id: String = row.get(0)?;
+            let missing: i64 = row.get(2)?;
+            let Some(data) = row.get::<_, Option<String>>(1)?.filter(|_| missing == 0) else {
+                if skipped_ids.last() != Some(&id) {
+                    skipped_ids.push(id);
+                }
+                continue;
+            };
+            let class: Class = decode_blob(&data)?;
+
+            if current_id.as_deref() != Some(id.as_str()) {
+                schedule_count += 1;
+                section_count = 0;
+                if format == ExportFormat::Json {
+                    if current_id.is_some() {
+                        write!(out, "]}},")?;
+                    }
+                    write!(out, "{{\"id\":{},\"number\":{schedule_count},", serde_json::to_string(&id)?)?;
+                    if let Some(details) = details.remove(&id) {
+                        write!(out, "\"details\":{},", serde_json::to_string::<FavoriteDetails>(&details)?)?;
+                    }
+                    write!(out, "\"sections\":[")?;
+                }
+                current_id = Some(id);
+            }
+
+            let id = current_id.as_deref().unwrap_or_default();
+            match format {
+                ExportFormat::Csv => write_csv_rows(&mut out, id, schedule_count, &class)?,
+                ExportFormat::Json => section_count = write_json_sections(&mut out, &class, section_count)?,
+            }
+        }
+
+        if format == ExportFormat::Json {
+            if current_id.is_some() {
+                write!(out, "]}}")?;
+            }
+            writeln!(out, "]}}")?;
+        }
+        out.flush()?;
+        if !skipped_ids.is_empty() {
+            eprintln!("Warning: Skipped {} schedule(s) with sections missing from the cache: {}", skipped_ids.len(), skipped_ids.join(", "));
+        }
+        println!("Exported {schedule_count} schedule(s) from {table} to {path}");
+        Ok(schedule_count)
+    }).await?
+}
+
+// === WRITERS ===
+
+/**
+ * Writes the CSV rows of one cached class: one per meeting pattern of each section
+ *
+ * @param {&mut impl Write} out - Destination
+ * @param {&str} schedule_id - Content-hash ID of the schedule the class belongs to
+ * @param {usize} schedule_number - Position of the schedule in the table, starting at 1
+ * @param {&Class} class - Cached class holding the schedule's sections of one course
+ * @returns {Result<(), anyhow::Error>} Success or error
+ * @throws {anyhow::Error} If writing fails
+ */
+fn write_csv_rows(out: &mut impl Write, schedule_id: &str, schedule_number: usize, class: &Class) -> Result<(), anyhow::Error> {
+    let course = format!("{} {}", class.code, class.name);
+    for section in &class.classes {
+        let patterns = meeting_patterns(&section.days);
+        let meetings: Vec<(String, String, String)> = if patterns.is_empty() {
+            vec![Default::default()]
+        } else {
+            patterns
+                .into_iter()
+                .map(|((start, end), days)| (day_names(&days).join(" "), format_time(start), format_time(end)))
+                .collect()
+        };
+
+        for (days, start, end) in meetings {
+            let fields = [
+                schedule_id,
+                &schedule_number.to_string(),
+                &course,
+                &section.section,
+                &section.instructor,
+                &days,
+                &start,
+                &end,
+                &section.location,
+            ];
+            let line = fields.iter().map(|field| escape_csv(field)).collect::<Vec<_>>().join(",");
+            writeln!(out, "{line}")?;
+        }
+    }
+    Ok(())
+}
+
+/**
+ * Writes the JSON objects of one cached class's sections into a schedule's "sections" array
+ *
+ * @param {&mut impl Write} out - Destination
+ * @param {&Class} class - Cached class holding the schedule's sections of one course
+ * @param {usize} written - Sections already written to the array, to place separators
+ * @returns {Result<usize, anyhow::Error>} Sections in the array after this class
+ * @throws {anyhow::Error} If writing fails
+ */
+fn write_json_sections(out: &mut impl Write, class: &Class, written: usize) -> Result<usize, anyhow::Error> {
+    let mut written = written;
+    for section in &class.classes {
+        let meetings = meeting_patterns(&section.days)
+            .into_iter()
+            .map(|((start, end), days)| ExportedMeeting { days: day_names(&days), start: format_time(start), end: format_time(end) })
+            .collect();
+        if written > 0 {
+            write!(out, ",")?;
+        }
+        serde_json::to_writer(
+            &mut *out,
+            &ExportedSection {
+                code: &class.code,
+                name: &class.name,
+                section: &section.section,
+                instructor: &section.instructor,
+                location: &section.location,
+                meetings,
+            },
+        )?;
+        written += 1;
+    }
+    Ok(written)
+}
+
+// === FORMATTING HELPERS ===
+
+/**
+ * Names of the weekdays a meeting pattern applies to
+ *
+ * @param {&[bool; 5]} days - Whether the pattern applies to Monday through Friday
+ * @returns {Vec<&'static str>} Day names in weekday order
+ */
+fn day_names(days: &[bool; 5]) -> Vec<&'static str> {
+    DAY_NAMES.iter().zip(days).filter(|(_, &meets)| meets).map(|(&name, _)| name).collect()
+}
+
+/**
+ * Formats an HHMM time as "HH:MM"
+ *
+ * @param {i32} time - Time as HHMM (e.g. 935)
+ * @returns {String} Time such as "09:35"
+ */
+fn format_time(time: i32) -> String {
+    format!("{:02}:{:02}", time / 100, time % 100)
+}
+
+/**
+ * Quotes a CSV field when it contains a separator, quote, line break or edge whitespace
+ *
+ * @param {&str} field - Raw field
+ * @returns {String} Field safe to place between commas
+ */
+fn escape_csv(field: &str) -> String {
+    let needs_quotes = field.contains([',', '"', '\n', '\r']) || field.trim() != field;
+    if needs_quotes {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        database_functions::{unit_of_work, ClassRepository, ScheduleRepository},
+        test_support::{class, memory_pool, scratch_dir, MON_WED, TUE_THU},
+        TimeBlock,
+    };
+
+    /** Lab meeting Mon/Wed 9:00-9:50 and Fri 10:00-11:50, plus an online section without times */
+    fn lab() -> Class {
+        let mut days = [((-1, -1), false); 5];
+        days[0] = ((900, 950), true);
+        days[2] = ((900, 950), true);
+        days[4] = ((1000, 1150), true);
+        Class {
+            code: "CH".to_string(),
+            name: "101".to_string(),
+            description: String::new(),
+            classes: vec![
+                TimeBlock { section: "201".to_string(), location: "Dabney 220".to_string(), days, instructor: "Smith, J".to_string() },
+                TimeBlock { section: "601".to_string(), location: String::new(), days: [((-1, -1), false); 5], instructor: String::new() },
+            ],
+        }
+    }
+
+    fn csv_lines(class: &Class) -> Vec<String> {
+        let mut out = Vec::new();
+        write_csv_rows(&mut out, "abc", 2, class).unwrap();
+        String::from_utf8(out).unwrap().lines().map(str::to_string).collect()
+    }
+
+    /** Stores two generated schedules and favorites the second; returns their IDs */
+    async fn store_schedules(pool: &DbPool) -> Vec<String> {
+        let first = vec![class("CSC", "116", "001", "Smith,John", MON_WED, 935, 1025), lab()];
+        let second = vec![class("CSC", "116", "002", "Lee,Ann", TUE_THU, 830, 920), lab()];
+        ClassRepository::save_sections_batch(&[vec![first[0].clone(), second[0].clone(), lab()]], pool).await.unwrap();
+        let schedules = vec![first, second];
+        let ids = unit_of_work(pool, move |tx| ScheduleRepository::save_batch_in_transaction(tx, &schedules, &Default::default()))
+            .await
+            .unwrap();
+        FavoriteRepository::change_status(ids[1].clone(), true, pool).await.unwrap();
+        ids
+    }
+
+    /** Exports a table to a fresh file with the given extension and returns its contents */
+    async fn export(pool: &DbPool, table: &str, extension: &str) -> (usize, String) {
+        let dir = scratch_dir("schedule-export");
+        let path = dir.join(format!("export.{extension}"));
+        let count = export_schedule_table(path.to_string_lossy().into_owned(), table.to_string(), pool).await.unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        (count, contents)
+    }
+
+    #[test]
+    fn csv_fields_are_quoted_only_when_needed() {
+        assert_eq!(escape_csv("EB2 1231"), "EB2 1231");
+        assert_eq!(escape_csv(""), "");
+        assert_eq!(escape_csv("Smith, J"), "\"Smith, J\"");
+        assert_eq!(escape_csv("The \"Lab\""), "\"The \"\"Lab\"\"\"");
+        assert_eq!(escape_csv("two\nlines"), "\"two\nlines\"");
+        assert_eq!(escape_csv(" padded"), "\" padded\"");
+    }
+
+    #[test]
+    fn csv_has_a_row_per_meeting_pattern() {
+        assert_eq!(csv_lines(&lab()), [
+            "abc,2,CH 101,201,\"Smith, J\",Mon Wed,09:00,09:50,Dabney 220",
+            "abc,2,CH 101,201,\"Smith, J\",Fri,10:00,11:50,Dabney 220",
+            // Sections without set times get one row with empty days and times
+            "abc,2,CH 101,601,,,,,",
+        ]);
+    }
+
+    #[tokio::test]
+    async fn json_exports_are_valid_with_and_without_details() {
+        let pool = memory_pool();
+        let ids = store_schedules(&pool).await;
+        FavoriteRepository::update_details(ids[1].clone(), FavoriteDetails { title: "Mornings".to_string(), ..Default::default() }, &pool)
+            .await
+            .unwrap();
+
+        let (count, contents) = export(&pool, "schedules", "json").await;
+        let json: serde_json::Value = serde_json::from_str(&contents).unwrap();
+        assert_eq!(count, 2);
+        assert_eq!((json["format"].as_str(), json["version"].as_u64(), json["table"].as_str()), (Some(JSON_FORMAT_NAME), Some(1), Some("schedules")));
+        let schedules = json["schedules"].as_array().unwrap();
+        assert_eq!(schedules.iter().map(|schedule| schedule["id"].as_str().unwrap()).collect::<Vec<_>>(), ids);
+        assert!(schedules.iter().all(|schedule| schedule.get("details").is_none()));
+        let sections = schedules[0]["sections"].as_array().unwrap();
+        assert_eq!(sections.len(), 3);
+        assert_eq!(sections[1]["meetings"].as_array().unwrap().len(), 2);
+        assert!(sections[2]["meetings"].as_array().unwrap().is_empty());
+
+        let (count, contents) = export(&pool, "favorites", "json").await;
+        let json: serde_json::Value = serde_json::from_str(&contents).unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(json["schedules"][0]["number"].as_u64(), Some(1));
+        assert_eq!(json["schedules"][0]["details"]["title"].as_str(), Some("Mornings"));
+
+        let empty = memory_pool();
+        let (count, contents) = export(&empty, "favorites", "json").await;
+        assert_eq!(count, 0);
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&contents).unwrap()["schedules"], serde_json::json!([]));
+    }
+
+    #[tokio::test]
+    async fn schedules_with_uncached_sections_are_skipped() {
+        let pool = memory_pool();
+        let ids = store_schedules(&pool).await;
+        let uncached = ClassRepository::section_id(&class("CSC", "116", "001", "Smith,John", MON_WED, 935, 1025));
+        pool.get().unwrap().execute("DELETE FROM classes WHERE id = ?1", params![uncached]).unwrap();
+
+        let (count, contents) = export(&pool, "schedules", "csv").await;
+
+        assert_eq!(count, 1);
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(lines.len(), 5);
+        assert!(lines[1..].iter().all(|line| line.starts_with(&format!("{},1,", ids[1]))));
+    }
+}
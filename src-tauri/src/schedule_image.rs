@@ -0,0 +1,531 @@
+//! Weekly timetable rendering of schedules as SVG and PNG
+//!
+//! The renderer draws Monday through Friday (plus the weekend when an included event falls
+//! on it) between the earliest and latest hour used, with one colored block per meeting
+//! showing the course, section, times and room. Sections without set meeting times are
+//! listed underneath the grid. Colors are assigned by course order, so a schedule always
+//! renders the same way. PNGs are rasterized from the same SVG with resvg.
+
+use std::{
+    cmp::Reverse,
+    fmt::Write as _,
+    fs,
+    path::Path,
+    sync::{Arc, OnceLock},
+};
+
+use anyhow::anyhow;
+use resvg::{tiny_skia, usvg};
+
+use crate::{
+    database_functions::{EventRepository, ScheduleRepository},
+    tauri_backend::{
+        class_combinations::to_minutes,
+        event_processor::{ColumnPlacement, EventProcessor},
+    },
+    Class, DbPool, Event,
+};
+
+// === CONFIGURATION CONSTANTS ===
+
+/** Background colors assigned to courses in schedule order; white text stays readable on all of them */
+const COURSE_COLORS: [&str; 10] = [
+    "#2563eb", "#16a34a", "#dc2626", "#9333ea", "#ea580c",
+    "#0891b2", "#c026d3", "#4d7c0f", "#b45309", "#4f46e5",
+];
+
+/** Background color of personal events */
+const EVENT_COLOR: &str = "#64748b";
+
+/** Font stack; the first family installed on the system is used when rasterizing */
+const FONT_FAMILY: &str = "Segoe UI, Helvetica, Arial, DejaVu Sans, sans-serif";
+
+/** Column headers, Monday first */
+const DAY_NAMES: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/** Hours always shown, so short days don't produce a cramped image */
+const DEFAULT_HOURS: (i32, i32) = (8, 17);
+
+const PADDING: f32 = 16.0;
+const TIME_COLUMN_WIDTH: f32 = 52.0;
+const DAY_COLUMN_WIDTH: f32 = 170.0;
+const HEADER_HEIGHT: f32 = 28.0;
+const HOUR_HEIGHT: f32 = 60.0;
+const LINE_HEIGHT: f32 = 13.0;
+const UNSCHEDULED_LINE_HEIGHT: f32 = 18.0;
+
+/** Approximate width of one character at the block font size, used to shorten long labels */
+const CHARACTER_WIDTH: f32 = 6.0;
+
+/** Resolution multiplier of exported PNGs; 2x keeps text sharp when printed */
+const PNG_SCALE: f32 = 2.0;
+
+// === LAYOUT TYPES ===
+
+/** One rectangle on the grid */
+struct Block {
+    /** Column, 0 for Monday */
+    day: usize,
+    /** Start and end as minutes since midnight */
+    start: i32,
+    end: i32,
+    color: &'static str,
+    /** Text lines, most important first; lines that don't fit are dropped */
+    lines: Vec<String>,
+    /** Side-by-side position among overlapping blocks */
+    placement: ColumnPlacement,
+}
+
+// === PUBLIC API ===
+
+/**
+ * Renders a stored schedule to an image file
+ *
+ * The format is chosen by the file extension: ".svg" or ".png".
+ *
+ * @param {String} path - Destination file path (chosen by the user)
+ * @param {String} id - Content-hash schedule identifier
+ * @param {bool} include_events - Whether to draw the active plan's events as well
+ * @param {&DbPool} pool - Database connection pool
+ * @returns {Result<(), anyhow::Error>} Success or error
+ * @throws {anyhow::Error} If the extension is unsupported, the schedule doesn't exist, or writing fails
+ */
+pub async fn export_schedule_image(path: String, id: String, include_events: bool, pool: &DbPool) -> Result<(), anyhow::Error> {
+    let extension = Path::new(&path)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+    if extension != "svg" && extension != "png" {
+        return Err(anyhow!("Unsupported image format '{}'; use .svg or .png", extension));
+    }
+
+    let svg = render_stored_schedule(id, include_events, pool).await?;
+    let bytes = if extension == "png" {
+        tokio::task::spawn_blocking(move || render_png(&svg, PNG_SCALE)).await??
+    } else {
+        svg.into_bytes()
+    };
+    fs::write(&path, bytes)?;
+    println!("Exported schedule image to {path}");
+    Ok(())
+}
+
+/**
+ * Renders a stored schedule as an SVG document, for previews and printing
+ *
+ * @param {String} id - Content-hash schedule identifier
+ * @param {bool} include_events - Whether to draw the active plan's events as well
+ * @param {&DbPool} pool - Database connection pool
+ * @returns {Result<String, anyhow::Error>} SVG document
+ * @throws {anyhow::Error} If the schedule doesn't exist or database operation fails
+ */
+pub async fn render_stored_schedule(id: String, include_events: bool, pool: &DbPool) -> Result<String, anyhow::Error> {
+    let events = if include_events {
+        EventRepository::load_all("events", pool).await?
+    } else {
+        Vec::new()
+    };
+
+    let pool = pool.clone();
+    tokio::task::spawn_blocking(move || -> Result<String, anyhow::Error> {
+        let conn = pool.get()?;
+        let schedule = ScheduleRepository::load_schedule(&conn, &id)?;
+        if schedule.is_empty() {
+            return Err(anyhow!("Schedule with id '{}' not found", id));
+        }
+        Ok(render_svg(&schedule, &events))
+    }).await?
+}
+
+// === RENDERING ===
+
+/**
+ * Renders a schedule as a weekly timetable
+ *
+ * @param {&[Class]} schedule - Sections in the schedule
+ * @param {&[Event]} events - Personal events drawn alongside the classes
+ * @returns {String} SVG document
+ */
+pub(crate) fn render_svg(schedule: &[Class], events: &[Event]) -> String {
+    let (mut blocks, unscheduled) = collect_blocks(schedule, events);
+    place_blocks(&mut blocks);
+
+    let day_count = if blocks.iter().any(|block| block.day >= 5) { 7 } else { 5 };
+    let first_hour = blocks.iter().map(|block| block.start / 60).min().unwrap_or(DEFAULT_HOURS.0).min(DEFAULT_HOURS.0);
+    let last_hour = blocks.iter().map(|block| (block.end + 59) / 60).max().unwrap_or(DEFAULT_HOURS.1).max(DEFAULT_HOURS.1);
+
+    let grid_left = PADDING + TIME_COLUMN_WIDTH;
+    let grid_top = PADDING + HEADER_HEIGHT;
+    let grid_width = DAY_COLUMN_WIDTH * day_count as f32;
+    let grid_height = HOUR_HEIGHT * (last_hour - first_hour) as f32;
+    let unscheduled_height = if unscheduled.is_empty() {
+        0.0
+    } else {
+        PADDING + UNSCHEDULED_LINE_HEIGHT * (unscheduled.len() + 1) as f32
+    };
+    let width = grid_left + grid_width + PADDING;
+    let height = grid_top + grid_height + unscheduled_height + PADDING;
+    let y_of = |minutes: i32| grid_top + (minutes - first_hour * 60) as f32 * HOUR_HEIGHT / 60.0;
+
+    let mut svg = String::new();
+    let _ = write!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="{FONT_FAMILY}">"#
+    );
+    let _ = write!(svg, r##"<rect width="{width}" height="{height}" fill="#ffffff"/>"##);
+
+    // Day headers and column separators
+    for (day, name) in DAY_NAMES.iter().take(day_count).enumerate() {
+        let x = grid_left + DAY_COLUMN_WIDTH * day as f32;
+        let _ = write!(
+            svg,
+            r##"<text x="{}" y="{}" font-size="13" font-weight="600" fill="#334155" text-anchor="middle">{name}</text>"##,
+            x + DAY_COLUMN_WIDTH / 2.0,
+            grid_top - 10.0
+        );
+        let _ = write!(svg, r##"<line x1="{x}" y1="{grid_top}" x2="{x}" y2="{}" stroke="#e2e8f0"/>"##, grid_top + grid_height);
+    }
+    let _ = write!(
+        svg,
+        r##"<line x1="{0}" y1="{grid_top}" x2="{0}" y2="{1}" stroke="#e2e8f0"/>"##,
+        grid_left + grid_width,
+        grid_top + grid_height
+    );
+
+    // Hour rows and labels
+    for hour in first_hour..=last_hour {
+        let y = y_of(hour * 60);
+        let _ = write!(svg, r##"<line x1="{grid_left}" y1="{y}" x2="{}" y2="{y}" stroke="#e2e8f0"/>"##, grid_left + grid_width);
+        if hour < last_hour {
+            let _ = write!(
+                svg,
+                r##"<text x="{}" y="{}" font-size="11" fill="#64748b" text-anchor="end">{}</text>"##,
+                grid_left - 6.0,
+                y + 4.0,
+                format_hour(hour)
+            );
+        }
+    }
+
+    // Meetings and events
+    for block in &blocks {
+        let column_width = DAY_COLUMN_WIDTH / block.placement.columns as f32;
+        let x = grid_left + DAY_COLUMN_WIDTH * block.day as f32 + column_width * block.placement.column as f32 + 2.0;
+        let y = y_of(block.start) + 1.0;
+        let block_width = column_width * block.placement.span as f32 - 4.0;
+        let block_height = (y_of(block.end) - y_of(block.start) - 2.0).max(LINE_HEIGHT);
+        let _ = write!(
+            svg,
+            r#"<rect x="{x}" y="{y}" width="{block_width}" height="{block_height}" rx="4" fill="{}"/>"#,
+            block.color
+        );
+
+        let max_lines = ((block_height - 4.0) / LINE_HEIGHT).floor().max(1.0) as usize;
+        let max_characters = ((block_width - 8.0) / CHARACTER_WIDTH).floor().max(1.0) as usize;
+        for (index, line) in block.lines.iter().take(max_lines).enumerate() {
+            let weight = if index == 0 { r#" font-weight="600""# } else { "" };
+            let _ = write!(
+                svg,
+                r##"<text x="{}" y="{}" font-size="11"{weight} fill="#ffffff">{}</text>"##,
+                x + 4.0,
+                y + LINE_HEIGHT * (index + 1) as f32 - 1.0,
+                escape_xml(&truncate(line, max_characters))
+            );
+        }
+    }
+
+    // Sections without set meeting times
+    if !unscheduled.is_empty() {
+        let mut y = grid_top + grid_height + PADDING + UNSCHEDULED_LINE_HEIGHT - 4.0;
+        let _ = write!(
+            svg,
+            r##"<text x="{PADDING}" y="{y}" font-size="12" font-weight="600" fill="#334155">No set meeting time</text>"##
+        );
+        for (label, color) in &unscheduled {
+            y += UNSCHEDULED_LINE_HEIGHT;
+            let _ = write!(svg, r#"<rect x="{PADDING}" y="{}" width="10" height="10" rx="2" fill="{color}"/>"#, y - 9.0);
+            let _ = write!(
+                svg,
+                r##"<text x="{}" y="{y}" font-size="12" fill="#334155">{}</text>"##,
+                PADDING + 16.0,
+                escape_xml(label)
+            );
+        }
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+/**
+ * Rasterizes an SVG document rendered by `render_svg`
+ *
+ * @param {&str} svg - SVG document
+ * @param {f32} scale - Resolution multiplier
+ * @returns {Result<Vec<u8>, anyhow::Error>} PNG file contents
+ * @throws {anyhow::Error} If the SVG can't be parsed or the image can't be encoded
+ */
+fn render_png(svg: &str, scale: f32) -> Result<Vec<u8>, anyhow::Error> {
+    let options = usvg::Options {
+        fontdb: system_fonts(),
+        ..usvg::Options::default()
+    };
+    let tree = usvg::Tree::from_str(svg, &options)?;
+    let size = tree
+        .size()
+        .to_int_size()
+        .scale_by(scale)
+        .ok_or_else(|| anyhow!("Invalid image size"))?;
+    let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height()).ok_or_else(|| anyhow!("Invalid image size"))?;
+    resvg::render(&tree, tiny_skia::Transform::from_scale(scale, scale), &mut pixmap.as_mut());
+    Ok(pixmap.encode_png()?)
+}
+
+/**
+ * Fonts installed on the system, loaded once since scanning them is slow
+ *
+ * @returns {Arc<usvg::fontdb::Database>} Shared font database
+ */
+fn system_fonts() -> Arc<usvg::fontdb::Database> {
+    static FONTS: OnceLock<Arc<usvg::fontdb::Database>> = OnceLock::new();
+    FONTS
+        .get_or_init(|| {
+            let mut fonts = usvg::fontdb::Database::new();
+            fonts.load_system_fonts();
+            Arc::new(fonts)
+        })
+        .clone()
+}
+
+// === LAYOUT ===
+
+/**
+ * Turns the schedule's meetings and the events into grid blocks
+ *
+ * @param {&[Class]} schedule - Sections in the schedule
+ * @param {&[Event]} events - Personal events
+ * @returns {(Vec<Block>, Vec<(String, &'static str)>)} Blocks, and labels and colors of sections without set times
+ */
+fn collect_blocks(schedule: &[Class], events: &[Event]) -> (Vec<Block>, Vec<(String, &'static str)>) {
+    let mut blocks = Vec::new();
+    let mut unscheduled = Vec::new();
+
+    for (index, class) in schedule.iter().enumerate() {
+        let color = COURSE_COLORS[index % COURSE_COLORS.len()];
+        let course = format!("{} {}", class.code, class.name);
+        for section in &class.classes {
+            let mut has_meeting = false;
+            for (day, &((start, end), meets)) in section.days.iter().enumerate() {
+                if !meets || start < 0 || end <= start {
+                    continue;
+                }
+                has_meeting = true;
+                let mut lines = vec![course.clone(), format!("Sec {}", section.section), format_time_range(start, end)];
+                if !section.location.trim().is_empty() {
+                    lines.push(section.location.trim().to_string());
+                }
+                blocks.push(Block { day, start: to_minutes(start), end: to_minutes(end), color, lines, placement: ColumnPlacement::default() });
+            }
+            if !has_meeting {
+                let mut label = format!("{course} (Sec {})", section.section);
+                if !section.instructor.trim().is_empty() {
+                    label.push_str(&format!(" - {}", section.instructor.trim()));
+                }
+                unscheduled.push((label, color));
+            }
+        }
+    }
+
+    for event in events {
+        if event.end_time <= event.start_time {
+            continue;
+        }
+        // Event days are a bitmask with bit 0 for Sunday; columns start on Monday
+        for day in 0..7 {
+            let bit = (day + 1) % 7;
+            if event.day & (1 << bit) == 0 {
+                continue;
+            }
+            blocks.push(Block {
+                day,
+                start: to_minutes(event.start_time),
+                end: to_minutes(event.end_time),
+                color: EVENT_COLOR,
+                lines: vec![event.title.clone(), format_time_range(event.start_time, event.end_time)],
+                placement: ColumnPlacement::default(),
+            });
+        }
+    }
+
+    (blocks, unscheduled)
+}
+
+/**
+ * Places overlapping blocks side by side, the same way the calendar view does
+ *
+ * @param {&mut [Block]} blocks - Blocks to lay out, reordered by day and start time
+ */
+fn place_blocks(blocks: &mut [Block]) {
+    // Longer blocks take the leftmost column on ties, as required by `assign_columns`
+    blocks.sort_by_key(|block| (block.day, block.start, Reverse(block.end)));
+
+    for day_blocks in blocks.chunk_by_mut(|first, second| first.day == second.day) {
+        let intervals: Vec<(i32, i32)> = day_blocks.iter().map(|block| (block.start, block.end)).collect();
+        for (block, placement) in day_blocks.iter_mut().zip(EventProcessor::assign_columns(&intervals)) {
+            block.placement = placement;
+        }
+    }
+}
+
+// === FORMATTING HELPERS ===
+
+/**
+ * Converts a 24-hour clock hour to the 12-hour clock
+ *
+ * @param {i32} hour - Hour, 0-23
+ * @returns {(i32, &str)} Hour (1-12) and "AM" or "PM"
+ */
+fn twelve_hour(hour: i32) -> (i32, &'static str) {
+    let suffix = if hour < 12 { "AM" } else { "PM" };
+    match hour % 12 {
+        0 => (12, suffix),
+        hour => (hour, suffix),
+    }
+}
+
+/**
+ * Formats an hour as a 12-hour clock label
+ *
+ * @param {i32} hour - Hour, 0-23
+ * @returns {String} Label such as "8 AM"
+ */
+fn format_hour(hour: i32) -> String {
+    let (hour, suffix) = twelve_hour(hour);
+    format!("{hour} {suffix}")
+}
+
+/**
+ * Formats an HHMM time range on a 12-hour clock
+ *
+ * @param {i32} start - Start as HHMM
+ * @param {i32} end - End as HHMM
+ * @returns {String} Range such as "9:35 AM - 10:25 AM"
+ */
+fn format_time_range(start: i32, end: i32) -> String {
+    let format = |time: i32| {
+        let (hour, suffix) = twelve_hour(time / 100);
+        format!("{hour}:{:02} {suffix}", time % 100)
+    };
+    format!("{} - {}", format(start), format(end))
+}
+
+/**
+ * Shortens a label to a number of characters, marking the cut with an ellipsis
+ *
+ * @param {&str} text - Label
+ * @param {usize} max_characters - Maximum length in characters
+ * @returns {String} Label that fits
+ */
+fn truncate(text: &str, max_characters: usize) -> String {
+    if text.chars().count() <= max_characters {
+        return text.to_string();
+    }
+    let mut shortened: String = text.chars().take(max_characters.saturating_sub(1)).collect();
+    shortened.push('…');
+    shortened
+}
+
+/**
+ * Escapes text for use in SVG content
+ *
+ * @param {&str} text - Raw text
+ * @returns {String} Escaped text
+ */
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{class, unscheduled_class, MON_WED, TUE_THU};
+
+    /** Builds a personal event on the given day bitmask (bit 0 is Sunday) */
+    fn event(title: &str, day: i32, start: i32, end: i32) -> Event {
+        Event {
+            id: "e1".to_string(),
+            title: title.to_string(),
+            start_time: start,
+            end_time: end,
+            day,
+            professor: String::new(),
+            description: String::new(),
+        }
+    }
+
+    /** Whether the SVG contains a text element with exactly this content */
+    fn has_text(svg: &str, text: &str) -> bool {
+        svg.contains(&format!(">{text}</text>"))
+    }
+
+    #[test]
+    fn hours_widen_for_early_and_late_meetings() {
+        let svg = render_svg(&[class("CSC", "116", "001", "", MON_WED, 1040, 1130)], &[]);
+        assert!(has_text(&svg, &format_hour(DEFAULT_HOURS.0)));
+        assert!(has_text(&svg, &format_hour(DEFAULT_HOURS.1 - 1)));
+        assert!(!has_text(&svg, &format_hour(7)));
+
+        // A 7:30 start adds the 7 AM row; a 19:10 end rounds up to include the 7 PM row
+        let schedule = [class("CSC", "116", "001", "", MON_WED, 730, 820), class("MA", "241", "002", "", TUE_THU, 1800, 1910)];
+        let svg = render_svg(&schedule, &[]);
+        assert!(has_text(&svg, &format_hour(7)));
+        assert!(has_text(&svg, &format_hour(19)));
+        assert!(!has_text(&svg, &format_hour(6)));
+        assert!(!has_text(&svg, &format_hour(20)));
+    }
+
+    #[test]
+    fn weekend_columns_appear_only_for_weekend_events() {
+        let schedule = [class("CSC", "116", "001", "", MON_WED, 1040, 1130)];
+
+        let svg = render_svg(&schedule, &[event("Gym", 1 << 1, 1200, 1300)]);
+        assert!(has_text(&svg, "Fri"));
+        assert!(!has_text(&svg, "Sat"));
+        assert!(!has_text(&svg, "Sun"));
+
+        // Sunday is bit 0 of the event day mask but the last column
+        let svg = render_svg(&schedule, &[event("Shift", 1, 1200, 1300)]);
+        assert!(has_text(&svg, "Sat"));
+        assert!(has_text(&svg, "Sun"));
+    }
+
+    #[test]
+    fn sections_without_meeting_times_are_listed() {
+        let schedule = [class("CSC", "116", "001", "", MON_WED, 1040, 1130), unscheduled_class("HI", "295", &["601"])];
+        let svg = render_svg(&schedule, &[]);
+        assert!(has_text(&svg, "No set meeting time"));
+        assert!(has_text(&svg, "HI 295 (Sec 601)"));
+
+        let svg = render_svg(&schedule[..1], &[]);
+        assert!(!has_text(&svg, "No set meeting time"));
+    }
+
+    #[test]
+    fn markup_in_titles_is_escaped() {
+        let svg = render_svg(&[class("CSC", "116", "001", "", MON_WED, 1040, 1130)], &[event("<b>R&D</b>", 1 << 2, 1200, 1300)]);
+        assert!(has_text(&svg, "&lt;b&gt;R&amp;D&lt;/b&gt;"));
+        assert!(!svg.contains("<b>"));
+        assert!(usvg::Tree::from_str(&svg, &usvg::Options::default()).is_ok());
+    }
+}
@@ -23,9 +23,6 @@ use crate::{
 
 // === CONSTANTS ===
 
-/// Directory name for storing Chrome and ChromeDriver resources
-const RESOURCES_DIR: &str = "resources";
-
 /// Base URL for Chrome for Testing downloads from Google's official repository
 const CHROME_URL: &str = "https://storage.googleapis.com/chrome-for-testing-public";
 
@@ -116,7 +113,7 @@ struct Channel {
  * 
  * This function handles the complete Chrome/ChromeDriver setup workflow:
  * 1. Determines the current operating system
- * 2. Loads or creates connection info in the database
+ * 2. Loads or creates connection info in the database, keeping the resolved resources directory
  * 3. Fetches the latest Chrome version from Google's API
  * 4. Compares versions and updates database if needed
  * 5. Downloads and installs Chrome/ChromeDriver if required
@@ -136,6 +133,7 @@ pub async fn setup_program(
 
     // 2. Load connection info from the database. If it doesn't exist, create it.
     let mut stored_info = load_connect_info(pool, os_string)?;
+    stored_info.resources_dir = connect_info_arc.lock().await.resources_dir.clone();
 
     // 3. Fetch the latest version from the web.
     let latest_version = fetch_latest_chrome_version().await?;
@@ -184,8 +182,9 @@ pub async fn start_chromedriver(connect_info: &ConnectInfo) -> Result<WebDriver,
     caps.set_binary(&binary_path.to_string_lossy()).context("Unable to set binary path")?;
 
     let driver_path = get_chromedriver_path(connect_info);
+    let log_path = connect_info.resources_dir.join("chromedriver.log");
     Command::new(&driver_path)
-        .args(["--port=9515", "--verbose", "--log-path=chromedriver.log"])
+        .args(["--port=9515", "--verbose", &format!("--log-path={}", log_path.display())])
         .spawn()
         .with_context(|| format!("Failed to start chromedriver from path: {driver_path:?}"))?;
     
@@ -376,8 +375,8 @@ fn set_executable(_: &PathBuf) -> Result<(), anyhow::Error> {
  */
 async fn setup_chrome_and_driver(connect_info: &ConnectInfo) -> Result<(), anyhow::Error> {
     println!("Updating Chrome resources...");
-    fs::remove_dir_all(RESOURCES_DIR).ok(); // Clean-up old resources
-    let resources_path = ensure_resources_dir()?;
+    fs::remove_dir_all(&connect_info.resources_dir).ok(); // Clean-up old resources
+    let resources_path = ensure_resources_dir(connect_info)?;
     let downloader = Downloader::new();
 
     // Download and extract Chrome browser
@@ -406,8 +405,7 @@ async fn setup_chrome_and_driver(connect_info: &ConnectInfo) -> Result<(), anyho
  * @returns {PathBuf} Full path to ChromeDriver executable
  */
 fn get_chromedriver_path(connect_info: &ConnectInfo) -> PathBuf {
-    let mut path = std::env::current_dir().expect("Failed to get current directory");
-    path.push(RESOURCES_DIR);
+    let mut path = connect_info.resources_dir.clone();
     let os_folder = format!("chromedriver-{}", connect_info.os);
     path.push(os_folder);
     path.push("chromedriver");
@@ -427,9 +425,7 @@ fn get_chromedriver_path(connect_info: &ConnectInfo) -> PathBuf {
  * @throws {panic} If running on unsupported operating system
  */
 fn get_chromebinary_path(connect_info: &ConnectInfo) -> PathBuf {
-    let mut path_buf = std::env::current_dir().expect("Failed to get current directory")
-        .join(RESOURCES_DIR)
-        .join(format!("chrome-{}", connect_info.os));
+    let mut path_buf = connect_info.resources_dir.join(format!("chrome-{}", connect_info.os));
 
     match env::consts::OS {
         "macos" => {
@@ -449,11 +445,12 @@ fn get_chromebinary_path(connect_info: &ConnectInfo) -> PathBuf {
 /**
  * Ensures the resources directory exists, creating it if necessary
  * 
+ * @param {&ConnectInfo} connect_info - Connection info containing the resources directory
  * @returns {Result<PathBuf, anyhow::Error>} Path to resources directory or error
  * @throws {anyhow::Error} If directory creation fails
  */
-fn ensure_resources_dir() -> Result<PathBuf, anyhow::Error> {
-    let resources_path = std::env::current_dir()?.join(RESOURCES_DIR);
+fn ensure_resources_dir(connect_info: &ConnectInfo) -> Result<PathBuf, anyhow::Error> {
+    let resources_path = connect_info.resources_dir.clone();
     if !resources_path.exists() {
         fs::create_dir_all(&resources_path)?;
     }
@@ -0,0 +1,424 @@
+//! Compact, copy-pasteable codes for sharing a schedule
+//!
+//! A code packs the active plan's term start and the schedule's course/section list into
+//! a small binary payload: a format version byte, the term start as days since 1970 (0 if
+//! unset), then for every course its code, number and section labels as length-prefixed
+//! strings. A 32-bit checksum is appended and the result is written in Crockford base32,
+//! which is case-insensitive and tolerates the usual O/0 and I/L/1 mix-ups.
+//!
+//! Decoding only needs the sections' identities; the full class data (times, rooms,
+//! instructors) is looked up in the classes cache, and courses missing from it are scraped.
+
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use tokio::sync::Mutex;
+
+use crate::{
+    calendar::Date,
+    database_functions::{stable_hash, PlanRepository, ScheduleRepository},
+    tauri_backend::scrape_classes::collect_sections,
+    Class, ClassParam, ConnectInfo, DbPool, ScheduleConstraints, ScrapeClassesParameters, SelectionMode, SharedSchedule,
+};
+
+// === CONFIGURATION CONSTANTS ===
+
+/** Version of the payload layout; bumped whenever the encoding changes */
+const SHARE_CODE_VERSION: u8 = 1;
+
+/** Crockford base32 alphabet (no I, L, O or U) */
+const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/** Length of the trailing checksum, in bytes */
+const CHECKSUM_LENGTH: usize = 4;
+
+/** Characters per group when a code is displayed; separators are ignored when decoding */
+const GROUP_LENGTH: usize = 5;
+
+/** Courses a single code may hold, to reject garbage before allocating */
+const MAX_COURSES: u64 = 64;
+
+/** Earliest and latest years a shared term may start in; anything else is garbage */
+const TERM_YEARS: (i64, i64) = (1970, 2200);
+
+// === ENCODING ===
+
+/**
+ * Creates the share code of a stored schedule
+ *
+ * @param {String} id - Content-hash ID of the schedule
+ * @param {&DbPool} pool - Database connection pool
+ * @returns {Result<String, anyhow::Error>} Code such as "0G2Q8-..."
+ * @throws {anyhow::Error} If the schedule doesn't exist or database query fails
+ */
+pub async fn encode_schedule_code(id: String, pool: &DbPool) -> Result<String, anyhow::Error> {
+    let pool = pool.clone();
+    tokio::task::spawn_blocking(move || -> Result<String, anyhow::Error> {
+        let conn = pool.get()?;
+        let schedule = ScheduleRepository::load_schedule(&conn, &id)?;
+        if schedule.is_empty() {
+            return Err(anyhow!("Schedule with id '{}' not found", id));
+        }
+        let term_start = PlanRepository::load_active_term(&conn)?
+            .map(|term| Date::parse(&term.start))
+            .transpose()?;
+        Ok(encode(&schedule, term_start))
+    }).await?
+}
+
+/**
+ * Packs a schedule into a share code
+ *
+ * @param {&[Class]} schedule - Sections in the schedule
+ * @param {Option<Date>} term_start - First day of the term, if known
+ * @returns {String} Grouped Crockford base32 code
+ */
+fn encode(schedule: &[Class], term_start: Option<Date>) -> String {
+    let mut payload = vec![SHARE_CODE_VERSION];
+    let term_start = term_start.filter(|date| is_supported_term_start(*date));
+    write_varint(&mut payload, term_start.map_or(0, |date| date.days_since_epoch() as u64 + 1));
+    write_varint(&mut payload, schedule.len() as u64);
+    for class in schedule {
+        write_string(&mut payload, &class.code);
+        write_string(&mut payload, &class.name);
+        write_varint(&mut payload, class.classes.len() as u64);
+        for block in &class.classes {
+            write_string(&mut payload, &block.section);
+        }
+    }
+    payload.extend_from_slice(&checksum(&payload));
+
+    let encoded = to_base32(&payload);
+    encoded.as_bytes()
+        .chunks(GROUP_LENGTH)
+        .map(|group| std::str::from_utf8(group).unwrap_or_default())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+// === DECODING ===
+
+/**
+ * Course and sections named by a share code
+ */
+struct CodedCourse {
+    code: String,
+    name: String,
+    sections: Vec<String>,
+}
+
+/**
+ * Decodes a share code back into full class data
+ *
+ * Sections are looked up in the classes cache; courses missing from it are scraped
+ * (and cached) first.
+ *
+ * @param {&str} code - Share code; case, spaces and dashes don't matter
+ * @param {&Arc<Mutex<ConnectInfo>>} connect_info - Chrome connection info for scraping
+ * @param {&DbPool} pool - Database connection pool
+ * @returns {Result<SharedSchedule, anyhow::Error>} Term start and the schedule's classes
+ * @throws {anyhow::Error} If the code is malformed, a section no longer exists, or scraping fails
+ */
+pub async fn decode_schedule_code(code: &str, connect_info: &Arc<Mutex<ConnectInfo>>, pool: &DbPool) -> Result<SharedSchedule, anyhow::Error> {
+    let (term_start, courses) = decode(code)?;
+
+    let parameters = ScrapeClassesParameters {
+        params_checkbox: [false; 3],
+        classes: courses.iter().enumerate().map(|(index, course)| ClassParam {
+            id: index.to_string(),
+            code: course.code.clone(),
+            name: course.name.clone(),
+            section: String::new(),
+            instructor: String::new(),
+        }).collect(),
+        events: Vec::new(),
+        selection_mode: SelectionMode::default(),
+        constraints: ScheduleConstraints::default(),
+    };
+    let sections = collect_sections(&parameters, connect_info, pool).await?;
+
+    let mut classes = Vec::with_capacity(courses.len());
+    for (course, candidates) in courses.iter().zip(sections) {
+        let class = candidates.into_iter()
+            .find(|candidate| {
+                candidate.code == course.code
+                    && candidate.name == course.name
+                    && candidate.classes.iter().map(|block| &block.section).eq(&course.sections)
+            })
+            .ok_or_else(|| anyhow!(
+                "Section {} of {} {} is no longer offered",
+                course.sections.join("/"), course.code, course.name
+            ))?;
+        classes.push(class);
+    }
+
+    Ok(SharedSchedule { term_start: term_start.map(Date::to_iso), classes })
+}
+
+/**
+ * Unpacks a share code without touching the database
+ *
+ * @param {&str} code - Share code
+ * @returns {Result<(Option<Date>, Vec<CodedCourse>), anyhow::Error>} Term start and courses
+ * @throws {anyhow::Error} If the code has invalid characters, a bad checksum, an unknown version, a truncated payload or an implausible term start
+ */
+fn decode(code: &str) -> Result<(Option<Date>, Vec<CodedCourse>), anyhow::Error> {
+    let bytes = from_base32(code)?;
+    if bytes.len() <= 1 + CHECKSUM_LENGTH {
+        return Err(anyhow!("Schedule code is too short"));
+    }
+    let (payload, expected) = bytes.split_at(bytes.len() - CHECKSUM_LENGTH);
+    if checksum(payload) != expected {
+        return Err(anyhow!("Schedule code is damaged or mistyped (checksum mismatch)"));
+    }
+    if payload[0] != SHARE_CODE_VERSION {
+        return Err(anyhow!(
+            "Schedule code version {} is not supported (expected version {SHARE_CODE_VERSION})",
+            payload[0]
+        ));
+    }
+
+    let mut reader = Reader { bytes: &payload[1..] };
+    let term_start = match reader.varint()? {
+        0 => None,
+        days => {
+            let date = i64::try_from(days - 1).ok()
+                .map(Date::from_days_since_epoch)
+                .filter(|date| is_supported_term_start(*date))
+                .ok_or_else(|| anyhow!("Schedule code holds an invalid term start"))?;
+            Some(date)
+        }
+    };
+    let course_count = reader.varint()?;
+    if course_count == 0 || course_count > MAX_COURSES {
+        return Err(anyhow!("Schedule code holds an invalid number of courses ({course_count})"));
+    }
+    let mut courses = Vec::with_capacity(course_count as usize);
+    for _ in 0..course_count {
+        let code = reader.string()?;
+        let name = reader.string()?;
+        let section_count = reader.varint()?;
+        if section_count > MAX_COURSES {
+            return Err(anyhow!("Schedule code holds an invalid number of sections ({section_count})"));
+        }
+        let sections = (0..section_count).map(|_| reader.string()).collect::<Result<Vec<_>, _>>()?;
+        courses.push(CodedCourse { code, name, sections });
+    }
+    if !reader.bytes.is_empty() {
+        return Err(anyhow!("Schedule code has unexpected trailing data"));
+    }
+    Ok((term_start, courses))
+}
+
+/**
+ * Whether a term start lies in the years a share code may carry
+ *
+ * @param {Date} date - First day of the term
+ * @returns {bool} True if the year is within `TERM_YEARS`
+ */
+fn is_supported_term_start(date: Date) -> bool {
+    let (first_year, last_year) = TERM_YEARS;
+    match (Date::from_civil(first_year, 1, 1), Date::from_civil(last_year, 12, 31)) {
+        (Some(first), Some(last)) => (first..=last).contains(&date),
+        _ => false,
+    }
+}
+
+/** Cursor over a payload being decoded */
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl Reader<'_> {
+    /**
+     * Reads an unsigned LEB128 integer
+     *
+     * @returns {Result<u64, anyhow::Error>} Value
+     * @throws {anyhow::Error} If the payload ends early or the value overflows
+     */
+    fn varint(&mut self) -> Result<u64, anyhow::Error> {
+        let mut value: u64 = 0;
+        for shift in (0..64).step_by(7) {
+            let (&byte, rest) = self.bytes.split_first().ok_or_else(|| anyhow!("Schedule code is truncated"))?;
+            self.bytes = rest;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(anyhow!("Schedule code contains an invalid number"))
+    }
+
+    /**
+     * Reads a length-prefixed UTF-8 string
+     *
+     * @returns {Result<String, anyhow::Error>} String
+     * @throws {anyhow::Error} If the payload ends early or the bytes aren't UTF-8
+     */
+    fn string(&mut self) -> Result<String, anyhow::Error> {
+        let length = self.varint()? as usize;
+        if length > self.bytes.len() {
+            return Err(anyhow!("Schedule code is truncated"));
+        }
+        let (text, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+        String::from_utf8(text.to_vec()).map_err(|_| anyhow!("Schedule code contains invalid text"))
+    }
+}
+
+// === BINARY HELPERS ===
+
+/**
+ * Appends an unsigned LEB128 integer
+ *
+ * @param {&mut Vec<u8>} out - Payload being built
+ * @param {u64} value - Value to append
+ */
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/**
+ * Appends a length-prefixed UTF-8 string
+ *
+ * @param {&mut Vec<u8>} out - Payload being built
+ * @param {&str} text - String to append
+ */
+fn write_string(out: &mut Vec<u8>, text: &str) {
+    write_varint(out, text.len() as u64);
+    out.extend_from_slice(text.as_bytes());
+}
+
+/**
+ * Checksum of a payload: the 64-bit FNV-1a hash folded to 32 bits
+ *
+ * @param {&[u8]} payload - Payload to protect
+ * @returns {[u8; 4]} Big-endian checksum bytes
+ */
+fn checksum(payload: &[u8]) -> [u8; CHECKSUM_LENGTH] {
+    let hash = stable_hash(payload);
+    ((hash ^ (hash >> 32)) as u32).to_be_bytes()
+}
+
+/**
+ * Encodes bytes in Crockford base32 without padding
+ *
+ * @param {&[u8]} bytes - Data to encode
+ * @returns {String} Upper-case base32 text
+ */
+fn to_base32(bytes: &[u8]) -> String {
+    let mut text = String::with_capacity(bytes.len() * 8 / 5 + 1);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for &byte in bytes {
+        buffer = (buffer << 8) | u32::from(byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            text.push(ALPHABET[((buffer >> bits) & 31) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        text.push(ALPHABET[((buffer << (5 - bits)) & 31) as usize] as char);
+    }
+    text
+}
+
+/**
+ * Decodes Crockford base32, ignoring case, dashes and whitespace
+ *
+ * @param {&str} text - Encoded text
+ * @returns {Result<Vec<u8>, anyhow::Error>} Decoded bytes
+ * @throws {anyhow::Error} If the text contains a character outside the alphabet
+ */
+fn from_base32(text: &str) -> Result<Vec<u8>, anyhow::Error> {
+    let mut bytes = Vec::with_capacity(text.len() * 5 / 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for ch in text.chars().filter(|ch| *ch != '-' && !ch.is_whitespace()) {
+        let value = match ch.to_ascii_uppercase() {
+            'O' => 0,
+            'I' | 'L' => 1,
+            upper => ALPHABET.iter().position(|&symbol| symbol as char == upper)
+                .ok_or_else(|| anyhow!("Schedule code contains an invalid character '{}'", ch))?,
+        };
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::unscheduled_class as class;
+
+    /** Packs a raw payload the way `encode` does, so malformed contents still pass the checksum */
+    fn seal(mut payload: Vec<u8>) -> String {
+        payload.extend_from_slice(&checksum(&payload));
+        to_base32(&payload)
+    }
+
+    #[test]
+    fn codes_round_trip() {
+        let term_start = Date::parse("2025-08-18").unwrap();
+        let schedule = [class("CSC", "116", &["001", "001L"]), class("MA", "241", &["002"])];
+        let code = encode(&schedule, Some(term_start));
+
+        let (decoded_start, courses) = decode(&code.to_lowercase().replace('0', "o")).unwrap();
+        assert!(decoded_start == Some(term_start));
+        assert_eq!(courses.len(), 2);
+        assert_eq!((courses[0].code.as_str(), courses[0].name.as_str()), ("CSC", "116"));
+        assert_eq!(courses[0].sections, ["001", "001L"]);
+        assert_eq!(courses[1].sections, ["002"]);
+
+        let (decoded_start, _) = decode(&encode(&schedule, None)).unwrap();
+        assert!(decoded_start.is_none());
+    }
+
+    #[test]
+    fn malformed_codes_are_rejected() {
+        let code = encode(&[class("CSC", "116", &["001"])], None);
+        let mistyped = code.replacen(&code[..1], if code.starts_with('1') { "2" } else { "1" }, 1);
+
+        for (input, message) in [
+            ("", "too short"),
+            ("0G2Q8-U", "invalid character"),
+            (mistyped.as_str(), "checksum mismatch"),
+            (&code[..code.len() - 8], "checksum mismatch"),
+        ] {
+            let error = decode(input).err().unwrap().to_string();
+            assert!(error.contains(message), "{input:?}: {error}");
+        }
+
+        let mut wrong_version = vec![SHARE_CODE_VERSION + 1, 0, 1];
+        write_string(&mut wrong_version, "CSC");
+        assert!(decode(&seal(wrong_version)).err().unwrap().to_string().contains("not supported"));
+        assert!(decode(&seal(vec![SHARE_CODE_VERSION, 0, 1, 3, b'C'])).err().unwrap().to_string().contains("truncated"));
+        assert!(decode(&seal(vec![SHARE_CODE_VERSION, 0, 0])).err().unwrap().to_string().contains("number of courses"));
+    }
+
+    #[test]
+    fn implausible_term_starts_are_rejected() {
+        for days in [u64::MAX, i64::MAX as u64, 1 << 40] {
+            let mut payload = vec![SHARE_CODE_VERSION];
+            write_varint(&mut payload, days);
+            write_varint(&mut payload, 0);
+            let error = decode(&seal(payload)).err().unwrap().to_string();
+            assert!(error.contains("invalid term start"), "{days}: {error}");
+        }
+
+        // Out-of-range dates are dropped when encoding rather than written
+        let far_future = Date::from_civil(2300, 1, 1).unwrap();
+        let (decoded_start, _) = decode(&encode(&[class("CSC", "116", &["001"])], Some(far_future))).unwrap();
+        assert!(decoded_start.is_none());
+    }
+}
@@ -0,0 +1,212 @@
+//! Resolution of on-disk storage locations
+//!
+//! The database lives in the platform app-data directory and the downloaded Chrome
+//! resources in the app-cache directory, so launching Koka from any working directory
+//! finds the same data. Both can be relocated to a single directory, either with the
+//! `KOKA_DATA_DIR` environment variable or a setting stored in the app-config directory;
+//! an absolute environment variable wins. Data left in the working directory by older releases
+//! is moved over on first run.
+
+use std::{ffi::OsString, fs, path::{Path, PathBuf}};
+
+use anyhow::anyhow;
+use rusqlite::{Connection, MAIN_DB};
+use serde::{Deserialize, Serialize};
+use tauri::PathResolver;
+
+use crate::objects::StoragePaths;
+
+// === CONFIGURATION CONSTANTS ===
+
+/** Environment variable that overrides the storage directory */
+const DATA_DIR_ENV: &str = "KOKA_DATA_DIR";
+
+/** File name of the SQLite database */
+const DATABASE_FILE: &str = "programData.db";
+
+/** Directory name for storing Chrome and ChromeDriver resources */
+const RESOURCES_DIR: &str = "resources";
+
+/** File in the app-config directory holding storage settings */
+const SETTINGS_FILE: &str = "storage.json";
+
+/** Persisted storage settings */
+#[derive(Serialize, Deserialize, Default)]
+struct StorageSettings {
+    /** Directory used for both data and cache instead of the platform defaults */
+    data_dir: Option<PathBuf>,
+}
+
+// === PATH RESOLUTION ===
+
+/**
+ * Determines where the database and Chrome resources are stored
+ *
+ * Precedence: `KOKA_DATA_DIR`, then the saved override setting, then the
+ * platform app-data (database) and app-cache (resources) directories.
+ *
+ * @param {&PathResolver} resolver - Tauri path resolver for the platform directories
+ * @returns {Result<StoragePaths, anyhow::Error>} Resolved paths; directories are created if missing
+ * @throws {anyhow::Error} If no directory can be determined or created
+ */
+pub fn resolve_storage_paths(resolver: &PathResolver) -> Result<StoragePaths, anyhow::Error> {
+    let settings_file = resolver.app_config_dir()
+        .ok_or_else(|| anyhow!("Unable to determine the app config directory"))?
+        .join(SETTINGS_FILE);
+
+    let (data_dir, cache_dir) = match override_dir(std::env::var_os(DATA_DIR_ENV), &settings_file) {
+        Some(dir) => (dir.clone(), dir),
+        None => (
+            resolver.app_data_dir().ok_or_else(|| anyhow!("Unable to determine the app data directory"))?,
+            resolver.app_cache_dir().ok_or_else(|| anyhow!("Unable to determine the app cache directory"))?,
+        ),
+    };
+    fs::create_dir_all(&data_dir)?;
+    fs::create_dir_all(&cache_dir)?;
+
+    Ok(StoragePaths {
+        database: data_dir.join(DATABASE_FILE),
+        resources: cache_dir.join(RESOURCES_DIR),
+        data_dir,
+        cache_dir,
+        settings_file,
+    })
+}
+
+/**
+ * Picks the storage directory override, if any
+ *
+ * A relative `KOKA_DATA_DIR` would depend on the working directory, which is what the
+ * platform directories avoid, so it is ignored with a warning like an unset variable.
+ *
+ * @param {Option<OsString>} env_dir - Value of `KOKA_DATA_DIR`
+ * @param {&Path} settings_file - Settings file path
+ * @returns {Option<PathBuf>} Directory to use for data and cache, or None for the platform defaults
+ */
+fn override_dir(env_dir: Option<OsString>, settings_file: &Path) -> Option<PathBuf> {
+    let env_dir = env_dir.filter(|dir| !dir.is_empty()).map(PathBuf::from).filter(|dir| {
+        let absolute = dir.is_absolute();
+        if !absolute {
+            eprintln!("Warning: Ignoring {DATA_DIR_ENV}={}: the storage directory must be an absolute path", dir.display());
+        }
+        absolute
+    });
+    env_dir.or_else(|| load_settings(settings_file).data_dir)
+}
+
+/**
+ * Saves or clears the storage directory override
+ * The new location is used from the next launch; existing data is not moved
+ *
+ * @param {&Path} settings_file - Settings file path (from StoragePaths)
+ * @param {Option<PathBuf>} data_dir - Directory to use, or None to restore the platform defaults
+ * @returns {Result<(), anyhow::Error>} Success or error
+ * @throws {anyhow::Error} If the directory isn't absolute or the settings can't be written
+ */
+pub fn save_data_dir_override(settings_file: &Path, data_dir: Option<PathBuf>) -> Result<(), anyhow::Error> {
+    if data_dir.as_ref().is_some_and(|dir| !dir.is_absolute()) {
+        return Err(anyhow!("Storage directory must be an absolute path"));
+    }
+    if let Some(parent) = settings_file.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let settings = StorageSettings { data_dir };
+    fs::write(settings_file, serde_json::to_string_pretty(&settings)?)?;
+    Ok(())
+}
+
+/**
+ * Reads the storage settings, falling back to defaults if the file is missing or invalid
+ *
+ * @param {&Path} settings_file - Settings file path
+ * @returns {StorageSettings} Parsed settings
+ */
+fn load_settings(settings_file: &Path) -> StorageSettings {
+    let Ok(json) = fs::read_to_string(settings_file) else {
+        return StorageSettings::default();
+    };
+    serde_json::from_str(&json).unwrap_or_else(|e| {
+        eprintln!("Warning: Ignoring invalid storage settings in {}: {e}", settings_file.display());
+        StorageSettings::default()
+    })
+}
+
+// === LEGACY MIGRATION ===
+
+/**
+ * Moves data stored in the working directory by older releases
+ *
+ * This function:
+ * 1. Copies ./programData.db into the data directory with SQLite's online backup API,
+ *    unless a database already exists there; the original file is left in place
+ * 2. Moves ./resources into the cache directory so Chrome isn't downloaded again
+ *
+ * @param {&StoragePaths} paths - Resolved storage paths
+ * @returns {Result<(), anyhow::Error>} Success or error
+ * @throws {anyhow::Error} If the legacy database can't be copied
+ */
+pub fn migrate_legacy_storage(paths: &StoragePaths) -> Result<(), anyhow::Error> {
+    let Ok(working_dir) = std::env::current_dir() else {
+        return Ok(());
+    };
+
+    let legacy_database = working_dir.join(DATABASE_FILE);
+    if legacy_database.is_file() && !paths.database.exists() && !same_file(&legacy_database, &paths.database) {
+        println!("Copying database from {} to {}.", legacy_database.display(), paths.database.display());
+        let legacy = Connection::open(&legacy_database)?;
+        legacy.backup(MAIN_DB, &paths.database, None)?;
+    }
+
+    let legacy_resources = working_dir.join(RESOURCES_DIR);
+    if legacy_resources.is_dir() && !paths.resources.exists() && !same_file(&legacy_resources, &paths.resources) {
+        println!("Moving Chrome resources from {} to {}.", legacy_resources.display(), paths.resources.display());
+        // Renaming fails across file systems; Chrome is then downloaded again instead
+        if let Err(e) = fs::rename(&legacy_resources, &paths.resources) {
+            eprintln!("Warning: Failed to move Chrome resources: {e}");
+        }
+    }
+    Ok(())
+}
+
+/**
+ * Checks whether two paths refer to the same existing file or directory
+ *
+ * @param {&Path} a - First path
+ * @param {&Path} b - Second path
+ * @returns {bool} True if both resolve to the same location
+ */
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::scratch_dir;
+
+    #[test]
+    fn environment_beats_settings_beats_platform_defaults() {
+        let dir = scratch_dir("storage-precedence");
+        let settings_file = dir.join("config").join(SETTINGS_FILE);
+        let env_dir = dir.join("from-env");
+        let saved_dir = dir.join("from-settings");
+
+        assert_eq!(override_dir(None, &settings_file), None);
+        assert_eq!(override_dir(Some(OsString::new()), &settings_file), None);
+
+        save_data_dir_override(&settings_file, Some(saved_dir.clone())).unwrap();
+        assert_eq!(override_dir(None, &settings_file), Some(saved_dir.clone()));
+        assert_eq!(override_dir(Some(env_dir.clone().into_os_string()), &settings_file), Some(env_dir));
+
+        // A relative variable falls back to the next source instead of the working directory
+        assert_eq!(override_dir(Some(OsString::from("relative/data")), &settings_file), Some(saved_dir));
+        save_data_dir_override(&settings_file, None).unwrap();
+        assert_eq!(override_dir(Some(OsString::from("relative/data")), &settings_file), None);
+
+        assert!(save_data_dir_override(&settings_file, Some(PathBuf::from("relative/data"))).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
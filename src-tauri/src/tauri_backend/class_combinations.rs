@@ -4,7 +4,21 @@
 //! using an optimized backtracking approach with index-based references to minimize
 //! memory allocation during the recursive exploration process.
 
-use crate::Class;
+use std::collections::{HashMap, HashSet};
+
+use crate::{database_functions::ScheduleRepository, tauri_backend::{schedule_constraints::{penalty, satisfies_hard}, scrape_classes::validate_time_ok}, Class, EventParam, ScheduleConstraints, ScheduleGroup, SectionAlternative};
+
+/** Weekly meeting pattern of a single time block: [((start, end), meets)] for Mon-Fri */
+type DayPattern = [((i32, i32), bool); 5];
+
+/** Course key paired with the meeting patterns of every time block of the chosen section */
+type CourseFootprint = (String, Vec<DayPattern>);
+
+/** Distance weight of a weekday that is free in one schedule but not the other (in minutes) */
+const DAY_OFF_WEIGHT: i32 = 240;
+
+/** Distance weight of a course placed in a different section (in minutes) */
+const SECTION_WEIGHT: i32 = 30;
 
 /**
  * Generates all valid schedule combinations from course groups using backtracking
@@ -14,22 +28,102 @@ use crate::Class;
  * 2. Flattens nested course structure for efficient access
  * 3. Uses recursive backtracking to explore all possible combinations
  * 4. Validates time conflicts between courses in different groups
- * 5. Only clones Class objects at the end for final results
+ * 5. Prunes partial schedules that break a hard daily-structure constraint
+ * 6. Only clones Class objects at the end for final results
+ * 7. Orders results by soft-constraint penalty (stable, so ties keep backtracking order)
  * 
  * @param {Vec<Vec<Class>>} classes - Course groups where each inner Vec contains sections for one course
+ * @param {&ScheduleConstraints} constraints - Daily structure constraints (hard ones prune, soft ones rank)
  * @returns {Result<Vec<Vec<Class>>, anyhow::Error>} All valid schedule combinations or error
  * @throws {anyhow::Error} If backtracking algorithm encounters unexpected state
  */
-pub async fn generate_combinations(classes: Vec<Vec<Class>>) -> Result<Vec<Vec<Class>>, anyhow::Error> {
+pub async fn generate_combinations(classes: Vec<Vec<Class>>, constraints: &ScheduleConstraints) -> Result<Vec<Vec<Class>>, anyhow::Error> {
+    let groups: Vec<Vec<&Class>> = classes.iter().map(|group| group.iter().collect()).collect();
+    let mut final_results = combine(&groups, constraints);
+
+    // Schedules violating fewer soft constraints come first
+    final_results.sort_by_cached_key(|schedule| penalty(schedule, constraints));
+
+    Ok(final_results)
+}
+
+/**
+ * Extends existing schedules with newly added course groups
+ * 
+ * Adding a course can only remove options, so every valid schedule for the larger
+ * course list is an existing schedule plus one compatible section per new course.
+ * Each existing schedule is treated as a set of single-section groups and the
+ * backtracking search only branches over the added groups.
+ * 
+ * @param {&[Vec<Class>]} base_schedules - Previously generated schedules
+ * @param {&[Vec<Class>]} added_groups - Filtered sections of each added course
+ * @param {&ScheduleConstraints} constraints - Daily structure constraints (hard ones prune, soft ones rank)
+ * @returns {Vec<Vec<Class>>} Extended schedules, base classes first, ordered by soft-constraint penalty
+ */
+pub fn extend_combinations(base_schedules: &[Vec<Class>], added_groups: &[Vec<Class>], constraints: &ScheduleConstraints) -> Vec<Vec<Class>> {
+    let mut final_results: Vec<Vec<Class>> = Vec::new();
+
+    for base in base_schedules {
+        let groups: Vec<Vec<&Class>> = base
+            .iter()
+            .map(|class| vec![class])
+            .chain(added_groups.iter().map(|group| group.iter().collect()))
+            .collect();
+        final_results.extend(combine(&groups, constraints));
+    }
+
+    final_results.sort_by_cached_key(|schedule| penalty(schedule, constraints));
+    final_results
+}
+
+/**
+ * Drops removed courses from existing schedules
+ * 
+ * Removing a course can't make the rest of a schedule overlap or break a hard constraint,
+ * so every projected schedule is still valid. Schedules that only differed in a removed
+ * course collapse into the same one, so duplicates are dropped by content hash, keeping
+ * the first occurrence.
+ * 
+ * @param {&[Vec<Class>]} base_schedules - Previously generated schedules
+ * @param {&[String]} removed_courses - Course keys (code + name, e.g. "CSC116") to drop
+ * @param {&ScheduleConstraints} constraints - Daily structure constraints the results are ranked by
+ * @returns {Vec<Vec<Class>>} Distinct remaining schedules, ordered by soft-constraint penalty
+ */
+pub fn project_combinations(base_schedules: &[Vec<Class>], removed_courses: &[String], constraints: &ScheduleConstraints) -> Vec<Vec<Class>> {
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut final_results: Vec<Vec<Class>> = base_schedules
+        .iter()
+        .map(|schedule| {
+            schedule
+                .iter()
+                .filter(|class| !removed_courses.contains(&format!("{}{}", class.code, class.name)))
+                .cloned()
+                .collect::<Vec<Class>>()
+        })
+        .filter(|schedule| seen.insert(ScheduleRepository::schedule_id(schedule)))
+        .collect();
+
+    final_results.sort_by_cached_key(|schedule| penalty(schedule, constraints));
+    final_results
+}
+
+/**
+ * Runs the backtracking search over course groups and clones the chosen classes
+ * 
+ * @param {&[Vec<&Class>]} groups - Course groups where each inner Vec contains sections for one course
+ * @param {&ScheduleConstraints} constraints - Daily structure constraints; hard ones prune the search
+ * @returns {Vec<Vec<Class>>} All valid schedule combinations in backtracking order
+ */
+fn combine(groups: &[Vec<&Class>], constraints: &ScheduleConstraints) -> Vec<Vec<Class>> {
 
     // Create indices for each class to avoid cloning during backtracking
-    let mut class_indices: Vec<Vec<usize>> = Vec::with_capacity(classes.len());
+    let mut class_indices: Vec<Vec<usize>> = Vec::with_capacity(groups.len());
     let mut flattened_classes: Vec<&Class> = Vec::new();
 
     // Build index structure
-    for group in &classes {
+    for group in groups {
         let mut group_indices = Vec::with_capacity(group.len());
-        for class in group {
+        for &class in group {
             group_indices.push(flattened_classes.len());
             flattened_classes.push(class);
         }
@@ -39,7 +133,7 @@ pub async fn generate_combinations(classes: Vec<Vec<Class>>) -> Result<Vec<Vec<C
     // Generate all schedules using backtracking with indices
     let mut results: Vec<Vec<usize>> = Vec::new();
     let mut current_schedule: Vec<usize> = Vec::new();
-    let total_groups = classes.len(); // This should be 2 in your 6x7 example
+    let total_groups = groups.len(); // This should be 2 in your 6x7 example
 
     // Start the backtracking process
     backtrack(
@@ -48,20 +142,19 @@ pub async fn generate_combinations(classes: Vec<Vec<Class>>) -> Result<Vec<Vec<C
         &mut current_schedule,
         0, // Start with the first group (index 0)
         &mut results,
-        total_groups
+        total_groups,
+        constraints
     );
 
     // Convert indices back to actual Class objects (clone only at the end)
-    let final_results = results
+    results
         .into_iter()
         .map(|schedule| {
             schedule.into_iter()
                 .map(|idx| flattened_classes[idx].clone())
                 .collect()
         })
-        .collect();
-
-    Ok(final_results)
+        .collect()
 }
 
 /**
@@ -80,6 +173,7 @@ pub async fn generate_combinations(classes: Vec<Vec<Class>>) -> Result<Vec<Vec<C
  * @param {usize} current_group - Index of course group currently being processed
  * @param {&mut Vec<Vec<usize>>} results - Accumulator for all valid schedule combinations found
  * @param {usize} total_groups - Total number of course groups that must be included in schedule
+ * @param {&ScheduleConstraints} constraints - Daily structure constraints; hard ones prune the search
  */
 fn backtrack(
     flattened_classes: &[&Class],
@@ -88,6 +182,7 @@ fn backtrack(
     current_group: usize,
     results: &mut Vec<Vec<usize>>,
     total_groups: usize,
+    constraints: &ScheduleConstraints,
 ) {
     // Base case: we've considered all course groups
     // A valid schedule must have exactly one class from each group.
@@ -108,15 +203,18 @@ fn backtrack(
         if is_compatible(flattened_classes, class_idx, current_schedule) {
             // If compatible, add it to the current schedule
             current_schedule.push(class_idx);
-            // Recurse for the next group
-            backtrack(
-                flattened_classes,
-                class_indices,
-                current_schedule,
-                current_group + 1, // Move to the next group
-                results,
-                total_groups
-            );
+            // Only recurse if the partial schedule still meets every hard constraint
+            if satisfies_hard(current_schedule.iter().map(|&idx| flattened_classes[idx]), constraints) {
+                backtrack(
+                    flattened_classes,
+                    class_indices,
+                    current_schedule,
+                    current_group + 1, // Move to the next group
+                    results,
+                    total_groups,
+                    constraints
+                );
+            }
             // Backtrack: remove the class to explore other possibilities
             current_schedule.pop();
         }
@@ -175,4 +273,516 @@ fn is_compatible(all_classes: &[&Class], new_class_idx: usize, schedule_indices:
     }
 
     true // No conflicts found with any existing class in the schedule
-}
\ No newline at end of file
+}
+
+/**
+ * Finds every other section of one course that fits into an existing schedule
+ * 
+ * This function:
+ * 1. Keeps all classes of the schedule except the one being replaced
+ * 2. Skips candidates with the same sections as the current choice
+ * 3. Rejects candidates without time blocks, conflicting with blocking events,
+ *    overlapping the rest of the schedule or breaking a hard constraint
+ * 4. Ranks the remaining candidates by soft-constraint penalty (ties keep cache order)
+ * 
+ * @param {&[Class]} schedule - Schedule containing the course
+ * @param {usize} course_index - Position of the course to replace within the schedule
+ * @param {Vec<Class>} candidates - All cached sections of that course
+ * @param {&Vec<EventParam>} events - User-defined blocking events
+ * @param {&ScheduleConstraints} constraints - Daily structure constraints used for filtering and ranking
+ * @returns {Vec<SectionAlternative>} Fitting sections, best first
+ */
+pub fn find_section_alternatives(
+    schedule: &[Class],
+    course_index: usize,
+    candidates: Vec<Class>,
+    events: &Vec<EventParam>,
+    constraints: &ScheduleConstraints,
+) -> Vec<SectionAlternative> {
+    let Some(current) = schedule.get(course_index) else {
+        return Vec::new();
+    };
+
+    let mut alternatives: Vec<SectionAlternative> = candidates
+        .into_iter()
+        .filter(|candidate| !candidate.classes.is_empty() && section_list(candidate) != section_list(current))
+        .filter(|candidate| candidate.classes.iter().all(|block| validate_time_ok(events, &block.days)))
+        .filter_map(|candidate| {
+            let mut proposed: Vec<&Class> = schedule
+                .iter()
+                .enumerate()
+                .filter(|(idx, _)| *idx != course_index)
+                .map(|(_, class)| class)
+                .collect();
+            let others = proposed.len();
+            proposed.push(&candidate);
+
+            let others_indices: Vec<usize> = (0..others).collect();
+            if !is_compatible(&proposed, others, &others_indices) || !satisfies_hard(proposed.iter().copied(), constraints) {
+                return None;
+            }
+
+            let mut replaced = schedule.to_vec();
+            replaced[course_index] = candidate.clone();
+            let score = penalty(&replaced, constraints);
+            Some(SectionAlternative { class: candidate, penalty: score })
+        })
+        .collect();
+
+    alternatives.sort_by_key(|alternative| alternative.penalty);
+    alternatives
+}
+
+/**
+ * Groups schedules that share the same weekly time footprint
+ * 
+ * Two schedules are equivalent when they contain the same courses and every course
+ * meets at exactly the same times, i.e. they differ only by interchangeable sections
+ * (different instructor or room). This function:
+ * 1. Builds a footprint key per schedule from course codes and meeting patterns
+ * 2. Keeps the first schedule of each footprint as the group representative
+ * 3. Collects every other section seen in the group as an alternative for that course
+ * 4. Preserves the order in which footprints first appear in the input
+ * 
+ * @param {Vec<Vec<Class>>} schedules - Schedules to group (e.g. output of generate_combinations)
+ * @returns {Vec<ScheduleGroup>} One group per distinct weekly footprint
+ */
+pub fn group_by_footprint(schedules: Vec<Vec<Class>>) -> Vec<ScheduleGroup> {
+    let mut groups: Vec<ScheduleGroup> = Vec::new();
+    let mut group_lookup: HashMap<Vec<CourseFootprint>, usize> = HashMap::new();
+
+    for schedule in schedules {
+        let key = schedule_footprint(&schedule);
+
+        match group_lookup.get(&key) {
+            Some(&group_idx) => {
+                let group = &mut groups[group_idx];
+                group.count += 1;
+
+                // Record each section that differs from the representative's choice
+                for (course_idx, class) in schedule.into_iter().enumerate() {
+                    let sections = section_list(&class);
+                    let is_known = section_list(&group.schedule[course_idx]) == sections
+                        || group.alternatives[course_idx].iter().any(|alt| section_list(alt) == sections);
+                    if !is_known {
+                        group.alternatives[course_idx].push(class);
+                    }
+                }
+            },
+            None => {
+                group_lookup.insert(key, groups.len());
+                groups.push(ScheduleGroup {
+                    id: ScheduleRepository::schedule_id(&schedule),
+                    alternatives: vec![Vec::new(); schedule.len()],
+                    schedule,
+                    count: 1,
+                });
+            },
+        }
+    }
+
+    groups
+}
+
+/**
+ * Builds the footprint key of a schedule
+ * Sections within a course are sorted so block ordering from the scraper doesn't matter
+ * 
+ * @param {&[Class]} schedule - Schedule to describe
+ * @returns {Vec<CourseFootprint>} Course keys with their meeting patterns
+ */
+fn schedule_footprint(schedule: &[Class]) -> Vec<CourseFootprint> {
+    schedule
+        .iter()
+        .map(|class| {
+            let mut patterns: Vec<DayPattern> = class.classes.iter().map(|block| block.days).collect();
+            patterns.sort();
+            (format!("{}{}", class.code, class.name), patterns)
+        })
+        .collect()
+}
+
+/**
+ * Lists the section numbers of every time block in a class
+ * 
+ * @param {&Class} class - Class to describe
+ * @returns {Vec<&str>} Section numbers in block order
+ */
+fn section_list(class: &Class) -> Vec<&str> {
+    class.classes.iter().map(|block| block.section.as_str()).collect()
+}
+
+/**
+ * Reorders schedules so the leading entries are spread across the schedule space
+ * 
+ * Diversity only breaks ties: schedules arrive sorted by soft-constraint penalty and are
+ * split into bands of equal penalty, so a better-scoring schedule is never moved behind a
+ * worse one. Within each band, `spread_band` picks schedules for diversity until
+ * `sample_size` schedules have been picked in total; later bands keep their order.
+ * 
+ * @param {Vec<Vec<Class>>} schedules - Schedules sorted by penalty, ties in generation order
+ * @param {usize} sample_size - Number of leading schedules to choose for diversity
+ * @param {&ScheduleConstraints} constraints - Constraints the schedules were ranked by
+ * @returns {Vec<Vec<Class>>} The same schedules, reordered
+ */
+pub fn order_by_diversity(schedules: Vec<Vec<Class>>, sample_size: usize, constraints: &ScheduleConstraints) -> Vec<Vec<Class>> {
+    let mut bands: Vec<(u32, Vec<Vec<Class>>)> = Vec::new();
+    for schedule in schedules {
+        let score = penalty(&schedule, constraints);
+        match bands.last_mut() {
+            Some((band_score, band)) if *band_score == score => band.push(schedule),
+            _ => bands.push((score, vec![schedule])),
+        }
+    }
+
+    let mut remaining = sample_size;
+    let mut ordered = Vec::new();
+    for (_, band) in bands {
+        let band_sample = remaining.min(band.len());
+        remaining -= band_sample;
+        ordered.extend(spread_band(band, band_sample));
+    }
+    ordered
+}
+
+/**
+ * Reorders schedules of equal penalty so the leading entries differ as much as possible
+ * 
+ * Backtracking emits schedules in depth-first order, so consecutive results share most
+ * of their sections. This function performs greedy farthest-point sampling:
+ * 1. Summarizes each schedule by its campus days and daily start/end times
+ * 2. Starts from the first schedule and repeatedly picks the schedule farthest from
+ *    everything already picked (distance = days-off, start/end time and section differences)
+ * 3. Stops after `sample_size` picks and appends the rest in their original order
+ * 
+ * @param {Vec<Vec<Class>>} schedules - Schedules in generation order
+ * @param {usize} sample_size - Number of leading schedules to choose for diversity
+ * @returns {Vec<Vec<Class>>} The same schedules, reordered
+ */
+fn spread_band(schedules: Vec<Vec<Class>>, sample_size: usize) -> Vec<Vec<Class>> {
+    let sample_size = sample_size.min(schedules.len());
+    if sample_size < 2 {
+        return schedules;
+    }
+
+    let profiles: Vec<WeekProfile> = schedules.iter().map(|schedule| WeekProfile::new(schedule)).collect();
+    let mut picked = vec![false; schedules.len()];
+    let mut order: Vec<usize> = Vec::with_capacity(schedules.len());
+
+    // Distance from every schedule to the closest schedule picked so far
+    let mut min_distance = vec![i32::MAX; schedules.len()];
+    let mut next = 0;
+
+    while order.len() < sample_size {
+        picked[next] = true;
+        order.push(next);
+
+        let mut farthest: Option<(usize, i32)> = None;
+        for idx in 0..schedules.len() {
+            if picked[idx] {
+                continue;
+            }
+            let distance = profiles[idx].distance(&profiles[next], &schedules[idx], &schedules[next]);
+            min_distance[idx] = min_distance[idx].min(distance);
+            if farthest.is_none_or(|(_, best)| min_distance[idx] > best) {
+                farthest = Some((idx, min_distance[idx]));
+            }
+        }
+
+        match farthest {
+            Some((idx, _)) => next = idx,
+            None => break,
+        }
+    }
+
+    // Remaining schedules keep their generation order
+    order.extend((0..schedules.len()).filter(|&idx| !picked[idx]));
+
+    let mut slots: Vec<Option<Vec<Class>>> = schedules.into_iter().map(Some).collect();
+    order.into_iter().filter_map(|idx| slots[idx].take()).collect()
+}
+
+/**
+ * Weekly shape of a schedule used for diversity comparisons
+ * Times are minutes since midnight; days without classes have no bounds
+ */
+struct WeekProfile {
+    /** Earliest start and latest end for each weekday (Mon-Fri) */
+    bounds: [Option<(i32, i32)>; 5],
+}
+
+impl WeekProfile {
+    /**
+     * Summarizes a schedule's campus days and daily time bounds
+     * 
+     * @param {&[Class]} schedule - Schedule to summarize
+     * @returns {WeekProfile} Profile of the schedule
+     */
+    fn new(schedule: &[Class]) -> Self {
+        let mut bounds: [Option<(i32, i32)>; 5] = [None; 5];
+        for block in schedule.iter().flat_map(|class| class.classes.iter()) {
+            for (day_idx, ((start, end), meets)) in block.days.iter().enumerate() {
+                if !meets {
+                    continue;
+                }
+                let (start, end) = (to_minutes(*start), to_minutes(*end));
+                bounds[day_idx] = Some(match bounds[day_idx] {
+                    Some((earliest, latest)) => (earliest.min(start), latest.max(end)),
+                    None => (start, end),
+                });
+            }
+        }
+        Self { bounds }
+    }
+
+    /**
+     * Computes the distance between two schedules
+     * 
+     * @param {&WeekProfile} other - Profile of the other schedule
+     * @param {&[Class]} schedule - Schedule this profile was built from
+     * @param {&[Class]} other_schedule - Schedule the other profile was built from
+     * @returns {i32} Larger values mean more different weekly shapes
+     */
+    fn distance(&self, other: &WeekProfile, schedule: &[Class], other_schedule: &[Class]) -> i32 {
+        let mut distance = 0;
+        for (own, theirs) in self.bounds.iter().zip(other.bounds.iter()) {
+            distance += match (own, theirs) {
+                (Some((start_a, end_a)), Some((start_b, end_b))) => (start_a - start_b).abs() + (end_a - end_b).abs(),
+                (None, None) => 0,
+                _ => DAY_OFF_WEIGHT,
+            };
+        }
+
+        let different_sections = schedule
+            .iter()
+            .zip(other_schedule.iter())
+            .filter(|(a, b)| section_list(a) != section_list(b))
+            .count() as i32;
+
+        distance + different_sections * SECTION_WEIGHT
+    }
+}
+
+/**
+ * Converts an HHMM time integer to minutes since midnight
+ * 
+ * @param {i32} time_int - Time as integer in HHMM format
+ * @returns {i32} Minutes since midnight
+ */
+pub(crate) fn to_minutes(time_int: i32) -> i32 {
+    (time_int / 100) * 60 + time_int % 100
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_support::{class, unscheduled_class, FRIDAY, MON_WED, TUE_THU},
+        Constraint,
+    };
+
+    fn sections(classes: &[Class]) -> Vec<Vec<&str>> {
+        classes.iter().map(section_list).collect()
+    }
+
+    #[test]
+    fn identical_footprints_with_different_instructors_share_a_group() {
+        let calculus = class("MA", "241", "001", "Lee,Ann", TUE_THU, 900, 1015);
+        let schedules = vec![
+            vec![class("CSC", "116", "001", "Smith,John", MON_WED, 900, 1015), calculus.clone()],
+            vec![class("CSC", "116", "002", "Jones,Mary", MON_WED, 900, 1015), calculus.clone()],
+            vec![class("CSC", "116", "003", "Smith,John", TUE_THU, 1030, 1145), calculus.clone()],
+            // Seen again, so it counts but is not listed twice
+            vec![class("CSC", "116", "002", "Jones,Mary", MON_WED, 900, 1015), calculus],
+        ];
+
+        let groups = group_by_footprint(schedules.clone());
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].id, ScheduleRepository::schedule_id(&schedules[0]));
+        assert_eq!(sections(&groups[0].schedule), [["001"], ["001"]]);
+        assert_eq!(groups[0].count, 3);
+        assert_eq!(sections(&groups[0].alternatives[0]), [["002"]]);
+        assert!(groups[0].alternatives[1].is_empty());
+        assert_eq!(sections(&groups[1].schedule), [["003"], ["001"]]);
+        assert_eq!(groups[1].count, 1);
+    }
+
+    #[test]
+    fn sections_without_meeting_times_group_together() {
+        let lecture = class("CSC", "116", "001", "", MON_WED, 900, 1015);
+        let schedules = vec![
+            vec![lecture.clone(), unscheduled_class("HES", "100", &["601"])],
+            vec![lecture.clone(), unscheduled_class("HES", "100", &["602"])],
+            // A section of the same course that does meet is a different week
+            vec![lecture, class("HES", "100", "001", "", TUE_THU, 1200, 1250)],
+        ];
+
+        let groups = group_by_footprint(schedules);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].count, 2);
+        assert_eq!(sections(&groups[0].alternatives[1]), [["602"]]);
+        assert_eq!(sections(&groups[1].schedule[1..]), [["001"]]);
+    }
+
+    fn alternative_sections(alternatives: &[SectionAlternative]) -> Vec<Vec<&str>> {
+        alternatives.iter().map(|alternative| section_list(&alternative.class)).collect()
+    }
+
+    #[test]
+    fn alternatives_must_fit_the_other_sections_as_they_are() {
+        let schedule = vec![
+            class("CSC", "116", "001", "", MON_WED, 900, 1015),
+            class("MA", "241", "001", "", TUE_THU, 900, 1015),
+        ];
+        let candidates = vec![
+            class("MA", "241", "001", "", TUE_THU, 900, 1015),
+            class("MA", "241", "002", "", TUE_THU, 1030, 1145),
+            // Would fit if CSC 116 moved, but the rest of the schedule stays as it is
+            class("MA", "241", "003", "", MON_WED, 930, 1045),
+            unscheduled_class("MA", "241", &[]),
+        ];
+
+        let alternatives = find_section_alternatives(&schedule, 1, candidates, &Vec::new(), &ScheduleConstraints::default());
+
+        assert_eq!(alternative_sections(&alternatives), [["002"]]);
+        assert!(find_section_alternatives(&schedule, 2, Vec::new(), &Vec::new(), &ScheduleConstraints::default()).is_empty());
+    }
+
+    #[test]
+    fn alternatives_respect_events_and_constraints() {
+        let schedule = vec![
+            class("CSC", "116", "001", "", MON_WED, 900, 1015),
+            class("MA", "241", "001", "", TUE_THU, 900, 1015),
+        ];
+        let candidates = vec![
+            class("MA", "241", "002", "", FRIDAY, 900, 950),
+            class("MA", "241", "003", "", TUE_THU, 1200, 1315),
+            class("MA", "241", "004", "", TUE_THU, 1400, 1515),
+        ];
+        let lunch = vec![EventParam { time: (1200, 1300), days: TUE_THU }];
+        let constraints = ScheduleConstraints {
+            free_days: Some(Constraint { value: FRIDAY, hard: false }),
+            ..Default::default()
+        };
+
+        // The soft free Friday ranks section 002 last instead of removing it
+        let alternatives = find_section_alternatives(&schedule, 1, candidates.clone(), &lunch, &constraints);
+        assert_eq!(alternative_sections(&alternatives), [["004"], ["002"]]);
+        assert_eq!(alternatives.iter().map(|alternative| alternative.penalty).collect::<Vec<_>>(), [0, 1]);
+
+        let constraints = ScheduleConstraints {
+            free_days: Some(Constraint { value: FRIDAY, hard: true }),
+            ..Default::default()
+        };
+        let alternatives = find_section_alternatives(&schedule, 1, candidates, &lunch, &constraints);
+        assert_eq!(alternative_sections(&alternatives), [["004"]]);
+    }
+
+    #[test]
+    fn extensions_add_every_fitting_section_to_each_schedule() {
+        let base = vec![
+            vec![class("CSC", "116", "001", "", MON_WED, 900, 1015)],
+            vec![class("CSC", "116", "002", "", TUE_THU, 900, 1015)],
+            vec![class("CSC", "116", "003", "", FRIDAY, 900, 950)],
+        ];
+        let added = vec![vec![
+            class("MA", "241", "001", "", MON_WED, 930, 1045),
+            class("MA", "241", "002", "", TUE_THU, 1030, 1145),
+        ]];
+        let constraints = ScheduleConstraints {
+            free_days: Some(Constraint { value: FRIDAY, hard: false }),
+            ..Default::default()
+        };
+
+        let extended = extend_combinations(&base, &added, &constraints);
+
+        // MA 241 001 overlaps CSC 116 001; the Friday schedules break the soft constraint and come last
+        let sections: Vec<Vec<Vec<&str>>> = extended.iter().map(|schedule| sections(schedule)).collect();
+        assert_eq!(sections, [
+            [["001"], ["002"]],
+            [["002"], ["001"]],
+            [["002"], ["002"]],
+            [["003"], ["001"]],
+            [["003"], ["002"]],
+        ]);
+    }
+
+    #[test]
+    fn projections_drop_removed_courses_and_duplicates() {
+        let lecture = class("CSC", "116", "001", "", MON_WED, 900, 1015);
+        let base = vec![
+            vec![lecture.clone(), class("MA", "241", "001", "", TUE_THU, 900, 1015)],
+            vec![class("CSC", "116", "003", "", FRIDAY, 900, 950), class("MA", "241", "001", "", TUE_THU, 900, 1015)],
+            vec![lecture, class("MA", "241", "002", "", TUE_THU, 1030, 1145)],
+        ];
+        let constraints = ScheduleConstraints {
+            free_days: Some(Constraint { value: FRIDAY, hard: false }),
+            ..Default::default()
+        };
+
+        let projected = project_combinations(&base, &["MA241".to_string()], &constraints);
+
+        let sections: Vec<Vec<Vec<&str>>> = projected.iter().map(|schedule| sections(schedule)).collect();
+        assert_eq!(sections, [[["001"]], [["003"]]]);
+    }
+
+    /** Smallest distance between any two of the given schedules */
+    fn closest_pair(schedules: &[Vec<Class>]) -> i32 {
+        let profiles: Vec<WeekProfile> = schedules.iter().map(|schedule| WeekProfile::new(schedule)).collect();
+        let mut closest = i32::MAX;
+        for a in 0..schedules.len() {
+            for b in a + 1..schedules.len() {
+                closest = closest.min(profiles[a].distance(&profiles[b], &schedules[a], &schedules[b]));
+            }
+        }
+        closest
+    }
+
+    #[test]
+    fn diversity_never_moves_a_schedule_ahead_of_a_lower_penalty_band() {
+        let schedules = vec![
+            vec![class("CSC", "116", "001", "", MON_WED, 800, 850)],
+            vec![class("CSC", "116", "002", "", MON_WED, 810, 900)],
+            vec![class("CSC", "116", "003", "", TUE_THU, 1600, 1650)],
+            // Class on Friday breaks the soft constraint
+            vec![class("CSC", "116", "004", "", FRIDAY, 800, 850)],
+            vec![class("CSC", "116", "005", "", FRIDAY, 1600, 1650)],
+        ];
+        let constraints = ScheduleConstraints {
+            free_days: Some(Constraint { value: FRIDAY, hard: false }),
+            ..Default::default()
+        };
+
+        for sample_size in 0..=schedules.len() {
+            let ordered = order_by_diversity(schedules.clone(), sample_size, &constraints);
+            let penalties: Vec<u32> = ordered.iter().map(|schedule| penalty(schedule, &constraints)).collect();
+            assert_eq!(penalties, [0, 0, 0, 1, 1], "sample size {sample_size}");
+        }
+    }
+
+    #[test]
+    fn diversity_spreads_the_first_picks_of_a_band() {
+        let schedules: Vec<Vec<Class>> = [800, 810, 820, 830, 1300, 1700]
+            .iter()
+            .enumerate()
+            .map(|(index, &start)| vec![class("CSC", "116", &format!("00{}", index + 1), "", MON_WED, start, start + 50)])
+            .collect();
+
+        for sample_size in 2..=4 {
+            let ordered = order_by_diversity(schedules.clone(), sample_size, &ScheduleConstraints::default());
+            assert_eq!(ordered.len(), schedules.len());
+            assert_eq!(section_list(&ordered[0][0]), section_list(&schedules[0][0]));
+            assert!(
+                closest_pair(&ordered[..sample_size]) > closest_pair(&schedules[..sample_size]),
+                "sample size {sample_size}"
+            );
+        }
+
+        // Schedules past the sample keep their generation order
+        let ordered = order_by_diversity(schedules.clone(), 3, &ScheduleConstraints::default());
+        let rest: Vec<Vec<Vec<&str>>> = ordered[3..].iter().map(|schedule| sections(schedule)).collect();
+        assert_eq!(rest, [[["002"]], [["003"]], [["004"]]]);
+    }
+}
@@ -6,20 +6,20 @@
 //! calendar grid display.
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::{cmp::Reverse, collections::HashMap};
 
 use crate::{Event};
 
 // === CALENDAR DISPLAY CONSTANTS ===
 
-/** Starting hour for calendar display (8 AM) */
-const START_HOUR: i32 = 8;
+/** Default starting hour for calendar display (8 AM) */
+const DEFAULT_START_HOUR: i32 = 8;
 
-/** Ending hour for calendar display (8 PM) */
-const END_HOUR: i32 = 20;
+/** Default ending hour for calendar display (8 PM) */
+const DEFAULT_END_HOUR: i32 = 20;
 
-/** Total minutes displayed in calendar view (12 hours * 60 minutes) */
-const TOTAL_MINUTES_IN_VIEW: i32 = (END_HOUR - START_HOUR) * 60;
+/** Minimum free time kept above the earliest and below the latest event when fitting the view */
+const WINDOW_PADDING_MINUTES: i32 = 30;
 
 // === DATA STRUCTURES ===
 
@@ -71,6 +71,87 @@ pub struct ProcessedEvent {
     /** CSS height percentage for calendar positioning */
     #[serde(rename = "heightPosition")]
     pub height_position: String,
+    /** Whether the event starts before the visible window and was cut off at its top */
+    #[serde(rename = "clippedStart", default)]
+    pub clipped_start: bool,
+    /** Whether the event ends after the visible window and was cut off at its bottom */
+    #[serde(rename = "clippedEnd", default)]
+    pub clipped_end: bool,
+    /** Whether the event is a meeting of a previewed schedule rather than a user event */
+    #[serde(rename = "isPreview", default)]
+    pub is_preview: bool,
+}
+
+/**
+ * Range of whole hours shown by the calendar grid
+ * 
+ * Event positions are percentages of this range, so the grid must draw the
+ * same hours that were used to position the events.
+ */
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct ViewWindow {
+    /** First hour shown (0-23) */
+    #[serde(rename = "startHour")]
+    pub start_hour: i32,
+    /** Hour at which the view ends (1-24), exclusive */
+    #[serde(rename = "endHour")]
+    pub end_hour: i32,
+}
+
+impl Default for ViewWindow {
+    fn default() -> Self {
+        ViewWindow { start_hour: DEFAULT_START_HOUR, end_hour: DEFAULT_END_HOUR }
+    }
+}
+
+impl ViewWindow {
+    /**
+     * Checks that the window lies within one day and is not empty
+     * 
+     * @returns {Result<(), String>} Success or a description of the problem
+     * @throws {String} If the hours are out of range or the window is empty
+     */
+    pub fn validate(&self) -> Result<(), String> {
+        if !(0..24).contains(&self.start_hour) || !(1..=24).contains(&self.end_hour) {
+            return Err(format!("View window hours must be between 0 and 24, got {}-{}", self.start_hour, self.end_hour));
+        }
+        if self.start_hour >= self.end_hour {
+            return Err(format!("View window must end after it starts, got {}-{}", self.start_hour, self.end_hour));
+        }
+        Ok(())
+    }
+
+    /**
+     * Computes a window that shows every timed event with some padding
+     * 
+     * The window never shrinks below the default 8 AM - 8 PM view, so the grid
+     * only grows when an event actually falls outside it.
+     * 
+     * @param {IntoIterator<Item = &ProcessedEvent>} events - Processed events; events without valid times are ignored
+     * @returns {ViewWindow} Window covering the default view and all timed events
+     */
+    pub fn fit_events<'a>(events: impl IntoIterator<Item = &'a ProcessedEvent>) -> ViewWindow {
+        let mut window = ViewWindow::default();
+        for event in events.into_iter().filter(|event| EventProcessor::has_valid_time(event)) {
+            let start = EventProcessor::minutes_of_day(event.start_time_int) - WINDOW_PADDING_MINUTES;
+            let end = EventProcessor::minutes_of_day(event.end_time_int) + WINDOW_PADDING_MINUTES;
+            window.start_hour = window.start_hour.min(start.div_euclid(60));
+            window.end_hour = window.end_hour.max((end + 59).div_euclid(60));
+        }
+        window.start_hour = window.start_hour.max(0);
+        window.end_hour = window.end_hour.min(24);
+        window
+    }
+
+    /** Minute of the day at which the window starts */
+    fn start_minutes(&self) -> i32 {
+        self.start_hour * 60
+    }
+
+    /** Minute of the day at which the window ends */
+    fn end_minutes(&self) -> i32 {
+        self.end_hour * 60
+    }
 }
 
 /**
@@ -88,6 +169,27 @@ pub struct ProcessedEventsResult {
     /** No-time events organized by day key */
     #[serde(rename = "noTimeEventsByDay")]
     pub no_time_events_by_day: HashMap<String, Vec<ProcessedEvent>>,
+    /** Hours the positions were calculated against, to be drawn by the calendar grid */
+    pub window: ViewWindow,
+}
+
+/**
+ * Horizontal placement of one event among the events overlapping it
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct ColumnPlacement {
+    /** Leftmost column occupied, 0-based */
+    pub column: usize,
+    /** Number of adjacent columns occupied */
+    pub span: usize,
+    /** Number of columns the overlapping group is split into */
+    pub columns: usize,
+}
+
+impl Default for ColumnPlacement {
+    fn default() -> Self {
+        ColumnPlacement { column: 0, span: 1, columns: 1 }
+    }
 }
 
 // === EVENT PROCESSOR IMPLEMENTATION ===
@@ -122,15 +224,15 @@ impl EventProcessor {
     }
 
     /**
-     * Calculates minutes elapsed since calendar start hour
+     * Converts a time integer to minutes since midnight
      * 
-     * Converts time integers to minutes offset from START_HOUR for positioning
-     * calculations. This enables percentage-based positioning in the calendar grid.
+     * Used for overlap detection and, relative to the view window's start,
+     * for percentage-based positioning in the calendar grid.
      * 
      * @param {i32} time_int - Time as integer in HHMM format
-     * @returns {i32} Minutes since START_HOUR (can be negative for early times)
+     * @returns {i32} Minutes since midnight (0 for invalid times)
      */
-    fn get_minutes_since_start(time_int: i32) -> i32 {
+    fn minutes_of_day(time_int: i32) -> i32 {
         // Handle invalid times
         if time_int <= 0 {
             return 0;
@@ -138,8 +240,7 @@ impl EventProcessor {
         // Extract hours and minutes from HHMM format
         let hours = time_int / 100;
         let minutes = time_int % 100;
-        // Calculate total minutes and subtract calendar start offset
-        (hours * 60 + minutes) - (START_HOUR * 60)
+        hours * 60 + minutes
     }
 
     /**
@@ -177,6 +278,9 @@ impl EventProcessor {
             left: "0%".to_string(),
             top_position: "0%".to_string(),
             height_position: "0%".to_string(),
+            clipped_start: false,
+            clipped_end: false,
+            is_preview: false,
         }
     }
 
@@ -240,83 +344,136 @@ impl EventProcessor {
     /**
      * Calculates overlap groups and horizontal positioning for events in a single day
      * 
-     * This function implements a sophisticated overlap detection algorithm:
-     * 1. Sorts events by start time for chronological processing
-     * 2. Groups overlapping events together using interval overlap detection
-     * 3. Calculates width and horizontal offset for each event based on group size
-     * 4. Ensures overlapping events are displayed side-by-side without visual conflicts
+     * Sorts the events chronologically (longer events first on ties), packs them into
+     * columns with `assign_columns` and converts each placement to CSS percentages.
      * 
-     * @param {&mut Vec<ProcessedEvent>} day_events - Events for a single day (modified in-place)
+     * @param {&mut [ProcessedEvent]} day_events - Events for a single day (modified in-place)
      */
     fn calculate_overlap_groups(day_events: &mut [ProcessedEvent]) {
-        // Sort events by start time for chronological processing
-        day_events.sort_by(|a, b| a.start_time_int.cmp(&b.start_time_int));
-
-        // Track groups of overlapping events
-        let mut groups: Vec<Vec<usize>> = Vec::new();
-        let mut current_group: Vec<usize> = Vec::new();
-
-        for (i, event) in day_events.iter().enumerate() {
-            let event_start_minutes = Self::get_minutes_since_start(event.start_time_int);
-            
-            // Check if this event overlaps with any event in the current group
-            let overlaps_with_group = current_group.iter().any(|&group_event_idx| {
-                let group_event = &day_events[group_event_idx];
-                let group_event_end_minutes = Self::get_minutes_since_start(group_event.end_time_int);
-                // Overlap occurs if new event starts before existing event ends
-                event_start_minutes < group_event_end_minutes
-            });
-
-            if overlaps_with_group {
-                // Add to current overlap group
-                current_group.push(i);
-            } else {
-                // No overlap - finalize current group and start new one
-                if !current_group.is_empty() {
-                    groups.push(current_group.clone());
-                }
-                current_group = vec![i];
-            }
+        // Sort chronologically; on ties the longer event takes the leftmost column
+        day_events.sort_by_key(|event| (event.start_time_int, Reverse(event.end_time_int)));
+
+        let intervals: Vec<(i32, i32)> = day_events
+            .iter()
+            .map(|event| (Self::minutes_of_day(event.start_time_int), Self::minutes_of_day(event.end_time_int)))
+            .collect();
+
+        for (event, placement) in day_events.iter_mut().zip(Self::assign_columns(&intervals)) {
+            let column_width = 100.0 / placement.columns as f64;
+            event.width = format!("{:.2}%", placement.span as f64 * column_width);
+            event.left = format!("{:.2}%", placement.column as f64 * column_width);
         }
-        
-        // Don't forget the last group
-        if !current_group.is_empty() {
-            groups.push(current_group);
+    }
+
+    /**
+     * Packs one day's time intervals into side-by-side columns
+     * 
+     * This works like an interval graph coloring:
+     * 1. Splits the day into clusters of transitively overlapping intervals
+     * 2. Places each interval in the leftmost column that is free at its start time,
+     *    so a cluster uses as few columns as its busiest moment requires
+     * 3. Expands each interval rightwards over the columns that stay free for its whole
+     *    duration, so short intervals don't leave dead space beside them
+     * 
+     * Intervals that only touch (one ends when the next starts) don't overlap. Shared by
+     * the calendar view and the exported schedule images, so both lay out alike.
+     * 
+     * @param {&[(i32, i32)]} intervals - Start and end minutes, sorted by start time with longer intervals first on ties
+     * @returns {Vec<ColumnPlacement>} Placement of each interval, in input order
+     */
+    pub(crate) fn assign_columns(intervals: &[(i32, i32)]) -> Vec<ColumnPlacement> {
+        let mut placements = Vec::with_capacity(intervals.len());
+
+        let mut cluster_start = 0;
+        while cluster_start < intervals.len() {
+            // Extend the cluster while intervals start before everything so far has ended
+            let mut cluster_end = intervals[cluster_start].1;
+            let mut next = cluster_start + 1;
+            while next < intervals.len() && intervals[next].0 < cluster_end {
+                cluster_end = cluster_end.max(intervals[next].1);
+                next += 1;
+            }
+
+            placements.extend(Self::assign_cluster_columns(&intervals[cluster_start..next]));
+            cluster_start = next;
         }
 
-        // Apply width and left positioning based on overlap groups
-        for group in groups {
-            let group_width = 100.0; // Full width available for the group
-            let event_width = group_width / group.len() as f64; // Equal width distribution
-            
-            // Set positioning for each event in the group
-            for (index, &event_idx) in group.iter().enumerate() {
-                day_events[event_idx].width = format!("{event_width:.2}%");
-                day_events[event_idx].left = format!("{:.2}%", index as f64 * event_width);
+        placements
+    }
+
+    /**
+     * Assigns columns and spans to one cluster of overlapping intervals
+     * 
+     * @param {&[(i32, i32)]} cluster - Transitively overlapping intervals sorted by start time
+     * @returns {Vec<ColumnPlacement>} Placement of each interval, in input order
+     */
+    fn assign_cluster_columns(cluster: &[(i32, i32)]) -> Vec<ColumnPlacement> {
+        // Greedy coloring: reuse the leftmost column whose last interval has ended
+        let mut column_ends: Vec<i32> = Vec::new();
+        let mut columns: Vec<usize> = Vec::with_capacity(cluster.len());
+        for &(start, end) in cluster {
+            match column_ends.iter().position(|&column_end| column_end <= start) {
+                Some(column) => {
+                    column_ends[column] = end;
+                    columns.push(column);
+                }
+                None => {
+                    column_ends.push(end);
+                    columns.push(column_ends.len() - 1);
+                }
             }
         }
+
+        let column_count = column_ends.len();
+        cluster
+            .iter()
+            .zip(&columns)
+            .map(|(&(start, end), &column)| {
+                // Expand into following columns until one holds an overlapping interval
+                let span = (column + 1..column_count)
+                    .take_while(|&other_column| {
+                        !cluster.iter().zip(&columns).any(|(&(other_start, other_end), &assigned)| {
+                            assigned == other_column && other_start < end && start < other_end
+                        })
+                    })
+                    .count()
+                    + 1;
+                ColumnPlacement { column, span, columns: column_count }
+            })
+            .collect()
     }
 
     /**
      * Calculates vertical positioning for events based on time ranges
      * 
      * Converts event start times and durations to CSS percentage values
-     * for proper vertical positioning in the calendar grid. Uses the
-     * calendar's time range (START_HOUR to END_HOUR) as the basis for
-     * percentage calculations.
+     * for proper vertical positioning in the calendar grid. Uses the view
+     * window as the basis for percentage calculations; events reaching past
+     * either edge are clamped to it and flagged as clipped, so positions stay
+     * within 0-100%.
      * 
      * @param {&mut Vec<ProcessedEvent>} day_events - Events to position (modified in-place)
+     * @param {ViewWindow} window - Hours shown by the calendar grid
      */
-    fn calculate_positioning(day_events: &mut [ProcessedEvent]) {
+    fn calculate_positioning(day_events: &mut [ProcessedEvent], window: ViewWindow) {
+        let window_start = window.start_minutes();
+        let window_end = window.end_minutes();
+        let total_minutes = (window_end - window_start) as f64;
+
         for event in day_events.iter_mut() {
-            // Calculate time offsets in minutes from calendar start
-            let start_minutes = Self::get_minutes_since_start(event.start_time_int);
-            let end_minutes = Self::get_minutes_since_start(event.end_time_int);
-            let duration = (end_minutes - start_minutes).max(0); // Ensure non-negative duration
+            let start_minutes = Self::minutes_of_day(event.start_time_int);
+            let end_minutes = Self::minutes_of_day(event.end_time_int);
+
+            // Clamp to the visible window and remember which edges were cut off
+            event.clipped_start = start_minutes < window_start;
+            event.clipped_end = end_minutes > window_end;
+            let visible_start = start_minutes.clamp(window_start, window_end);
+            let visible_end = end_minutes.clamp(window_start, window_end);
+            let duration = (visible_end - visible_start).max(0); // Ensure non-negative duration
 
             // Convert to percentage of total calendar view
-            event.top_position = format!("{:.2}%", (start_minutes as f64 / TOTAL_MINUTES_IN_VIEW as f64) * 100.0);
-            event.height_position = format!("{:.2}%", (duration as f64 / TOTAL_MINUTES_IN_VIEW as f64) * 100.0);
+            event.top_position = format!("{:.2}%", ((visible_start - window_start) as f64 / total_minutes) * 100.0);
+            event.height_position = format!("{:.2}%", (duration as f64 / total_minutes) * 100.0);
         }
     }
 
@@ -326,20 +483,28 @@ impl EventProcessor {
      * This function coordinates the entire process of converting raw events into
      * calendar-ready data with proper positioning and overlap handling:
      * 1. Handles empty input gracefully
-     * 2. Converts raw events to processed format with initial formatting
-     * 3. Groups events by day and separates timed vs no-time events
-     * 4. For each day: calculates overlap groups and positioning
-     * 5. Returns organized data ready for calendar rendering
+     * 2. Converts raw and preview events to processed format with initial formatting
+     * 3. Uses the requested view window, or fits one around all timed events
+     * 4. Groups events by day and separates timed vs no-time events
+     * 5. For each day: calculates overlap groups and positioning within the window
+     * 6. Returns organized data and the effective window for calendar rendering
+     * 
+     * Preview events are drawn full width above the user's events, so they share the
+     * window but take no part in the overlap columns; they follow the user's events
+     * in each day's list.
      * 
      * @param {Vec<Event>} raw_events - Raw events from database
+     * @param {Vec<Event>} preview_events - Meetings of the previewed schedule, if any
+     * @param {Option<ViewWindow>} window - Hours to position against; fitted to the events when None
      * @returns {ProcessedEventsResult} Fully processed events organized for calendar display
      */
-    pub fn process_events(raw_events: Vec<Event>) -> ProcessedEventsResult {
+    pub fn process_events(raw_events: Vec<Event>, preview_events: Vec<Event>, window: Option<ViewWindow>) -> ProcessedEventsResult {
         // Handle empty input case
-        if raw_events.is_empty() {
+        if raw_events.is_empty() && preview_events.is_empty() {
             return ProcessedEventsResult {
                 events_by_day: HashMap::new(),
                 no_time_events_by_day: HashMap::new(),
+                window: window.unwrap_or_default(),
             };
         }
 
@@ -348,22 +513,282 @@ impl EventProcessor {
             .into_iter()
             .map(Self::event_to_processed_event)
             .collect();
+        let processed_previews: Vec<ProcessedEvent> = preview_events
+            .into_iter()
+            .map(|event| ProcessedEvent { is_preview: true, ..Self::event_to_processed_event(event) })
+            .collect();
+
+        // Step 2: Settle on the hours the calendar will show
+        let window = window.unwrap_or_else(|| ViewWindow::fit_events(processed_events.iter().chain(&processed_previews)));
 
-        // Step 2: Group events by day and separate by time validity
-        let (mut events_by_day, no_time_events_by_day) = Self::group_events_by_day(processed_events);
+        // Step 3: Group events by day and separate by time validity
+        let (mut events_by_day, mut no_time_events_by_day) = Self::group_events_by_day(processed_events);
+        let (mut previews_by_day, no_time_previews_by_day) = Self::group_events_by_day(processed_previews);
 
-        // Step 3: Process each day's timed events for positioning and overlap handling
+        // Step 4: Process each day's timed events for positioning and overlap handling
         for day_events in events_by_day.values_mut() {
             // Calculate horizontal positioning based on overlaps
             Self::calculate_overlap_groups(day_events);
             // Calculate vertical positioning based on time ranges
-            Self::calculate_positioning(day_events);
+            Self::calculate_positioning(day_events, window);
+        }
+        for (day_key, mut day_previews) in previews_by_day.drain() {
+            Self::calculate_positioning(&mut day_previews, window);
+            events_by_day.entry(day_key).or_default().extend(day_previews);
+        }
+        for (day_key, day_previews) in no_time_previews_by_day {
+            no_time_events_by_day.entry(day_key).or_default().extend(day_previews);
         }
 
         // Return fully processed and positioned events
         ProcessedEventsResult {
             events_by_day,
             no_time_events_by_day,
+            window,
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /** Builds a Monday event from HHMM start and end times */
+    fn event(id: &str, start_time: i32, end_time: i32) -> Event {
+        Event {
+            id: id.to_string(),
+            title: id.to_string(),
+            start_time,
+            end_time,
+            day: 1 << 1,
+            professor: String::new(),
+            description: String::new(),
+        }
+    }
+
+    /** Lays out one day's events and returns (id, width, left) in input order */
+    fn layout(events: Vec<Event>) -> Vec<(String, String, String)> {
+        let ids: Vec<String> = events.iter().map(|event| event.id.clone()).collect();
+        let mut day_events: Vec<ProcessedEvent> = events.into_iter().map(EventProcessor::event_to_processed_event).collect();
+        EventProcessor::calculate_overlap_groups(&mut day_events);
+        ids.into_iter()
+            .map(|id| {
+                let event = day_events.iter().find(|event| event.id == id).unwrap();
+                (id, event.width.clone(), event.left.clone())
+            })
+            .collect()
+    }
+
+    fn placed(id: &str, width: &str, left: &str) -> (String, String, String) {
+        (id.to_string(), width.to_string(), left.to_string())
+    }
+
+    #[test]
+    fn separate_events_use_full_width() {
+        let result = layout(vec![event("a", 900, 1000), event("b", 1100, 1200)]);
+        assert_eq!(result, vec![placed("a", "100.00%", "0.00%"), placed("b", "100.00%", "0.00%")]);
+    }
+
+    #[test]
+    fn touching_events_do_not_overlap() {
+        let result = layout(vec![event("a", 900, 1000), event("b", 1000, 1100)]);
+        assert_eq!(result, vec![placed("a", "100.00%", "0.00%"), placed("b", "100.00%", "0.00%")]);
+    }
+
+    #[test]
+    fn long_event_beside_sequential_short_events_uses_two_columns() {
+        let result = layout(vec![event("long", 900, 1200), event("first", 900, 1000), event("second", 1000, 1100)]);
+        assert_eq!(
+            result,
+            vec![
+                placed("long", "50.00%", "0.00%"),
+                placed("first", "50.00%", "50.00%"),
+                placed("second", "50.00%", "50.00%"),
+            ]
+        );
+    }
+
+    #[test]
+    fn mutually_overlapping_events_share_width_equally() {
+        let result = layout(vec![event("a", 900, 1000), event("b", 915, 1015), event("c", 930, 1030)]);
+        assert_eq!(
+            result,
+            vec![
+                placed("a", "33.33%", "0.00%"),
+                placed("b", "33.33%", "33.33%"),
+                placed("c", "33.33%", "66.67%"),
+            ]
+        );
+    }
+
+    #[test]
+    fn chain_reuses_freed_column() {
+        // a overlaps b and b overlaps c, but a and c never meet, so two columns suffice
+        let result = layout(vec![event("a", 900, 1000), event("b", 930, 1030), event("c", 1000, 1100)]);
+        assert_eq!(
+            result,
+            vec![
+                placed("a", "50.00%", "0.00%"),
+                placed("b", "50.00%", "50.00%"),
+                placed("c", "50.00%", "0.00%"),
+            ]
+        );
+    }
+
+    #[test]
+    fn event_expands_into_free_columns() {
+        // Three columns are needed at 9:00, but "late" only competes with "long" after 10:00
+        let result = layout(vec![
+            event("long", 900, 1200),
+            event("b", 900, 1000),
+            event("c", 900, 1000),
+            event("late", 1000, 1100),
+        ]);
+        assert_eq!(
+            result,
+            vec![
+                placed("long", "33.33%", "0.00%"),
+                placed("b", "33.33%", "33.33%"),
+                placed("c", "33.33%", "66.67%"),
+                placed("late", "66.67%", "33.33%"),
+            ]
+        );
+    }
+
+    #[test]
+    fn expansion_stops_at_first_blocked_column() {
+        // "e" reuses column 1; column 3 is free again by then, but "blocker" holds column 2
+        let result = layout(vec![
+            event("a", 900, 1100),
+            event("b", 905, 930),
+            event("blocker", 910, 1100),
+            event("d", 915, 925),
+            event("e", 930, 1000),
+        ]);
+        assert_eq!(
+            result,
+            vec![
+                placed("a", "25.00%", "0.00%"),
+                placed("b", "25.00%", "25.00%"),
+                placed("blocker", "25.00%", "50.00%"),
+                placed("d", "25.00%", "75.00%"),
+                placed("e", "25.00%", "25.00%"),
+            ]
+        );
+    }
+
+    #[test]
+    fn clusters_on_the_same_day_are_independent() {
+        let result = layout(vec![event("a", 900, 1000), event("b", 930, 1030), event("c", 1300, 1400)]);
+        assert_eq!(
+            result,
+            vec![
+                placed("a", "50.00%", "0.00%"),
+                placed("b", "50.00%", "50.00%"),
+                placed("c", "100.00%", "0.00%"),
+            ]
+        );
+    }
+
+    #[test]
+    fn ties_place_the_longer_event_first() {
+        let result = layout(vec![event("short", 900, 930), event("long", 900, 1100), event("next", 930, 1000)]);
+        assert_eq!(
+            result,
+            vec![
+                placed("short", "50.00%", "50.00%"),
+                placed("long", "50.00%", "0.00%"),
+                placed("next", "50.00%", "50.00%"),
+            ]
+        );
+    }
+
+    #[test]
+    fn days_are_laid_out_separately() {
+        let mut monday_and_tuesday = event("both", 900, 1000);
+        monday_and_tuesday.day = (1 << 1) | (1 << 2);
+        let result = EventProcessor::process_events(vec![monday_and_tuesday, event("monday", 930, 1030)], Vec::new(), None);
+
+        let monday = &result.events_by_day["1"];
+        assert!(monday.iter().all(|event| event.width == "50.00%"));
+        let tuesday = &result.events_by_day["2"];
+        assert_eq!(tuesday.len(), 1);
+        assert_eq!(tuesday[0].width, "100.00%");
+        assert_eq!(tuesday[0].left, "0.00%");
+    }
+
+    #[test]
+    fn default_window_is_kept_when_events_fit() {
+        let result = EventProcessor::process_events(vec![event("a", 900, 1000)], Vec::new(), None);
+        assert_eq!(result.window, ViewWindow { start_hour: 8, end_hour: 20 });
+        let monday = &result.events_by_day["1"];
+        assert_eq!(monday[0].top_position, "8.33%");
+        assert_eq!(monday[0].height_position, "8.33%");
+    }
+
+    #[test]
+    fn window_grows_with_padding_around_early_and_late_events() {
+        let result = EventProcessor::process_events(vec![event("lab", 730, 920), event("study", 2100, 2300)], Vec::new(), None);
+        assert_eq!(result.window, ViewWindow { start_hour: 7, end_hour: 24 });
+
+        let monday = &result.events_by_day["1"];
+        let lab = monday.iter().find(|event| event.id == "lab").unwrap();
+        assert_eq!(lab.top_position, "2.94%");
+        assert!(!lab.clipped_start && !lab.clipped_end);
+    }
+
+    #[test]
+    fn requested_window_clamps_and_flags_events_outside_it() {
+        let window = ViewWindow { start_hour: 8, end_hour: 20 };
+        let result = EventProcessor::process_events(vec![event("lab", 730, 930), event("study", 1900, 2130)], Vec::new(), Some(window));
+        assert_eq!(result.window, window);
+
+        let monday = &result.events_by_day["1"];
+        let lab = monday.iter().find(|event| event.id == "lab").unwrap();
+        assert_eq!((lab.top_position.as_str(), lab.height_position.as_str()), ("0.00%", "12.50%"));
+        assert!(lab.clipped_start && !lab.clipped_end);
+
+        let study = monday.iter().find(|event| event.id == "study").unwrap();
+        assert_eq!((study.top_position.as_str(), study.height_position.as_str()), ("91.67%", "8.33%"));
+        assert!(!study.clipped_start && study.clipped_end);
+    }
+
+    #[test]
+    fn window_fits_previewed_meetings_and_moves_user_events_onto_it() {
+        let result = EventProcessor::process_events(vec![event("study", 900, 1000)], vec![event("lab", 730, 920)], None);
+        assert_eq!(result.window, ViewWindow { start_hour: 7, end_hour: 20 });
+
+        let monday = &result.events_by_day["1"];
+        let study = monday.iter().find(|event| event.id == "study").unwrap();
+        assert_eq!(study.top_position, "15.38%");
+        let lab = monday.iter().find(|event| event.id == "lab").unwrap();
+        assert_eq!(lab.top_position, "3.85%");
+        assert!(lab.is_preview && !study.is_preview);
+    }
+
+    #[test]
+    fn previews_stay_full_width_over_user_events() {
+        let mut online = event("online", 0, 0);
+        online.day = (1 << 1) | (1 << 3);
+        let result = EventProcessor::process_events(
+            vec![event("a", 900, 1000), event("b", 930, 1030)],
+            vec![event("lecture", 900, 950), online],
+            None,
+        );
+
+        let monday = &result.events_by_day["1"];
+        assert_eq!(monday.iter().map(|event| event.id.as_str()).collect::<Vec<_>>(), ["a", "b", "lecture"]);
+        assert_eq!((monday[2].width.as_str(), monday[2].left.as_str()), ("100%", "0%"));
+        assert!(monday[..2].iter().all(|event| event.width == "50.00%"));
+        assert!(["1", "3"].iter().all(|day| result.no_time_events_by_day[*day][0].is_preview));
+    }
+
+    #[test]
+    fn invalid_windows_are_rejected() {
+        assert!(ViewWindow { start_hour: 8, end_hour: 20 }.validate().is_ok());
+        assert!(ViewWindow { start_hour: 0, end_hour: 24 }.validate().is_ok());
+        assert!(ViewWindow { start_hour: 12, end_hour: 12 }.validate().is_err());
+        assert!(ViewWindow { start_hour: -1, end_hour: 20 }.validate().is_err());
+        assert!(ViewWindow { start_hour: 8, end_hour: 25 }.validate().is_err());
+    }
+}
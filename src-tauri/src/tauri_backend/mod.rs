@@ -1,3 +1,4 @@
 pub mod scrape_classes;
 pub mod class_combinations;
-pub mod event_processor;
\ No newline at end of file
+pub mod event_processor;
+pub mod schedule_constraints;
\ No newline at end of file
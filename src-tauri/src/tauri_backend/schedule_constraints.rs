@@ -0,0 +1,250 @@
+//! Schedule-level time constraints applied during combination generation
+//! 
+//! This module evaluates the daily structure of a (partial) schedule against the user's
+//! constraints: maximum class time per day, maximum back-to-back time without a break,
+//! a required free block on chosen days and days kept completely free. Hard constraints
+//! prune the backtracking search; soft constraints only contribute to a penalty score
+//! used to rank the generated schedules.
+
+use crate::{tauri_backend::class_combinations::to_minutes, Class, ScheduleConstraints};
+
+/** Gaps shorter than this (in minutes) count as walking time, not as a break */
+const MIN_BREAK_MINUTES: i32 = 30;
+
+/**
+ * Checks whether a schedule satisfies every hard constraint
+ * 
+ * All constraints only get worse as classes are added, so this check is safe to
+ * apply to partial schedules while backtracking. The classes are taken as an iterator
+ * so callers can check a schedule of indices in place without collecting it first.
+ * 
+ * @param {impl IntoIterator<Item = &Class>} schedule - Classes currently in the (partial) schedule
+ * @param {&ScheduleConstraints} constraints - User constraints
+ * @returns {bool} True if no hard constraint is violated
+ */
+pub fn satisfies_hard<'a>(schedule: impl IntoIterator<Item = &'a Class>, constraints: &ScheduleConstraints) -> bool {
+    count_violations(schedule, constraints, true) == 0
+}
+
+/**
+ * Computes the soft-constraint penalty of a complete schedule
+ * 
+ * @param {&[Class]} schedule - Schedule to score
+ * @param {&ScheduleConstraints} constraints - User constraints
+ * @returns {u32} Number of violated soft constraints (lower is better)
+ */
+pub fn penalty(schedule: &[Class], constraints: &ScheduleConstraints) -> u32 {
+    count_violations(schedule, constraints, false)
+}
+
+/**
+ * Counts violated constraints of one strictness
+ * Returns early without building the daily intervals if no constraint has that strictness
+ * 
+ * @param {impl IntoIterator<Item = &Class>} schedule - Classes in the schedule
+ * @param {&ScheduleConstraints} constraints - User constraints
+ * @param {bool} hard - Whether to evaluate hard (true) or soft (false) constraints
+ * @returns {u32} Number of violated constraints
+ */
+fn count_violations<'a>(schedule: impl IntoIterator<Item = &'a Class>, constraints: &ScheduleConstraints, hard: bool) -> u32 {
+    let strictness = [
+        constraints.max_daily_minutes.as_ref().map(|c| c.hard),
+        constraints.max_consecutive_minutes.as_ref().map(|c| c.hard),
+        constraints.free_block.as_ref().map(|c| c.hard),
+        constraints.free_days.as_ref().map(|c| c.hard),
+    ];
+    if !strictness.contains(&Some(hard)) {
+        return 0;
+    }
+
+    let days = daily_intervals(schedule);
+    let mut violations = 0;
+
+    if let Some(limit) = constraints.max_daily_minutes.as_ref().filter(|c| c.hard == hard) {
+        if days.iter().any(|intervals| intervals.iter().map(|(start, end)| end - start).sum::<i32>() > limit.value) {
+            violations += 1;
+        }
+    }
+
+    if let Some(limit) = constraints.max_consecutive_minutes.as_ref().filter(|c| c.hard == hard) {
+        if days.iter().any(|intervals| longest_stretch(intervals) > limit.value) {
+            violations += 1;
+        }
+    }
+
+    if let Some(block) = constraints.free_block.as_ref().filter(|c| c.hard == hard) {
+        let block = block.value;
+        let window = (to_minutes(block.window.0), to_minutes(block.window.1));
+        let missing_block = days
+            .iter()
+            .zip(block.days.iter())
+            .any(|(intervals, &required)| required && longest_gap(intervals, window) < block.minutes);
+        if missing_block {
+            violations += 1;
+        }
+    }
+
+    if let Some(free_days) = constraints.free_days.as_ref().filter(|c| c.hard == hard) {
+        if days.iter().zip(free_days.value.iter()).any(|(intervals, &keep_free)| keep_free && !intervals.is_empty()) {
+            violations += 1;
+        }
+    }
+
+    violations
+}
+
+/**
+ * Collects the meeting intervals of every weekday, sorted by start time
+ * 
+ * @param {impl IntoIterator<Item = &Class>} schedule - Classes in the schedule
+ * @returns {[Vec<(i32, i32)>; 5]} Intervals in minutes since midnight for Mon-Fri
+ */
+fn daily_intervals<'a>(schedule: impl IntoIterator<Item = &'a Class>) -> [Vec<(i32, i32)>; 5] {
+    let mut days: [Vec<(i32, i32)>; 5] = Default::default();
+    for block in schedule.into_iter().flat_map(|class| class.classes.iter()) {
+        for (day_idx, ((start, end), meets)) in block.days.iter().enumerate() {
+            if *meets {
+                days[day_idx].push((to_minutes(*start), to_minutes(*end)));
+            }
+        }
+    }
+    for intervals in days.iter_mut() {
+        intervals.sort();
+    }
+    days
+}
+
+/**
+ * Finds the longest stretch of classes without a break
+ * Consecutive intervals separated by less than MIN_BREAK_MINUTES are merged
+ * 
+ * @param {&[(i32, i32)]} intervals - Sorted intervals for one day
+ * @returns {i32} Length of the longest stretch in minutes
+ */
+fn longest_stretch(intervals: &[(i32, i32)]) -> i32 {
+    let mut longest = 0;
+    let mut current: Option<(i32, i32)> = None;
+    for &(start, end) in intervals {
+        current = match current {
+            Some((stretch_start, stretch_end)) if start - stretch_end < MIN_BREAK_MINUTES => Some((stretch_start, stretch_end.max(end))),
+            _ => Some((start, end)),
+        };
+        if let Some((stretch_start, stretch_end)) = current {
+            longest = longest.max(stretch_end - stretch_start);
+        }
+    }
+    longest
+}
+
+/**
+ * Finds the longest gap between classes inside a time window
+ * 
+ * @param {&[(i32, i32)]} intervals - Sorted intervals for one day
+ * @param {(i32, i32)} window - Window bounds in minutes since midnight
+ * @returns {i32} Length of the longest free gap in minutes
+ */
+fn longest_gap(intervals: &[(i32, i32)], window: (i32, i32)) -> i32 {
+    let (window_start, window_end) = window;
+    let mut longest = 0;
+    let mut free_from = window_start;
+    for &(start, end) in intervals {
+        if end <= free_from {
+            continue;
+        }
+        if start >= window_end {
+            break;
+        }
+        longest = longest.max(start - free_from);
+        free_from = free_from.max(end);
+    }
+    longest.max(window_end - free_from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_support::{class, FRIDAY, MON_WED},
+        Constraint, FreeBlock,
+    };
+
+    /** Mon/Wed 9:00-10:15, 10:30-11:20 and 13:00-13:50 */
+    fn monday_classes() -> Vec<Class> {
+        vec![
+            class("CSC", "116", "001", "", MON_WED, 900, 1015),
+            class("MA", "241", "001", "", MON_WED, 1030, 1120),
+            class("PY", "205", "001", "", MON_WED, 1300, 1350),
+        ]
+    }
+
+    fn soft<T>(value: T) -> Option<Constraint<T>> {
+        Some(Constraint { value, hard: false })
+    }
+
+    fn hard<T>(value: T) -> Option<Constraint<T>> {
+        Some(Constraint { value, hard: true })
+    }
+
+    #[test]
+    fn short_gaps_do_not_break_a_stretch() {
+        assert_eq!(longest_stretch(&[]), 0);
+        // 29 minutes between classes is walking time, so both count as one stretch
+        assert_eq!(longest_stretch(&[(540, 590), (619, 669)]), 129);
+        // 30 minutes is a break
+        assert_eq!(longest_stretch(&[(540, 590), (620, 670)]), 50);
+        assert_eq!(longest_stretch(&[(540, 590), (600, 700), (760, 800)]), 160);
+    }
+
+    #[test]
+    fn gaps_are_measured_inside_the_window() {
+        let noon_to_two = (720, 840);
+        assert_eq!(longest_gap(&[], noon_to_two), 120);
+        assert_eq!(longest_gap(&[(600, 750), (780, 900)], noon_to_two), 30);
+        // Classes entirely outside the window leave it free
+        assert_eq!(longest_gap(&[(540, 600), (900, 960)], noon_to_two), 120);
+        assert_eq!(longest_gap(&[(720, 840)], noon_to_two), 0);
+        assert_eq!(longest_gap(&[(750, 780)], noon_to_two), 60);
+    }
+
+    #[test]
+    fn hard_constraints_reject_schedules() {
+        let schedule = monday_classes();
+        let friday = class("ST", "370", "001", "", FRIDAY, 900, 950);
+
+        let free_friday = ScheduleConstraints { free_days: hard(FRIDAY), ..Default::default() };
+        assert!(satisfies_hard(&schedule, &free_friday));
+        assert!(!satisfies_hard(schedule.iter().chain([&friday]), &free_friday));
+
+        // 9:00-11:20 is one stretch of 140 minutes, since the 15 minute gap is no break
+        let at_most_two_hours = ScheduleConstraints { max_consecutive_minutes: hard(120), ..Default::default() };
+        assert!(!satisfies_hard(&schedule, &at_most_two_hours));
+        assert!(satisfies_hard(&schedule[1..], &at_most_two_hours));
+
+        let lunch = ScheduleConstraints {
+            free_block: hard(FreeBlock { minutes: 120, days: MON_WED, window: (1100, 1400) }),
+            ..Default::default()
+        };
+        assert!(!satisfies_hard(&schedule, &lunch));
+        assert!(satisfies_hard(&schedule[..2], &lunch));
+
+        // Soft constraints never reject
+        let soft_only = ScheduleConstraints { free_days: soft(MON_WED), max_daily_minutes: soft(60), ..Default::default() };
+        assert!(satisfies_hard(&schedule, &soft_only));
+    }
+
+    #[test]
+    fn penalty_counts_violated_soft_constraints() {
+        let schedule = monday_classes();
+        assert_eq!(penalty(&schedule, &ScheduleConstraints::default()), 0);
+
+        let constraints = ScheduleConstraints {
+            // 175 minutes of class on Monday
+            max_daily_minutes: soft(120),
+            max_consecutive_minutes: soft(150),
+            free_days: soft([true, true, false, false, false]),
+            free_block: hard(FreeBlock { minutes: 180, days: MON_WED, window: (800, 1700) }),
+        };
+        assert_eq!(penalty(&schedule, &constraints), 2);
+        assert_eq!(penalty(&schedule[..1], &constraints), 1);
+    }
+}
@@ -4,109 +4,414 @@
 //! including authentication, course search, data extraction, and result processing.
 //! It manages Chrome browser automation, caching strategies, and schedule generation.
 
-use std::{collections::HashMap, sync::Arc, time::Duration};
+use std::{collections::{HashMap, HashSet}, sync::Arc, time::Duration};
 use thirtyfour::prelude::*;
 use anyhow::anyhow;
 use tokio::time::{sleep, Instant};
 
-use crate::{database_functions::{ClassRepository, ScheduleRepository}, services::start_chromedriver, tauri_backend::class_combinations::generate_combinations, AppState, Class, ClassParam, EventParam, ScrapeClassesParameters, TimeBlock};
+use tokio::sync::Mutex;
+
+use crate::{database_functions::{unit_of_work, ClassRepository, GenerationRepository, ScheduleRepository, SystemRepository}, services::start_chromedriver, tauri_backend::class_combinations::{extend_combinations, generate_combinations, order_by_diversity, project_combinations}, AppState, Class, ClassParam, ConnectInfo, DbPool, EventParam, PinnedSchedule, ScrapeClassesParameters, SectionAlternativesParameters, SelectionMode, StoredSchedule, TimeBlock};
+
+/** Number of leading schedules picked for diversity when SelectionMode::Diverse is requested */
+const DIVERSE_SAMPLE_SIZE: usize = 100;
 
 /**
  * Main orchestrator function for the complete scraping and schedule generation workflow
  * 
  * This function manages the entire process:
  * 1. Validates input parameters and checks for empty class lists
- * 2. Implements intelligent caching by checking database for existing course data
- * 3. Performs web scraping only for courses not found in cache
- * 4. Combines cached and freshly scraped data
+ * 2. Compares the request with the last generation to detect courses that were only added or removed
+ * 3. Implements intelligent caching by checking database for existing course data
+ * 4. Performs web scraping only for courses not found in cache
  * 5. Filters results based on user constraints (sections, instructors, time conflicts)
- * 6. Generates schedule combinations from filtered data
- * 7. Saves results to database for future use
+ * 6. Extends the stored schedules with the added courses, drops the removed courses from
+ *    them, or generates all combinations from the (mostly cached) section data when
+ *    anything else changed or the stored schedules are no longer the complete result of
+ *    the last generation
+ * 7. Reorders equally ranked results for diversity if requested by the selection mode
+ * 8. Saves results to database, keeps the pinned schedule if it is still valid and
+ *    records the run in the plan's generation history, all in one transaction
+ * 
+ * Dropping removed courses keeps only combinations that fit some section of each removed
+ * course, since others were never stored. When that leaves nothing, all combinations are
+ * generated instead; any other input change also regenerates the full set.
  * 
  * @param {ScrapeClassesParameters} parameters - Complete scraping configuration
  * @param {Vec<ClassParam>} parameters.classes - Course codes and sections to scrape
  * @param {Vec<bool>} parameters.params_checkbox - Search constraints (open sections, waitlist, etc.)
  * @param {Vec<EventParam>} parameters.events - User events to avoid time conflicts
+ * @param {SelectionMode} parameters.selection_mode - Ordering of results (backtracking order or diverse first page)
+ * @param {ScheduleConstraints} parameters.constraints - Daily structure constraints enforced during generation
  * @param {tauri::State<AppState>} state - Application state with database and Chrome connection info
- * @returns {Result<Vec<Vec<Class>>, anyhow::Error>} Generated schedule combinations or error
+ * @returns {Result<Vec<StoredSchedule>, anyhow::Error>} Generated schedule combinations with their IDs or error
  * @throws {anyhow::Error} If no classes provided, web scraping fails, or database operations fail
  */
-pub async fn setup_scrape(parameters: ScrapeClassesParameters, state: tauri::State<'_, AppState>) -> Result<Vec<Vec<Class>>, anyhow::Error> {
+pub async fn setup_scrape(parameters: ScrapeClassesParameters, state: tauri::State<'_, AppState>) -> Result<Vec<StoredSchedule>, anyhow::Error> {
     if parameters.classes.is_empty() {
         return Err(anyhow!("No classes set to scrape"));
     }
 
+    let started = Instant::now();
     let connect_info_mutex = Arc::clone(&state.connect_info);
     let db_pool = state.db_pool.clone();
 
-    let result: Result<Vec<Vec<Class>>, anyhow::Error> = async move {
-        let mut classes_to_scrape_params: Vec<ClassParam> = Vec::new();
-        let mut cached_results: HashMap<usize, Vec<Class>> = HashMap::new();
-        let mut scrape_indices: Vec<usize> = Vec::new();
-
-        for (index, class_param) in parameters.classes.iter().enumerate() {
-            let name = format!("{}{}", class_param.code, class_param.name);
-            let database_classes = ClassRepository::get_by_name(name.clone(), &db_pool).await.unwrap_or_else(|e| {
-                 eprintln!("Warning: Failed to query cache for {name}: {e}");
-                 Vec::new()
-            });
+    let previous_parameters = GenerationRepository::load_last_parameters(&db_pool).await.unwrap_or_else(|e| {
+        eprintln!("Warning: Failed to load previous generation parameters: {e}");
+        None
+    });
+    let stored_schedules = ScheduleRepository::get_all("schedules", &db_pool).await?;
+    // Pins on favorites are unaffected by regeneration; only generated schedules need re-pointing
+    let pinned_schedule = SystemRepository::get_display_schedule(&db_pool).await?
+        .filter(|pin| pin.table == "schedules")
+        .and_then(|pin| stored_schedules.iter().find(|schedule| schedule.id == pin.id))
+        .map(|schedule| schedule.classes.clone());
+    let previous_schedules: Vec<Vec<Class>> = stored_schedules
+        .into_iter()
+        .map(|schedule| schedule.classes)
+        .collect();
 
-            if !database_classes.is_empty() {
-                cached_results.insert(index, database_classes);
-            } else {
-                classes_to_scrape_params.push(class_param.clone());
-                scrape_indices.push(index);
-            }
+    let added_courses = previous_parameters
+        .as_ref()
+        .and_then(|previous| find_added_courses(previous, &parameters));
+    let removed_courses = previous_parameters
+        .as_ref()
+        .and_then(|previous| find_removed_courses(previous, &parameters));
+    // Schedules missing from the stored set would stay lost if only the rest were reused
+    let reusable = (added_courses.is_some() || removed_courses.is_some()) && {
+        let complete = GenerationRepository::stored_schedules_complete(&db_pool).await.unwrap_or_else(|e| {
+            eprintln!("Warning: Failed to verify the stored schedules: {e}");
+            false
+        });
+        if !complete {
+            println!("Stored schedules differ from the last generation's results. Generating all combinations.");
         }
+        complete
+    };
+    let projected = match removed_courses {
+        Some(removed_keys) if reusable => {
+            println!("Only courses were removed. Dropping them from {} stored schedules.", previous_schedules.len());
+            Some(project_combinations(&previous_schedules, &removed_keys, &parameters.constraints))
+                .filter(|projected| !projected.is_empty())
+        },
+        _ => None,
+    };
 
-        let mut scraped_results_map: HashMap<usize, Vec<Class>> = HashMap::new();
-        if !classes_to_scrape_params.is_empty() {
-            let connect_info = connect_info_mutex.lock().await.clone();
-            // Note: The check for Chrome updates is now handled at startup.
-            // It is not re-checked here to avoid unnecessary delays.
-            let driver = start_chromedriver(&connect_info).await?;
-            
-            let scrape_params_for_call = ScrapeClassesParameters {
-                 params_checkbox: parameters.params_checkbox, 
-                 classes: classes_to_scrape_params, 
-                 events: parameters.events.clone(),
+    let mut combinations_generated = match (projected, added_courses.filter(|_| reusable)) {
+        (Some(projected), _) => projected,
+        (None, Some(added_indices)) => {
+            println!("Only new courses were requested. Extending {} stored schedules.", previous_schedules.len());
+            let added_parameters = ScrapeClassesParameters {
+                params_checkbox: parameters.params_checkbox,
+                classes: added_indices.iter().map(|&index| parameters.classes[index].clone()).collect(),
+                events: parameters.events.clone(),
+                selection_mode: parameters.selection_mode,
+                constraints: parameters.constraints.clone(),
             };
+            let added_sections = collect_sections(&added_parameters, &connect_info_mutex, &db_pool).await?;
+            let filtered_classes = filter_classes(added_sections, &added_parameters)?;
+            let mut extended = extend_combinations(&previous_schedules, &filtered_classes, &parameters.constraints);
+            order_by_parameters(&mut extended, &parameters.classes);
+            extended
+        },
+        (None, None) => {
+            let combined_classes = collect_sections(&parameters, &connect_info_mutex, &db_pool).await?;
+            let filtered_classes = filter_classes(combined_classes, &parameters)?;
+            if filtered_classes.iter().all(|group| group.is_empty()) { return Ok(Vec::new()); }
+            generate_combinations(filtered_classes, &parameters.constraints).await?
+        },
+    };
 
-            let scraped_data = perform_scrape(&scrape_params_for_call, driver).await?;
-            
-            if let Err(e) = ClassRepository::save_sections_batch(&scraped_data, &db_pool).await {
-                 eprintln!("Warning: Failed to save scraped class sections: {e}");
-            }
+    if parameters.selection_mode == SelectionMode::Diverse {
+        combinations_generated = order_by_diversity(combinations_generated, DIVERSE_SAMPLE_SIZE, &parameters.constraints);
+    }
 
-            for (i, data) in scraped_data.into_iter().enumerate() {
-                let original_index = scrape_indices[i];
-                scraped_results_map.insert(original_index, data);
-            }
+    // Re-point the pin at the schedule that replaced the pinned one; saving the batch
+    // already clears it if the pinned schedule was not regenerated
+    let successor = pinned_schedule
+        .and_then(|pinned| find_successor(&pinned, &combinations_generated, &parameters.classes));
+
+    // Schedules, pin, last parameters and the history entry change together so the next
+    // generation never compares against inputs that don't match the stored results
+    let schedules = combinations_generated.clone();
+    let saved_parameters = parameters.clone();
+    let duration = started.elapsed();
+    let ids = unit_of_work(&db_pool, move |tx| {
+        let ids = ScheduleRepository::save_batch_in_transaction(tx, &schedules, &saved_parameters.constraints)?;
+        if let Some(successor) = successor {
+            let pin = PinnedSchedule { id: ids[successor].clone(), table: "schedules".to_string() };
+            SystemRepository::set_pin_in_transaction(tx, Some(&pin))?;
         }
+        GenerationRepository::save_last_parameters_in_transaction(tx, &saved_parameters)?;
+        GenerationRepository::record_run_in_transaction(tx, &saved_parameters, &ids, duration)?;
+        Ok(ids)
+    }).await?;
 
-        let mut combined_classes: Vec<Vec<Class>> = vec![Vec::new(); parameters.classes.len()];
-        for (index, cached_data) in cached_results {
-             if index < combined_classes.len() { combined_classes[index] = cached_data; }
+    Ok(ids
+        .into_iter()
+        .zip(combinations_generated)
+        .map(|(id, classes)| StoredSchedule { id, classes, details: None })
+        .collect())
+}
+
+/**
+ * Loads section data for every requested course, from cache where possible
+ * 
+ * This function:
+ * 1. Queries the classes cache for each requested course
+ * 2. Scrapes the courses missing from the cache in a single browser session
+ * 3. Stores freshly scraped sections in the cache
+ * 4. Returns section groups in the same order as the requested courses
+ * 
+ * @param {&ScrapeClassesParameters} parameters - Courses to load plus scraping filters
+ * @param {&Arc<Mutex<ConnectInfo>>} connect_info_mutex - Chrome connection info for scraping
+ * @param {&DbPool} db_pool - Database connection pool
+ * @returns {Result<Vec<Vec<Class>>, anyhow::Error>} Sections per requested course
+ * @throws {anyhow::Error} If web scraping fails
+ */
+pub(crate) async fn collect_sections(parameters: &ScrapeClassesParameters, connect_info_mutex: &Arc<Mutex<ConnectInfo>>, db_pool: &DbPool) -> Result<Vec<Vec<Class>>, anyhow::Error> {
+    let mut classes_to_scrape_params: Vec<ClassParam> = Vec::new();
+    let mut cached_results: HashMap<usize, Vec<Class>> = HashMap::new();
+    let mut scrape_indices: Vec<usize> = Vec::new();
+
+    for (index, class_param) in parameters.classes.iter().enumerate() {
+        let name = format!("{}{}", class_param.code, class_param.name);
+        let database_classes = ClassRepository::get_by_name(name.clone(), db_pool).await.unwrap_or_else(|e| {
+             eprintln!("Warning: Failed to query cache for {name}: {e}");
+             Vec::new()
+        });
+
+        if !database_classes.is_empty() {
+            cached_results.insert(index, database_classes);
+        } else {
+            classes_to_scrape_params.push(class_param.clone());
+            scrape_indices.push(index);
         }
-        for (index, scraped_data) in scraped_results_map {
-             if index < combined_classes.len() { combined_classes[index] = scraped_data; }
+    }
+
+    let mut scraped_results_map: HashMap<usize, Vec<Class>> = HashMap::new();
+    if !classes_to_scrape_params.is_empty() {
+        let scrape_params_for_call = ScrapeClassesParameters {
+             params_checkbox: parameters.params_checkbox, 
+             classes: classes_to_scrape_params, 
+             events: parameters.events.clone(),
+             selection_mode: parameters.selection_mode,
+             constraints: parameters.constraints.clone(),
+        };
+
+        let scraped_data = scrape_sections(&scrape_params_for_call, connect_info_mutex, db_pool).await?;
+
+        for (i, data) in scraped_data.into_iter().enumerate() {
+            let original_index = scrape_indices[i];
+            scraped_results_map.insert(original_index, data);
         }
-        
-        let filtered_classes = filter_classes(combined_classes, &parameters)?;
-        if filtered_classes.iter().all(|group| group.is_empty()) { return Ok(Vec::new()); }
+    }
+
+    let mut combined_classes: Vec<Vec<Class>> = vec![Vec::new(); parameters.classes.len()];
+    for (index, cached_data) in cached_results {
+         if index < combined_classes.len() { combined_classes[index] = cached_data; }
+    }
+    for (index, scraped_data) in scraped_results_map {
+         if index < combined_classes.len() { combined_classes[index] = scraped_data; }
+    }
+    Ok(combined_classes)
+}
+
+/**
+ * Searches myPack for the requested courses, bypassing the cache, and caches the results
+ * 
+ * The search applies the open-section and waitlist checkboxes, so the sections returned
+ * reflect current seat availability when those filters are set.
+ * 
+ * @param {&ScrapeClassesParameters} parameters - Courses to search plus search filters
+ * @param {&Arc<Mutex<ConnectInfo>>} connect_info_mutex - Chrome connection info for scraping
+ * @param {&DbPool} db_pool - Database connection pool
+ * @returns {Result<Vec<Vec<Class>>, anyhow::Error>} Sections per requested course
+ * @throws {anyhow::Error} If web scraping fails
+ */
+pub(crate) async fn scrape_sections(parameters: &ScrapeClassesParameters, connect_info_mutex: &Arc<Mutex<ConnectInfo>>, db_pool: &DbPool) -> Result<Vec<Vec<Class>>, anyhow::Error> {
+    let connect_info = connect_info_mutex.lock().await.clone();
+    // Note: The check for Chrome updates is now handled at startup.
+    // It is not re-checked here to avoid unnecessary delays.
+    let driver = start_chromedriver(&connect_info).await?;
+
+    let scraped_data = perform_scrape(parameters, driver).await?;
+
+    if let Err(e) = ClassRepository::save_sections_batch(&scraped_data, db_pool).await {
+         eprintln!("Warning: Failed to save scraped class sections: {e}");
+    }
+    Ok(scraped_data)
+}
+
+/**
+ * Builds the fresh search used to find alternatives to one course of a schedule
+ * 
+ * Seat status isn't cached, so when only open sections are wanted the course is searched
+ * again with the same checkboxes, events and constraints generation uses. Any section or
+ * instructor may replace the current one, so neither is restricted.
+ * 
+ * @param {&SectionAlternativesParameters} parameters - Schedule, course position, events, filters and constraints
+ * @returns {Option<ScrapeClassesParameters>} Search for the course, or None if the cached sections suffice
+ */
+pub(crate) fn alternatives_search(parameters: &SectionAlternativesParameters) -> Option<ScrapeClassesParameters> {
+    if !parameters.params_checkbox[0] {
+        return None;
+    }
+    let course = parameters.schedule.get(parameters.course_index)?;
+    Some(ScrapeClassesParameters {
+        params_checkbox: parameters.params_checkbox,
+        classes: vec![ClassParam {
+            id: parameters.course_index.to_string(),
+            code: course.code.clone(),
+            name: course.name.clone(),
+            section: String::new(),
+            instructor: String::new(),
+        }],
+        events: parameters.events.clone(),
+        selection_mode: SelectionMode::default(),
+        constraints: parameters.constraints.clone(),
+    })
+}
+
+/**
+ * Determines whether a request only adds courses to the last generation
+ * 
+ * The stored schedules can be extended only if the checkboxes, blocking events and
+ * constraints are unchanged, every previous course is still requested with the same
+ * section/instructor filter, and at least one new course was added.
+ * 
+ * @param {&ScrapeClassesParameters} previous - Parameters of the last generation
+ * @param {&ScrapeClassesParameters} current - Parameters of the new request
+ * @returns {Option<Vec<usize>>} Indices of the added courses in `current.classes`, or None if a full run is needed
+ */
+fn find_added_courses(previous: &ScrapeClassesParameters, current: &ScrapeClassesParameters) -> Option<Vec<usize>> {
+    let changes = compare_courses(previous, current)?;
+    (changes.removed.is_empty() && !changes.added.is_empty()).then_some(changes.added)
+}
+
+/**
+ * Determines whether a request only removes courses from the last generation
+ * 
+ * Same rules as `find_added_courses`, except that no course may be added and at least
+ * one previous course must be missing from the request.
+ * 
+ * @param {&ScrapeClassesParameters} previous - Parameters of the last generation
+ * @param {&ScrapeClassesParameters} current - Parameters of the new request
+ * @returns {Option<Vec<String>>} Course keys (code + name) of the removed courses, or None if a full run is needed
+ */
+fn find_removed_courses(previous: &ScrapeClassesParameters, current: &ScrapeClassesParameters) -> Option<Vec<String>> {
+    let changes = compare_courses(previous, current)?;
+    (changes.added.is_empty() && !changes.removed.is_empty()).then_some(changes.removed)
+}
+
+/** Courses a request adds to or removes from the last generation */
+struct CourseChanges {
+    /** Indices of the added courses in the new request */
+    added: Vec<usize>,
+    /** Course keys of previous courses missing from the new request */
+    removed: Vec<String>,
+}
+
+/**
+ * Matches the courses of a request against the last generation
+ * 
+ * @param {&ScrapeClassesParameters} previous - Parameters of the last generation
+ * @param {&ScrapeClassesParameters} current - Parameters of the new request
+ * @returns {Option<CourseChanges>} Added and removed courses, or None if filters, events,
+ *          constraints or a kept course's section/instructor changed
+ */
+fn compare_courses(previous: &ScrapeClassesParameters, current: &ScrapeClassesParameters) -> Option<CourseChanges> {
+    let same_filters = previous.params_checkbox == current.params_checkbox
+        && serde_json::to_value(&previous.events).ok() == serde_json::to_value(&current.events).ok()
+        && serde_json::to_value(&previous.constraints).ok() == serde_json::to_value(&current.constraints).ok();
+    if !same_filters {
+        return None;
+    }
 
-        let combinations_generated = generate_combinations(filtered_classes).await?;
-        let mut ids = Vec::with_capacity(combinations_generated.len());
-        for combination in &combinations_generated {
-            ids.push(serde_json::to_string(combination)?);
+    let mut previous_courses: HashMap<String, &ClassParam> = HashMap::new();
+    for class_param in &previous.classes {
+        if previous_courses.insert(course_key(class_param), class_param).is_some() {
+            return None; // Duplicate course requests can't be matched reliably
         }
+    }
 
-        ScheduleRepository::save_batch(ids, &combinations_generated, &db_pool).await?;
-        Ok(combinations_generated)
+    let mut added = Vec::new();
+    let mut matched: HashSet<String> = HashSet::new();
+    for (index, class_param) in current.classes.iter().enumerate() {
+        let key = course_key(class_param);
+        match previous_courses.get(&key) {
+            Some(previous_param) => {
+                if previous_param.section != class_param.section || previous_param.instructor != class_param.instructor {
+                    return None;
+                }
+                if !matched.insert(key) {
+                    return None; // Requested twice now, but only once before
+                }
+            },
+            None => added.push(index),
+        }
     }
-    .await;
 
-    result
+    let removed: Vec<String> = previous.classes
+        .iter()
+        .map(course_key)
+        .filter(|key| !matched.contains(key))
+        .collect();
+    Some(CourseChanges { added, removed })
+}
+
+/**
+ * Sorts the classes of each schedule into the order of the requested courses
+ * Extended schedules have added courses appended, while full runs follow parameter order
+ * 
+ * @param {&mut [Vec<Class>]} schedules - Schedules to reorder in place
+ * @param {&[ClassParam]} classes - Requested courses in display order
+ */
+fn order_by_parameters(schedules: &mut [Vec<Class>], classes: &[ClassParam]) {
+    let positions: HashMap<String, usize> = classes
+        .iter()
+        .enumerate()
+        .map(|(index, class_param)| (course_key(class_param), index))
+        .collect();
+    for schedule in schedules.iter_mut() {
+        schedule.sort_by_key(|class| positions.get(&format!("{}{}", class.code, class.name)).copied().unwrap_or(usize::MAX));
+    }
+}
+
+/**
+ * Finds the new schedule that keeps every still-requested section of a pinned schedule
+ * 
+ * @param {&[Class]} pinned - Previously pinned schedule
+ * @param {&[Vec<Class>]} schedules - Newly generated schedules
+ * @param {&[ClassParam]} classes - Courses requested in the new generation
+ * @returns {Option<usize>} Index of the first matching schedule, or None if the pin is no longer valid
+ */
+fn find_successor(pinned: &[Class], schedules: &[Vec<Class>], classes: &[ClassParam]) -> Option<usize> {
+    let requested: Vec<String> = classes.iter().map(course_key).collect();
+    let kept: Vec<&Class> = pinned
+        .iter()
+        .filter(|class| requested.contains(&format!("{}{}", class.code, class.name)))
+        .collect();
+
+    schedules.iter().position(|schedule| {
+        kept.iter().all(|pinned_class| {
+            schedule.iter().any(|class| {
+                class.code == pinned_class.code
+                    && class.name == pinned_class.name
+                    && class.classes.iter().map(|block| &block.section).eq(pinned_class.classes.iter().map(|block| &block.section))
+            })
+        })
+    })
+}
+
+/**
+ * Builds the cache key of a requested course (e.g., "CSC116")
+ * 
+ * @param {&ClassParam} class_param - Requested course
+ * @returns {String} Course code followed by catalog number
+ */
+fn course_key(class_param: &ClassParam) -> String {
+    format!("{}{}", class_param.code, class_param.name)
 }
 
 /**
@@ -262,13 +567,30 @@ async fn perform_scrape(parameters: &ScrapeClassesParameters, driver: WebDriver)
     Ok(results)
 }
 
+/**
+ * Whether a section's instructor matches the requested one
+ * 
+ * A request written as "Last,First" must match exactly; a bare last name (as left by a
+ * course list import when the course wasn't cached yet) matches any first name.
+ * 
+ * @param {&str} instructor - Instructor of a time block, as scraped ("Last,First")
+ * @param {&str} wanted - Requested instructor
+ * @returns {bool} True if the names match, ignoring case
+ */
+fn instructor_matches(instructor: &str, wanted: &str) -> bool {
+    if wanted.contains(',') {
+        return instructor.eq_ignore_ascii_case(wanted);
+    }
+    instructor.split(',').next().is_some_and(|last_name| last_name.trim().eq_ignore_ascii_case(wanted.trim()))
+}
+
 /**
  * Filters scraped course data based on user preferences and constraints
  * 
  * This function applies multiple filtering criteria to course sections:
  * 1. Validates that sections have valid time blocks
  * 2. Matches specific section numbers if requested by user
- * 3. Matches instructor names (case-insensitive) if specified
+ * 3. Matches instructor names (case-insensitive, "Last,First" or a bare last name) if specified
  * 4. Checks for time conflicts with user-defined events
  * 5. Maintains course group structure even if all sections are filtered out
  * 
@@ -321,7 +643,7 @@ pub fn filter_classes(input_classes: Vec<Vec<Class>>, parameters: &ScrapeClasses
 
                 // 3. Check Instructor Match
                 let instructor_match = desired_class.instructor.is_empty() ||
-                    section.classes.iter().any(|block| block.instructor.eq_ignore_ascii_case(&desired_class.instructor));
+                    section.classes.iter().any(|block| instructor_matches(&block.instructor, &desired_class.instructor));
                 if !instructor_match { return false; }
 
                 // 4. Check Time Validity: All blocks must be valid
@@ -455,7 +777,7 @@ async fn scrape_search_results(driver: &WebElement, predetermined_info: &[String
  * @param {&[((i32, i32), bool); 5]} days - Course time blocks for each weekday [((start, end), active)]
  * @returns {bool} True if no conflicts found, false if any time overlap detected
  */
-fn validate_time_ok(events: &Vec<EventParam>, days: &[((i32, i32), bool); 5]) -> bool {
+pub(crate) fn validate_time_ok(events: &Vec<EventParam>, days: &[((i32, i32), bool); 5]) -> bool {
     
     // For each day in the week
     for (day_index, day) in days.iter().enumerate() {
@@ -623,4 +945,92 @@ fn extract_text_after(text: &str, prefix: &str, suffix: &str) -> String {
     } else {
         String::new()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{class, MON_WED, TUE_THU};
+
+    fn alternatives_parameters(params_checkbox: [bool; 3]) -> SectionAlternativesParameters {
+        SectionAlternativesParameters {
+            schedule: vec![
+                class("CSC", "116", "001", "Smith,John", MON_WED, 900, 1015),
+                class("MA", "241", "002", "Lee,Ann", TUE_THU, 900, 1015),
+            ],
+            course_index: 1,
+            events: vec![EventParam { time: (1200, 1300), days: MON_WED }],
+            params_checkbox,
+            constraints: Default::default(),
+        }
+    }
+
+    #[test]
+    fn open_section_alternatives_search_with_the_same_filters() {
+        assert!(alternatives_search(&alternatives_parameters([false, true, false])).is_none());
+
+        let search = alternatives_search(&alternatives_parameters([true, true, false])).unwrap();
+        assert_eq!(search.params_checkbox, [true, true, false]);
+        assert_eq!(search.events.len(), 1);
+        assert_eq!(search.events[0].time, (1200, 1300));
+        let [course] = search.classes.as_slice() else { panic!("expected one course") };
+        assert_eq!((course.code.as_str(), course.name.as_str()), ("MA", "241"));
+        // Any section or instructor may replace the current one
+        assert!(course.section.is_empty() && course.instructor.is_empty());
+    }
+
+    fn course(code: &str, name: &str, section: &str) -> ClassParam {
+        ClassParam { id: format!("{code}{name}"), code: code.to_string(), name: name.to_string(), section: section.to_string(), instructor: String::new() }
+    }
+
+    fn generation(classes: Vec<ClassParam>) -> ScrapeClassesParameters {
+        ScrapeClassesParameters {
+            params_checkbox: [false, false, false],
+            classes,
+            events: Vec::new(),
+            selection_mode: SelectionMode::default(),
+            constraints: Default::default(),
+        }
+    }
+
+    #[test]
+    fn only_added_courses_allow_extension() {
+        let previous = generation(vec![course("CSC", "116", ""), course("MA", "241", "002")]);
+
+        let added = generation(vec![course("PY", "205", ""), course("CSC", "116", ""), course("MA", "241", "002")]);
+        assert_eq!(find_added_courses(&previous, &added), Some(vec![0]));
+        assert_eq!(find_removed_courses(&previous, &added), None);
+
+        // Nothing added
+        assert_eq!(find_added_courses(&previous, &previous), None);
+        // A kept course now wants another section
+        let changed = generation(vec![course("CSC", "116", ""), course("MA", "241", "003"), course("PY", "205", "")]);
+        assert_eq!(find_added_courses(&previous, &changed), None);
+        // A course was swapped for another
+        let swapped = generation(vec![course("CSC", "116", ""), course("PY", "205", "")]);
+        assert_eq!(find_added_courses(&previous, &swapped), None);
+        // A kept course is requested twice
+        let repeated = generation(vec![course("CSC", "116", ""), course("CSC", "116", ""), course("MA", "241", "002")]);
+        assert_eq!(find_added_courses(&previous, &repeated), None);
+        // Other filters changed
+        let mut open_only = added.clone();
+        open_only.params_checkbox[0] = true;
+        assert_eq!(find_added_courses(&previous, &open_only), None);
+        let mut blocked = added;
+        blocked.events.push(EventParam { time: (1200, 1300), days: MON_WED });
+        assert_eq!(find_added_courses(&previous, &blocked), None);
+    }
+
+    #[test]
+    fn only_removed_courses_allow_projection() {
+        let previous = generation(vec![course("CSC", "116", ""), course("MA", "241", "002"), course("PY", "205", "")]);
+
+        let removed = generation(vec![course("PY", "205", ""), course("CSC", "116", "")]);
+        assert_eq!(find_removed_courses(&previous, &removed), Some(vec!["MA241".to_string()]));
+        assert_eq!(find_added_courses(&previous, &removed), None);
+
+        assert_eq!(find_removed_courses(&previous, &previous), None);
+        let swapped = generation(vec![course("CSC", "116", ""), course("ST", "370", "")]);
+        assert_eq!(find_removed_courses(&previous, &swapped), None);
+    }
+}
@@ -0,0 +1,97 @@
+//! Fixtures shared by the unit tests of every module
+//!
+//! Databases are single-connection in-memory pools, so every repository call in a test
+//! sees the same data. Classes are built directly rather than scraped; sections that
+//! don't meet on a day use the scraper's ((-1, -1), false) placeholder.
+
+use std::{fs, path::PathBuf};
+
+use r2d2_sqlite::SqliteConnectionManager;
+use uuid::Uuid;
+
+use crate::{migrations::run_migrations, Class, DbPool, TimeBlock};
+
+/** Meeting days (Mon-Fri) used throughout the tests */
+pub(crate) const MON_WED: [bool; 5] = [true, false, true, false, false];
+pub(crate) const TUE_THU: [bool; 5] = [false, true, false, true, false];
+pub(crate) const FRIDAY: [bool; 5] = [false, false, false, false, true];
+
+/**
+ * Single-connection pool over an empty in-memory database
+ *
+ * @returns {DbPool} Pool without any schema, for replaying migrations
+ */
+pub(crate) fn empty_pool() -> DbPool {
+    r2d2::Pool::builder().max_size(1).build(SqliteConnectionManager::memory()).unwrap()
+}
+
+/**
+ * Single-connection pool over a fully migrated in-memory database
+ *
+ * @returns {DbPool} Pool with the latest schema and the default plan
+ */
+pub(crate) fn memory_pool() -> DbPool {
+    let pool = empty_pool();
+    run_migrations(&pool).unwrap();
+    pool
+}
+
+/**
+ * Creates an empty directory under the system temp directory, unique to one test run
+ *
+ * @param {&str} name - Readable prefix, usually the test's name
+ * @returns {PathBuf} New directory; the test removes it when done
+ */
+pub(crate) fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("koka-{name}-{}", Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/**
+ * Builds a class with one section meeting on the given weekdays
+ *
+ * @param {&str} code - Subject, e.g. "CSC"
+ * @param {&str} name - Course number, e.g. "116"
+ * @param {&str} section - Section label
+ * @param {&str} instructor - Instructor as scraped ("Last,First")
+ * @param {[bool; 5]} days - Weekdays (Mon-Fri) the section meets
+ * @param {i32} start - Start time as HHMM
+ * @param {i32} end - End time as HHMM
+ * @returns {Class} Class with a single time block
+ */
+pub(crate) fn class(code: &str, name: &str, section: &str, instructor: &str, days: [bool; 5], start: i32, end: i32) -> Class {
+    Class {
+        code: code.to_string(),
+        name: name.to_string(),
+        description: String::new(),
+        classes: vec![TimeBlock {
+            section: section.to_string(),
+            location: String::new(),
+            days: days.map(|meets| if meets { ((start, end), true) } else { ((-1, -1), false) }),
+            instructor: instructor.to_string(),
+        }],
+    }
+}
+
+/**
+ * Builds a class whose blocks are the given sections, without meeting times
+ *
+ * @param {&str} code - Subject
+ * @param {&str} name - Course number
+ * @param {&[&str]} sections - Section labels, one time block each
+ * @returns {Class} Class that never meets
+ */
+pub(crate) fn unscheduled_class(code: &str, name: &str, sections: &[&str]) -> Class {
+    Class {
+        code: code.to_string(),
+        name: name.to_string(),
+        description: String::new(),
+        classes: sections.iter().map(|section| TimeBlock {
+            section: section.to_string(),
+            location: String::new(),
+            days: [((-1, -1), false); 5],
+            instructor: String::new(),
+        }).collect(),
+    }
+}
@@ -1,5 +1,6 @@
 import { create } from 'zustand';
-import { systemAPI, eventsAPI, schedulesAPI, favoritesAPI, classParametersAPI } from './api';
+import { open, save } from '@tauri-apps/api/dialog';
+import { systemAPI, eventsAPI, schedulesAPI, favoritesAPI, classParametersAPI, plansAPI, historyAPI, dataAPI, undoAPI, calendarAPI, shareAPI, imageAPI } from './api';
 
 /**
  * Helper function to stringify schedules for use as unique keys
@@ -26,6 +27,18 @@ const stringifySchedule = (schedule) => {
     }
 };
 
+/**
+ * Visible calendar hours used until the backend reports the window it positioned events against
+ * @type {{startHour: number, endHour: number}}
+ */
+const DEFAULT_VIEW_WINDOW = { startHour: 8, endHour: 20 };
+
+/**
+ * Number of schedules fetched per page in the schedule and favorites lists
+ * @type {number}
+ */
+const SCHEDULE_PAGE_SIZE = 50;
+
 /**
  * Helper function to add a new event to the processed events state object
  * Categorizes events by day and whether they have specific times
@@ -78,22 +91,53 @@ const useStore = create((set, get) => ({
     // --- State ---
     
     isExpanded: false,
-    userEvents: { eventsByDay: {}, noTimeEventsByDay: {} },
-    // FIX: Initialize schedules as an empty array for more consistent logic.
+    userEvents: { eventsByDay: {}, noTimeEventsByDay: {}, window: DEFAULT_VIEW_WINDOW },
+    // Loaded pages of the schedule and favorites lists; the backend holds the rest
     schedules: [], 
     favoritedSchedules: [],
+    // Total matches of the list query per table, for "Load more"
+    scheduleTotals: { schedules: 0, favorites: 0 },
+    // Sort and filter applied to both lists (see schedulesAPI.query)
+    scheduleQuery: { sort: null, descending: false, filter: {} },
+    // Backend IDs of loaded schedules that are favorites
+    favoriteIds: new Set(),
     selectedScheduleId: null,
+    // Classes of the pinned schedule, which need not be on a loaded page
+    selectedSchedule: null,
     currentHoveredSchedule: null,
+    sharedSchedule: null,
+    // Sections that can replace one course of the selected schedule: { courseIndex, alternatives }
+    // (alternatives is null while loading), or null when no course is being swapped
+    sectionAlternatives: null,
     detailsEvent: null,
     schedulerLoading: true,
     schedulerError: null,
     scrapeState: { isScraping: false, status: "" },
     paramCheckboxes: { box1: false, box2: false },
+    // "Diverse" spreads the first results across different weekly shapes
+    selectionMode: 'Sequential',
+    // Daily structure constraints, each { value, hard } or null when unused (see ScheduleConstraints)
+    constraints: { max_daily_minutes: null, max_consecutive_minutes: null, free_block: null, free_days: null },
     classes: [],
     activeTab: 'schedules',
     renderFavorites: false,
+    // Whether the schedule list collapses schedules with the same weekly footprint
+    groupSchedules: false,
+    // Loaded footprint groups ({ id, schedule, alternatives, count }) while grouping is on
+    scheduleGroups: [],
     scheduleDisplayNumbers: new Map(),
     nextScheduleNumber: 1,
+    // Maps stringified schedules to their backend content-hash IDs
+    scheduleIds: new Map(),
+    // Maps stringified favorites to their { title, notes, color, tags }
+    favoriteDetails: new Map(),
+    // Maps stringified schedules to SVG thumbnails rendered by the backend
+    schedulePreviews: new Map(),
+    plans: [],
+    generationRuns: [],
+    historyLimit: null,
+    undoState: { undo: null, redo: null },
+    termCalendar: null,
 
     // --- Actions ---
 
@@ -150,6 +194,67 @@ const useStore = create((set, get) => ({
         get()._persistScheduleNumbers();
     },
 
+    /**
+     * Records backend IDs of stored schedules and returns their class lists
+     * @param {Array<{id: string, classes: Array}>} storedSchedules - Schedules returned by the backend
+     * @returns {Array<Array>} - Schedules as class lists, in the same order
+     */
+    _registerScheduleIds: (storedSchedules) => {
+        const scheduleIds = new Map(get().scheduleIds);
+        const schedules = storedSchedules.map(({ id, classes }) => {
+            scheduleIds.set(stringifySchedule(classes), id);
+            return classes;
+        });
+        set({ scheduleIds });
+        return schedules;
+    },
+
+    /**
+     * Loads the schedule a backend pin refers to and selects it
+     * @param {{id: string, table: string} | null} pin - Pin returned by the backend
+     */
+    _applyPin: async (pin) => {
+        if (!pin) {
+            set({ selectedScheduleId: null, selectedSchedule: null });
+            return;
+        }
+        const page = await schedulesAPI.query({ table: pin.table, limit: 1, filter: { ids: [pin.id] } });
+        const [schedule] = get()._registerScheduleIds(page.schedules || []);
+        set({
+            selectedScheduleId: schedule ? stringifySchedule(schedule) : null,
+            selectedSchedule: schedule || null,
+        });
+    },
+
+    /**
+     * Fetches one page of a schedule list with the current sort and filter
+     * @param {string} table - "schedules" or "favorites"
+     * @param {number} offset - Number of matches to skip
+     * @returns {Promise<{schedules: Array<{id: string, classes: Array, details: Object|null}>, total: number}>} - Page from the backend
+     */
+    _querySchedulePage: (table, offset) => {
+        const { sort, descending, filter } = get().scheduleQuery;
+        return schedulesAPI.query({ table, offset, limit: SCHEDULE_PAGE_SIZE, sort, descending, filter });
+    },
+
+    /**
+     * Adds the loaded schedules that are favorites to favoriteIds
+     * Favorites pages are favorites by definition; schedules pages are looked up by ID
+     * @param {Array<{id: string}>} storedSchedules - Schedules just loaded into the schedules list
+     * @param {Array<{id: string}>} storedFavorites - Schedules just loaded into the favorites list
+     * @returns {Promise<Set<string>>} - Updated set of favorite IDs
+     */
+    _lookupFavoriteIds: async (storedSchedules, storedFavorites) => {
+        const favoriteIds = new Set(get().favoriteIds);
+        storedFavorites.forEach(({ id }) => favoriteIds.add(id));
+        const unknownIds = storedSchedules.map(({ id }) => id).filter(id => !favoriteIds.has(id));
+        if (unknownIds.length > 0) {
+            const matches = await schedulesAPI.query({ table: 'favorites', limit: unknownIds.length, filter: { ids: unknownIds } });
+            (matches.schedules || []).forEach(({ id }) => favoriteIds.add(id));
+        }
+        return favoriteIds;
+    },
+
     getScheduleDisplayNumber: (scheduleString) => {
         const state = get();
         return state.scheduleDisplayNumbers.get(scheduleString) || "?";
@@ -161,7 +266,7 @@ const useStore = create((set, get) => ({
         try {
             const loadedEventsResult = await eventsAPI.getAll();
             set({
-                userEvents: loadedEventsResult || { eventsByDay: {}, noTimeEventsByDay: {} },
+                userEvents: loadedEventsResult || { eventsByDay: {}, noTimeEventsByDay: {}, window: DEFAULT_VIEW_WINDOW },
                 schedulerError: null,
             });
         } catch (err) {
@@ -170,21 +275,29 @@ const useStore = create((set, get) => ({
         }
     },
 
+    /**
+     * Processes the user's events together with a previewed schedule's meetings
+     * The backend fits one window over both, so the grid can draw exactly the hours returned
+     * @param {Array} previewEvents - Raw meetings ({ id, title, startTime, endTime, day, professor, description })
+     * @returns {Promise<Object|null>} Processed events with their window, or null if processing failed
+     */
+    processPreviewEvents: async (previewEvents) => {
+        try {
+            return await eventsAPI.getAll(null, previewEvents);
+        } catch (err) {
+            console.error('Error processing preview events:', err);
+            return null;
+        }
+    },
+
     _updateSchedulerData: async () => {
         try {
-            const [loadedEventsResult, loadedSchedules, loadedFavorites] = await Promise.all([
+            const [loadedEventsResult] = await Promise.all([
                 eventsAPI.getAll(),
-                schedulesAPI.getAll(),
-                favoritesAPI.getAll()
+                get()._reloadScheduleLists(),
             ]);
-
-            const finalSchedules = Array.isArray(loadedSchedules) ? loadedSchedules : [];
-            get()._assignScheduleDisplayNumbers(finalSchedules);
-
             set({
-                userEvents: loadedEventsResult || { eventsByDay: {}, noTimeEventsByDay: {} },
-                schedules: finalSchedules,
-                favoritedSchedules: Array.isArray(loadedFavorites) ? loadedFavorites : [],
+                userEvents: loadedEventsResult || { eventsByDay: {}, noTimeEventsByDay: {}, window: DEFAULT_VIEW_WINDOW },
                 schedulerError: null,
             });
         } catch (err) {
@@ -193,6 +306,87 @@ const useStore = create((set, get) => ({
         }
     },
 
+    /**
+     * Replaces both schedule lists with their first page under the current sort and filter
+     * Favorite status is looked up afresh, since undo or a plan switch may have changed it
+     */
+    _reloadScheduleLists: async () => {
+        set({ favoriteIds: new Set() });
+        // Sequential, since both lists add to favoriteIds
+        await get()._loadScheduleList('favorites', false);
+        await get()._loadScheduleList('schedules', false);
+        await get()._loadScheduleGroups();
+    },
+
+    /**
+     * Loads the generated schedules grouped by weekly footprint, when grouping is on
+     * Group representatives are registered like list entries so they can be pinned
+     */
+    _loadScheduleGroups: async () => {
+        if (!get().groupSchedules) return;
+        const groups = (await schedulesAPI.getGrouped()) || [];
+        const representatives = get()._registerScheduleIds(groups.map(({ id, schedule }) => ({ id, classes: schedule })));
+        get()._assignScheduleDisplayNumbers(representatives);
+        set({ scheduleGroups: groups });
+    },
+
+    /**
+     * Loads a page of one schedule list under the current sort and filter
+     * @param {string} table - "schedules" or "favorites"
+     * @param {boolean} append - Append the next page instead of replacing the list with the first
+     */
+    _loadScheduleList: async (table, append) => {
+        const isFavorites = table === 'favorites';
+        const listKey = isFavorites ? 'favoritedSchedules' : 'schedules';
+        const page = await get()._querySchedulePage(table, append ? get()[listKey].length : 0);
+        const loaded = page.schedules || [];
+        const favoriteIds = await get()._lookupFavoriteIds(isFavorites ? [] : loaded, isFavorites ? loaded : []);
+        const list = get()._registerScheduleIds(loaded);
+        if (!isFavorites) get()._assignScheduleDisplayNumbers(list);
+
+        set(state => {
+            const changes = {
+                [listKey]: append ? [...state[listKey], ...list] : list,
+                scheduleTotals: { ...state.scheduleTotals, [table]: page.total || 0 },
+                favoriteIds,
+            };
+            if (isFavorites) {
+                const favoriteDetails = append ? new Map(state.favoriteDetails) : new Map();
+                loaded.forEach(({ classes, details }) => {
+                    if (details) favoriteDetails.set(stringifySchedule(classes), details);
+                });
+                changes.favoriteDetails = favoriteDetails;
+            }
+            return changes;
+        });
+    },
+
+    /**
+     * Appends the next page of the list currently shown (schedules or favorites)
+     */
+    loadMoreSchedules: async () => {
+        try {
+            await get()._loadScheduleList(get().renderFavorites ? 'favorites' : 'schedules', true);
+        } catch (err) {
+            console.error("Failed to load more schedules:", err);
+            set({ schedulerError: 'Failed to load schedules.' });
+        }
+    },
+
+    /**
+     * Changes the sort or filter of the schedule lists and reloads their first pages
+     * @param {{sort?: string|null, descending?: boolean, filter?: Object}} changes - Query fields to replace
+     */
+    setScheduleQuery: async (changes) => {
+        set(state => ({ scheduleQuery: { ...state.scheduleQuery, ...changes }, schedulerError: null }));
+        try {
+            await get()._reloadScheduleLists();
+        } catch (err) {
+            console.error("Failed to query schedules:", err);
+            set({ schedulerError: 'Failed to load schedules.' });
+        }
+    },
+
     loadSchedulerPage: async () => {
         set({ schedulerLoading: true, schedulerError: null });
         
@@ -202,18 +396,15 @@ const useStore = create((set, get) => ({
         try {
             await get()._updateSchedulerData();
 
-            const [loadedSelectedScheduleIndex, loadedClasses] = await Promise.all([
+            const [loadedPin, loadedClasses] = await Promise.all([
                 systemAPI.getDisplaySchedule(),
-                classParametersAPI.getAll()
+                classParametersAPI.getAll(),
+                get().loadPlans(),
+                get().refreshUndoState()
             ]);
-            
-            const { schedules } = get();
-            const selectedId = (loadedSelectedScheduleIndex !== null && schedules[loadedSelectedScheduleIndex])
-                ? stringifySchedule(schedules[loadedSelectedScheduleIndex])
-                : null;
+            await get()._applyPin(loadedPin);
 
             set({
-                selectedScheduleId: selectedId,
                 classes: loadedClasses || [],
                 schedulerLoading: false,
             });
@@ -221,9 +412,11 @@ const useStore = create((set, get) => ({
             console.error('Error loading scheduler page data:', err);
             set({
                 schedulerError: 'Failed to load schedule data. Please try again later.',
-                userEvents: { eventsByDay: {}, noTimeEventsByDay: {} },
+                userEvents: { eventsByDay: {}, noTimeEventsByDay: {}, window: DEFAULT_VIEW_WINDOW },
                 schedules: [],
                 favoritedSchedules: [],
+                scheduleTotals: { schedules: 0, favorites: 0 },
+                selectedSchedule: null,
                 classes: [],
                 schedulerLoading: false,
             });
@@ -248,6 +441,7 @@ const useStore = create((set, get) => ({
             
             await eventsAPI.create(eventPayload);
             await get()._updateEventsData();
+            get().refreshUndoState();
         } catch (err) {
             console.error('Error saving event:', err);
             set({ schedulerError: 'Failed to save event. Please try again.' });
@@ -278,6 +472,9 @@ const useStore = create((set, get) => ({
             delete eventPayload.startTime;
             delete eventPayload.endTime;
             await eventsAPI.update(eventPayload);
+            // Re-fetch so positions (and the visible window) reflect the new times
+            await get()._updateEventsData();
+            get().refreshUndoState();
         } catch (err) {
             console.error('Error updating event:', err);
             set({ 
@@ -295,6 +492,7 @@ const useStore = create((set, get) => ({
         try {
             await eventsAPI.delete(eventId);
             await get()._updateEventsData();
+            get().refreshUndoState();
         } catch (err) {
             console.error('Error deleting event:', err);
             set({ schedulerError: 'Failed to delete event. Please try again.'});
@@ -302,6 +500,37 @@ const useStore = create((set, get) => ({
         }
     },
 
+    /**
+     * Converts the user's events to the blocking events sent to the backend
+     * @returns {Array<{time: [number, number], days: boolean[]}>} - One entry per distinct event
+     */
+    _blockingEvents: () => {
+        const { userEvents } = get();
+        const rawUserEvents = [];
+        const seenIds = new Set();
+        [...Object.values(userEvents.eventsByDay), ...Object.values(userEvents.noTimeEventsByDay)]
+            .flat()
+            .forEach(event => {
+                if (!seenIds.has(event.id)) {
+                    rawUserEvents.push(event);
+                    seenIds.add(event.id);
+                }
+            });
+
+        return rawUserEvents.map(event => {
+            const timePair = [event.startTime ?? 0, event.endTime ?? 0];
+            const daysArray = [];
+            for (let dayBit = 1; dayBit <= 5; dayBit++) {
+                if ((event.day & (1 << dayBit)) !== 0) {
+                    daysArray.push(true);
+                } else {
+                    daysArray.push(false);
+                }
+            }
+            return { time: timePair, days: daysArray };
+        });
+    },
+
     generateSchedules: async () => {
         set({
             scrapeState: { isScraping: true, status: "Preparing data..." },
@@ -310,36 +539,14 @@ const useStore = create((set, get) => ({
             scheduleDisplayNumbers: new Map(),
             nextScheduleNumber: 1,
         });
-        const { paramCheckboxes, classes, userEvents } = get();
-        try {
-            const rawUserEvents = [];
-            const seenIds = new Set();
-            [...Object.values(userEvents.eventsByDay), ...Object.values(userEvents.noTimeEventsByDay)]
-                .flat()
-                .forEach(event => {
-                    if (!seenIds.has(event.id)) {
-                        rawUserEvents.push(event);
-                        seenIds.add(event.id);
-                    }
-                });
-
-            const formattedUserEventsForScrape = rawUserEvents.map(event => {
-                const timePair = [event.startTime ?? 0, event.endTime ?? 0];
-                const daysArray = [];
-                for (let dayBit = 1; dayBit <= 5; dayBit++) {
-                    if ((event.day & (1 << dayBit)) !== 0) {
-                        daysArray.push(true);
-                    } else {
-                        daysArray.push(false);
-                    }
-                }
-                return { time: timePair, days: daysArray };
-            });
-
+        const { paramCheckboxes, selectionMode, constraints, classes } = get();
+        try {
             const payload = {
                 classes: classes,
-                events: formattedUserEventsForScrape,
+                events: get()._blockingEvents(),
                 params_checkbox: [paramCheckboxes.box1, paramCheckboxes.box2, false],
+                selection_mode: selectionMode,
+                constraints,
             };
             
             const result = await schedulesAPI.generate(payload);
@@ -351,15 +558,13 @@ const useStore = create((set, get) => ({
                     ? `${result.length} schedule(s) generated successfully!` 
                     : "No matching schedules found. Try adjusting your courses or parameters.";
 
-                // Assign display numbers to new schedules, starting from 1
-                get()._assignScheduleDisplayNumbers(result || []);
+                // Assign display numbers to new schedules in generation order, starting from 1
+                get()._assignScheduleDisplayNumbers((result || []).map(({ classes }) => classes));
+                await get()._reloadScheduleLists();
 
-                set({
-                    scrapeState: { isScraping: false, status: successMessage },
-                    schedules: result || [],
-                    selectedScheduleId: null,
-                });
-                await systemAPI.setDisplaySchedule(null);
+                // The backend keeps the pin when the pinned schedule survived regeneration
+                await get()._applyPin(await systemAPI.getDisplaySchedule());
+                set({ scrapeState: { isScraping: false, status: successMessage } });
             }
         } catch (error) {
             console.error("Error during schedule generation:", error);
@@ -371,17 +576,29 @@ const useStore = create((set, get) => ({
     setSelectedSchedule: async (scheduleData) => {
         set({ schedulerError: null });
         const scheduleId = stringifySchedule(scheduleData);
-        const { selectedScheduleId, schedules } = get();
+        const { selectedScheduleId, schedules, scheduleGroups, scheduleIds } = get();
         
         const newSelectedId = selectedScheduleId === scheduleId ? null : scheduleId;
         
         try {
-            const newSelectedIndex = newSelectedId 
-                ? schedules.findIndex(s => stringifySchedule(s) === newSelectedId) 
+            const pin = newSelectedId && scheduleIds.has(newSelectedId)
+                ? {
+                    id: scheduleIds.get(newSelectedId),
+                    table: schedules.some(s => stringifySchedule(s) === newSelectedId)
+                        || scheduleGroups.some(group => stringifySchedule(group.schedule) === newSelectedId)
+                        ? 'schedules' : 'favorites',
+                }
                 : null;
             
-            await systemAPI.setDisplaySchedule(newSelectedIndex === -1 ? null : newSelectedIndex);
-            set({ selectedScheduleId: newSelectedId, currentHoveredSchedule: null });
+            await systemAPI.setDisplaySchedule(pin);
+            set({
+                selectedScheduleId: newSelectedId,
+                selectedSchedule: newSelectedId ? scheduleData : null,
+                currentHoveredSchedule: null,
+                sharedSchedule: null,
+                sectionAlternatives: null,
+            });
+            get().refreshUndoState();
         } catch (error) {
             console.error("Failed to set display schedule:", error);
             set({ schedulerError: "Failed to pin schedule." });
@@ -393,26 +610,34 @@ const useStore = create((set, get) => ({
         const originalState = {
             schedules: get().schedules,
             favoritedSchedules: get().favoritedSchedules,
+            scheduleTotals: get().scheduleTotals,
             selectedScheduleId: get().selectedScheduleId,
+            selectedSchedule: get().selectedSchedule,
             scheduleDisplayNumbers: get().scheduleDisplayNumbers,
         };
 
         set(state => {
             const newDisplayNumbers = new Map(state.scheduleDisplayNumbers);
             newDisplayNumbers.delete(scheduleIdString);
+            const schedules = state.schedules.filter(s => stringifySchedule(s) !== scheduleIdString);
+            const favoritedSchedules = state.favoritedSchedules.filter(s => stringifySchedule(s) !== scheduleIdString);
+            const isSelected = state.selectedScheduleId === scheduleIdString;
             return {
-                schedules: state.schedules.filter(s => stringifySchedule(s) !== scheduleIdString),
-                favoritedSchedules: state.favoritedSchedules.filter(s => stringifySchedule(s) !== scheduleIdString),
-                selectedScheduleId: state.selectedScheduleId === scheduleIdString ? null : state.selectedScheduleId,
+                schedules,
+                favoritedSchedules,
+                scheduleTotals: {
+                    schedules: state.scheduleTotals.schedules - (state.schedules.length - schedules.length),
+                    favorites: state.scheduleTotals.favorites - (state.favoritedSchedules.length - favoritedSchedules.length),
+                },
+                selectedScheduleId: isSelected ? null : state.selectedScheduleId,
+                selectedSchedule: isSelected ? null : state.selectedSchedule,
                 scheduleDisplayNumbers: newDisplayNumbers,
             };
         });
 
         try {
-            await schedulesAPI.delete(scheduleIdString, isCurrentlyFavorite);
-            if (originalState.selectedScheduleId === scheduleIdString) {
-                await systemAPI.setDisplaySchedule(null);
-            }
+            // The backend clears the pin when the pinned schedule is deleted
+            await schedulesAPI.delete(get().scheduleIds.get(scheduleIdString), isCurrentlyFavorite);
             // Persist the updated numbers after deletion
             get()._persistScheduleNumbers();
         } catch (error) {
@@ -427,8 +652,16 @@ const useStore = create((set, get) => ({
     toggleFavoriteSchedule: async (scheduleData, scheduleString, isCurrentlyFavorite) => {
         set({ schedulerError: null });
         try {
-            await favoritesAPI.changeFavorite(scheduleString, isCurrentlyFavorite, scheduleData);
-            await get()._updateSchedulerData();
+            const id = get().scheduleIds.get(scheduleString);
+            await favoritesAPI.changeFavorite(id, isCurrentlyFavorite);
+            set(state => {
+                const favoriteIds = new Set(state.favoriteIds);
+                if (isCurrentlyFavorite) favoriteIds.delete(id); else favoriteIds.add(id);
+                return { favoriteIds };
+            });
+            // Only the favorites list changes; the schedules list keeps its loaded pages
+            await get()._loadScheduleList('favorites', false);
+            get().refreshUndoState();
         } catch (error) {
             console.error("Failed to update favorite status:", error);
             set({ schedulerError: `Failed to update favorite status.` });
@@ -436,18 +669,60 @@ const useStore = create((set, get) => ({
         }
     },
 
+    updateFavoriteDetails: async (scheduleString, details) => {
+        set({ schedulerError: null });
+        try {
+            const stored = await favoritesAPI.updateDetails(get().scheduleIds.get(scheduleString), details);
+            set(state => {
+                const favoriteDetails = new Map(state.favoriteDetails);
+                favoriteDetails.set(scheduleString, stored);
+                return { favoriteDetails };
+            });
+            get().refreshUndoState();
+            return true;
+        } catch (error) {
+            console.error("Failed to update favorite details:", error);
+            set({ schedulerError: typeof error === 'string' ? error : 'Failed to update favorite details.' });
+            return false;
+        }
+    },
+
     // Other UI State Actions
     setHoveredSchedule: (scheduleData) => set({ currentHoveredSchedule: scheduleData }),
     clearHoveredSchedule: () => set({ currentHoveredSchedule: null }),
     showEventDetailsModal: (event) => set({ detailsEvent: event }),
     closeEventDetailsModal: () => set({ detailsEvent: null }),
     toggleRenderFavorites: () => set(state => ({ renderFavorites: !state.renderFavorites, currentHoveredSchedule: null })),
+    /**
+     * Switches the generated schedules between the plain list and footprint groups
+     */
+    toggleGroupSchedules: async () => {
+        const groupSchedules = !get().groupSchedules;
+        set({ groupSchedules, scheduleGroups: [], currentHoveredSchedule: null, schedulerError: null });
+        try {
+            await get()._loadScheduleGroups();
+        } catch (err) {
+            console.error("Failed to group schedules:", err);
+            set({ schedulerError: 'Failed to group schedules.' });
+        }
+    },
     setActiveTab: (tabName) => set({ activeTab: tabName }),
     toggleParamCheckbox: (boxName) => {
         set(state => ({
             paramCheckboxes: { ...state.paramCheckboxes, [boxName]: !state.paramCheckboxes[boxName] }
         }));
     },
+    toggleSelectionMode: () => set(state => ({
+        selectionMode: state.selectionMode === 'Diverse' ? 'Sequential' : 'Diverse'
+    })),
+    /**
+     * Sets or clears one daily structure constraint used by the next generation
+     * @param {string} key - Constraint name, e.g. 'max_daily_minutes'
+     * @param {{value: any, hard: boolean} | null} constraint - New constraint, or null to drop it
+     */
+    setConstraint: (key, constraint) => set(state => ({
+        constraints: { ...state.constraints, [key]: constraint }
+    })),
 
     // Class Parameter Actions
     addClass: () => {
@@ -464,6 +739,7 @@ const useStore = create((set, get) => ({
         }));
         try {
             await classParametersAPI.update(classData);
+            get().refreshUndoState();
         } catch (err) {
             console.error("Error updating class:", err);
             set({ schedulerError: 'Failed to update class.', classes: originalClasses });
@@ -474,11 +750,468 @@ const useStore = create((set, get) => ({
         set(state => ({ classes: state.classes.filter(item => item.id !== classId) }));
         try {
             await classParametersAPI.remove(classId);
+            get().refreshUndoState();
         } catch (err) {
             console.error("Error deleting class:", err);
             set({ schedulerError: 'Failed to delete class.', classes: originalClasses });
         }
     },
+
+    /**
+     * Adds the courses of a pasted list or CSV in one undoable step
+     * @param {string} text - Pasted text
+     * @returns {Promise<boolean>} - Whether the import ran
+     */
+    importClassList: async (text) => {
+        set({ schedulerError: null });
+        try {
+            get()._applyClassImport(await classParametersAPI.importList(text));
+            return true;
+        } catch (err) {
+            console.error("Error importing courses:", err);
+            set({ schedulerError: `${err}` });
+            return false;
+        }
+    },
+
+    importClassFile: async () => {
+        set({ schedulerError: null });
+        try {
+            const path = await open({
+                multiple: false,
+                filters: [{ name: 'Course List', extensions: ['csv', 'txt'] }],
+            });
+            if (!path) return;
+            get()._applyClassImport(await classParametersAPI.importFile(path));
+        } catch (err) {
+            console.error("Error importing courses:", err);
+            set({ schedulerError: `${err}` });
+        }
+    },
+
+    /**
+     * Reloads the course list after an import and reports what was skipped
+     * @param {{added: number, unchanged: number, skipped: string[]}} result - Import outcome from the backend
+     */
+    _applyClassImport: async (result) => {
+        const classes = await classParametersAPI.getAll();
+        set({ classes: classes || [] });
+        get().refreshUndoState();
+
+        let status = `Added ${result.added} course(s)`;
+        if (result.unchanged > 0) status += `, ${result.unchanged} already listed`;
+        if (result.skipped.length > 0) status += `; skipped ${result.skipped.length}: ${result.skipped.join('; ')}`;
+        set({ scrapeState: { isScraping: false, status } });
+    },
+
+    // --- Undo / Redo ---
+
+    refreshUndoState: async () => {
+        try {
+            const undoState = await undoAPI.getState();
+            set({ undoState: undoState || { undo: null, redo: null } });
+        } catch (err) {
+            console.error("Error loading undo state:", err);
+        }
+    },
+    _applyUndoStep: async (step, action) => {
+        set({ currentHoveredSchedule: null, schedulerError: null });
+        try {
+            const undoState = await step();
+            set({ undoState });
+        } catch (err) {
+            console.error(`Error during ${action}:`, err);
+            set({ schedulerError: `${err}` });
+            await get().refreshUndoState();
+        }
+        // Events, courses, favorites and the pin may all have changed
+        try {
+            await get()._updateSchedulerData();
+            const [loadedPin, loadedClasses] = await Promise.all([
+                systemAPI.getDisplaySchedule(),
+                classParametersAPI.getAll()
+            ]);
+            await get()._applyPin(loadedPin);
+            set({ classes: loadedClasses || [] });
+        } catch (err) {
+            console.error(`Error reloading after ${action}:`, err);
+            set({ schedulerError: 'Failed to refresh schedule data.' });
+        }
+    },
+    undo: async () => {
+        if (!get().undoState.undo) return;
+        await get()._applyUndoStep(undoAPI.undo, 'undo');
+    },
+    redo: async () => {
+        if (!get().undoState.redo) return;
+        await get()._applyUndoStep(undoAPI.redo, 'redo');
+    },
+
+    // --- Generation History ---
+
+    loadGenerationHistory: async () => {
+        try {
+            const [runs, limit] = await Promise.all([historyAPI.getRuns(), historyAPI.getLimit()]);
+            set({ generationRuns: runs || [], historyLimit: limit });
+        } catch (err) {
+            console.error("Error loading generation history:", err);
+            set({ schedulerError: 'Failed to load generation history.' });
+        }
+    },
+    restoreGenerationRun: async (runId) => {
+        set({ currentHoveredSchedule: null, schedulerError: null });
+        try {
+            const restored = await historyAPI.restore(runId);
+            get()._assignScheduleDisplayNumbers((restored || []).map(({ classes }) => classes));
+            await get()._reloadScheduleLists();
+            await get()._applyPin(await systemAPI.getDisplaySchedule());
+        } catch (err) {
+            console.error("Error restoring generation run:", err);
+            set({ schedulerError: 'Failed to restore generation run.' });
+        }
+    },
+    deleteGenerationRun: async (runId) => {
+        const originalRuns = get().generationRuns;
+        set(state => ({ generationRuns: state.generationRuns.filter(run => run.id !== runId) }));
+        try {
+            await historyAPI.delete(runId);
+        } catch (err) {
+            console.error("Error deleting generation run:", err);
+            set({ schedulerError: 'Failed to delete generation run.', generationRuns: originalRuns });
+        }
+    },
+    setHistoryLimit: async (limit) => {
+        try {
+            await historyAPI.setLimit(limit);
+            await get().loadGenerationHistory();
+        } catch (err) {
+            console.error("Error setting history limit:", err);
+            set({ schedulerError: 'Failed to update history limit.' });
+        }
+    },
+
+    // --- Data Export / Import ---
+
+    exportData: async (path, includeCache) => {
+        try {
+            await dataAPI.export(path, includeCache);
+        } catch (err) {
+            console.error("Error exporting data:", err);
+            set({ schedulerError: `Failed to export data: ${err}` });
+        }
+    },
+    importData: async (path, mode) => {
+        try {
+            await dataAPI.import(path, mode);
+            await get().loadSchedulerPage();
+        } catch (err) {
+            console.error("Error importing data:", err);
+            set({ schedulerError: `Failed to import data: ${err}` });
+        }
+    },
+
+    // --- Calendar ---
+
+    loadTermCalendar: async () => {
+        try {
+            const term = await calendarAPI.getTerm();
+            set({ termCalendar: term });
+        } catch (err) {
+            console.error("Error loading term dates:", err);
+            set({ schedulerError: 'Failed to load term dates.' });
+        }
+    },
+    saveTermCalendar: async (term) => {
+        try {
+            const stored = await calendarAPI.setTerm(term);
+            set({ termCalendar: stored });
+            return true;
+        } catch (err) {
+            console.error("Error saving term dates:", err);
+            set({ schedulerError: `Failed to save term dates: ${err}` });
+            return false;
+        }
+    },
+    exportScheduleCalendar: async (scheduleString) => {
+        set({ schedulerError: null });
+        try {
+            const path = await save({
+                defaultPath: 'schedule.ics',
+                filters: [{ name: 'iCalendar', extensions: ['ics'] }],
+            });
+            if (!path) return;
+            await calendarAPI.exportSchedule(path, get().scheduleIds.get(scheduleString));
+        } catch (err) {
+            console.error("Error exporting calendar:", err);
+            set({ schedulerError: `${err}` });
+        }
+    },
+
+    importCalendarEvents: async () => {
+        set({ schedulerError: null });
+        try {
+            const path = await open({
+                multiple: false,
+                filters: [{ name: 'iCalendar', extensions: ['ics'] }],
+            });
+            if (!path) return;
+            // getTimezoneOffset is positive west of UTC; the backend expects the offset to add to UTC
+            const result = await calendarAPI.importEvents(path, -new Date().getTimezoneOffset());
+            await get()._updateEventsData();
+            get().refreshUndoState();
+
+            const changed = result.added + result.updated;
+            let status = `Imported ${changed} event(s) from calendar`;
+            if (result.unchanged > 0) status += `, ${result.unchanged} already up to date`;
+            if (result.skipped.length > 0) status += `; skipped ${result.skipped.length}: ${result.skipped.join('; ')}`;
+            set({ scrapeState: { isScraping: false, status } });
+        } catch (err) {
+            console.error("Error importing calendar:", err);
+            set({ schedulerError: `${err}` });
+        }
+    },
+
+    // --- Schedule Images ---
+
+    /**
+     * Exports a schedule, including the user's events, as a PNG or SVG timetable
+     * @param {string} scheduleString - Stringified schedule to export
+     */
+    exportScheduleImage: async (scheduleString) => {
+        set({ schedulerError: null });
+        try {
+            const path = await save({
+                defaultPath: 'schedule.png',
+                filters: [
+                    { name: 'PNG Image', extensions: ['png'] },
+                    { name: 'SVG Image', extensions: ['svg'] },
+                ],
+            });
+            if (!path) return;
+            await imageAPI.exportSchedule(path, get().scheduleIds.get(scheduleString), true);
+        } catch (err) {
+            console.error("Error exporting schedule image:", err);
+            set({ schedulerError: `${err}` });
+        }
+    },
+
+    /**
+     * Prints a schedule, including the user's events, through a hidden frame
+     * @param {string} scheduleString - Stringified schedule to print
+     */
+    printSchedule: async (scheduleString) => {
+        set({ schedulerError: null });
+        try {
+            const svg = await imageAPI.getSvg(get().scheduleIds.get(scheduleString), true);
+            const frame = document.createElement('iframe');
+            frame.style.position = 'fixed';
+            frame.style.width = '0';
+            frame.style.height = '0';
+            frame.style.border = '0';
+            document.body.appendChild(frame);
+            frame.contentDocument.write(
+                `<html><head><style>@page { size: landscape; } body { margin: 0; } svg { width: 100%; height: auto; }</style></head><body>${svg}</body></html>`
+            );
+            frame.contentDocument.close();
+            frame.contentWindow.focus();
+            frame.contentWindow.print();
+            frame.remove();
+        } catch (err) {
+            console.error("Error printing schedule:", err);
+            set({ schedulerError: `${err}` });
+        }
+    },
+
+    /**
+     * Loads the thumbnail of a schedule once; thumbnails never change since IDs are content hashes
+     * @param {string} scheduleString - Stringified schedule
+     */
+    loadSchedulePreview: async (scheduleString) => {
+        const { schedulePreviews, scheduleIds } = get();
+        if (schedulePreviews.has(scheduleString) || !scheduleIds.has(scheduleString)) return;
+        try {
+            const svg = await imageAPI.getSvg(scheduleIds.get(scheduleString), false);
+            set(state => ({ schedulePreviews: new Map(state.schedulePreviews).set(scheduleString, svg) }));
+        } catch (err) {
+            console.error("Error rendering schedule preview:", err);
+        }
+    },
+
+    /**
+     * Exports every schedule of the current view (generated or favorites) as CSV or JSON
+     */
+    exportScheduleTable: async () => {
+        set({ schedulerError: null });
+        const table = get().renderFavorites ? 'favorites' : 'schedules';
+        try {
+            const path = await save({
+                defaultPath: `${table}.csv`,
+                filters: [
+                    { name: 'CSV Spreadsheet', extensions: ['csv'] },
+                    { name: 'JSON', extensions: ['json'] },
+                ],
+            });
+            if (!path) return;
+            const count = await schedulesAPI.exportTable(path, table);
+            set({ scrapeState: { isScraping: false, status: `Exported ${count} schedule(s)` } });
+        } catch (err) {
+            console.error("Error exporting schedules:", err);
+            set({ schedulerError: `${err}` });
+        }
+    },
+
+    // --- Share Codes ---
+
+    /**
+     * Copies a compact share code for a schedule to the clipboard
+     * @param {string} scheduleString - Stringified schedule to share
+     */
+    copyScheduleCode: async (scheduleString) => {
+        set({ schedulerError: null });
+        try {
+            const code = await shareAPI.getCode(get().scheduleIds.get(scheduleString));
+            await navigator.clipboard.writeText(code);
+            set({ scrapeState: { isScraping: false, status: `Copied schedule code: ${code}` } });
+        } catch (err) {
+            console.error("Error creating schedule code:", err);
+            set({ schedulerError: `${err}` });
+        }
+    },
+
+    /**
+     * Decodes a share code and previews the schedule it describes
+     * @param {string} code - Code pasted by the user
+     * @returns {Promise<boolean>} - Whether the code was opened
+     */
+    openScheduleCode: async (code) => {
+        set({ schedulerError: null, scrapeState: { isScraping: true, status: "Opening schedule code..." } });
+        try {
+            const shared = await shareAPI.open(code);
+            const term = shared.term_start ? ` (term starting ${shared.term_start})` : '';
+            set({
+                sharedSchedule: shared.classes,
+                currentHoveredSchedule: null,
+                scrapeState: { isScraping: false, status: `Showing shared schedule${term}` },
+            });
+            return true;
+        } catch (err) {
+            console.error("Error opening schedule code:", err);
+            set({ schedulerError: `${err}`, scrapeState: { isScraping: false, status: "" } });
+            return false;
+        }
+    },
+
+    clearSharedSchedule: () => set({ sharedSchedule: null }),
+
+    /**
+     * Lists the sections that could replace one course of the selected schedule
+     * Uses the same events, search filters and constraints as generation
+     * @param {number} courseIndex - Position of the course within the selected schedule
+     */
+    findAlternativeSections: async (courseIndex) => {
+        const { selectedSchedule, paramCheckboxes, constraints } = get();
+        if (!selectedSchedule) return;
+        set({ schedulerError: null, sectionAlternatives: { courseIndex, alternatives: null } });
+        try {
+            const alternatives = await schedulesAPI.findAlternativeSections({
+                schedule: selectedSchedule,
+                course_index: courseIndex,
+                events: get()._blockingEvents(),
+                params_checkbox: [paramCheckboxes.box1, paramCheckboxes.box2, false],
+                constraints,
+            });
+            set({ sectionAlternatives: { courseIndex, alternatives: alternatives || [] } });
+        } catch (err) {
+            console.error("Error finding alternative sections:", err);
+            set({ schedulerError: `${err}`, sectionAlternatives: null });
+        }
+    },
+
+    /**
+     * Previews the selected schedule with one course swapped for an alternative section
+     * The swapped schedule is shown like a shared schedule, since it need not be stored
+     * @param {Object} alternativeClass - Class of the chosen alternative section
+     */
+    previewSectionSwap: (alternativeClass) => {
+        const { selectedSchedule, sectionAlternatives } = get();
+        if (!selectedSchedule || !sectionAlternatives) return;
+        const sections = alternativeClass.classes.map(block => block.section).join('/');
+        set({
+            sharedSchedule: selectedSchedule.map((classData, i) => (i === sectionAlternatives.courseIndex ? alternativeClass : classData)),
+            currentHoveredSchedule: null,
+            scrapeState: { isScraping: false, status: `Showing ${alternativeClass.code} ${alternativeClass.name} section ${sections}` },
+        });
+    },
+
+    clearSectionAlternatives: () => set({ sectionAlternatives: null }),
+
+    // --- Plans ---
+
+    loadPlans: async () => {
+        try {
+            const plans = await plansAPI.getAll();
+            set({ plans: plans || [] });
+        } catch (err) {
+            console.error("Error loading plans:", err);
+            set({ schedulerError: 'Failed to load plans.' });
+        }
+    },
+    // Plan edits resolve to whether they succeeded, so name fields can stay open on failure
+    createPlan: async (name) => {
+        try {
+            await plansAPI.create(name);
+            await get().loadPlans();
+            return true;
+        } catch (err) {
+            console.error("Error creating plan:", err);
+            set({ schedulerError: `Failed to create plan: ${err}` });
+            return false;
+        }
+    },
+    duplicatePlan: async (planId, name) => {
+        try {
+            await plansAPI.duplicate(planId, name);
+            await get().loadPlans();
+            return true;
+        } catch (err) {
+            console.error("Error duplicating plan:", err);
+            set({ schedulerError: `Failed to duplicate plan: ${err}` });
+            return false;
+        }
+    },
+    renamePlan: async (planId, name) => {
+        try {
+            await plansAPI.rename(planId, name);
+            await get().loadPlans();
+            return true;
+        } catch (err) {
+            console.error("Error renaming plan:", err);
+            set({ schedulerError: `Failed to rename plan: ${err}` });
+            return false;
+        }
+    },
+    switchPlan: async (planId) => {
+        set({ currentHoveredSchedule: null, schedulerError: null });
+        try {
+            await plansAPI.switch(planId);
+            // Everything on the scheduler page belongs to the active plan
+            await Promise.all([get().loadSchedulerPage(), get().loadTermCalendar(), get().loadGenerationHistory()]);
+        } catch (err) {
+            console.error("Error switching plan:", err);
+            set({ schedulerError: 'Failed to switch plan.' });
+        }
+    },
+    deletePlan: async (planId) => {
+        try {
+            await plansAPI.delete(planId);
+            await get().loadPlans();
+            return true;
+        } catch (err) {
+            console.error("Error deleting plan:", err);
+            set({ schedulerError: `Failed to delete plan: ${err}` });
+            return false;
+        }
+    },
 }));
 
 export { stringifySchedule };
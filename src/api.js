@@ -30,8 +30,15 @@ export const systemAPI = {
   getDisplaySchedule() {
     return invokeWrapper('get_display_schedule');
   },
-  setDisplaySchedule(scheduleIndex) {
-    return invokeWrapper('set_display_schedule', { id: scheduleIndex });
+  setDisplaySchedule(pin) {
+    return invokeWrapper('set_display_schedule', { pin });
+  },
+  getStoragePaths() {
+    return invokeWrapper('get_storage_paths');
+  },
+  setStorageOverride(path) {
+    // Takes effect on next launch; pass null to restore the platform default directories
+    return invokeWrapper('set_storage_override', { path });
   }
 };
 
@@ -40,8 +47,9 @@ export const eventsAPI = {
   create(eventData) {
     return invokeWrapper('create_event', { eventData });
   },
-  getAll() {
-    return invokeWrapper('get_events');
+  getAll(viewWindow = null, previewEvents = null) {
+    // Preview events are positioned with the user's events and widen the returned window
+    return invokeWrapper('get_events', { viewWindow, previewEvents });
   },
   update(eventData) {
     return invokeWrapper('update_event', { event: eventData });
@@ -56,21 +64,58 @@ export const schedulesAPI = {
   generate(parameters) {
     return invokeWrapper('generate_schedules', { parameters });
   },
-  getAll() {
-    return invokeWrapper('get_schedules', { table: 'schedules' });
+  getGrouped() {
+    return invokeWrapper('get_schedule_groups', { table: 'schedules' });
+  },
+  query({ table = 'schedules', offset = 0, limit = 50, sort = null, descending = false, filter = {} } = {}) {
+    // sort: null (stored order: generation ranking, or order added for favorites) | 'StartTime' | 'DaysOnCampus' | 'Score' (soft-constraint penalty)
+    // filter: { include_sections: ['CSC116/001'], exclude_instructors: ['Smith,John'], free_days: [false, false, false, false, true], ids: ['<schedule id>'] }
+    return invokeWrapper('query_schedules', { query: { table, offset, limit, sort, descending, filter } });
+  },
+  findAlternativeSections(parameters) {
+    // parameters: { schedule, course_index, events, params_checkbox: [openOnly, waitlistOk, false], constraints }
+    // With openOnly set the course is searched again, since seat status isn't cached
+    return invokeWrapper('find_alternative_sections', { parameters });
+  },
+  delete(scheduleId, isFavorited) {
+    return invokeWrapper('delete_schedule', { id: scheduleId, isFavorited });
+  },
+  exportTable(path, table) {
+    // The format follows the extension of path: .csv or .json; resolves to the number of schedules written
+    return invokeWrapper('export_schedule_table_file', { path, table });
+  }
+};
+
+// --- Generation History API ---
+export const historyAPI = {
+  getRuns() {
+    return invokeWrapper('get_generation_runs');
+  },
+  restore(runId) {
+    return invokeWrapper('restore_generation_run', { id: runId });
+  },
+  delete(runId) {
+    return invokeWrapper('delete_generation_run', { id: runId });
+  },
+  getLimit() {
+    return invokeWrapper('get_history_limit');
   },
-  delete(scheduleIdString, isFavorited) {
-    return invokeWrapper('delete_schedule', { id: scheduleIdString, isFavorited });
+  setLimit(limit) {
+    return invokeWrapper('set_history_limit', { limit });
   }
 };
 
 // --- Favorites Table API ---
 export const favoritesAPI = {
-  getAll() {
-    return invokeWrapper('get_schedules', { table: 'favorites' });
+  changeFavorite(scheduleId, isFavorited) {
+    return invokeWrapper('change_favorite_schedule', { id: scheduleId, isFavorited });
+  },
+  updateDetails(scheduleId, details) {
+    // details: { title, notes, color: '#rrggbb' | null, tags: ['...'] }
+    return invokeWrapper('update_favorite_details', { id: scheduleId, details });
   },
-  changeFavorite(scheduleIdString, isFavorited, scheduleData) {
-    return invokeWrapper('change_favorite_schedule', { id: scheduleIdString, isFavorited, schedule: scheduleData });
+  getTags() {
+    return invokeWrapper('get_favorite_tags');
   }
 };
 
@@ -84,5 +129,109 @@ export const classParametersAPI = {
   },
   remove(classId) {
     return invokeWrapper('remove_class', { id: classId });
+  },
+  importList(text) {
+    // text: free-form ("CSC 316 001, MA 305, ST 370 Smith") or CSV with a header row
+    // Resolves to { added, unchanged, skipped: ['Line 3: reason'] }
+    return invokeWrapper('import_classes', { text });
+  },
+  importFile(path) {
+    return invokeWrapper('import_classes_file', { path });
+  }
+};
+
+// --- Undo / Redo API ---
+export const undoAPI = {
+  // Each call resolves to { undo, redo }: labels of the next steps, or null
+  getState() {
+    return invokeWrapper('get_undo_state');
+  },
+  undo() {
+    return invokeWrapper('undo');
+  },
+  redo() {
+    return invokeWrapper('redo');
+  }
+};
+
+// --- Plans Table API ---
+export const plansAPI = {
+  getAll() {
+    return invokeWrapper('get_plans');
+  },
+  create(name) {
+    return invokeWrapper('create_plan', { name });
+  },
+  duplicate(planId, name) {
+    return invokeWrapper('duplicate_plan', { id: planId, name });
+  },
+  rename(planId, name) {
+    return invokeWrapper('rename_plan', { id: planId, name });
+  },
+  switch(planId) {
+    return invokeWrapper('switch_plan', { id: planId });
+  },
+  delete(planId) {
+    return invokeWrapper('delete_plan', { id: planId });
+  }
+};
+
+// --- Data Export / Import API ---
+export const dataAPI = {
+  export(path, includeCache) {
+    return invokeWrapper('export_data', { path, includeCache });
+  },
+  import(path, mode) {
+    // mode: 'Merge' adds the archived plans, 'Replace' replaces all existing plans
+    return invokeWrapper('import_data', { path, mode });
   }
-};
\ No newline at end of file
+};
+
+// --- Course Catalog API ---
+export const coursesAPI = {
+  search(query, limit) {
+    // Results carry a snippet with matches wrapped in <mark> tags
+    return invokeWrapper('search_courses', { query, limit });
+  }
+};
+
+// --- Calendar API ---
+export const calendarAPI = {
+  getTerm() {
+    return invokeWrapper('get_term_calendar');
+  },
+  setTerm(term) {
+    // term: { start: 'YYYY-MM-DD', end: 'YYYY-MM-DD', holidays: ['YYYY-MM-DD'] }, or null to clear it
+    return invokeWrapper('set_term_calendar', { term });
+  },
+  exportSchedule(path, scheduleId) {
+    // Resolves to the number of recurring events written
+    return invokeWrapper('export_calendar', { path, id: scheduleId });
+  },
+  importEvents(path, utcOffsetMinutes) {
+    // Resolves to { added, updated, unchanged, skipped: ['Title: reason'] }
+    return invokeWrapper('import_calendar_events', { path, utcOffsetMinutes });
+  }
+};
+
+// --- Share Code API ---
+export const shareAPI = {
+  getCode(scheduleId) {
+    return invokeWrapper('get_schedule_code', { id: scheduleId });
+  },
+  open(code) {
+    // Resolves to { term_start: 'YYYY-MM-DD' | null, classes: [...] }; rejects if the code is malformed
+    return invokeWrapper('open_schedule_code', { code });
+  }
+};
+
+// --- Schedule Image API ---
+export const imageAPI = {
+  exportSchedule(path, scheduleId, includeEvents) {
+    // The format follows the extension of path: .svg or .png
+    return invokeWrapper('export_schedule_picture', { path, id: scheduleId, includeEvents });
+  },
+  getSvg(scheduleId, includeEvents) {
+    return invokeWrapper('get_schedule_svg', { id: scheduleId, includeEvents });
+  }
+};
@@ -1,19 +1,87 @@
+import { useEffect, useState } from "react";
 import "../../App.css";
 import Sidebar from "../Sidebar/Sidebar";
 import ss from "./Courses.module.css";
+import { coursesAPI } from "../../api";
 
+/** Delay after the last keystroke before searching, in milliseconds */
+const SEARCH_DEBOUNCE_MS = 250;
+
+/**
+ * Renders a search snippet, highlighting the <mark>-wrapped matches
+ * The snippet is split into text nodes rather than injected as HTML,
+ * since it contains scraped course text
+ */
+function Snippet({ text }) {
+    const parts = text.split(/<mark>|<\/mark>/);
+    return (
+        <p className={ss['snippet']}>
+            {parts.map((part, idx) => idx % 2 === 1
+                ? <mark key={idx}>{part}</mark>
+                : <span key={idx}>{part}</span>)}
+        </p>
+    );
+}
 
 function Courses() {
+    const [query, setQuery] = useState("");
+    const [results, setResults] = useState([]);
+    const [error, setError] = useState(null);
+
+    useEffect(() => {
+        if (!query.trim()) {
+            setResults([]);
+            return;
+        }
+        let cancelled = false;
+        const timer = setTimeout(async () => {
+            try {
+                const found = await coursesAPI.search(query);
+                if (!cancelled) {
+                    setResults(found);
+                    setError(null);
+                }
+            } catch (e) {
+                if (!cancelled) setError(String(e));
+            }
+        }, SEARCH_DEBOUNCE_MS);
+        return () => {
+            cancelled = true;
+            clearTimeout(timer);
+        };
+    }, [query]);
 
     return (
         <div>
             <Sidebar/>
             <div className = {ss['courses']}>
                 <h1 className='page-header'>Courses</h1>
+                <input
+                    className={ss['searchInput']}
+                    type="search"
+                    placeholder="Search cached courses by code, title, description or instructor"
+                    value={query}
+                    onChange={(e) => setQuery(e.target.value)}
+                />
+                {error && <p className={ss['error']}>{error}</p>}
+                {query.trim() && !error && results.length === 0 && (
+                    <p className={ss['empty']}>No cached courses match. Courses are cached when schedules are generated.</p>
+                )}
+                <ul className={ss['results']}>
+                    {results.map((course) => (
+                        <li key={course.classname} className={ss['result']}>
+                            <h3>{course.code} {course.name}</h3>
+                            <Snippet text={course.snippet}/>
+                            {course.instructors.length > 0 && (
+                                <p className={ss['instructors']}>{course.instructors.join(", ")}</p>
+                            )}
+                        </li>
+                    ))}
+                </ul>
             </div>
 
         </div>
     );
 }
 
-export default Courses;
\ No newline at end of file
+export default Courses;
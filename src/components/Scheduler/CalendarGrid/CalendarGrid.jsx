@@ -77,6 +77,8 @@ const addMinutesToTimeInt = (timeInt, minutesToAdd) => {
  * @param {string} props.event.title - Event title
  * @param {string} [props.event.professor] - Professor/instructor name
  * @param {boolean} [props.event.isPreview] - Whether this is a preview event from schedule
+ * @param {boolean} [props.event.clippedStart] - Whether the event starts before the visible hours
+ * @param {boolean} [props.event.clippedEnd] - Whether the event ends after the visible hours
  * @param {Object} props.eventStyle - CSS styles for positioning and sizing
  * @param {Function} [props.onEdit] - Callback for editing user events
  * @param {Function} [props.onShowDetails] - Callback for showing event details
@@ -101,7 +103,9 @@ const Event = ({ event, eventStyle, onEdit, onShowDetails }) => {
   const eventClasses = [
     ss.event,
     event.professor === '' ? ss.activity : ss.class, // Check if professor is empty string
-    event.isPreview ? ss.previewEvent : ''
+    event.isPreview ? ss.previewEvent : '',
+    event.clippedStart ? ss.clippedStart : '',
+    event.clippedEnd ? ss.clippedEnd : ''
   ].filter(Boolean).join(' ');
 
   return (
@@ -128,6 +132,8 @@ Event.propTypes = {
     description: PropTypes.string,
     topPosition: PropTypes.string,
     heightPosition: PropTypes.string,
+    clippedStart: PropTypes.bool,
+    clippedEnd: PropTypes.bool,
     width: PropTypes.string,
     left: PropTypes.string
   }).isRequired,
@@ -1,7 +1,7 @@
 // src/components/Scheduler/CourseManagementPanel.jsx
 import React, { useState, useEffect, useCallback } from 'react';
 import PropTypes from 'prop-types';
-import { Trash2, Plus, X } from 'lucide-react';
+import { Trash2, Plus, X, Pencil, Copy, CalendarPlus, Share2, Image as ImageIcon, Printer } from 'lucide-react';
 import useStore, { stringifySchedule } from '../../Store.jsx';
 
 
@@ -291,8 +291,534 @@ AddClassCard.propTypes = {
   ss: PropTypes.object.isRequired
 };
 
+// === SCHEDULE CONSTRAINTS EDITOR COMPONENT ===
+
+/** Short weekday labels, Mon-Fri */
+const WEEKDAY_LABELS = ['M', 'T', 'W', 'Th', 'F'];
+
+/**
+ * Values a constraint starts with when it is switched on
+ * Times are HHMM integers, like section meeting times
+ */
+const DEFAULT_CONSTRAINT_VALUES = {
+    max_daily_minutes: 360,
+    max_consecutive_minutes: 180,
+    free_block: { minutes: 60, days: [true, true, true, true, true], window: [1100, 1400] },
+    free_days: [false, false, false, false, true],
+};
+
+/**
+ * Converts an HHMM integer to the "HH:MM" value of a time input
+ * @param {number} time - Time as HHMM
+ * @returns {string} - Time input value
+ */
+const toTimeInput = (time) => `${String(Math.floor(time / 100)).padStart(2, '0')}:${String(time % 100).padStart(2, '0')}`;
+
+/**
+ * Converts the "HH:MM" value of a time input to an HHMM integer
+ * @param {string} value - Time input value
+ * @returns {number} - Time as HHMM
+ */
+const fromTimeInput = (value) => {
+    const [hours, minutes] = value.split(':').map(Number);
+    return (hours || 0) * 100 + (minutes || 0);
+};
+
+/**
+ * Parses a minutes field, treating empty or invalid input as 0
+ * @param {string} value - Input value
+ * @returns {number} - Non-negative whole minutes
+ */
+const parseMinutes = (value) => Math.max(0, parseInt(value, 10) || 0);
+
+/**
+ * Row of Mon-Fri toggle buttons
+ * 
+ * @component
+ * @param {Object} props - Component props
+ * @param {boolean[]} props.days - Selected weekdays (Mon-Fri)
+ * @param {Function} props.onChange - Called with the new weekday array
+ * @param {Object} props.ss - CSS module styles object
+ * @returns {JSX.Element} Weekday toggles
+ */
+const WeekdayToggles = ({ days, onChange, ss }) => (
+    <div className={ss.weekdayToggles}>
+        {WEEKDAY_LABELS.map((label, index) => (
+            <button
+                key={label}
+                type="button"
+                className={`${ss.button} ${ss.toggleButton} ${days[index] ? ss.active : ''}`}
+                onClick={() => onChange(days.map((selected, i) => (i === index ? !selected : selected)))}
+            >
+                {label}
+            </button>
+        ))}
+    </div>
+);
+
+WeekdayToggles.propTypes = {
+  days: PropTypes.arrayOf(PropTypes.bool).isRequired,
+  onChange: PropTypes.func.isRequired,
+  ss: PropTypes.object.isRequired
+};
+
+/**
+ * One switchable constraint with its value fields and a required/preferred toggle
+ * 
+ * @component
+ * @param {Object} props - Component props
+ * @param {string} props.label - Constraint description
+ * @param {{value: any, hard: boolean} | null} props.constraint - Current constraint, null when off
+ * @param {any} props.defaultValue - Value used when the constraint is switched on
+ * @param {Function} props.onChange - Called with the new constraint, or null to switch it off
+ * @param {Function} props.children - Renders the value fields for the current value
+ * @param {Object} props.ss - CSS module styles object
+ * @returns {JSX.Element} Constraint row
+ */
+const ConstraintRow = ({ label, constraint, defaultValue, onChange, children, ss }) => (
+    <div className={ss.constraintRow}>
+        <label className={ss.favoriteEditorRow}>
+            <input
+                type="checkbox"
+                checked={!!constraint}
+                onChange={(e) => onChange(e.target.checked ? { value: defaultValue, hard: false } : null)}
+            />
+            <span>{label}</span>
+        </label>
+        {constraint && (
+            <div className={ss.favoriteEditorRow}>
+                {children(constraint.value, (value) => onChange({ ...constraint, value }))}
+                <button
+                    type="button"
+                    className={`${ss.button} ${ss.toggleButton} ${constraint.hard ? ss.active : ''}`}
+                    onClick={() => onChange({ ...constraint, hard: !constraint.hard })}
+                    title="Required constraints remove schedules; preferred ones only rank them"
+                >
+                    {constraint.hard ? 'Required' : 'Preferred'}
+                </button>
+            </div>
+        )}
+    </div>
+);
+
+ConstraintRow.propTypes = {
+  label: PropTypes.string.isRequired,
+  constraint: PropTypes.shape({
+    value: PropTypes.any,
+    hard: PropTypes.bool.isRequired
+  }),
+  defaultValue: PropTypes.any.isRequired,
+  onChange: PropTypes.func.isRequired,
+  children: PropTypes.func.isRequired,
+  ss: PropTypes.object.isRequired
+};
+
+/**
+ * Collapsible editor for the daily structure constraints sent with each generation
+ * Required (hard) constraints remove schedules; preferred (soft) ones only lower their rank
+ * 
+ * @component
+ * @param {Object} props - Component props
+ * @param {Object} props.constraints - Current constraints keyed by name, each { value, hard } or null
+ * @param {Function} props.setConstraint - Called with a constraint name and its new value
+ * @param {Object} props.ss - CSS module styles object
+ * @returns {JSX.Element} Constraint settings
+ */
+const ConstraintsEditor = React.memo(({ constraints, setConstraint, ss }) => {
+    /** @type {boolean} Whether the settings are shown */
+    const [isOpen, setIsOpen] = useState(false);
+    const activeCount = Object.values(constraints).filter(Boolean).length;
+
+    return (
+        <div className={ss.constraintsEditor}>
+            <button className={`${ss.button} ${ss.toggleButton} ${activeCount > 0 ? ss.active : ''}`} onClick={() => setIsOpen(!isOpen)}>
+                {activeCount > 0 ? `Constraints (${activeCount})` : 'Constraints'}
+            </button>
+            {isOpen && (
+                <>
+                    <ConstraintRow
+                        label="Max class time per day"
+                        constraint={constraints.max_daily_minutes}
+                        defaultValue={DEFAULT_CONSTRAINT_VALUES.max_daily_minutes}
+                        onChange={(constraint) => setConstraint('max_daily_minutes', constraint)}
+                        ss={ss}
+                    >
+                        {(value, onValueChange) => (
+                            <input type="number" min="0" step="15" className={ss.inputField} value={value}
+                                onChange={(e) => onValueChange(parseMinutes(e.target.value))} aria-label="Minutes per day" />
+                        )}
+                    </ConstraintRow>
+                    <ConstraintRow
+                        label="Max classes without a break"
+                        constraint={constraints.max_consecutive_minutes}
+                        defaultValue={DEFAULT_CONSTRAINT_VALUES.max_consecutive_minutes}
+                        onChange={(constraint) => setConstraint('max_consecutive_minutes', constraint)}
+                        ss={ss}
+                    >
+                        {(value, onValueChange) => (
+                            <input type="number" min="0" step="15" className={ss.inputField} value={value}
+                                onChange={(e) => onValueChange(parseMinutes(e.target.value))} aria-label="Minutes without a 30 minute break" />
+                        )}
+                    </ConstraintRow>
+                    <ConstraintRow
+                        label="Free block (e.g. lunch)"
+                        constraint={constraints.free_block}
+                        defaultValue={DEFAULT_CONSTRAINT_VALUES.free_block}
+                        onChange={(constraint) => setConstraint('free_block', constraint)}
+                        ss={ss}
+                    >
+                        {(value, onValueChange) => (
+                            <div className={ss.constraintFields}>
+                                <div className={ss.favoriteEditorRow}>
+                                    <input type="number" min="0" step="15" className={ss.inputField} value={value.minutes}
+                                        onChange={(e) => onValueChange({ ...value, minutes: parseMinutes(e.target.value) })} aria-label="Free minutes" />
+                                    <input type="time" className={ss.inputField} value={toTimeInput(value.window[0])}
+                                        onChange={(e) => onValueChange({ ...value, window: [fromTimeInput(e.target.value), value.window[1]] })} aria-label="Block window start" />
+                                    <input type="time" className={ss.inputField} value={toTimeInput(value.window[1])}
+                                        onChange={(e) => onValueChange({ ...value, window: [value.window[0], fromTimeInput(e.target.value)] })} aria-label="Block window end" />
+                                </div>
+                                <WeekdayToggles days={value.days} onChange={(days) => onValueChange({ ...value, days })} ss={ss} />
+                            </div>
+                        )}
+                    </ConstraintRow>
+                    <ConstraintRow
+                        label="Days without classes"
+                        constraint={constraints.free_days}
+                        defaultValue={DEFAULT_CONSTRAINT_VALUES.free_days}
+                        onChange={(constraint) => setConstraint('free_days', constraint)}
+                        ss={ss}
+                    >
+                        {(value, onValueChange) => <WeekdayToggles days={value} onChange={onValueChange} ss={ss} />}
+                    </ConstraintRow>
+                </>
+            )}
+        </div>
+    );
+});
+
+// Set display name for ConstraintsEditor
+ConstraintsEditor.displayName = 'ConstraintsEditor';
+
+// PropTypes for ConstraintsEditor
+ConstraintsEditor.propTypes = {
+  constraints: PropTypes.object.isRequired,
+  setConstraint: PropTypes.func.isRequired,
+  ss: PropTypes.object.isRequired
+};
+
+// === FAVORITE DETAILS EDITOR COMPONENT ===
+
+/**
+ * Inline form for a favorite's title, color, tags and notes
+ * Tags are entered comma-separated; the backend trims and deduplicates them
+ * 
+ * @component
+ * @param {Object} props - Component props
+ * @param {Object} props.details - Current details ({ title, notes, color, tags })
+ * @param {Function} props.onSave - Called with the edited details; resolves to whether saving succeeded
+ * @param {Function} props.onCancel - Closes the editor without saving
+ * @param {Object} props.ss - CSS module styles object
+ * @returns {JSX.Element} Favorite details form
+ */
+const FavoriteDetailsEditor = ({ details, onSave, onCancel, ss }) => {
+    const [title, setTitle] = useState(details.title || '');
+    const [color, setColor] = useState(details.color || '');
+    const [tags, setTags] = useState((details.tags || []).join(', '));
+    const [notes, setNotes] = useState(details.notes || '');
+    const [isSaving, setIsSaving] = useState(false);
+
+    const handleSubmit = async (e) => {
+        e.preventDefault();
+        setIsSaving(true);
+        const saved = await onSave({
+            title,
+            notes,
+            color: color || null,
+            tags: tags.split(',').map(tag => tag.trim()).filter(Boolean),
+        });
+        setIsSaving(false);
+        if (saved) onCancel();
+    };
+
+    return (
+        <form
+            className={ss.favoriteEditor}
+            onSubmit={handleSubmit}
+            onClick={(e) => e.stopPropagation()}
+            onKeyDown={(e) => { if (e.key === 'Escape') onCancel(); }}
+        >
+            <div className={ss.favoriteEditorRow}>
+                <input
+                    className={ss.inputField}
+                    value={title}
+                    onChange={(e) => setTitle(e.target.value)}
+                    placeholder="Title"
+                    maxLength={100}
+                    autoFocus
+                />
+                <input
+                    type="color"
+                    className={ss.favoriteColorInput}
+                    value={color || '#3b82f6'}
+                    onChange={(e) => setColor(e.target.value)}
+                    aria-label="Favorite color"
+                />
+                {color && (
+                    <button type="button" className={ss.iconButton} onClick={() => setColor('')} aria-label="Clear color">
+                        <X size={14} />
+                    </button>
+                )}
+            </div>
+            <input
+                className={ss.inputField}
+                value={tags}
+                onChange={(e) => setTags(e.target.value)}
+                placeholder="Tags, comma separated"
+            />
+            <textarea
+                className={`${ss.inputField} ${ss.favoriteNotes}`}
+                value={notes}
+                onChange={(e) => setNotes(e.target.value)}
+                placeholder="Notes"
+                rows={3}
+            />
+            <div className={ss.favoriteEditorActions}>
+                <button type="button" className={ss.button} onClick={onCancel}>Cancel</button>
+                <button type="submit" className={`${ss.button} ${ss['button-primary']}`} disabled={isSaving}>
+                    {isSaving ? 'Saving...' : 'Save'}
+                </button>
+            </div>
+        </form>
+    );
+};
+
+FavoriteDetailsEditor.propTypes = {
+  details: PropTypes.shape({
+    title: PropTypes.string,
+    notes: PropTypes.string,
+    color: PropTypes.string,
+    tags: PropTypes.arrayOf(PropTypes.string)
+  }).isRequired,
+  onSave: PropTypes.func.isRequired,
+  onCancel: PropTypes.func.isRequired,
+  ss: PropTypes.object.isRequired
+};
+
+// === SCHEDULE GROUPS COMPONENT ===
+
+/**
+ * Describes a class's sections and instructor, e.g. "002 (Smith,John)"
+ * @param {Object} classData - Class with its time blocks
+ * @returns {string} Section label
+ */
+const describeSections = (classData) => {
+    const sections = classData.classes.map(block => block.section).join('/');
+    const instructor = classData.classes.find(block => block.instructor)?.instructor;
+    return instructor ? `${sections} (${instructor})` : sections;
+};
+
+/**
+ * Generated schedules collapsed by weekly footprint
+ * Each group shows its representative schedule; schedules that only swap in an
+ * interchangeable section (same times, other instructor or room) are listed as alternatives
+ * 
+ * @component ScheduleGroupsList
+ * @param {Object} props - Component props
+ * @param {Array} props.scheduleGroups - Groups as returned by get_schedule_groups
+ * @param {string|null} props.selectedScheduleId - Currently selected schedule ID
+ * @param {Function} props.setSelectedSchedule - Select/pin a schedule
+ * @param {Function} props.setHoveredSchedule - Set hovered schedule for preview
+ * @param {Function} props.clearHoveredSchedule - Clear hovered schedule
+ * @param {Function} props.getScheduleDisplayNumber - Get display number for schedule
+ * @param {Object} props.ss - CSS module styles object
+ * @returns {JSX.Element} Grouped schedule list
+ */
+const ScheduleGroupsList = React.memo(({
+    scheduleGroups, selectedScheduleId, setSelectedSchedule, setHoveredSchedule, clearHoveredSchedule, getScheduleDisplayNumber, ss
+}) => {
+    /** @type {string|null} ID of the group whose alternatives are shown */
+    const [expandedGroup, setExpandedGroup] = useState(null);
+
+    return scheduleGroups.map(({ id, schedule, alternatives, count }) => {
+        const scheduleString = stringifySchedule(schedule);
+        const displayNum = getScheduleDisplayNumber(scheduleString);
+        const isExpanded = expandedGroup === id;
+
+        return (
+            <React.Fragment key={id}>
+                <div
+                    className={`${ss.scheduleItem} ${scheduleString === selectedScheduleId ? ss['selected-schedule'] : ''}`}
+                    onClick={() => setSelectedSchedule(schedule)}
+                    onMouseEnter={() => setHoveredSchedule(schedule)}
+                    onMouseLeave={clearHoveredSchedule}
+                >
+                    <div className={ss.scheduleLabel}>
+                        <span>Schedule {displayNum}</span>
+                        {count > 1 && <span className={ss.groupCount}>{count} variations</span>}
+                    </div>
+                    {count > 1 && (
+                        <div className={ss.scheduleItemActions}>
+                            <button
+                                className={`${ss.button} ${ss.toggleButton} ${isExpanded ? ss.active : ''}`}
+                                onClick={(e) => { e.stopPropagation(); setExpandedGroup(isExpanded ? null : id); }}
+                                aria-label={`Show sections interchangeable with Schedule ${displayNum}`}
+                            >
+                                Sections
+                            </button>
+                        </div>
+                    )}
+                </div>
+                {isExpanded && (
+                    <ul className={ss.groupAlternatives}>
+                        {schedule.map((classData, courseIndex) => alternatives[courseIndex]?.length > 0 && (
+                            <li key={`${classData.code}${classData.name}`}>
+                                <strong>{classData.code} {classData.name}:</strong>{' '}
+                                {[classData, ...alternatives[courseIndex]].map(describeSections).join(', ')}
+                            </li>
+                        ))}
+                    </ul>
+                )}
+            </React.Fragment>
+        );
+    });
+});
+
+// Set display name for ScheduleGroupsList
+ScheduleGroupsList.displayName = 'ScheduleGroupsList';
+
+// PropTypes for ScheduleGroupsList
+ScheduleGroupsList.propTypes = {
+  scheduleGroups: PropTypes.arrayOf(PropTypes.shape({
+    id: PropTypes.string.isRequired,
+    schedule: PropTypes.array.isRequired,
+    alternatives: PropTypes.arrayOf(PropTypes.array).isRequired,
+    count: PropTypes.number.isRequired
+  })).isRequired,
+  selectedScheduleId: PropTypes.string,
+  setSelectedSchedule: PropTypes.func.isRequired,
+  setHoveredSchedule: PropTypes.func.isRequired,
+  clearHoveredSchedule: PropTypes.func.isRequired,
+  getScheduleDisplayNumber: PropTypes.func.isRequired,
+  ss: PropTypes.object.isRequired
+};
+
+// === SECTION SWAPPER COMPONENT ===
+
+/**
+ * Swaps one course of the selected schedule for another section that still fits
+ * Alternatives come from the backend ranked best first; hovering one previews it
+ * and clicking keeps the swapped schedule on the calendar
+ * 
+ * @component SectionSwapper
+ * @param {Object} props - Component props
+ * @param {Array} props.selectedSchedule - Classes of the selected schedule
+ * @param {{courseIndex: number, alternatives: Array|null} | null} props.sectionAlternatives - Alternatives being shown
+ * @param {Function} props.findAlternativeSections - Load alternatives for a course index
+ * @param {Function} props.previewSectionSwap - Show the schedule with an alternative swapped in
+ * @param {Function} props.clearSectionAlternatives - Close the alternatives
+ * @param {Function} props.setHoveredSchedule - Set hovered schedule for preview
+ * @param {Function} props.clearHoveredSchedule - Clear hovered schedule
+ * @param {boolean} props.disabled - Whether a search or generation is running
+ * @param {Object} props.ss - CSS module styles object
+ * @returns {JSX.Element} Section swap controls
+ */
+const SectionSwapper = React.memo(({
+    selectedSchedule, sectionAlternatives, findAlternativeSections, previewSectionSwap, clearSectionAlternatives,
+    setHoveredSchedule, clearHoveredSchedule, disabled, ss
+}) => {
+    const courseIndex = sectionAlternatives?.courseIndex ?? '';
+    const alternatives = sectionAlternatives?.alternatives;
+
+    /**
+     * Builds the selected schedule with an alternative in place of the chosen course
+     * @param {Object} alternativeClass - Class of the alternative section
+     * @returns {Array} Swapped schedule
+     */
+    const swapped = (alternativeClass) =>
+        selectedSchedule.map((classData, i) => (i === sectionAlternatives.courseIndex ? alternativeClass : classData));
+
+    return (
+        <div className={ss.sectionSwapper}>
+            <div className={ss.favoriteEditorRow}>
+                <select
+                    className={ss.inputField}
+                    value={courseIndex}
+                    onChange={(e) => (e.target.value === '' ? clearSectionAlternatives() : findAlternativeSections(Number(e.target.value)))}
+                    disabled={disabled}
+                    aria-label="Course to swap"
+                >
+                    <option value="">Swap a section...</option>
+                    {selectedSchedule.map((classData, i) => (
+                        <option key={`${classData.code}${classData.name}`} value={i}>
+                            {classData.code} {classData.name} ({classData.classes.map(block => block.section).join('/')})
+                        </option>
+                    ))}
+                </select>
+                {sectionAlternatives && (
+                    <button className={ss.iconButton} onClick={clearSectionAlternatives} aria-label="Close section alternatives">
+                        <X size={16} />
+                    </button>
+                )}
+            </div>
+            {sectionAlternatives && (
+                alternatives === null ? (
+                    <p className={ss.swapMessage}>Finding sections...</p>
+                ) : alternatives.length === 0 ? (
+                    <p className={ss.swapMessage}>No other section fits this schedule.</p>
+                ) : (
+                    <ul className={ss.groupAlternatives}>
+                        {alternatives.map(({ class: alternativeClass, penalty }) => (
+                            <li key={alternativeClass.classes.map(block => block.section).join('/')}>
+                                <button
+                                    className={ss.swapOption}
+                                    onClick={() => previewSectionSwap(alternativeClass)}
+                                    onMouseEnter={() => setHoveredSchedule(swapped(alternativeClass))}
+                                    onMouseLeave={clearHoveredSchedule}
+                                    title={penalty > 0 ? `Breaks ${penalty} preferred constraint(s)` : undefined}
+                                >
+                                    {describeSections(alternativeClass)}{penalty > 0 ? ` · ${penalty}` : ''}
+                                </button>
+                            </li>
+                        ))}
+                    </ul>
+                )
+            )}
+        </div>
+    );
+});
+
+// Set display name for SectionSwapper
+SectionSwapper.displayName = 'SectionSwapper';
+
+// PropTypes for SectionSwapper
+SectionSwapper.propTypes = {
+  selectedSchedule: PropTypes.array.isRequired,
+  sectionAlternatives: PropTypes.shape({
+    courseIndex: PropTypes.number.isRequired,
+    alternatives: PropTypes.array
+  }),
+  findAlternativeSections: PropTypes.func.isRequired,
+  previewSectionSwap: PropTypes.func.isRequired,
+  clearSectionAlternatives: PropTypes.func.isRequired,
+  setHoveredSchedule: PropTypes.func.isRequired,
+  clearHoveredSchedule: PropTypes.func.isRequired,
+  disabled: PropTypes.bool.isRequired,
+  ss: PropTypes.object.isRequired
+};
+
 // === SCHEDULES LIST COMPONENT ===
 
+/**
+ * Sort options of the schedule lists, as backend sort keys with labels
+ * @type {Array<{value: string, label: string}>}
+ */
+const SCHEDULE_SORT_OPTIONS = [
+    { value: '', label: 'Ranking' },
+    { value: 'Score', label: 'Best Score' },
+    { value: 'StartTime', label: 'Earliest Start' },
+    { value: 'DaysOnCampus', label: 'Fewest Days' },
+];
+
 /**
  * Component for displaying and managing generated schedules and favorites
  * Handles switching between regular schedules and favorites view
@@ -301,12 +827,20 @@ AddClassCard.propTypes = {
  * @component SchedulesList
  * @param {Object} props - Component props
  * @param {boolean} props.renderFavorites - Whether to show favorites or regular schedules
- * @param {Array} props.favoritedSchedules - Array of favorited schedule objects
- * @param {Array} props.schedules - Array of generated schedule objects
+ * @param {Array} props.favoritedSchedules - Loaded pages of favorited schedule objects
+ * @param {Array} props.schedules - Loaded pages of generated schedule objects
+ * @param {Object} props.scheduleTotals - Total matches per list ({ schedules, favorites })
+ * @param {Object} props.scheduleQuery - Current sort and filter of the lists
+ * @param {Function} props.setScheduleQuery - Change the sort or filter and reload the lists
+ * @param {Function} props.loadMoreSchedules - Append the next page of the shown list
+ * @param {boolean} props.groupSchedules - Whether generated schedules are shown grouped by footprint
+ * @param {Array} props.scheduleGroups - Footprint groups of the generated schedules
+ * @param {Function} props.toggleGroupSchedules - Toggle between the plain and grouped list
  * @param {Object} props.scrapeState - Schedule generation state object
  * @param {boolean} props.scrapeState.isScraping - Whether generation is in progress
  * @param {string} props.scrapeState.status - Generation status message
  * @param {Set} props.favoritedScheduleStrings - Set of favorited schedule string IDs
+ * @param {Map} props.favoriteDetails - Titles, notes, colors and tags keyed by favorite schedule string
  * @param {string|null} props.selectedScheduleId - Currently selected schedule ID
  * @param {Function} props.toggleRenderFavorites - Toggle between schedules and favorites view
  * @param {Function} props.setSelectedSchedule - Select/pin a schedule
@@ -314,18 +848,43 @@ AddClassCard.propTypes = {
  * @param {Function} props.clearHoveredSchedule - Clear hovered schedule
  * @param {Function} props.toggleFavoriteSchedule - Toggle favorite status of schedule
  * @param {Function} props.deleteSchedule - Delete a schedule
+ * @param {Function} props.updateFavoriteDetails - Save a favorite's details
+ * @param {Function} props.exportScheduleCalendar - Export a schedule as an .ics file
+ * @param {Function} props.copyScheduleCode - Copy a schedule's share code to the clipboard
+ * @param {Function} props.exportScheduleImage - Export a schedule as a PNG or SVG timetable
+ * @param {Function} props.printSchedule - Print a schedule's timetable
+ * @param {Map} props.schedulePreviews - Map of schedule strings to SVG thumbnails
+ * @param {Function} props.loadSchedulePreview - Render a schedule's thumbnail
+ * @param {Function} props.exportScheduleTable - Export the current view's schedules as CSV or JSON
  * @param {Function} props.getScheduleDisplayNumber - Get display number for schedule
  * @param {Object} props.ss - CSS module styles object
  * @returns {JSX.Element} Schedules list with controls
  */
 const SchedulesList = React.memo(({
-    renderFavorites, favoritedSchedules, schedules, scrapeState, favoritedScheduleStrings,
+    renderFavorites, favoritedSchedules, schedules, scheduleTotals, scheduleQuery, setScheduleQuery, loadMoreSchedules,
+    groupSchedules, scheduleGroups, toggleGroupSchedules, scrapeState, favoritedScheduleStrings, favoriteDetails,
     selectedScheduleId, toggleRenderFavorites, setSelectedSchedule, setHoveredSchedule,
-    clearHoveredSchedule, toggleFavoriteSchedule, deleteSchedule, getScheduleDisplayNumber, ss
+    clearHoveredSchedule, toggleFavoriteSchedule, deleteSchedule, updateFavoriteDetails, exportScheduleCalendar, copyScheduleCode,
+    exportScheduleImage, printSchedule, schedulePreviews, loadSchedulePreview, exportScheduleTable, getScheduleDisplayNumber, ss
 }) => {
+    /** @type {string|null} Schedule string of the favorite whose details are being edited */
+    const [editingSchedule, setEditingSchedule] = useState(null);
+
     // Determine which schedules to display based on current view mode
     const schedulesToRender = renderFavorites ? favoritedSchedules : schedules;
+    const total = renderFavorites ? scheduleTotals.favorites : scheduleTotals.schedules;
+
+    // Favorites show a thumbnail of their timetable
+    useEffect(() => {
+        if (!renderFavorites) return;
+        favoritedSchedules.forEach(schedule => {
+            const scheduleString = stringifySchedule(schedule);
+            if (scheduleString) loadSchedulePreview(scheduleString);
+        });
+    }, [renderFavorites, favoritedSchedules, loadSchedulePreview]);
     const isEmpty = !schedulesToRender?.some(s => s?.length > 0);
+    // Groups cover every generated schedule, so sorting and paging don't apply to them
+    const isGrouped = groupSchedules && !renderFavorites;
 
     return (
         <div className={ss.schedulesContainer}>
@@ -336,6 +895,34 @@ const SchedulesList = React.memo(({
                     onClick={toggleRenderFavorites}>
                     {renderFavorites ? "★ Favorites" : "Show Favorites"}
                 </button>
+                <button
+                    className={ss.button}
+                    onClick={exportScheduleTable}
+                    disabled={isEmpty}
+                    title={`Export all ${renderFavorites ? "favorites" : "generated schedules"} (.csv, .json)`}
+                >
+                    Export List
+                </button>
+                {!renderFavorites && (
+                    <button
+                        className={`${ss.toggleButton} ${ss.button} ${groupSchedules ? ss.active : ''}`}
+                        onClick={toggleGroupSchedules}
+                        disabled={isEmpty}
+                        title="Collapse schedules that only differ by interchangeable sections"
+                    >
+                        Group Similar
+                    </button>
+                )}
+                {/* Sort - applied by the backend, so it covers schedules not loaded yet */}
+                <select
+                    className={ss.inputField}
+                    disabled={isGrouped}
+                    value={scheduleQuery.sort || ''}
+                    onChange={(e) => setScheduleQuery({ sort: e.target.value || null })}
+                    aria-label="Sort schedules"
+                >
+                    {SCHEDULE_SORT_OPTIONS.map(option => <option key={option.value} value={option.value}>{option.label}</option>)}
+                </select>
             </div>
 
             {/* Empty State or Schedule List */}
@@ -347,6 +934,16 @@ const SchedulesList = React.memo(({
                      )
                     }
                 </div>
+            ) : isGrouped ? (
+                <ScheduleGroupsList
+                    scheduleGroups={scheduleGroups}
+                    selectedScheduleId={selectedScheduleId}
+                    setSelectedSchedule={setSelectedSchedule}
+                    setHoveredSchedule={setHoveredSchedule}
+                    clearHoveredSchedule={clearHoveredSchedule}
+                    getScheduleDisplayNumber={getScheduleDisplayNumber}
+                    ss={ss}
+                />
             ) : (
                 // Render individual schedule items
                 schedulesToRender.map((schedule, i) => {
@@ -356,11 +953,15 @@ const SchedulesList = React.memo(({
                     const isFavorite = favoritedScheduleStrings.has(currentScheduleString);
                     const displayNum = getScheduleDisplayNumber(currentScheduleString);
                     const isSelected = currentScheduleString === selectedScheduleId;
+                    // Annotations are only shown in the favorites view
+                    const details = renderFavorites ? favoriteDetails.get(currentScheduleString) : null;
+                    const isEditing = renderFavorites && editingSchedule === currentScheduleString;
 
                     return (
+                        <React.Fragment key={currentScheduleString || `schedule-item-${i}`}>
                         <div
-                            key={currentScheduleString || `schedule-item-${i}`}
                             className={`${ss.scheduleItem} ${isSelected ? ss['selected-schedule'] : ''}`}
+                            style={details?.color ? { borderLeft: `4px solid ${details.color}` } : undefined}
                             onClick={() => setSelectedSchedule(schedule)}
                             onMouseEnter={() => setHoveredSchedule(schedule)}
                             onMouseLeave={clearHoveredSchedule}
@@ -374,20 +975,103 @@ const SchedulesList = React.memo(({
                                 {isFavorite ? '★' : '☆'}
                             </button>
                             
-                            {/* Schedule Display Number */}
-                            <span>Schedule {displayNum}</span>
+                            {/* Schedule Title (favorites) or Display Number */}
+                            <div className={ss.scheduleLabel}>
+                                <span title={details?.notes || undefined}>{details?.title || `Schedule ${displayNum}`}</span>
+                                {renderFavorites && schedulePreviews.has(currentScheduleString) && (
+                                    <img
+                                        className={ss.favoriteThumbnail}
+                                        src={`data:image/svg+xml;charset=utf-8,${encodeURIComponent(schedulePreviews.get(currentScheduleString))}`}
+                                        alt={`Timetable of Schedule ${displayNum}`}
+                                    />
+                                )}
+                                {details?.tags?.length > 0 && (
+                                    <div className={ss.favoriteTags}>
+                                        {details.tags.map(tag => <span key={tag} className={ss.favoriteTag}>{tag}</span>)}
+                                    </div>
+                                )}
+                            </div>
                             
-                            {/* Delete Button */}
-                            <button
-                                className={ss.iconButton}
-                                onClick={(e) => { e.stopPropagation(); deleteSchedule(currentScheduleString, isFavorite); }}
-                            >
-                                <Trash2 size={16} />
-                            </button>
+                            <div className={ss.scheduleItemActions}>
+                                {/* Edit Details Button - favorites view only */}
+                                {renderFavorites && (
+                                    <button
+                                        className={ss.iconButton}
+                                        onClick={(e) => { e.stopPropagation(); setEditingSchedule(isEditing ? null : currentScheduleString); }}
+                                        aria-label={`Edit details of Schedule ${displayNum}`}
+                                    >
+                                        <Pencil size={16} />
+                                    </button>
+                                )}
+
+                                {/* Calendar Export Button */}
+                                <button
+                                    className={ss.iconButton}
+                                    onClick={(e) => { e.stopPropagation(); exportScheduleCalendar(currentScheduleString); }}
+                                    aria-label={`Export Schedule ${displayNum} to calendar`}
+                                    title="Export to calendar (.ics)"
+                                >
+                                    <CalendarPlus size={16} />
+                                </button>
+
+                                {/* Share Code Button */}
+                                <button
+                                    className={ss.iconButton}
+                                    onClick={(e) => { e.stopPropagation(); copyScheduleCode(currentScheduleString); }}
+                                    aria-label={`Copy share code for Schedule ${displayNum}`}
+                                    title="Copy share code"
+                                >
+                                    <Share2 size={16} />
+                                </button>
+
+                                {/* Image Export Button */}
+                                <button
+                                    className={ss.iconButton}
+                                    onClick={(e) => { e.stopPropagation(); exportScheduleImage(currentScheduleString); }}
+                                    aria-label={`Export Schedule ${displayNum} as an image`}
+                                    title="Export as image (.png, .svg)"
+                                >
+                                    <ImageIcon size={16} />
+                                </button>
+
+                                {/* Print Button */}
+                                <button
+                                    className={ss.iconButton}
+                                    onClick={(e) => { e.stopPropagation(); printSchedule(currentScheduleString); }}
+                                    aria-label={`Print Schedule ${displayNum}`}
+                                    title="Print"
+                                >
+                                    <Printer size={16} />
+                                </button>
+
+                                {/* Delete Button */}
+                                <button
+                                    className={ss.iconButton}
+                                    onClick={(e) => { e.stopPropagation(); deleteSchedule(currentScheduleString, isFavorite); }}
+                                >
+                                    <Trash2 size={16} />
+                                </button>
+                            </div>
                         </div>
+                        {isEditing && (
+                            <FavoriteDetailsEditor
+                                details={details || {}}
+                                onSave={(edited) => updateFavoriteDetails(currentScheduleString, edited)}
+                                onCancel={() => setEditingSchedule(null)}
+                                ss={ss}
+                            />
+                        )}
+                        </React.Fragment>
                     );
                 })
             )}
+
+            {/* Next Page - the backend sends the lists one page at a time */}
+            {!isEmpty && !isGrouped && schedulesToRender.length < total && (
+                <button className={ss.button} onClick={loadMoreSchedules}>
+                    Load More ({schedulesToRender.length} of {total})
+                </button>
+            )}
         </div>
     );
 });
@@ -400,11 +1084,26 @@ SchedulesList.propTypes = {
   renderFavorites: PropTypes.bool.isRequired,
   favoritedSchedules: PropTypes.array.isRequired,
   schedules: PropTypes.array.isRequired,
+  scheduleTotals: PropTypes.shape({
+    schedules: PropTypes.number.isRequired,
+    favorites: PropTypes.number.isRequired
+  }).isRequired,
+  scheduleQuery: PropTypes.shape({
+    sort: PropTypes.string,
+    descending: PropTypes.bool,
+    filter: PropTypes.object
+  }).isRequired,
+  setScheduleQuery: PropTypes.func.isRequired,
+  loadMoreSchedules: PropTypes.func.isRequired,
+  groupSchedules: PropTypes.bool.isRequired,
+  scheduleGroups: PropTypes.array.isRequired,
+  toggleGroupSchedules: PropTypes.func.isRequired,
   scrapeState: PropTypes.shape({
     isScraping: PropTypes.bool.isRequired,
     status: PropTypes.string.isRequired
   }).isRequired,
   favoritedScheduleStrings: PropTypes.instanceOf(Set).isRequired,
+  favoriteDetails: PropTypes.instanceOf(Map).isRequired,
   selectedScheduleId: PropTypes.string,
   toggleRenderFavorites: PropTypes.func.isRequired,
   setSelectedSchedule: PropTypes.func.isRequired,
@@ -412,10 +1111,326 @@ SchedulesList.propTypes = {
   clearHoveredSchedule: PropTypes.func.isRequired,
   toggleFavoriteSchedule: PropTypes.func.isRequired,
   deleteSchedule: PropTypes.func.isRequired,
+  updateFavoriteDetails: PropTypes.func.isRequired,
+  exportScheduleCalendar: PropTypes.func.isRequired,
+  copyScheduleCode: PropTypes.func.isRequired,
+  exportScheduleImage: PropTypes.func.isRequired,
+  printSchedule: PropTypes.func.isRequired,
+  schedulePreviews: PropTypes.instanceOf(Map).isRequired,
+  loadSchedulePreview: PropTypes.func.isRequired,
+  exportScheduleTable: PropTypes.func.isRequired,
   getScheduleDisplayNumber: PropTypes.func.isRequired,
   ss: PropTypes.object.isRequired
 };
 
+/**
+ * Card for adding many courses at once from a pasted list or a CSV file
+ * 
+ * @component ImportClassesCard
+ * @param {Object} props - Component props
+ * @param {Function} props.importClassList - Import pasted text; resolves to whether it ran
+ * @param {Function} props.importClassFile - Pick and import a .csv or .txt file
+ * @param {Object} props.ss - CSS module styles object
+ * @returns {JSX.Element} Paste area with import buttons
+ */
+const ImportClassesCard = React.memo(({ importClassList, importClassFile, ss }) => {
+    /** @type {string} Text pasted by the user */
+    const [text, setText] = useState('');
+
+    const handleImport = async () => {
+        if (await importClassList(text)) setText('');
+    };
+
+    return (
+        <div className={ss.importClassesCard}>
+            <textarea
+                className={ss.inputField}
+                value={text}
+                onChange={(e) => setText(e.target.value)}
+                placeholder={"Paste courses, e.g.\nCSC 316 001, MA 305, ST 370 Smith"}
+                rows={3}
+            />
+            <div className={ss.favoriteEditorRow}>
+                <button className={`${ss.button} ${ss['button-primary']}`} onClick={handleImport} disabled={!text.trim()}>
+                    Import List
+                </button>
+                <button className={ss.button} onClick={importClassFile}>
+                    From File...
+                </button>
+            </div>
+        </div>
+    );
+});
+
+// Set display name for ImportClassesCard
+ImportClassesCard.displayName = 'ImportClassesCard';
+
+// PropTypes for ImportClassesCard
+ImportClassesCard.propTypes = {
+  importClassList: PropTypes.func.isRequired,
+  importClassFile: PropTypes.func.isRequired,
+  ss: PropTypes.object.isRequired
+};
+
+// === PLAN SWITCHER COMPONENT ===
+
+/**
+ * Lists the user's plans and manages them
+ * Clicking a plan makes it active; each plan can be renamed, duplicated or deleted.
+ * The active plan and the only plan can't be deleted, matching the backend.
+ * Names are checked for blanks and duplicates before anything is sent.
+ * 
+ * @component
+ * @param {Object} props - Component props
+ * @param {Array} props.plans - Plans in creation order ({ id, name, active })
+ * @param {Function} props.switchPlan - Activates a plan by ID
+ * @param {Function} props.createPlan - Creates an empty plan; resolves to success
+ * @param {Function} props.duplicatePlan - Copies a plan under a new name; resolves to success
+ * @param {Function} props.renamePlan - Renames a plan; resolves to success
+ * @param {Function} props.deletePlan - Deletes an inactive plan; resolves to success
+ * @param {boolean} props.disabled - Whether changes are currently blocked (e.g. while generating)
+ * @param {Object} props.ss - CSS module styles object
+ * @returns {JSX.Element} Plan list with a name field for the pending action
+ */
+const PlanSwitcher = React.memo(({ plans, switchPlan, createPlan, duplicatePlan, renamePlan, deletePlan, disabled, ss }) => {
+    /** @type {Object|null} Action awaiting a name: { action: 'create'|'rename'|'duplicate', planId } */
+    const [pending, setPending] = useState(null);
+    /** @type {string} Name typed for the pending action */
+    const [name, setName] = useState('');
+
+    const startAction = (action, plan = null) => {
+        setPending({ action, planId: plan?.id ?? null });
+        setName(action === 'rename' ? plan.name : action === 'duplicate' ? `${plan.name} (copy)` : '');
+    };
+
+    const trimmedName = name.trim();
+    const nameTaken = plans.some(plan => plan.name === trimmedName && plan.id !== (pending?.action === 'rename' ? pending.planId : null));
+    const nameError = !trimmedName ? 'Enter a name' : nameTaken ? 'Another plan has this name' : null;
+
+    const handleSubmit = async () => {
+        if (nameError) return;
+        const { action, planId } = pending;
+        const succeeded = action === 'create' ? await createPlan(trimmedName)
+            : action === 'rename' ? await renamePlan(planId, trimmedName)
+            : await duplicatePlan(planId, trimmedName);
+        if (succeeded) setPending(null);
+    };
+
+    return (
+        <div className={ss.planSwitcher}>
+            <div className={ss.planHeader}>
+                <span>Plans</span>
+                <button
+                    className={ss.iconButton}
+                    onClick={() => startAction('create')}
+                    disabled={disabled}
+                    aria-label="New plan"
+                    title="New empty plan"
+                >
+                    <Plus size={16} />
+                </button>
+            </div>
+            {plans.map(plan => (
+                <div key={plan.id} className={ss.planRow}>
+                    <button
+                        className={`${ss.planName} ${plan.active ? ss.active : ''}`}
+                        onClick={() => { if (!plan.active) switchPlan(plan.id); }}
+                        disabled={disabled}
+                        aria-pressed={plan.active}
+                        title={plan.active ? 'Active plan' : `Switch to ${plan.name}`}
+                    >
+                        {plan.name}
+                    </button>
+                    <button className={ss.iconButton} onClick={() => startAction('rename', plan)} disabled={disabled} aria-label={`Rename ${plan.name}`} title="Rename">
+                        <Pencil size={14} />
+                    </button>
+                    <button className={ss.iconButton} onClick={() => startAction('duplicate', plan)} disabled={disabled} aria-label={`Duplicate ${plan.name}`} title="Duplicate">
+                        <Copy size={14} />
+                    </button>
+                    <button
+                        className={ss.iconButton}
+                        onClick={() => deletePlan(plan.id)}
+                        disabled={disabled || plan.active || plans.length <= 1}
+                        aria-label={`Delete ${plan.name}`}
+                        title={plan.active ? 'Switch to another plan to delete this one' : 'Delete'}
+                    >
+                        <Trash2 size={14} />
+                    </button>
+                </div>
+            ))}
+            {pending && (
+                <div className={ss.favoriteEditorRow}>
+                    <input
+                        className={ss.inputField}
+                        value={name}
+                        onChange={(e) => setName(e.target.value)}
+                        onKeyDown={(e) => {
+                            if (e.key === 'Enter') handleSubmit();
+                            else if (e.key === 'Escape') setPending(null);
+                        }}
+                        placeholder="Plan name"
+                        title={nameError ?? ''}
+                        autoFocus
+                    />
+                    <button className={`${ss.button} ${ss['button-primary']}`} onClick={handleSubmit} disabled={disabled || !!nameError}>
+                        {pending.action === 'create' ? 'Create' : pending.action === 'rename' ? 'Rename' : 'Duplicate'}
+                    </button>
+                    <button className={ss.iconButton} onClick={() => setPending(null)} aria-label="Cancel" title="Cancel">
+                        <X size={16} />
+                    </button>
+                </div>
+            )}
+        </div>
+    );
+});
+
+// Set display name for PlanSwitcher
+PlanSwitcher.displayName = 'PlanSwitcher';
+
+// PropTypes for PlanSwitcher
+PlanSwitcher.propTypes = {
+  plans: PropTypes.arrayOf(PropTypes.shape({
+    id: PropTypes.string.isRequired,
+    name: PropTypes.string.isRequired,
+    active: PropTypes.bool.isRequired
+  })).isRequired,
+  switchPlan: PropTypes.func.isRequired,
+  createPlan: PropTypes.func.isRequired,
+  duplicatePlan: PropTypes.func.isRequired,
+  renamePlan: PropTypes.func.isRequired,
+  deletePlan: PropTypes.func.isRequired,
+  disabled: PropTypes.bool,
+  ss: PropTypes.object.isRequired
+};
+
+// === GENERATION HISTORY COMPONENT ===
+
+/**
+ * Lists the active plan's recorded generation runs, newest first
+ * Each run can be restored as the current schedules or deleted. The retention limit
+ * is one setting for the whole app; every plan keeps that many of its newest runs.
+ * History is reloaded when the list opens and whenever a generation finishes.
+ * 
+ * @component
+ * @param {Object} props - Component props
+ * @param {Array} props.generationRuns - Runs ({ id, created_at, parameters, schedule_count, duration_ms })
+ * @param {number|null} props.historyLimit - Runs kept per plan, or null until loaded
+ * @param {Function} props.loadGenerationHistory - Reloads the runs and the limit
+ * @param {Function} props.restoreGenerationRun - Makes a run's schedules current
+ * @param {Function} props.deleteGenerationRun - Removes a run from the history
+ * @param {Function} props.setHistoryLimit - Changes the retention limit (0 disables history)
+ * @param {boolean} props.disabled - Whether a generation is running
+ * @param {Object} props.ss - CSS module styles object
+ * @returns {JSX.Element} Retention setting and run list
+ */
+const GenerationHistory = React.memo(({
+    generationRuns,
+    historyLimit,
+    loadGenerationHistory,
+    restoreGenerationRun,
+    deleteGenerationRun,
+    setHistoryLimit,
+    disabled,
+    ss
+}) => {
+    /** @type {string} Limit being typed, committed on blur or Enter */
+    const [limitInput, setLimitInput] = useState('');
+
+    useEffect(() => {
+        if (!disabled) loadGenerationHistory();
+    }, [disabled, loadGenerationHistory]);
+
+    useEffect(() => {
+        setLimitInput(historyLimit === null ? '' : String(historyLimit));
+    }, [historyLimit]);
+
+    const commitLimit = () => {
+        const limit = parseInt(limitInput, 10);
+        if (Number.isInteger(limit) && limit >= 0 && limit !== historyLimit) {
+            setHistoryLimit(limit);
+        } else {
+            setLimitInput(historyLimit === null ? '' : String(historyLimit));
+        }
+    };
+
+    return (
+        <div className={ss.schedulesContainer}>
+            <label className={ss.favoriteEditorRow} title="Applies to every plan; 0 turns history off">
+                <span>Runs kept per plan</span>
+                <input
+                    type="number"
+                    min="0"
+                    className={`${ss.inputField} ${ss.historyLimitInput}`}
+                    value={limitInput}
+                    onChange={(e) => setLimitInput(e.target.value)}
+                    onBlur={commitLimit}
+                    onKeyDown={(e) => { if (e.key === 'Enter') commitLimit(); }}
+                    disabled={disabled}
+                />
+            </label>
+            {generationRuns.length === 0 && (
+                <p className={ss.swapMessage}>
+                    {historyLimit === 0 ? 'History is turned off.' : 'No generation runs recorded for this plan yet.'}
+                </p>
+            )}
+            {generationRuns.map(run => {
+                const courses = run.parameters.classes.map(course => `${course.code} ${course.name}`.trim()).join(', ');
+                return (
+                    <div key={run.id} className={ss.historyItem}>
+                        <div className={ss.scheduleLabel}>
+                            <span>{new Date(run.created_at * 1000).toLocaleString()}</span>
+                            <span className={ss.historyDetails}>
+                                {run.schedule_count} schedule{run.schedule_count === 1 ? '' : 's'} in {(run.duration_ms / 1000).toFixed(1)}s
+                            </span>
+                            {courses && <span className={ss.historyDetails} title={courses}>{courses}</span>}
+                        </div>
+                        <div className={ss.scheduleItemActions}>
+                            <button
+                                className={ss.button}
+                                onClick={() => restoreGenerationRun(run.id)}
+                                disabled={disabled}
+                                title="Replace the current schedules with this run's results"
+                            >
+                                Restore
+                            </button>
+                            <button
+                                className={ss.iconButton}
+                                onClick={() => deleteGenerationRun(run.id)}
+                                disabled={disabled}
+                                aria-label="Delete run"
+                                title="Delete run"
+                            >
+                                <Trash2 size={14} />
+                            </button>
+                        </div>
+                    </div>
+                );
+            })}
+        </div>
+    );
+});
+
+// Set display name for GenerationHistory
+GenerationHistory.displayName = 'GenerationHistory';
+
+// PropTypes for GenerationHistory
+GenerationHistory.propTypes = {
+  generationRuns: PropTypes.arrayOf(PropTypes.shape({
+    id: PropTypes.string.isRequired,
+    created_at: PropTypes.number.isRequired,
+    parameters: PropTypes.shape({ classes: PropTypes.array.isRequired }).isRequired,
+    schedule_count: PropTypes.number.isRequired,
+    duration_ms: PropTypes.number.isRequired
+  })).isRequired,
+  historyLimit: PropTypes.number,
+  loadGenerationHistory: PropTypes.func.isRequired,
+  restoreGenerationRun: PropTypes.func.isRequired,
+  deleteGenerationRun: PropTypes.func.isRequired,
+  setHistoryLimit: PropTypes.func.isRequired,
+  disabled: PropTypes.bool,
+  ss: PropTypes.object.isRequired
+};
+
 // === COURSES LIST COMPONENT ===
 
 /**
@@ -428,10 +1443,12 @@ SchedulesList.propTypes = {
  * @param {Function} props.updateClass - Update course data callback
  * @param {Function} props.deleteClass - Delete course callback
  * @param {Function} props.addClass - Add new course callback
+ * @param {Function} props.importClassList - Import a pasted course list
+ * @param {Function} props.importClassFile - Import a course list file
  * @param {Object} props.ss - CSS module styles object
- * @returns {JSX.Element} List of course cards with add button
+ * @returns {JSX.Element} List of course cards with add and import cards
  */
-const ClassesList = React.memo(({ classes, updateClass, deleteClass, addClass, ss }) => (
+const ClassesList = React.memo(({ classes, updateClass, deleteClass, addClass, importClassList, importClassFile, ss }) => (
     <div className={ss.classesContainer}>
         {/* Render all existing course cards */}
         {classes.map((classItem) => (
@@ -445,6 +1462,8 @@ const ClassesList = React.memo(({ classes, updateClass, deleteClass, addClass, s
         ))}
         {/* Add new course button */}
         <AddClassCard onClick={addClass} ss={ss} />
+        {/* Bulk import from a pasted list or CSV */}
+        <ImportClassesCard importClassList={importClassList} importClassFile={importClassFile} ss={ss} />
     </div>
 ));
 
@@ -463,6 +1482,8 @@ ClassesList.propTypes = {
   updateClass: PropTypes.func.isRequired,
   deleteClass: PropTypes.func.isRequired,
   addClass: PropTypes.func.isRequired,
+  importClassList: PropTypes.func.isRequired,
+  importClassFile: PropTypes.func.isRequired,
   ss: PropTypes.object.isRequired
 };
 
@@ -481,30 +1502,90 @@ ClassesList.propTypes = {
 const CourseManagementPanel = ({ ss }) => {
     const schedules = useStore(state => state.schedules);
     const favoritedSchedules = useStore(state => state.favoritedSchedules);
+    const scheduleTotals = useStore(state => state.scheduleTotals);
+    const scheduleQuery = useStore(state => state.scheduleQuery);
+    const favoriteIds = useStore(state => state.favoriteIds);
+    const scheduleIds = useStore(state => state.scheduleIds);
+    const favoriteDetails = useStore(state => state.favoriteDetails);
     const selectedScheduleId = useStore(state => state.selectedScheduleId);
     const scrapeState = useStore(state => state.scrapeState);
     const paramCheckboxes = useStore(state => state.paramCheckboxes);
+    const selectionMode = useStore(state => state.selectionMode);
+    const constraints = useStore(state => state.constraints);
     const classes = useStore(state => state.classes);
     const activeTab = useStore(state => state.activeTab);
     const renderFavorites = useStore(state => state.renderFavorites);
+    const groupSchedules = useStore(state => state.groupSchedules);
+    const scheduleGroups = useStore(state => state.scheduleGroups);
     const schedulerError = useStore(state => state.schedulerError);
     const generateSchedules = useStore(state => state.generateSchedules);
     const clearScrapeStatus = useStore(state => state.clearScrapeStatus);
     const toggleFavoriteSchedule = useStore(state => state.toggleFavoriteSchedule);
     const deleteSchedule = useStore(state => state.deleteSchedule);
+    const updateFavoriteDetails = useStore(state => state.updateFavoriteDetails);
+    const exportScheduleCalendar = useStore(state => state.exportScheduleCalendar);
+    const importCalendarEvents = useStore(state => state.importCalendarEvents);
+    const copyScheduleCode = useStore(state => state.copyScheduleCode);
+    const exportScheduleImage = useStore(state => state.exportScheduleImage);
+    const printSchedule = useStore(state => state.printSchedule);
+    const schedulePreviews = useStore(state => state.schedulePreviews);
+    const loadSchedulePreview = useStore(state => state.loadSchedulePreview);
+    const exportScheduleTable = useStore(state => state.exportScheduleTable);
+    const openScheduleCode = useStore(state => state.openScheduleCode);
+    const sharedSchedule = useStore(state => state.sharedSchedule);
+    const clearSharedSchedule = useStore(state => state.clearSharedSchedule);
+    const selectedSchedule = useStore(state => state.selectedSchedule);
+    const sectionAlternatives = useStore(state => state.sectionAlternatives);
+    const findAlternativeSections = useStore(state => state.findAlternativeSections);
+    const previewSectionSwap = useStore(state => state.previewSectionSwap);
+    const clearSectionAlternatives = useStore(state => state.clearSectionAlternatives);
     const setSelectedSchedule = useStore(state => state.setSelectedSchedule);
     const setHoveredSchedule = useStore(state => state.setHoveredSchedule);
     const clearHoveredSchedule = useStore(state => state.clearHoveredSchedule);
     const toggleRenderFavorites = useStore(state => state.toggleRenderFavorites);
+    const toggleGroupSchedules = useStore(state => state.toggleGroupSchedules);
+    const setScheduleQuery = useStore(state => state.setScheduleQuery);
+    const loadMoreSchedules = useStore(state => state.loadMoreSchedules);
     const setActiveTab = useStore(state => state.setActiveTab);
     const toggleParamCheckbox = useStore(state => state.toggleParamCheckbox);
+    const toggleSelectionMode = useStore(state => state.toggleSelectionMode);
+    const setConstraint = useStore(state => state.setConstraint);
     const addClass = useStore(state => state.addClass);
     const updateClass = useStore(state => state.updateClass);
     const deleteClass = useStore(state => state.deleteClass);
+    const importClassList = useStore(state => state.importClassList);
+    const importClassFile = useStore(state => state.importClassFile);
     const getScheduleDisplayNumber = useStore(state => state.getScheduleDisplayNumber);
+    const plans = useStore(state => state.plans);
+    const switchPlan = useStore(state => state.switchPlan);
+    const createPlan = useStore(state => state.createPlan);
+    const duplicatePlan = useStore(state => state.duplicatePlan);
+    const renamePlan = useStore(state => state.renamePlan);
+    const deletePlan = useStore(state => state.deletePlan);
+    const generationRuns = useStore(state => state.generationRuns);
+    const historyLimit = useStore(state => state.historyLimit);
+    const loadGenerationHistory = useStore(state => state.loadGenerationHistory);
+    const restoreGenerationRun = useStore(state => state.restoreGenerationRun);
+    const deleteGenerationRun = useStore(state => state.deleteGenerationRun);
+    const setHistoryLimit = useStore(state => state.setHistoryLimit);
+
+    /** @type {string} Share code typed or pasted by the user */
+    const [shareCode, setShareCode] = useState('');
+
+    /**
+     * Opens the entered share code, clearing the field once it decodes
+     */
+    const handleOpenShareCode = async () => {
+        if (await openScheduleCode(shareCode)) setShareCode('');
+    };
 
     // Derived values
-    const favoritedScheduleStrings = new Set(favoritedSchedules.map(s => stringifySchedule(s)).filter(Boolean));
+    // Favorite status comes from backend IDs, since a favorite need not be on a loaded page
+    const favoritedScheduleStrings = new Set(
+        [...schedules, ...favoritedSchedules]
+            .map(s => stringifySchedule(s))
+            .filter(scheduleString => scheduleString && favoriteIds.has(scheduleIds.get(scheduleString)))
+    );
 
     // Extract scrape state for easier access
     const { isScraping, status: scrapeStatus } = scrapeState;
@@ -513,6 +1594,18 @@ const CourseManagementPanel = ({ ss }) => {
         <aside className={ss.controlPanel}>
             {/* === GENERATION CONTROLS SECTION === */}
             <div className={ss.generationControls}>
+                {/* Plans - each owns its own courses, events and schedules */}
+                <PlanSwitcher
+                    plans={plans}
+                    switchPlan={switchPlan}
+                    createPlan={createPlan}
+                    duplicatePlan={duplicatePlan}
+                    renamePlan={renamePlan}
+                    deletePlan={deletePlan}
+                    disabled={isScraping}
+                    ss={ss}
+                />
+
                 {/* Main Generate Button */}
                 <button
                     className={`${ss.button} ${ss['button-primary']}`}
@@ -536,7 +1629,71 @@ const CourseManagementPanel = ({ ss }) => {
                     >
                         Waitlist OK
                     </button>
+                    <button 
+                        className={`${ss.button} ${ss.toggleButton} ${selectionMode === 'Diverse' ? ss.active : ''}`} 
+                        onClick={toggleSelectionMode}
+                        title="Among equally ranked schedules, show different weekly layouts first"
+                    >
+                        Varied First
+                    </button>
+                </div>
+
+                {/* Daily Structure Constraints - sent with each generation */}
+                <ConstraintsEditor constraints={constraints} setConstraint={setConstraint} ss={ss} />
+
+                {/* Calendar Import - recurring commitments become blocking events */}
+                <button
+                    className={ss.button}
+                    onClick={importCalendarEvents}
+                    disabled={isScraping}
+                    title="Add weekly events from an .ics file as times to keep free"
+                >
+                    Import Calendar (.ics)
+                </button>
+
+                {/* Share Code - previews a schedule someone else sent */}
+                <div className={ss.favoriteEditorRow}>
+                    <input
+                        className={ss.inputField}
+                        value={shareCode}
+                        onChange={(e) => setShareCode(e.target.value)}
+                        onKeyDown={(e) => { if (e.key === 'Enter' && shareCode.trim()) handleOpenShareCode(); }}
+                        placeholder="Paste a schedule code"
+                        disabled={isScraping}
+                    />
+                    <button
+                        className={ss.button}
+                        onClick={handleOpenShareCode}
+                        disabled={isScraping || !shareCode.trim()}
+                    >
+                        Open
+                    </button>
+                    {sharedSchedule && (
+                        <button
+                            className={ss.iconButton}
+                            onClick={clearSharedSchedule}
+                            aria-label="Close shared schedule"
+                            title="Close shared schedule"
+                        >
+                            <X size={16} />
+                        </button>
+                    )}
                 </div>
+
+                {/* Section Swap - other sections of one course that fit the selected schedule */}
+                {selectedSchedule && (
+                    <SectionSwapper
+                        selectedSchedule={selectedSchedule}
+                        sectionAlternatives={sectionAlternatives}
+                        findAlternativeSections={findAlternativeSections}
+                        previewSectionSwap={previewSectionSwap}
+                        clearSectionAlternatives={clearSectionAlternatives}
+                        setHoveredSchedule={setHoveredSchedule}
+                        clearHoveredSchedule={clearHoveredSchedule}
+                        disabled={isScraping}
+                        ss={ss}
+                    />
+                )}
                 
                 {/* Loading Indicator - Shown during generation */}
                 {isScraping && (
@@ -593,6 +1750,12 @@ const CourseManagementPanel = ({ ss }) => {
                     >
                         Courses
                     </button>
+                    <button 
+                        className={`${ss.tabButton} ${activeTab === 'history' ? ss.active : ''}`} 
+                        onClick={() => setActiveTab('history')}
+                    >
+                        History
+                    </button>
                 </div>
                 
                 {/* Tab Content */}
@@ -603,8 +1766,16 @@ const CourseManagementPanel = ({ ss }) => {
                             renderFavorites={renderFavorites}
                             favoritedSchedules={favoritedSchedules}
                             schedules={schedules}
+                            scheduleTotals={scheduleTotals}
+                            scheduleQuery={scheduleQuery}
+                            setScheduleQuery={setScheduleQuery}
+                            loadMoreSchedules={loadMoreSchedules}
+                            groupSchedules={groupSchedules}
+                            scheduleGroups={scheduleGroups}
+                            toggleGroupSchedules={toggleGroupSchedules}
                             scrapeState={scrapeState}
                             favoritedScheduleStrings={favoritedScheduleStrings}
+                            favoriteDetails={favoriteDetails}
                             selectedScheduleId={selectedScheduleId}
                             toggleRenderFavorites={toggleRenderFavorites}
                             setSelectedSchedule={setSelectedSchedule}
@@ -612,6 +1783,14 @@ const CourseManagementPanel = ({ ss }) => {
                             clearHoveredSchedule={clearHoveredSchedule}
                             toggleFavoriteSchedule={toggleFavoriteSchedule}
                             deleteSchedule={deleteSchedule}
+                            updateFavoriteDetails={updateFavoriteDetails}
+                            exportScheduleCalendar={exportScheduleCalendar}
+                            copyScheduleCode={copyScheduleCode}
+                            exportScheduleImage={exportScheduleImage}
+                            printSchedule={printSchedule}
+                            schedulePreviews={schedulePreviews}
+                            loadSchedulePreview={loadSchedulePreview}
+                            exportScheduleTable={exportScheduleTable}
                             getScheduleDisplayNumber={getScheduleDisplayNumber}
                             ss={ss}
                         />
@@ -624,6 +1803,22 @@ const CourseManagementPanel = ({ ss }) => {
                             updateClass={updateClass}
                             deleteClass={deleteClass}
                             addClass={addClass}
+                            importClassList={importClassList}
+                            importClassFile={importClassFile}
+                            ss={ss}
+                        />
+                   )}
+
+                   {/* History Tab - Shows earlier generation runs of the active plan */}
+                   {activeTab === 'history' && (
+                        <GenerationHistory
+                            generationRuns={generationRuns}
+                            historyLimit={historyLimit}
+                            loadGenerationHistory={loadGenerationHistory}
+                            restoreGenerationRun={restoreGenerationRun}
+                            deleteGenerationRun={deleteGenerationRun}
+                            setHistoryLimit={setHistoryLimit}
+                            disabled={isScraping}
                             ss={ss}
                         />
                    )}
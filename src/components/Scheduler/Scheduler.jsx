@@ -1,5 +1,5 @@
-import { useEffect, useMemo } from 'react';
-import useStore, { stringifySchedule } from '../../Store.jsx';
+import { useEffect, useMemo, useState } from 'react';
+import useStore from '../../Store.jsx';
 import CalendarGrid from './CalendarGrid/CalendarGrid.jsx';
 import Sidebar from "../Sidebar/Sidebar";
 import CourseManagementPanel from './CourseManagementPanel';
@@ -8,99 +8,8 @@ import ss from './Scheduler.module.css';
 //FIXME async classes not showing
 //FIXME schedule numbers still escalating 
 
-/**
- * Processes preview events from schedule data for calendar display
- * Converts raw schedule events into formatted calendar events with positioning
- * 
- * @param {Array} previewEvents - Array of raw preview event objects
- * @param {Object} previewEvents[].day - Day bitmask indicating which days the event occurs
- * @param {number} previewEvents[].startTime - Start time as integer (HHMM format)
- * @param {number} previewEvents[].endTime - End time as integer (HHMM format)
- * @returns {Object} Processed events organized by day and time category
- * @returns {Object} returns.eventsByDay - Events with specific times, keyed by day
- * @returns {Object} returns.noTimeEventsByDay - Events without specific times, keyed by day
- * 
- * @example
- * const events = [
- *   { day: 6, startTime: 900, endTime: 1050, title: "Math 101" }
- * ];
- * const { eventsByDay, noTimeEventsByDay } = processPreviewEvents(events);
- */
-const processPreviewEvents = (previewEvents) => {
-    const eventsByDay = {};
-    const noTimeEventsByDay = {};
-
-    // Calendar display constants
-    const START_HOUR = 8;
-    const END_HOUR = 20;
-    const TOTAL_MINUTES = (END_HOUR - START_HOUR) * 60;
-
-    /**
-     * Formats time integer to HH:MM string format
-     * @param {number} timeInt - Time as integer (e.g., 930 for 9:30)
-     * @returns {string} Formatted time string (e.g., "09:30")
-     */
-    const formatTime = (timeInt) => {
-        if (timeInt <= 0) return "00:00";
-        const timeStr = timeInt.toString().padStart(4, '0');
-        return `${timeStr.slice(0, 2)}:${timeStr.slice(2, 4)}`;
-    };
-
-    /**
-     * Calculates minutes elapsed since START_HOUR for positioning
-     * @param {number} timeInt - Time as integer (HHMM format)
-     * @returns {number} Minutes since start of calendar display
-     */
-    const getMinutesSinceStart = (timeInt) => {
-        if (timeInt <= 0) return 0;
-        const hours = Math.floor(timeInt / 100);
-        const minutes = timeInt % 100;
-        return (hours * 60 + minutes) - (START_HOUR * 60);
-    };
-
-    // Process each preview event
-    previewEvents.forEach(event => {
-        // Check each day bit in the bitmask (0-6 for days of week)
-        for (let dayBitIndex = 0; dayBitIndex < 7; dayBitIndex++) {
-            if ((event.day & (1 << dayBitIndex)) !== 0) {
-                const dayKey = dayBitIndex.toString();
-
-                // Create processed event with formatting and positioning
-                const processedEvent = {
-                    ...event,
-                    startTimeFormatted: formatTime(event.startTime),
-                    endTimeFormatted: formatTime(event.endTime),
-                    startTimeInt: event.startTime,
-                    endTimeInt: event.endTime,
-                    width: "100%",
-                    left: "0%",
-                    topPosition: "0%",
-                    heightPosition: "0%"
-                };
-
-                // Categorize by whether event has specific times
-                if (event.startTime > 0 && event.endTime > 0) {
-                    // Calculate positioning for timed events on the calendar
-                    const startMinutes = getMinutesSinceStart(event.startTime);
-                    const endMinutes = getMinutesSinceStart(event.endTime);
-                    const duration = Math.max(endMinutes - startMinutes, 0);
-
-                    processedEvent.topPosition = `${(startMinutes / TOTAL_MINUTES) * 100}%`;
-                    processedEvent.heightPosition = `${(duration / TOTAL_MINUTES) * 100}%`;
-
-                    if (!eventsByDay[dayKey]) eventsByDay[dayKey] = [];
-                    eventsByDay[dayKey].push(processedEvent);
-                } else {
-                    // No-time events go in separate category (top bar)
-                    if (!noTimeEventsByDay[dayKey]) noTimeEventsByDay[dayKey] = [];
-                    noTimeEventsByDay[dayKey].push(processedEvent);
-                }
-            }
-        }
-    });
-
-    return { eventsByDay, noTimeEventsByDay };
-};
+/** @constant {Object} Visible hours used until the backend reports its window */
+const DEFAULT_VIEW_WINDOW = { startHour: 8, endHour: 20 };
 
 /**
  * Main Scheduler component - renders the complete scheduling interface
@@ -128,14 +37,14 @@ const Scheduler = () => {
     /** @type {Object} User-created events organized by day and time category */
     const userEvents = useStore(state => state.userEvents);
     
-    /** @type {Array} Generated schedules array */
-    const schedules = useStore(state => state.schedules);
-    
-    /** @type {string|null} Currently selected/pinned schedule ID */
-    const selectedScheduleId = useStore(state => state.selectedScheduleId);
+    /** @type {Array|null} Currently selected/pinned schedule, from either list */
+    const selectedSchedule = useStore(state => state.selectedSchedule);
     
     /** @type {Object|null} Schedule being hovered for preview */
     const currentHoveredSchedule = useStore(state => state.currentHoveredSchedule);
+
+    /** @type {Array|null} Schedule opened from a share code */
+    const sharedSchedule = useStore(state => state.sharedSchedule);
     
     /** @type {Object|null} Event object for details modal */
     const detailsEvent = useStore(state => state.detailsEvent);
@@ -158,6 +67,12 @@ const Scheduler = () => {
     const deleteUserEvent = useStore(state => state.deleteUserEvent);
     const showEventDetailsModal = useStore(state => state.showEventDetailsModal);
     const closeEventDetailsModal = useStore(state => state.closeEventDetailsModal);
+    const undo = useStore(state => state.undo);
+    const redo = useStore(state => state.redo);
+    const processPreviewEvents = useStore(state => state.processPreviewEvents);
+
+    /** @type {Object|null} User and preview events processed together by the backend */
+    const [previewedEvents, setPreviewedEvents] = useState(null);
 
     // --- EFFECTS ---
     
@@ -169,34 +84,43 @@ const Scheduler = () => {
         loadSchedulerPage();
     }, [loadSchedulerPage]);
 
+    /**
+     * Undo/redo keyboard shortcuts: Ctrl/Cmd+Z, Ctrl/Cmd+Shift+Z and Ctrl+Y
+     * Ignored while typing so text fields keep their own undo behavior
+     */
+    useEffect(() => {
+        const handleKeyDown = (event) => {
+            if (!(event.ctrlKey || event.metaKey)) return;
+            const target = event.target;
+            if (target.isContentEditable || ['INPUT', 'TEXTAREA', 'SELECT'].includes(target.tagName)) return;
+
+            const key = event.key.toLowerCase();
+            if (key === 'z' && !event.shiftKey) {
+                event.preventDefault();
+                undo();
+            } else if ((key === 'z' && event.shiftKey) || key === 'y') {
+                event.preventDefault();
+                redo();
+            }
+        };
+        window.addEventListener('keydown', handleKeyDown);
+        return () => window.removeEventListener('keydown', handleKeyDown);
+    }, [undo, redo]);
+
     // --- MEMOIZED CALCULATIONS ---
     
     /**
-     * Combines user events with preview events from selected/hovered schedules
-     * Memoized to prevent unnecessary recalculations on unrelated state changes
+     * Extracts the meetings of the previewed schedule (hovered, then shared, then selected)
+     * Memoized to prevent unnecessary reprocessing on unrelated state changes
      * 
-     * @returns {Object} Combined events organized by day and time category
-     * @returns {Object} returns.eventsByDay - All timed events for calendar display
-     * @returns {Object} returns.noTimeEventsByDay - All no-time events for calendar display
+     * @returns {Array|null} Raw preview events with day bitmasks and HHMM times, or null if nothing is previewed
      */
-    const { eventsByDay, noTimeEventsByDay } = useMemo(() => {
-        // Determine which schedule to preview (hovered takes precedence over selected)
-        const selectedSchedule = selectedScheduleId
-            ? schedules.find(s => stringifySchedule(s) === selectedScheduleId)
-            : null;
-        const scheduleToDisplay = currentHoveredSchedule ?? selectedSchedule;
-
-        // Start with user events as base
-        const finalEventsByDay = { ...(userEvents.eventsByDay || {}) };
-        const finalNoTimeEventsByDay = { ...(userEvents.noTimeEventsByDay || {}) };
-
-        // If no schedule to preview, return just user events
-        if (!scheduleToDisplay) {
-            return { eventsByDay: finalEventsByDay, noTimeEventsByDay: finalNoTimeEventsByDay };
-        }
+    const previewEvents = useMemo(() => {
+        const scheduleToDisplay = currentHoveredSchedule ?? sharedSchedule ?? selectedSchedule;
+        if (!scheduleToDisplay) return null;
 
         // Extract preview events from schedule data structure
-        const previewEvents = scheduleToDisplay.flatMap((courseData) => {
+        return scheduleToDisplay.flatMap((courseData) => {
             if (!courseData?.classes?.length) return [];
 
             return courseData.classes.flatMap((classMeeting) => {
@@ -223,7 +147,6 @@ const Scheduler = () => {
 
                 const eventBase = {
                     id: `preview-${courseData.id}-${classMeeting.section}`,
-                    isPreview: true,
                     title: `${courseData.code || ''} ${courseData.name || ''}`.trim() + (classMeeting.section ? ` - Sec ${classMeeting.section}` : ''),
                     professor: classMeeting.instructor || '',
                     description: courseData.description || '',
@@ -253,20 +176,29 @@ const Scheduler = () => {
                 };
             }).filter(Boolean);
         });
+    }, [currentHoveredSchedule, sharedSchedule, selectedSchedule]);
 
-        // Process preview events and merge with user events
-        const { eventsByDay: previewEventsByDay, noTimeEventsByDay: previewNoTimeEventsByDay } = processPreviewEvents(previewEvents);
-
-        // Merge preview events with user events
-        Object.keys(previewEventsByDay).forEach(dayKey => {
-            finalEventsByDay[dayKey] = [...(finalEventsByDay[dayKey] || []), ...previewEventsByDay[dayKey]];
-        });
-        Object.keys(previewNoTimeEventsByDay).forEach(dayKey => {
-            finalNoTimeEventsByDay[dayKey] = [...(finalNoTimeEventsByDay[dayKey] || []), ...previewNoTimeEventsByDay[dayKey]];
+    /**
+     * Has the backend position the user's events and the preview on one window
+     * Responses for a preview that was replaced in the meantime are dropped
+     */
+    useEffect(() => {
+        if (!previewEvents) {
+            setPreviewedEvents(null);
+            return;
+        }
+        let cancelled = false;
+        processPreviewEvents(previewEvents).then(result => {
+            if (!cancelled) setPreviewedEvents(result);
         });
+        return () => { cancelled = true; };
+    }, [previewEvents, userEvents, processPreviewEvents]);
 
-        return { eventsByDay: finalEventsByDay, noTimeEventsByDay: finalNoTimeEventsByDay };
-    }, [userEvents, currentHoveredSchedule, selectedScheduleId, schedules]);
+    // User events alone until the preview has been processed
+    const displayedEvents = (previewEvents && previewedEvents) || userEvents;
+    const eventsByDay = displayedEvents.eventsByDay || {};
+    const noTimeEventsByDay = displayedEvents.noTimeEventsByDay || {};
+    const viewWindow = displayedEvents.window || DEFAULT_VIEW_WINDOW;
 
     // --- RESET SCHEDULE INDEX ON GENERATE ---
     // If you have a schedule index in your store, reset it when generating schedules.
@@ -320,6 +252,8 @@ const Scheduler = () => {
                     <CalendarGrid
                         events={eventsByDay}
                         noTimeEvents={noTimeEventsByDay}
+                        startHour={viewWindow.startHour}
+                        endHour={viewWindow.endHour}
                         onEventCreate={createUserEvent}
                         onEventDelete={deleteUserEvent}
                         onEventUpdate={updateUserEvent}